@@ -0,0 +1,261 @@
+//! Read-only `inspect` commands.
+
+use std::fmt::Display;
+
+use anchor_client::solana_client::rpc_client::RpcClient;
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use solana_nft_staking_vault_client::{
+    accounts, pda,
+    program::{RewardMode, Role, UserStakeAccount, VaultConfig},
+};
+
+fn field(label: &str, value: impl Display) {
+    println!("  {label:<28} {value}");
+}
+
+fn optional<T: Display>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| value.to_string())
+}
+
+fn role_name(role: &Role) -> &'static str {
+    match role {
+        Role::SuperAdmin => "SuperAdmin",
+        Role::Admin => "Admin",
+        Role::Moderator => "Moderator",
+        Role::Operator => "Operator",
+        Role::Revoked => "Revoked",
+        Role::Custom(_) => "Custom",
+    }
+}
+
+fn permissions(role: &Role) -> String {
+    let names: Vec<_> = [
+        (Role::PAUSE_VAULT, "pause"),
+        (Role::UPDATE_CONFIG, "config"),
+        (Role::MANAGE_ROLES, "roles"),
+        (Role::MODERATE_USERS, "moderate"),
+        (Role::MANAGE_TREASURY, "treasury"),
+        (Role::MANAGE_UPGRADES, "upgrades"),
+    ]
+    .into_iter()
+    .filter(|(permission, _)| role.has_permission(*permission))
+    .map(|(_, name)| name)
+    .collect();
+
+    if names.is_empty() {
+        "none".to_owned()
+    } else {
+        names.join(",")
+    }
+}
+
+fn pause_flags(flags: u8) -> String {
+    let names: Vec<_> = [
+        (VaultConfig::PAUSE_STAKE, "stake"),
+        (VaultConfig::PAUSE_CLAIM, "claim"),
+        (VaultConfig::PAUSE_UNSTAKE, "unstake"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| flags & flag != 0)
+    .map(|(_, name)| name)
+    .collect();
+
+    if names.is_empty() {
+        "none".to_owned()
+    } else {
+        names.join(", ")
+    }
+}
+
+pub fn vault(rpc: &RpcClient) -> Result<()> {
+    let vault = accounts::fetch_vault(rpc)?;
+    let config = accounts::fetch_vault_config(rpc)?;
+    let stats = accounts::fetch_vault_stats(rpc)?;
+    let shards = accounts::fetch_vault_shards(rpc)?;
+
+    println!("Vault {}", pda::vault());
+    field("authority", vault.authority);
+    field("version", vault.version);
+    field("upgrade locked", vault.upgrade_locked);
+    if let Some(upgrade) = &vault.pending_upgrade {
+        field(
+            "pending upgrade",
+            format!(
+                "v{} at {} by {}, {} of {} objections",
+                upgrade.new_version,
+                upgrade.scheduled_timestamp,
+                upgrade.proposer,
+                upgrade.objections,
+                upgrade.veto_threshold
+            ),
+        );
+    }
+    if let Some(recovery_key) = vault.recovery_key {
+        field(
+            "dead-man switch",
+            format!(
+                "{} after {}s, last heartbeat {}",
+                recovery_key, vault.heartbeat_timeout_seconds, vault.last_admin_heartbeat
+            ),
+        );
+    }
+    field("reward mint", vault.reward_token_mint);
+    field("reward decimals", vault.reward_decimals);
+    field(
+        "reward mode",
+        match vault.reward_mode {
+            RewardMode::Mint => "mint",
+            RewardMode::Pool => "pool",
+        },
+    );
+    field("collection mint", vault.collection_mint);
+    field("total staked", vault.total_staked);
+    field("pending unstakes", vault.pending_unstakes);
+    field("emergency mode", vault.emergency_mode);
+    field("decommissioned", vault.decommissioned);
+    field("action cooldown (s)", vault.action_cooldown_seconds);
+    field("claim cooldown (s)", vault.claim_cooldown_seconds);
+    field("min stake slots", vault.min_stake_slots);
+    field("fee (bps)", vault.fee_bps);
+    field("fee destination", optional(vault.fee_destination));
+    field("insurance share (bps)", vault.insurance_fee_bps);
+    field("insurance penalties owed", vault.insurance_penalties_owed);
+    field("max NFTs per user", vault.max_nfts_per_user);
+    field("max total staked", vault.max_total_staked);
+    field("rewards minted", vault.total_rewards_minted);
+    field("max reward supply", vault.max_reward_supply);
+
+    println!("Config {}", pda::vault_config());
+    field("reward rate (/s)", config.reward_rate_per_second);
+    field("reward rate fraction", config.reward_rate_fraction);
+    field("paused", pause_flags(config.pause_flags));
+    let limits = &config.daily_limit;
+    field(
+        "stakes today",
+        format!("{} / {}", limits.stakes_today, limits.max_stakes_per_day),
+    );
+    field(
+        "claims today",
+        format!("{} / {}", limits.claims_today, limits.max_claims_per_day),
+    );
+    field(
+        "rewards today",
+        format!(
+            "{} / {}",
+            limits.rewards_claimed_today, limits.max_total_rewards_per_day
+        ),
+    );
+    let breaker = &config.circuit_breaker;
+    let state = if breaker.blocked != 0 {
+        "open"
+    } else {
+        "closed"
+    };
+    let tripped = if breaker.manually_tripped != 0 {
+        ", tripped"
+    } else {
+        ""
+    };
+    field(
+        "circuit breaker",
+        format!(
+            "{state} ({}/{} failures{tripped})",
+            breaker.failure_count, breaker.failure_threshold
+        ),
+    );
+
+    println!("Stats {}", pda::vault_stats());
+    field("rewards distributed", stats.total_rewards_distributed);
+    field("unique stakers", stats.unique_stakers);
+    field("stake events", stats.total_stake_events);
+    field("unstake events", stats.total_unstake_events);
+    field("peak total staked", stats.peak_total_staked);
+
+    println!("Unfolded shard deltas");
+    for shard in shards
+        .iter()
+        .filter(|shard| shard.staked_delta != 0 || shard.rewards_minted != 0)
+    {
+        field(
+            &format!("shard {}", shard.index),
+            format!(
+                "staked {:+}, minted {}, folded at {}",
+                shard.staked_delta, shard.rewards_minted, shard.folded_at
+            ),
+        );
+    }
+    Ok(())
+}
+
+pub fn roles(rpc: &RpcClient) -> Result<()> {
+    let mut roles = accounts::fetch_roles(rpc)?;
+    roles.sort_by_key(|(_, role)| role.granted_at);
+
+    println!(
+        "{:<44} {:<10} {:<40} {:<44} {:>12}",
+        "user", "role", "permissions", "granted by", "expires at"
+    );
+    for (_, role) in &roles {
+        println!(
+            "{:<44} {:<10} {:<40} {:<44} {:>12}",
+            role.user.to_string(),
+            role_name(&role.role),
+            permissions(&role.role),
+            role.granted_by.to_string(),
+            optional(role.expires_at),
+        );
+    }
+    Ok(())
+}
+
+pub fn stake(rpc: &RpcClient, user: &Pubkey) -> Result<()> {
+    match accounts::fetch_user_stake(rpc, user)? {
+        Some(stake) => print_stake(&pda::user_stake(user), &stake),
+        None => println!("{user} has no stake account"),
+    }
+    Ok(())
+}
+
+pub fn stakes(rpc: &RpcClient) -> Result<()> {
+    let mut stakes = accounts::fetch_user_stakes(rpc)?;
+    stakes.sort_by_key(|(_, stake)| std::cmp::Reverse(stake.staked_nfts));
+
+    println!(
+        "{:<44} {:>6} {:>16} {:>12}",
+        "user", "NFTs", "pending", "updated at"
+    );
+    for (_, stake) in &stakes {
+        println!(
+            "{:<44} {:>6} {:>16} {:>12}",
+            stake.user.to_string(),
+            stake.staked_nfts,
+            stake.pending_rewards,
+            stake.last_update_timestamp,
+        );
+    }
+    println!("{} positions", stakes.len());
+    Ok(())
+}
+
+fn print_stake(address: &Pubkey, stake: &UserStakeAccount) {
+    println!("Stake {address}");
+    field("user", stake.user);
+    field("version", stake.version);
+    field("staked NFTs", stake.staked_nfts);
+    field("warming NFTs", stake.warming_nfts);
+    field("staked tokens", stake.staked_tokens);
+    field("pending rewards", stake.pending_rewards);
+    field("redistributed rewards", stake.redistributed_rewards);
+    field("credited rewards", stake.credited_rewards);
+    field("last update", stake.last_update_timestamp);
+    field("locked until", stake.locked_until);
+    field("lock boost (bps)", stake.lock_boost_bps);
+    field("booster boost (bps)", stake.booster_boost_bps);
+    field("burn boost (bps)", stake.burn_boost_bps);
+    field("NFTs burned", stake.burned_nfts);
+    field("streak (days)", stake.current_streak_days);
+    field("level", format!("{} ({} xp)", stake.level, stake.xp));
+    field("claim delegate", optional(stake.claim_delegate));
+    field("referrer", optional(stake.referrer));
+}
@@ -0,0 +1,471 @@
+//! `vault-admin`: operator commands for the NFT staking vault.
+//!
+//! Every command signs with `--keypair`, which must hold the role the
+//! instruction checks (or be the vault authority for `init-vault`).
+
+mod inspect;
+
+use anchor_client::solana_client::rpc_client::RpcClient;
+use anchor_client::solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use solana_nft_staking_vault_client::{
+    accounts, instructions,
+    program::{instruction, CircuitBreakerState, RewardMode, Role},
+};
+
+#[derive(Parser)]
+#[command(name = "vault-admin", version, about)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "https://api.devnet.solana.com"
+    )]
+    url: String,
+    /// Signer keypair file
+    #[arg(
+        long,
+        short = 'k',
+        global = true,
+        default_value = "~/.config/solana/id.json"
+    )]
+    keypair: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the vault, its config, stats, metrics and shards
+    InitVault(InitVaultArgs),
+    /// Grant a role, replacing any the wallet already holds
+    GrantRole {
+        user: Pubkey,
+        #[arg(value_enum)]
+        role: RoleArg,
+        /// Unix timestamp the role lapses at; never by default
+        #[arg(long)]
+        expires_at: Option<i64>,
+    },
+    /// Grant a custom role with exactly the listed permissions
+    GrantPermissions {
+        user: Pubkey,
+        #[arg(value_enum, value_delimiter = ',', required = true)]
+        permissions: Vec<PermissionArg>,
+        /// Unix timestamp the role lapses at; never by default
+        #[arg(long)]
+        expires_at: Option<i64>,
+    },
+    RevokeRole {
+        user: Pubkey,
+    },
+    /// Show the role managers are still active, resetting the dead-man switch
+    Heartbeat,
+    /// Pause staking, unstaking and claims
+    Pause,
+    Unpause,
+    #[command(subcommand)]
+    UpdateConfig(ConfigCommand),
+    /// Schedule a version bump after `timelock` seconds
+    /// Schedule an upgrade to a buffer whose authority is the vault PDA
+    ProposeUpgrade {
+        new_version: u32,
+        buffer: Pubkey,
+        #[arg(long, default_value_t = 86_400)]
+        timelock: i64,
+    },
+    ExecuteUpgrade,
+    CancelUpgrade,
+    /// Deposit reward tokens (base units) from the signer's ATA into the pool
+    FundRewards {
+        amount: u64,
+        /// Defaults to the vault's reward mint
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// The mint was added with `add_reward_config`
+        #[arg(long)]
+        secondary: bool,
+    },
+    #[command(subcommand)]
+    Inspect(InspectCommand),
+}
+
+#[derive(clap::Args)]
+struct InitVaultArgs {
+    #[arg(long)]
+    reward_mint: Pubkey,
+    #[arg(long)]
+    collection_mint: Pubkey,
+    /// Whole reward tokens per staked NFT per day
+    #[arg(long)]
+    reward_tokens_per_day: u64,
+    #[arg(long, value_enum, default_value_t = RewardModeArg::Mint)]
+    reward_mode: RewardModeArg,
+    #[arg(long, default_value_t = 1_000)]
+    max_stakes_per_day: u32,
+    #[arg(long, default_value_t = 10_000)]
+    max_claims_per_day: u32,
+    /// In whole reward tokens
+    #[arg(long)]
+    max_rewards_per_day: u64,
+    #[arg(long, default_value_t = CircuitBreakerState::DEFAULT_FAILURE_THRESHOLD)]
+    failure_threshold: u32,
+    #[arg(long, default_value_t = CircuitBreakerState::DEFAULT_RESET_TIMEOUT)]
+    reset_timeout: i64,
+    #[arg(long, default_value_t = 60)]
+    action_cooldown: i64,
+    #[arg(long, default_value_t = 60)]
+    claim_cooldown: i64,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Daily limits, the reward cap in whole tokens; omitted values are left
+    /// unchanged
+    Limits {
+        #[arg(long)]
+        max_stakes_per_day: Option<u32>,
+        #[arg(long)]
+        max_claims_per_day: Option<u32>,
+        #[arg(long)]
+        max_rewards_per_day: Option<u64>,
+    },
+    /// Per-user and vault-wide staking caps; 0 lifts a cap
+    StakeCaps {
+        #[arg(long)]
+        per_user: Option<u32>,
+        #[arg(long)]
+        total: Option<u32>,
+    },
+    Cooldowns {
+        #[arg(long)]
+        action: i64,
+        #[arg(long)]
+        claim: i64,
+    },
+    MinStakeSlots {
+        slots: u64,
+    },
+    Fee {
+        #[arg(long)]
+        bps: u64,
+        /// Reward-mint token account receiving fees
+        #[arg(long)]
+        destination: Option<Pubkey>,
+    },
+    /// Timelocked change of the reward rate or collection
+    Propose {
+        #[arg(long)]
+        reward_rate: Option<u64>,
+        #[arg(long)]
+        collection_mint: Option<Pubkey>,
+        #[arg(long, default_value_t = 86_400)]
+        timelock: i64,
+    },
+    /// Apply the pending change once its timelock has passed
+    Execute,
+    Cancel,
+}
+
+#[derive(Subcommand)]
+enum InspectCommand {
+    /// Vault state, config, stats and unfolded shard deltas
+    Vault,
+    /// Every wallet holding a role
+    Roles,
+    /// One user's position, or every position when omitted
+    Stakes { user: Option<Pubkey> },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RoleArg {
+    SuperAdmin,
+    Admin,
+    Moderator,
+    Operator,
+}
+
+impl From<RoleArg> for Role {
+    fn from(role: RoleArg) -> Self {
+        match role {
+            RoleArg::SuperAdmin => Role::SuperAdmin,
+            RoleArg::Admin => Role::Admin,
+            RoleArg::Moderator => Role::Moderator,
+            RoleArg::Operator => Role::Operator,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PermissionArg {
+    PauseVault,
+    UpdateConfig,
+    ManageRoles,
+    ModerateUsers,
+    ManageTreasury,
+    ManageUpgrades,
+}
+
+impl PermissionArg {
+    fn bit(self) -> u32 {
+        match self {
+            PermissionArg::PauseVault => Role::PAUSE_VAULT,
+            PermissionArg::UpdateConfig => Role::UPDATE_CONFIG,
+            PermissionArg::ManageRoles => Role::MANAGE_ROLES,
+            PermissionArg::ModerateUsers => Role::MODERATE_USERS,
+            PermissionArg::ManageTreasury => Role::MANAGE_TREASURY,
+            PermissionArg::ManageUpgrades => Role::MANAGE_UPGRADES,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RewardModeArg {
+    Mint,
+    Pool,
+}
+
+impl From<RewardModeArg> for RewardMode {
+    fn from(mode: RewardModeArg) -> Self {
+        match mode {
+            RewardModeArg::Mint => RewardMode::Mint,
+            RewardModeArg::Pool => RewardMode::Pool,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+
+    if let Command::Inspect(command) = &cli.command {
+        return match command {
+            InspectCommand::Vault => inspect::vault(&rpc),
+            InspectCommand::Roles => inspect::roles(&rpc),
+            InspectCommand::Stakes { user: Some(user) } => inspect::stake(&rpc, user),
+            InspectCommand::Stakes { user: None } => inspect::stakes(&rpc),
+        };
+    }
+
+    let signer = load_keypair(&cli.keypair)?;
+    let me = signer.pubkey();
+
+    let ixs = match cli.command {
+        Command::InitVault(args) => {
+            // The vault must exist before the shards can be created against it.
+            send(
+                &rpc,
+                &signer,
+                &[
+                    init_vault(&me, &args),
+                    instructions::initialize_vault_metrics(&me),
+                ],
+            )?;
+            instructions::initialize_vault_shards(&me)
+        }
+        Command::GrantRole {
+            user,
+            role,
+            expires_at,
+        } => vec![instructions::grant_role(
+            &me,
+            &user,
+            role.into(),
+            expires_at,
+        )],
+        Command::GrantPermissions {
+            user,
+            permissions,
+            expires_at,
+        } => vec![instructions::grant_role(
+            &me,
+            &user,
+            Role::Custom(
+                permissions
+                    .into_iter()
+                    .fold(0, |bits, permission| bits | permission.bit()),
+            ),
+            expires_at,
+        )],
+        Command::RevokeRole { user } => {
+            vec![instructions::manage_role(
+                &me,
+                &user,
+                instruction::RevokeRole,
+            )]
+        }
+        Command::Heartbeat => vec![instructions::admin_heartbeat(&me)],
+        Command::Pause => vec![instructions::pause_vault(&me)],
+        Command::Unpause => vec![instructions::unpause_vault(&me)],
+        Command::UpdateConfig(command) => vec![update_config(&me, command)],
+        Command::ProposeUpgrade {
+            new_version,
+            buffer,
+            timelock,
+        } => {
+            let buffer_hash = instructions::upgrade_buffer_hash(&rpc.get_account_data(&buffer)?);
+            vec![instructions::propose_upgrade(&me, new_version, &buffer, buffer_hash, timelock)]
+        }
+        Command::ExecuteUpgrade => {
+            let pending = accounts::fetch_vault(&rpc)?
+                .pending_upgrade
+                .ok_or_else(|| anyhow!("no upgrade is pending"))?;
+            vec![instructions::execute_upgrade(&me, &pending.buffer, &me)]
+        }
+        Command::CancelUpgrade => vec![instructions::cancel_upgrade(&me)],
+        Command::FundRewards {
+            amount,
+            mint,
+            secondary,
+        } => {
+            let mint = match mint {
+                Some(mint) => mint,
+                None => accounts::fetch_vault(&rpc)?.reward_token_mint,
+            };
+            vec![instructions::fund_rewards(&me, &mint, amount, secondary)]
+        }
+        Command::Inspect(_) => unreachable!("handled above"),
+    };
+
+    send(&rpc, &signer, &ixs)?;
+    Ok(())
+}
+
+fn init_vault(authority: &Pubkey, args: &InitVaultArgs) -> Instruction {
+    instructions::initialize_vault(
+        authority,
+        &args.reward_mint,
+        instruction::InitializeVault {
+            reward_tokens_per_day: args.reward_tokens_per_day,
+            collection_mint: args.collection_mint,
+            reward_mode: args.reward_mode.into(),
+            max_stakes_per_day: args.max_stakes_per_day,
+            max_claims_per_day: args.max_claims_per_day,
+            max_total_rewards_per_day: args.max_rewards_per_day,
+            failure_threshold: args.failure_threshold,
+            reset_timeout: args.reset_timeout,
+            action_cooldown_seconds: args.action_cooldown,
+            claim_cooldown_seconds: args.claim_cooldown,
+        },
+    )
+}
+
+fn update_config(updater: &Pubkey, command: ConfigCommand) -> Instruction {
+    use instructions::{update_config, update_vault_config};
+
+    match command {
+        ConfigCommand::Limits {
+            max_stakes_per_day,
+            max_claims_per_day,
+            max_rewards_per_day,
+        } => update_vault_config(
+            updater,
+            instruction::UpdateLimits {
+                max_stakes_per_day,
+                max_claims_per_day,
+                max_total_rewards_per_day: max_rewards_per_day,
+            },
+        ),
+        ConfigCommand::StakeCaps { per_user, total } => update_config(
+            updater,
+            instruction::SetStakeCaps {
+                max_nfts_per_user: per_user,
+                max_total_staked: total,
+            },
+        ),
+        ConfigCommand::Cooldowns { action, claim } => update_config(
+            updater,
+            instruction::SetCooldowns {
+                action_cooldown_seconds: action,
+                claim_cooldown_seconds: claim,
+            },
+        ),
+        ConfigCommand::MinStakeSlots { slots } => update_config(
+            updater,
+            instruction::SetMinStakeSlots {
+                min_stake_slots: slots,
+            },
+        ),
+        ConfigCommand::Fee { bps, destination } => update_config(
+            updater,
+            instruction::SetFeeConfig {
+                fee_bps: bps,
+                fee_destination: destination,
+            },
+        ),
+        ConfigCommand::Propose {
+            reward_rate,
+            collection_mint,
+            timelock,
+        } => update_vault_config(
+            updater,
+            instruction::ProposeConfigChange {
+                new_reward_rate: reward_rate,
+                new_collection_mint: collection_mint,
+                timelock_seconds: timelock,
+            },
+        ),
+        ConfigCommand::Execute => update_vault_config(updater, instruction::ExecuteConfigChange),
+        ConfigCommand::Cancel => update_config(updater, instruction::CancelConfigChange),
+    }
+}
+
+fn load_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => format!("{}/{rest}", std::env::var("HOME")?),
+        None => path.to_owned(),
+    };
+    read_keypair_file(&path).map_err(|error| anyhow!("reading keypair {path}: {error}"))
+}
+
+fn send(rpc: &RpcClient, signer: &Keypair, ixs: &[Instruction]) -> Result<Signature> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(ixs, Some(&signer.pubkey()), &[signer], blockhash);
+    let signature = rpc
+        .send_and_confirm_transaction(&tx)
+        .context("transaction failed")?;
+    println!("{signature}");
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn update_config_routes_to_matching_accounts() {
+        let updater = Pubkey::new_unique();
+        let fee = update_config(
+            &updater,
+            ConfigCommand::Fee {
+                bps: 100,
+                destination: None,
+            },
+        );
+        let limits = update_config(
+            &updater,
+            ConfigCommand::Limits {
+                max_stakes_per_day: Some(5),
+                max_claims_per_day: None,
+                max_rewards_per_day: None,
+            },
+        );
+        // UpdateVaultConfig also writes the vault config
+        assert_eq!(fee.accounts.len() + 1, limits.accounts.len());
+    }
+}
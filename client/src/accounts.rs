@@ -0,0 +1,175 @@
+//! Fetching and decoding program accounts.
+
+use anchor_client::solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use anchor_lang::prelude::*;
+use anchor_lang::{Discriminator, ZeroCopy};
+use solana_account_decoder::UiAccountEncoding;
+use solana_nft_staking_vault::{
+    AccountRole, RoleRegistry, UserStakeAccount, VaultAccount, VaultConfig, VaultMetrics,
+    VaultShard, VaultStats, ID,
+};
+
+use crate::{pda, Error, Result};
+
+/// Decodes a Borsh account, checking its discriminator.
+pub fn decode<T: AccountDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+        .map_err(|_| Error::InvalidAccount(*address, std::any::type_name::<T>()))
+}
+
+/// Decodes a zero-copy account such as `VaultConfig` or `VaultShard`.
+pub fn decode_zero_copy<T: ZeroCopy>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    let start = T::DISCRIMINATOR.len();
+    let end = start + std::mem::size_of::<T>();
+    if data.len() < end || !data.starts_with(T::DISCRIMINATOR) {
+        return Err(Error::InvalidAccount(*address, std::any::type_name::<T>()));
+    }
+    Ok(bytemuck::pod_read_unaligned(&data[start..end]))
+}
+
+fn fetch_data(rpc: &RpcClient, address: &Pubkey) -> Result<Vec<u8>> {
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())?
+        .value
+        .ok_or(Error::AccountNotFound(*address))?;
+    if account.owner != ID {
+        return Err(Error::InvalidAccount(*address, "program account"));
+    }
+    Ok(account.data)
+}
+
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    decode(address, &fetch_data(rpc, address)?)
+}
+
+pub fn fetch_zero_copy<T: ZeroCopy>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    decode_zero_copy(address, &fetch_data(rpc, address)?)
+}
+
+/// Like [`fetch`], but `None` for an account that doesn't exist yet.
+pub fn fetch_optional<T: AccountDeserialize>(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<Option<T>> {
+    match fetch(rpc, address) {
+        Ok(account) => Ok(Some(account)),
+        Err(Error::AccountNotFound(_)) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Every account of type `T`, found by its discriminator.
+pub fn fetch_all<T>(rpc: &RpcClient) -> Result<Vec<(Pubkey, T)>>
+where
+    T: AccountDeserialize + Discriminator,
+{
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            T::DISCRIMINATOR,
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    rpc.get_program_accounts_with_config(&ID, config)?
+        .into_iter()
+        .map(|(address, account)| Ok((address, decode(&address, &account.data)?)))
+        .collect()
+}
+
+pub fn fetch_vault(rpc: &RpcClient) -> Result<VaultAccount> {
+    fetch(rpc, &pda::vault())
+}
+
+pub fn fetch_vault_config(rpc: &RpcClient) -> Result<VaultConfig> {
+    fetch_zero_copy(rpc, &pda::vault_config())
+}
+
+pub fn fetch_vault_stats(rpc: &RpcClient) -> Result<VaultStats> {
+    fetch(rpc, &pda::vault_stats())
+}
+
+pub fn fetch_vault_metrics(rpc: &RpcClient) -> Result<VaultMetrics> {
+    fetch(rpc, &pda::vault_metrics())
+}
+
+pub fn fetch_vault_shard(rpc: &RpcClient, index: u8) -> Result<VaultShard> {
+    fetch_zero_copy(rpc, &pda::vault_shard(index))
+}
+
+/// All shards in index order; deltas not yet folded into the vault live
+/// here.
+pub fn fetch_vault_shards(rpc: &RpcClient) -> Result<Vec<VaultShard>> {
+    (0..VaultShard::COUNT)
+        .map(|index| fetch_vault_shard(rpc, index))
+        .collect()
+}
+
+pub fn fetch_user_stake(rpc: &RpcClient, user: &Pubkey) -> Result<Option<UserStakeAccount>> {
+    fetch_optional(rpc, &pda::user_stake(user))
+}
+
+pub fn fetch_role(rpc: &RpcClient, user: &Pubkey) -> Result<Option<AccountRole>> {
+    fetch_optional(rpc, &pda::role(user))
+}
+
+/// Every current role holder, as listed by the registry.
+pub fn fetch_role_registry(rpc: &RpcClient) -> Result<RoleRegistry> {
+    fetch(rpc, &pda::role_registry())
+}
+
+pub fn fetch_user_stakes(rpc: &RpcClient) -> Result<Vec<(Pubkey, UserStakeAccount)>> {
+    fetch_all(rpc)
+}
+
+pub fn fetch_roles(rpc: &RpcClient) -> Result<Vec<(Pubkey, AccountRole)>> {
+    fetch_all(rpc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_copy_decode_checks_discriminator_and_length() {
+        let address = Pubkey::new_unique();
+        let mut shard: VaultShard = bytemuck::Zeroable::zeroed();
+        shard.index = 3;
+        shard.staked_delta = -2;
+
+        let mut data = VaultShard::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&shard));
+        let decoded: VaultShard = decode_zero_copy(&address, &data).unwrap();
+        assert_eq!((decoded.index, decoded.staked_delta), (3, -2));
+
+        assert!(decode_zero_copy::<VaultShard>(&address, &data[..data.len() - 1]).is_err());
+        assert!(decode_zero_copy::<VaultConfig>(&address, &data).is_err());
+    }
+
+    #[test]
+    fn borsh_decode_rejects_other_accounts() {
+        let address = Pubkey::new_unique();
+        let role = AccountRole {
+            user: Pubkey::new_unique(),
+            role: solana_nft_staking_vault::Role::Admin,
+            granted_by: Pubkey::new_unique(),
+            granted_at: 0,
+            expires_at: None,
+        };
+        let mut data = Vec::new();
+        role.try_serialize(&mut data).unwrap();
+
+        let decoded: AccountRole = decode(&address, &data).unwrap();
+        assert_eq!(decoded.user, role.user);
+        assert!(decode::<VaultStats>(&address, &data).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+//! Decoding events the program emits through self-CPI (`emit_cpi!`).
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use solana_nft_staking_vault::{ClaimRejected, RewardsClaimed};
+
+/// Decodes `T` from the data of one of the program's event CPIs, i.e. an
+/// inner instruction signed by [`crate::pda::event_authority`].
+pub fn decode_event<T: Discriminator + AnchorDeserialize>(data: &[u8]) -> Option<T> {
+    let data = data.strip_prefix(EVENT_IX_TAG_LE)?;
+    let data = data.strip_prefix(T::DISCRIMINATOR)?;
+    T::deserialize(&mut &data[..]).ok()
+}
+
+/// What a successful `claim_rewards` did. Rejected claims succeed too, so
+/// the transaction status alone doesn't tell them apart.
+pub enum ClaimOutcome {
+    Paid(RewardsClaimed),
+    Rejected(ClaimRejected),
+}
+
+/// Finds the outcome among a `claim_rewards` transaction's event CPIs.
+pub fn claim_outcome<'a>(event_data: impl IntoIterator<Item = &'a [u8]>) -> Option<ClaimOutcome> {
+    event_data.into_iter().find_map(|data| {
+        decode_event(data)
+            .map(ClaimOutcome::Paid)
+            .or_else(|| decode_event(data).map(ClaimOutcome::Rejected))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+    use solana_nft_staking_vault::ClaimRejection;
+
+    fn event_cpi_data(event: &impl Event) -> Vec<u8> {
+        [EVENT_IX_TAG_LE, &event.data()].concat()
+    }
+
+    #[test]
+    fn rejected_claims_are_told_apart_from_payouts() {
+        let user = Pubkey::new_unique();
+        let paid = event_cpi_data(&RewardsClaimed {
+            user,
+            claimed_by: user,
+            destination: Pubkey::new_unique(),
+            amount: 2_000,
+            fee: 0,
+            staked_nfts: 1,
+            pending_rewards: 0,
+            total_staked: 1,
+            reward_rate_per_second: 10,
+            timestamp: 100,
+        });
+        let rejected = event_cpi_data(&ClaimRejected {
+            user,
+            reason: ClaimRejection::ExceedsBound,
+            requested: 5_000,
+            allowed: 2_000,
+            failure_count: 1,
+            timestamp: 100,
+        });
+
+        assert!(matches!(
+            claim_outcome([paid.as_slice()]),
+            Some(ClaimOutcome::Paid(event)) if event.amount == 2_000
+        ));
+        assert!(matches!(
+            claim_outcome([rejected.as_slice()]),
+            Some(ClaimOutcome::Rejected(event)) if event.reason == ClaimRejection::ExceedsBound
+        ));
+        // Log data without the event CPI tag is skipped
+        assert!(claim_outcome([&rejected[8..]]).is_none());
+    }
+}
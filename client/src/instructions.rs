@@ -0,0 +1,756 @@
+//! Instruction builders. Each one derives the PDAs and associated token
+//! accounts it needs, so callers only pass keys that can't be derived.
+//! Anything without a dedicated builder can go through [`build`] with the
+//! program's own `accounts` and `instruction` structs.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, instruction::Instruction, sysvar};
+use anchor_lang::InstructionData;
+use anchor_spl::{
+    associated_token::{get_associated_token_address, AssociatedToken},
+    metadata::Metadata,
+    token::Token,
+};
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use solana_nft_staking_vault::{
+    accounts, instruction, RewardMode, Role, UserStakeAccount, VaultAccount, VaultShard, ID,
+};
+use solana_sdk_ids::bpf_loader_upgradeable;
+
+use crate::pda;
+
+/// Pairs any of the program's `accounts::*` structs with its
+/// `instruction::*` arguments.
+pub fn build(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+/// Setters taking the `UpdateConfig` accounts.
+pub trait ConfigUpdate: InstructionData {}
+
+/// Setters taking the `UpdateVaultConfig` accounts, which also write
+/// `VaultConfig`.
+pub trait VaultConfigUpdate: InstructionData {}
+
+/// Admin actions taking the `PauseVault` accounts.
+pub trait PauseAction: InstructionData {}
+
+/// Role changes taking the `ManageRole` accounts.
+pub trait RoleAction: InstructionData {}
+
+macro_rules! implement {
+    ($trait:ident for $($ix:ident),* $(,)?) => {
+        $(impl $trait for instruction::$ix {})*
+    };
+}
+
+implement!(ConfigUpdate for
+    CancelConfigChange, SetStakeCaps, ProposeEmissionSchedule, ExecuteEmissionSchedule,
+    CancelEmissionSchedule, SetCurveConfig, SetLoyaltyConfig, SetStreakConfig, SetFeeConfig,
+    SetReferralConfig, SetOracleConfig, SetRewardSupplyCap, SetVestingConfig, SetTokenBoostConfig,
+    SetCooldowns, SetMinStakeSlots, SetMaxRewardAdjustment, SetWhitelistPhase, SetCrankBounty,
+    SetCpiCallerAllowlist, SetLockTiers, SetEarlyUnstakePenalty, SetUnstakeCooldown,
+    SetWarmupSeconds, ConfigureVoterWeight, SetRateVoteBounds, SetCouponSigner, SetAnomalyRules,
+    SetUserRateLimit, SetUpgradeVeto, SetInsuranceFee, SetTraitGate, SetBurnBoost,
+);
+implement!(VaultConfigUpdate for
+    ProposeConfigChange, ExecuteConfigChange, UpdateLimits, UpdateCircuitBreakerConfig,
+    SetRewardRateFraction,
+);
+implement!(PauseAction for
+    PauseVault, UnpauseVault, SetPauseFlags, SetEmergencyMode, TripCircuitBreaker,
+    ResetCircuitBreaker,
+);
+implement!(RoleAction for GrantRole, RenewRole, RevokeRole);
+
+/// Creates the vault, its config and stats, and makes `authority`
+/// SuperAdmin. Follow with [`initialize_vault_metrics`] and
+/// [`initialize_vault_shards`] before anyone stakes.
+pub fn initialize_vault(
+    authority: &Pubkey,
+    reward_token_mint: &Pubkey,
+    args: instruction::InitializeVault,
+) -> Instruction {
+    build(
+        accounts::InitializeVault {
+            vault: pda::vault(),
+            vault_config: pda::vault_config(),
+            vault_stats: pda::vault_stats(),
+            authority: *authority,
+            authority_role: pda::role(authority),
+            role_registry: pda::role_registry(),
+            reward_token_mint: *reward_token_mint,
+            token_program: Token::id(),
+            system_program: System::id(),
+        },
+        args,
+    )
+}
+
+/// Makes the vault authority SuperAdmin on a vault that predates role
+/// bootstrapping. Pass the wallets already holding roles as
+/// `existing_holders` so the registry lists them too.
+pub fn init_super_admin(authority: &Pubkey, existing_holders: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::InitSuperAdmin {
+            vault: pda::vault(),
+            authority: *authority,
+            authority_role: pda::role(authority),
+            role_registry: pda::role_registry(),
+            system_program: System::id(),
+        },
+        instruction::InitSuperAdmin,
+    );
+    ix.accounts.extend(
+        existing_holders
+            .iter()
+            .map(|holder| AccountMeta::new_readonly(pda::role(holder), false)),
+    );
+    ix
+}
+
+pub fn initialize_vault_metrics(payer: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeVaultMetrics {
+            vault: pda::vault(),
+            vault_metrics: pda::vault_metrics(),
+            payer: *payer,
+            system_program: System::id(),
+        },
+        instruction::InitializeVaultMetrics,
+    )
+}
+
+pub fn initialize_vault_shard(payer: &Pubkey, index: u8) -> Instruction {
+    build(
+        accounts::InitializeVaultShard {
+            vault_config: pda::vault_config(),
+            vault_shard: pda::vault_shard(index),
+            payer: *payer,
+            system_program: System::id(),
+        },
+        instruction::InitializeVaultShard { index },
+    )
+}
+
+pub fn initialize_vault_shards(payer: &Pubkey) -> Vec<Instruction> {
+    (0..VaultShard::COUNT)
+        .map(|index| initialize_vault_shard(payer, index))
+        .collect()
+}
+
+/// Permissionless crank folding one shard's deltas into the vault.
+pub fn fold_vault_shard(index: u8) -> Instruction {
+    build(
+        accounts::FoldVaultShard {
+            vault: pda::vault(),
+            vault_config: pda::vault_config(),
+            vault_stats: pda::vault_stats(),
+            vault_metrics: pda::vault_metrics(),
+            vault_shard: pda::vault_shard(index),
+        },
+        instruction::FoldVaultShard,
+    )
+}
+
+pub fn fold_vault_shards() -> Vec<Instruction> {
+    (0..VaultShard::COUNT).map(fold_vault_shard).collect()
+}
+
+/// Optional accounts for [`stake_nft`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StakeOptions {
+    /// Pays for the accounts created; the user when `None`.
+    pub payer: Option<Pubkey>,
+    /// Passes the user's whitelist entry, needed before the public start.
+    pub whitelisted: bool,
+    /// Passes the NFT's trait pass, needed while the vault has a trait gate.
+    pub trait_verified: bool,
+    /// Referrer recorded on the user's first stake.
+    pub referrer: Option<Pubkey>,
+    /// Registered partner program staking on the user's behalf through CPI.
+    pub partner_program: Option<Pubkey>,
+}
+
+/// Verifies `nft_mint` against the vault's trait gate. Without a `proof`,
+/// put the attestor's Ed25519 signature instruction right before this one.
+pub fn verify_nft_trait(
+    payer: &Pubkey,
+    nft_mint: &Pubkey,
+    proof: Option<Vec<[u8; 32]>>,
+) -> Instruction {
+    build(
+        accounts::VerifyNftTrait {
+            vault: pda::vault(),
+            nft_mint: *nft_mint,
+            trait_pass: pda::trait_pass(nft_mint),
+            payer: *payer,
+            instructions: sysvar::instructions::ID,
+            system_program: System::id(),
+        },
+        instruction::VerifyNftTrait { proof },
+    )
+}
+
+/// Burns an NFT of the vault's burn collection, held in the user's
+/// associated token account, for a permanent boost on their position.
+pub fn burn_for_boost(user: &Pubkey, nft_mint: &Pubkey, collection_mint: &Pubkey) -> Instruction {
+    build(
+        accounts::BurnForBoost {
+            vault: pda::vault(),
+            vault_config: pda::vault_config(),
+            user_stake: pda::user_stake(user),
+            user: *user,
+            nft_mint: *nft_mint,
+            nft_metadata: pda::metadata(nft_mint),
+            nft_master_edition: pda::master_edition(nft_mint),
+            user_nft_token_account: get_associated_token_address(user, nft_mint),
+            collection_metadata: pda::metadata(collection_mint),
+            metadata_program: Metadata::id(),
+            token_program: Token::id(),
+        },
+        instruction::BurnForBoost,
+    )
+}
+
+/// Stakes an NFT held in the user's associated token account.
+pub fn stake_nft(user: &Pubkey, nft_mint: &Pubkey, options: StakeOptions) -> Instruction {
+    let vault = pda::vault();
+    let receipt_mint = pda::receipt_mint(nft_mint);
+
+    build(
+        accounts::StakeNft {
+            vault,
+            vault_config: pda::vault_config(),
+            vault_shard: pda::vault_shard_for(user),
+            user_stake: pda::user_stake(user),
+            user: *user,
+            payer: options.payer.unwrap_or(*user),
+            user_status: pda::user_status(user),
+            whitelist_entry: options.whitelisted.then(|| pda::whitelist_entry(user)),
+            nft_mint: *nft_mint,
+            trait_pass: options.trait_verified.then(|| pda::trait_pass(nft_mint)),
+            stake_record: pda::stake_record(nft_mint),
+            referral: options.referrer.as_ref().map(pda::referral),
+            nft_group_tag: pda::nft_group(nft_mint),
+            nft_multiplier: pda::nft_multiplier(nft_mint),
+            voter_weight_record: pda::voter_weight_record(user),
+            receipt_mint,
+            user_receipt_token_account: get_associated_token_address(user, &receipt_mint),
+            nft_metadata: pda::metadata(nft_mint),
+            nft_master_edition: pda::master_edition(nft_mint),
+            user_nft_token_account: get_associated_token_address(user, nft_mint),
+            vault_nft_token_account: get_associated_token_address(&vault, nft_mint),
+            instructions: sysvar::instructions::ID,
+            partner: options.partner_program.as_ref().map(pda::partner),
+            metadata_program: Metadata::id(),
+            token_program: Token::id(),
+            associated_token_program: AssociatedToken::id(),
+            system_program: System::id(),
+            event_authority: pda::event_authority(),
+            program: ID,
+        },
+        instruction::StakeNft,
+    )
+}
+
+/// `rent_receiver` must be the stake record's `rent_receiver()`: whoever
+/// paid for it when the NFT was staked.
+pub fn unstake_nft(
+    user: &Pubkey,
+    nft_mint: &Pubkey,
+    rent_receiver: &Pubkey,
+    partner_program: Option<Pubkey>,
+) -> Instruction {
+    build(
+        unstake_accounts(user, nft_mint, rent_receiver, partner_program),
+        instruction::UnstakeNft,
+    )
+}
+
+/// Exits a locked position early, forfeiting part of the pending rewards.
+pub fn emergency_unstake(
+    user: &Pubkey,
+    nft_mint: &Pubkey,
+    rent_receiver: &Pubkey,
+    partner_program: Option<Pubkey>,
+) -> Instruction {
+    build(
+        unstake_accounts(user, nft_mint, rent_receiver, partner_program),
+        instruction::EmergencyUnstake,
+    )
+}
+
+fn unstake_accounts(
+    user: &Pubkey,
+    nft_mint: &Pubkey,
+    rent_receiver: &Pubkey,
+    partner_program: Option<Pubkey>,
+) -> accounts::UnstakeNft {
+    let vault = pda::vault();
+    let receipt_mint = pda::receipt_mint(nft_mint);
+
+    accounts::UnstakeNft {
+        vault,
+        vault_config: pda::vault_config(),
+        vault_shard: pda::vault_shard_for(user),
+        user_stake: pda::user_stake(user),
+        user: *user,
+        rent_receiver: *rent_receiver,
+        nft_mint: *nft_mint,
+        stake_record: pda::stake_record(nft_mint),
+        receipt_mint,
+        user_receipt_token_account: Some(get_associated_token_address(user, &receipt_mint)),
+        nft_group_tag: pda::nft_group(nft_mint),
+        nft_multiplier: pda::nft_multiplier(nft_mint),
+        voter_weight_record: pda::voter_weight_record(user),
+        user_nft_token_account: get_associated_token_address(user, nft_mint),
+        vault_nft_token_account: get_associated_token_address(&vault, nft_mint),
+        instructions: sysvar::instructions::ID,
+        partner: partner_program.as_ref().map(pda::partner),
+        token_program: Token::id(),
+        event_authority: pda::event_authority(),
+        program: ID,
+    }
+}
+
+/// Optional accounts for [`claim_rewards`]. [`ClaimOptions::for_position`]
+/// fills in the ones the vault and position require.
+#[derive(Clone, Debug, Default)]
+pub struct ClaimOptions {
+    /// Signs and pays for created accounts; the user when `None`. Must be
+    /// the user, their claim delegate or a session key.
+    pub claimer: Option<Pubkey>,
+    /// The claimer is one of the user's session keys.
+    pub session: bool,
+    /// Token account to pay into instead of the user's ATA.
+    pub destination: Option<Pubkey>,
+    /// Pays from the vault's pool; needed in `RewardMode::Pool`.
+    pub reward_pool: bool,
+    pub fee_destination: Option<Pubkey>,
+    /// Pays the insurance share of the fee into the insurance pool.
+    pub insurance_pool: bool,
+    /// Receives the referral bonus into their ATA.
+    pub referrer: Option<Pubkey>,
+    /// Passes the level config, if the vault has one, to credit XP.
+    pub level_config: bool,
+    /// Passes the set-bonus config, if the vault has one.
+    pub set_bonus: bool,
+    /// The user's `vesting_position_count`, when vesting is enabled.
+    pub vesting_position: Option<u64>,
+    pub partner_program: Option<Pubkey>,
+    /// The position's `reward_split` destinations, in order.
+    pub split_destinations: Vec<Pubkey>,
+}
+
+impl ClaimOptions {
+    pub fn for_position(vault: &VaultAccount, user_stake: &UserStakeAccount) -> Self {
+        Self {
+            destination: user_stake.payout_address,
+            reward_pool: vault.reward_mode == RewardMode::Pool,
+            fee_destination: vault.fee_destination,
+            insurance_pool: vault.fee_bps > 0 && vault.insurance_fee_bps > 0,
+            referrer: user_stake.referrer.filter(|_| vault.referral_bonus_bps > 0),
+            vesting_position: vault
+                .vesting
+                .is_enabled()
+                .then_some(user_stake.vesting_position_count),
+            split_destinations: user_stake
+                .reward_split
+                .iter()
+                .map(|share| share.destination)
+                .collect(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Claims base rewards. Secondary reward tokens go in as extra remaining
+/// accounts after the split destinations, four per reward mint.
+pub fn claim_rewards(
+    user: &Pubkey,
+    reward_token_mint: &Pubkey,
+    options: &ClaimOptions,
+) -> Instruction {
+    let vault = pda::vault();
+    let claimer = options.claimer.unwrap_or(*user);
+
+    let mut ix = build(
+        accounts::ClaimRewards {
+            vault,
+            vault_config: pda::vault_config(),
+            vault_shard: pda::vault_shard_for(user),
+            user_stake: pda::user_stake(user),
+            user: *user,
+            claimer,
+            session_authority: options.session.then(|| pda::session(user, &claimer)),
+            user_status: pda::user_status(user),
+            reward_token_mint: *reward_token_mint,
+            user_reward_token_account: get_associated_token_address(user, reward_token_mint),
+            destination: options.destination,
+            reward_pool: options
+                .reward_pool
+                .then(|| get_associated_token_address(&vault, reward_token_mint)),
+            fee_destination: options.fee_destination,
+            insurance_pool: options.insurance_pool.then(pda::insurance_pool),
+            referral: options.referrer.as_ref().map(pda::referral),
+            referrer_reward_token_account: options
+                .referrer
+                .map(|referrer| get_associated_token_address(&referrer, reward_token_mint)),
+            level_config: options.level_config.then(pda::level_config),
+            set_bonus: options.set_bonus.then(pda::set_bonus),
+            vesting_position: options
+                .vesting_position
+                .map(|id| pda::vesting_position(user, id)),
+            instructions: sysvar::instructions::ID,
+            partner: options.partner_program.as_ref().map(pda::partner),
+            token_program: Token::id(),
+            associated_token_program: AssociatedToken::id(),
+            system_program: System::id(),
+            event_authority: pda::event_authority(),
+            program: ID,
+        },
+        instruction::ClaimRewards,
+    );
+    ix.accounts.extend(
+        options
+            .split_destinations
+            .iter()
+            .map(|destination| AccountMeta::new(*destination, false)),
+    );
+    ix
+}
+
+/// Deposits `amount` of `reward_token_mint` from the funder's ATA into the
+/// vault's pool. Pass `secondary` for mints added with `add_reward_config`.
+pub fn fund_rewards(
+    funder: &Pubkey,
+    reward_token_mint: &Pubkey,
+    amount: u64,
+    secondary: bool,
+) -> Instruction {
+    let vault = pda::vault();
+
+    build(
+        accounts::FundRewards {
+            vault,
+            funder: *funder,
+            funder_role: pda::role(funder),
+            reward_token_mint: *reward_token_mint,
+            reward_config: secondary.then(|| pda::reward_config(reward_token_mint)),
+            funder_token_account: get_associated_token_address(funder, reward_token_mint),
+            reward_pool: get_associated_token_address(&vault, reward_token_mint),
+            token_program: Token::id(),
+            associated_token_program: AssociatedToken::id(),
+            system_program: System::id(),
+        },
+        instruction::FundRewards { amount },
+    )
+}
+
+pub fn initialize_insurance_pool(admin: &Pubkey, reward_token_mint: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeInsurancePool {
+            vault: pda::vault(),
+            admin: *admin,
+            admin_role: pda::role(admin),
+            reward_token_mint: *reward_token_mint,
+            insurance_pool: pda::insurance_pool(),
+            token_program: Token::id(),
+            system_program: System::id(),
+        },
+        instruction::InitializeInsurancePool,
+    )
+}
+
+/// Pays the pending insurance claim to `recipient`, the token account it
+/// was proposed for.
+pub fn pay_insurance_claim(admin: &Pubkey, recipient: &Pubkey) -> Instruction {
+    build(
+        accounts::PayInsuranceClaim {
+            vault: pda::vault(),
+            admin: *admin,
+            admin_role: pda::role(admin),
+            insurance_pool: pda::insurance_pool(),
+            recipient: *recipient,
+            token_program: Token::id(),
+        },
+        instruction::PayInsuranceClaim,
+    )
+}
+
+/// Moves the penalty share owed to the insurance pool into it, up to the
+/// allowance of shard `shard_index`. Pass `reward_pool` in
+/// `RewardMode::Pool`.
+pub fn fund_insurance_pool(
+    reward_token_mint: &Pubkey,
+    shard_index: u8,
+    reward_pool: bool,
+) -> Instruction {
+    let vault = pda::vault();
+
+    build(
+        accounts::FundInsurancePool {
+            vault,
+            vault_shard: pda::vault_shard(shard_index),
+            reward_token_mint: *reward_token_mint,
+            reward_pool: reward_pool
+                .then(|| get_associated_token_address(&vault, reward_token_mint)),
+            insurance_pool: pda::insurance_pool(),
+            token_program: Token::id(),
+        },
+        instruction::FundInsurancePool,
+    )
+}
+
+pub fn update_config(updater: &Pubkey, args: impl ConfigUpdate) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            vault: pda::vault(),
+            updater: *updater,
+            updater_role: pda::role(updater),
+        },
+        args,
+    )
+}
+
+pub fn update_vault_config(updater: &Pubkey, args: impl VaultConfigUpdate) -> Instruction {
+    build(
+        accounts::UpdateVaultConfig {
+            vault: pda::vault(),
+            vault_config: pda::vault_config(),
+            updater: *updater,
+            updater_role: pda::role(updater),
+        },
+        args,
+    )
+}
+
+pub fn pause_action(authority: &Pubkey, args: impl PauseAction) -> Instruction {
+    build(
+        accounts::PauseVault {
+            vault: pda::vault(),
+            vault_config: pda::vault_config(),
+            authority: *authority,
+            user_role: pda::role(authority),
+        },
+        args,
+    )
+}
+
+pub fn pause_vault(authority: &Pubkey) -> Instruction {
+    pause_action(authority, instruction::PauseVault)
+}
+
+pub fn unpause_vault(authority: &Pubkey) -> Instruction {
+    pause_action(authority, instruction::UnpauseVault)
+}
+
+/// Grants, renews or revokes `user`'s role.
+pub fn manage_role(granter: &Pubkey, user: &Pubkey, args: impl RoleAction) -> Instruction {
+    build(
+        accounts::ManageRole {
+            vault: pda::vault(),
+            granter: *granter,
+            granter_role: pda::role(granter),
+            user_role: pda::role(user),
+            role_registry: pda::role_registry(),
+            system_program: System::id(),
+        },
+        args,
+    )
+}
+
+pub fn grant_role(
+    granter: &Pubkey,
+    user: &Pubkey,
+    role: Role,
+    expires_at: Option<i64>,
+) -> Instruction {
+    manage_role(
+        granter,
+        user,
+        instruction::GrantRole {
+            user: *user,
+            role,
+            expires_at,
+        },
+    )
+}
+
+/// Pushes back the dead-man switch; needs a role manager's key.
+pub fn admin_heartbeat(admin: &Pubkey) -> Instruction {
+    build(
+        accounts::AdminHeartbeat {
+            vault: pda::vault(),
+            admin: *admin,
+            admin_role: pda::role(admin),
+        },
+        instruction::AdminHeartbeat,
+    )
+}
+
+pub fn claim_super_admin(recovery_key: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimSuperAdmin {
+            vault: pda::vault(),
+            recovery_key: *recovery_key,
+            recovery_role: pda::role(recovery_key),
+            role_registry: pda::role_registry(),
+            system_program: System::id(),
+        },
+        instruction::ClaimSuperAdmin,
+    )
+}
+
+/// Creates the role registry. Pass the role accounts of wallets granted
+/// before it existed as `existing_holders` so they are listed too.
+pub fn initialize_role_registry(granter: &Pubkey, existing_holders: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::InitializeRoleRegistry {
+            granter: *granter,
+            granter_role: pda::role(granter),
+            role_registry: pda::role_registry(),
+            system_program: System::id(),
+        },
+        instruction::InitializeRoleRegistry,
+    );
+    ix.accounts.extend(
+        existing_holders
+            .iter()
+            .map(|holder| AccountMeta::new_readonly(pda::role(holder), false)),
+    );
+    ix
+}
+
+/// `buffer_hash` is [`upgrade_buffer_hash`] of the buffer account's data.
+pub fn propose_upgrade(
+    proposer: &Pubkey,
+    new_version: u32,
+    buffer: &Pubkey,
+    buffer_hash: [u8; 32],
+    timelock_seconds: i64,
+) -> Instruction {
+    build(
+        accounts::ProposeUpgrade {
+            vault: pda::vault(),
+            proposer: *proposer,
+            proposer_role: pda::role(proposer),
+        },
+        instruction::ProposeUpgrade {
+            new_version,
+            buffer: *buffer,
+            buffer_hash,
+            timelock_seconds,
+        },
+    )
+}
+
+/// Hash `propose_upgrade` expects for a loader buffer account's data.
+pub fn upgrade_buffer_hash(buffer_data: &[u8]) -> [u8; 32] {
+    let offset = UpgradeableLoaderState::size_of_buffer_metadata().min(buffer_data.len());
+    hash(&buffer_data[offset..]).to_bytes()
+}
+
+/// Upgrades the program from `buffer`; its rent goes to `spill`.
+pub fn execute_upgrade(executor: &Pubkey, buffer: &Pubkey, spill: &Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteUpgrade {
+            vault: pda::vault(),
+            executor: *executor,
+            executor_role: pda::role(executor),
+            program: ID,
+            program_data: pda::program_data(),
+            buffer: *buffer,
+            spill: *spill,
+            bpf_loader_upgradeable: bpf_loader_upgradeable::ID,
+            rent: sysvar::rent::ID,
+            clock: sysvar::clock::ID,
+        },
+        instruction::ExecuteUpgrade,
+    )
+}
+
+/// Clears the program's upgrade authority for good.
+pub fn lock_upgrades(locker: &Pubkey) -> Instruction {
+    build(
+        accounts::LockUpgrades {
+            vault: pda::vault(),
+            locker: *locker,
+            locker_role: pda::role(locker),
+            program_data: pda::program_data(),
+            bpf_loader_upgradeable: bpf_loader_upgradeable::ID,
+        },
+        instruction::LockUpgrades,
+    )
+}
+
+pub fn cancel_upgrade(canceller: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelUpgrade {
+            vault: pda::vault(),
+            canceller: *canceller,
+            canceller_role: pda::role(canceller),
+        },
+        instruction::CancelUpgrade,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signers(ix: &Instruction) -> Vec<Pubkey> {
+        ix.accounts
+            .iter()
+            .filter(|meta| meta.is_signer)
+            .map(|meta| meta.pubkey)
+            .collect()
+    }
+
+    #[test]
+    fn stake_signers_are_user_and_payer() {
+        let user = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+
+        let ix = stake_nft(&user, &nft_mint, StakeOptions::default());
+        assert_eq!(ix.program_id, ID);
+        assert_eq!(signers(&ix), vec![user, user]);
+
+        let sponsored = StakeOptions {
+            payer: Some(payer),
+            ..StakeOptions::default()
+        };
+        assert_eq!(signers(&stake_nft(&user, &nft_mint, sponsored)), vec![user, payer]);
+    }
+
+    #[test]
+    fn omitted_optional_accounts_become_program_id() {
+        let user = Pubkey::new_unique();
+        let ix = stake_nft(&user, &Pubkey::new_unique(), StakeOptions::default());
+        // whitelist_entry, trait_pass, referral and partner
+        let placeholders = ix.accounts.iter().filter(|meta| meta.pubkey == ID).count();
+        // plus the event CPI's `program`
+        assert_eq!(placeholders, 5);
+    }
+
+    #[test]
+    fn claim_appends_split_destinations() {
+        let user = Pubkey::new_unique();
+        let splits = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let options = ClaimOptions {
+            split_destinations: splits.clone(),
+            ..ClaimOptions::default()
+        };
+
+        let ix = claim_rewards(&user, &Pubkey::new_unique(), &options);
+        let tail: Vec<_> = ix.accounts[ix.accounts.len() - 2..].to_vec();
+        assert_eq!(tail.iter().map(|meta| meta.pubkey).collect::<Vec<_>>(), splits);
+        assert!(tail.iter().all(|meta| meta.is_writable && !meta.is_signer));
+    }
+}
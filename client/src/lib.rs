@@ -0,0 +1,37 @@
+//! Off-chain client for the NFT staking vault program.
+//!
+//! - [`pda`] derives every program address from its seeds.
+//! - [`instructions`] builds instructions with their account metas filled in.
+//! - [`accounts`] fetches and decodes program accounts over RPC.
+//! - [`events`] decodes the program's self-CPI events.
+//!
+//! The program crate is re-exported for its account, argument and event types.
+
+pub mod accounts;
+pub mod events;
+pub mod instructions;
+pub mod pda;
+
+pub use solana_nft_staking_vault as program;
+pub use solana_nft_staking_vault::ID as PROGRAM_ID;
+
+use anchor_client::solana_client::client_error::ClientError;
+use anchor_lang::prelude::Pubkey;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("account {0} does not exist")]
+    AccountNotFound(Pubkey),
+    #[error("account {0} is not a {1}")]
+    InvalidAccount(Pubkey, &'static str),
+    #[error(transparent)]
+    Rpc(Box<ClientError>),
+}
+
+impl From<ClientError> for Error {
+    fn from(error: ClientError) -> Self {
+        Self::Rpc(Box::new(error))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,165 @@
+//! Program-derived addresses, mirroring the seeds in the program's account
+//! constraints.
+
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::Metadata;
+use solana_nft_staking_vault::{VaultShard, ID};
+use solana_sdk_ids::bpf_loader_upgradeable;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &ID).0
+}
+
+pub fn vault() -> Pubkey {
+    find(&[b"vault"])
+}
+
+pub fn vault_config() -> Pubkey {
+    find(&[b"vault_config"])
+}
+
+pub fn vault_stats() -> Pubkey {
+    find(&[b"vault_stats"])
+}
+
+pub fn vault_metrics() -> Pubkey {
+    find(&[b"vault_metrics"])
+}
+
+pub fn vault_shard(index: u8) -> Pubkey {
+    find(&[b"vault_shard", &index.to_le_bytes()])
+}
+
+/// The shard that `user`'s stakes, unstakes and claims write to.
+pub fn vault_shard_for(user: &Pubkey) -> Pubkey {
+    vault_shard(VaultShard::index_for(user))
+}
+
+pub fn reward_mint() -> Pubkey {
+    find(&[b"reward_mint"])
+}
+
+pub fn treasury() -> Pubkey {
+    find(&[b"treasury"])
+}
+
+pub fn insurance_pool() -> Pubkey {
+    find(&[b"insurance_pool"])
+}
+
+pub fn level_config() -> Pubkey {
+    find(&[b"level_config"])
+}
+
+pub fn set_bonus() -> Pubkey {
+    find(&[b"set_bonus"])
+}
+
+pub fn season(id: u32) -> Pubkey {
+    find(&[b"season", &id.to_le_bytes()])
+}
+
+pub fn role(user: &Pubkey) -> Pubkey {
+    find(&[b"role", user.as_ref()])
+}
+
+pub fn role_registry() -> Pubkey {
+    find(&[b"role_registry"])
+}
+
+pub fn user_stake(user: &Pubkey) -> Pubkey {
+    find(&[b"user_stake", user.as_ref()])
+}
+
+pub fn user_status(user: &Pubkey) -> Pubkey {
+    find(&[b"user_status", user.as_ref()])
+}
+
+pub fn whitelist_entry(user: &Pubkey) -> Pubkey {
+    find(&[b"whitelist", user.as_ref()])
+}
+
+pub fn voter_weight_record(user: &Pubkey) -> Pubkey {
+    find(&[b"voter_weight_record", user.as_ref()])
+}
+
+pub fn referral(referrer: &Pubkey) -> Pubkey {
+    find(&[b"referral", referrer.as_ref()])
+}
+
+pub fn session(user: &Pubkey, session_key: &Pubkey) -> Pubkey {
+    find(&[b"session", user.as_ref(), session_key.as_ref()])
+}
+
+/// `id` is the user's `vesting_position_count` when the position was created.
+pub fn vesting_position(user: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"vesting", user.as_ref(), &id.to_le_bytes()])
+}
+
+pub fn partner(program_id: &Pubkey) -> Pubkey {
+    find(&[b"partner", program_id.as_ref()])
+}
+
+pub fn reward_config(reward_mint: &Pubkey) -> Pubkey {
+    find(&[b"reward_config", reward_mint.as_ref()])
+}
+
+pub fn stake_record(nft_mint: &Pubkey) -> Pubkey {
+    find(&[b"stake_record", nft_mint.as_ref()])
+}
+
+pub fn receipt_mint(nft_mint: &Pubkey) -> Pubkey {
+    find(&[b"receipt_mint", nft_mint.as_ref()])
+}
+
+pub fn nft_group(nft_mint: &Pubkey) -> Pubkey {
+    find(&[b"nft_group", nft_mint.as_ref()])
+}
+
+pub fn nft_multiplier(nft_mint: &Pubkey) -> Pubkey {
+    find(&[b"nft_multiplier", nft_mint.as_ref()])
+}
+
+pub fn trait_pass(nft_mint: &Pubkey) -> Pubkey {
+    find(&[b"trait_pass", nft_mint.as_ref()])
+}
+
+/// Signer of the program's self-CPI event logs.
+pub fn event_authority() -> Pubkey {
+    find(&[b"__event_authority"])
+}
+
+/// The program's data account, owned by the upgradeable loader.
+pub fn program_data() -> Pubkey {
+    Pubkey::find_program_address(&[ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+pub fn metadata(mint: &Pubkey) -> Pubkey {
+    let program = Metadata::id();
+    Pubkey::find_program_address(&[b"metadata", program.as_ref(), mint.as_ref()], &program).0
+}
+
+pub fn master_edition(mint: &Pubkey) -> Pubkey {
+    let program = Metadata::id();
+    Pubkey::find_program_address(
+        &[b"metadata", program.as_ref(), mint.as_ref(), b"edition"],
+        &program,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn users_spread_over_shards() {
+        let user = Pubkey::new_unique();
+        let index = VaultShard::index_for(&user);
+        assert!(index < VaultShard::COUNT);
+        assert_eq!(vault_shard_for(&user), vault_shard(index));
+
+        let shards: std::collections::HashSet<_> = (0..VaultShard::COUNT).map(vault_shard).collect();
+        assert_eq!(shards.len(), VaultShard::COUNT as usize);
+    }
+}
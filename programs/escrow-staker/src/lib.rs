@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::Token};
+use solana_nft_staking_vault::{cpi::accounts::{StakeNft, UnstakeNft}, program::SolanaNftStakingVault};
+
+declare_id!("GtUcdmA7uuvQmwXjuuozrXAAYVGo29dBWNfLysMM1vxM");
+
+/// Example integration for program-owned stakers: NFTs deposited with this
+/// program sit in an escrow PDA, which stakes them in the vault by signing
+/// the CPI with its seeds. The escrow holds no data, so the depositor pays
+/// for the accounts the vault creates and gets the rent back on unstake.
+///
+/// The vault only accepts these CPIs once this program is registered with
+/// `configure_partner_program` (or added to its `cpi_caller_allowlist`).
+#[program]
+pub mod escrow_staker {
+    use super::*;
+
+    pub fn stake_escrowed(ctx: Context<StakeEscrowed>) -> Result<()> {
+        let depositor = ctx.accounts.depositor.key();
+        let seeds = &[b"escrow".as_ref(), depositor.as_ref(), &[ctx.bumps.escrow]];
+        let signer = &[&seeds[..]];
+
+        let accounts = &ctx.accounts.vault_accounts;
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.staking_program.to_account_info(),
+            StakeNft {
+                vault: accounts.vault.to_account_info(),
+                vault_config: accounts.vault_config.to_account_info(),
+                vault_shard: accounts.vault_shard.to_account_info(),
+                user_stake: accounts.user_stake.to_account_info(),
+                user: ctx.accounts.escrow.to_account_info(),
+                payer: ctx.accounts.depositor.to_account_info(),
+                user_status: accounts.user_status.to_account_info(),
+                whitelist_entry: None,
+                nft_mint: accounts.nft_mint.to_account_info(),
+                trait_pass: ctx.accounts.trait_pass.as_ref().map(|pass| pass.to_account_info()),
+                stake_record: accounts.stake_record.to_account_info(),
+                referral: None,
+                nft_group_tag: accounts.nft_group_tag.to_account_info(),
+                nft_multiplier: accounts.nft_multiplier.to_account_info(),
+                voter_weight_record: accounts.voter_weight_record.to_account_info(),
+                receipt_mint: accounts.receipt_mint.to_account_info(),
+                user_receipt_token_account: accounts.escrow_receipt_token_account.to_account_info(),
+                nft_metadata: ctx.accounts.nft_metadata.to_account_info(),
+                nft_master_edition: ctx.accounts.nft_master_edition.to_account_info(),
+                user_nft_token_account: accounts.escrow_nft_token_account.to_account_info(),
+                vault_nft_token_account: accounts.vault_nft_token_account.to_account_info(),
+                instructions: accounts.instructions.to_account_info(),
+                partner: Some(accounts.partner.to_account_info()),
+                metadata_program: ctx.accounts.metadata_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                event_authority: accounts.event_authority.to_account_info(),
+                program: ctx.accounts.staking_program.to_account_info(),
+            },
+            signer,
+        );
+        solana_nft_staking_vault::cpi::stake_nft(cpi_ctx)
+    }
+
+    /// Returns the NFT to the escrow; withdrawing it from there is left to
+    /// the integrating protocol.
+    pub fn unstake_escrowed(ctx: Context<UnstakeEscrowed>) -> Result<()> {
+        let depositor = ctx.accounts.depositor.key();
+        let seeds = &[b"escrow".as_ref(), depositor.as_ref(), &[ctx.bumps.escrow]];
+        let signer = &[&seeds[..]];
+
+        let accounts = &ctx.accounts.vault_accounts;
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.staking_program.to_account_info(),
+            UnstakeNft {
+                vault: accounts.vault.to_account_info(),
+                vault_config: accounts.vault_config.to_account_info(),
+                vault_shard: accounts.vault_shard.to_account_info(),
+                user_stake: accounts.user_stake.to_account_info(),
+                user: ctx.accounts.escrow.to_account_info(),
+                rent_receiver: ctx.accounts.depositor.to_account_info(),
+                nft_mint: accounts.nft_mint.to_account_info(),
+                stake_record: accounts.stake_record.to_account_info(),
+                receipt_mint: accounts.receipt_mint.to_account_info(),
+                user_receipt_token_account: Some(accounts.escrow_receipt_token_account.to_account_info()),
+                nft_group_tag: accounts.nft_group_tag.to_account_info(),
+                nft_multiplier: accounts.nft_multiplier.to_account_info(),
+                voter_weight_record: accounts.voter_weight_record.to_account_info(),
+                user_nft_token_account: accounts.escrow_nft_token_account.to_account_info(),
+                vault_nft_token_account: accounts.vault_nft_token_account.to_account_info(),
+                instructions: accounts.instructions.to_account_info(),
+                partner: Some(accounts.partner.to_account_info()),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                event_authority: accounts.event_authority.to_account_info(),
+                program: ctx.accounts.staking_program.to_account_info(),
+            },
+            signer,
+        );
+        solana_nft_staking_vault::cpi::unstake_nft(cpi_ctx)
+    }
+}
+
+/// Vault accounts shared by both instructions. The vault validates all of
+/// them, so they are passed through unchecked.
+#[derive(Accounts)]
+pub struct VaultAccounts<'info> {
+    /// CHECK: Validated by the vault.
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    pub vault_config: UncheckedAccount<'info>,
+    /// CHECK: The escrow's vault shard; validated by the vault.
+    #[account(mut)]
+    pub vault_shard: UncheckedAccount<'info>,
+    /// CHECK: The escrow's position; validated by the vault.
+    #[account(mut)]
+    pub user_stake: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    pub user_status: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    pub nft_mint: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    #[account(mut)]
+    pub stake_record: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    pub nft_group_tag: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    pub nft_multiplier: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    #[account(mut)]
+    pub voter_weight_record: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    #[account(mut)]
+    pub receipt_mint: UncheckedAccount<'info>,
+    /// CHECK: The escrow's receipt ATA; validated by the vault.
+    #[account(mut)]
+    pub escrow_receipt_token_account: UncheckedAccount<'info>,
+    /// CHECK: Token account holding the NFT, owned by the escrow.
+    #[account(mut)]
+    pub escrow_nft_token_account: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    #[account(mut)]
+    pub vault_nft_token_account: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar; validated by the vault.
+    pub instructions: UncheckedAccount<'info>,
+    /// CHECK: This program's `PartnerProgram` registration in the vault.
+    pub partner: UncheckedAccount<'info>,
+    /// CHECK: The vault's event authority for `emit_cpi!`.
+    pub event_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StakeEscrowed<'info> {
+    /// CHECK: Signing PDA that owns the escrowed NFT; holds no data.
+    #[account(seeds = [b"escrow", depositor.key().as_ref()], bump)]
+    pub escrow: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub vault_accounts: VaultAccounts<'info>,
+
+    /// CHECK: Validated by the vault.
+    pub nft_metadata: UncheckedAccount<'info>,
+    /// CHECK: Validated by the vault.
+    pub nft_master_edition: UncheckedAccount<'info>,
+    /// CHECK: The NFT's trait pass; validated by the vault. Required while
+    /// the vault has a trait gate.
+    pub trait_pass: Option<UncheckedAccount<'info>>,
+
+    pub staking_program: Program<'info, SolanaNftStakingVault>,
+    /// CHECK: Metaplex token metadata program; validated by the vault.
+    pub metadata_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeEscrowed<'info> {
+    /// CHECK: Signing PDA that owns the escrowed NFT; holds no data.
+    #[account(seeds = [b"escrow", depositor.key().as_ref()], bump)]
+    pub escrow: UncheckedAccount<'info>,
+
+    /// Paid for the position's accounts, so gets their rent back.
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub vault_accounts: VaultAccounts<'info>,
+
+    pub staking_program: Program<'info, SolanaNftStakingVault>,
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,469 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Math underflow")]
+    MathUnderflow,
+    #[msg("No NFTs staked")]
+    NoNftsStaked,
+    #[msg("No rewards to claim")]
+    NoRewardsToClaim,
+    #[msg("Invalid NFT - must have amount=1 and decimals=0")]
+    InvalidNft,
+    #[msg("NFT is not a genuine Master Edition")]
+    InvalidMasterEdition,
+    #[msg("Reward mint decimals out of range")]
+    InvalidRewardDecimals,
+    #[msg("Cooldown out of range")]
+    InvalidCooldown,
+    #[msg("Minimum stake slots out of range")]
+    InvalidMinStakeSlots,
+    #[msg("Staked too recently - wait for more slots")]
+    StakedTooRecently,
+    #[msg("Calling program is not on the CPI allowlist")]
+    CpiCallerNotAllowed,
+    #[msg("Too many CPI callers, or the vault program itself listed")]
+    InvalidCpiCallerAllowlist,
+    #[msg("The vault program cannot be registered as a partner")]
+    InvalidPartnerProgram,
+    #[msg("No collection found in NFT metadata")]
+    NoCollectionFound,
+    #[msg("Collection not verified")]
+    CollectionNotVerified,
+    #[msg("Wrong collection - NFT not from authorized collection")]
+    WrongCollection,
+    #[msg("Vault is paused")]
+    VaultPaused,
+    #[msg("Operation too frequent - rate limited")]
+    TooFrequent,
+    #[msg("Claim too frequent - the claim cooldown has not elapsed")]
+    TooFrequentClaim,
+    #[msg("Invalid time elapsed - accrual window ends before it starts")]
+    InvalidTimeElapsed,
+    #[msg("Excessive reward claim - exceeds maximum allowed")]
+    ExcessiveRewardClaim,
+    #[msg("Invalid reward rate - must be greater than 0 with a fraction below RATE_PRECISION")]
+    InvalidRewardRate,
+    #[msg("Already paused")]
+    AlreadyPaused,
+    #[msg("Not paused")]
+    NotPaused,
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Insufficient permissions for this action")]
+    InsufficientPermissions,
+    #[msg("Upgrades are permanently locked")]
+    UpgradesLocked,
+    #[msg("An upgrade is already pending")]
+    UpgradePending,
+    #[msg("No upgrade is currently pending")]
+    NoUpgradePending,
+    #[msg("Invalid version number")]
+    InvalidVersion,
+    #[msg("Invalid timelock duration")]
+    InvalidTimelock,
+    #[msg("Timelock period has not expired")]
+    TimelockNotExpired,
+    #[msg("Upgrades are already locked")]
+    UpgradesAlreadyLocked,
+    #[msg("Failed to transfer mint authority to vault")]
+    MintAuthorityTransferFailed,
+    #[msg("Invalid mint authority")]
+    InvalidMintAuthority,
+    #[msg("Circuit breaker is active - too many failures")]
+    CircuitBreakerActive,
+    #[msg("Daily stake limit reached")]
+    DailyStakeLimitExceeded,
+    #[msg("No compounded rewards to withdraw")]
+    NoCompoundedRewards,
+    #[msg("Compounded position is still locked")]
+    CompoundPositionLocked,
+    #[msg("Insufficient funds in the reward pool")]
+    InsufficientRewardFunds,
+    #[msg("Reward pool account is required in pool funding mode")]
+    RewardPoolRequired,
+    #[msg("Operation not supported in the vault's reward mode")]
+    InvalidRewardMode,
+    #[msg("Invalid amount - must be greater than 0")]
+    InvalidAmount,
+    #[msg("Invalid reward mint for this operation")]
+    InvalidRewardMint,
+    #[msg("Reward pool must be the vault-owned account for the reward mint")]
+    InvalidRewardPool,
+    #[msg("Reward position does not belong to this user or reward")]
+    InvalidRewardPosition,
+    #[msg("Reward destination must be the user's account for the reward mint")]
+    InvalidRewardDestination,
+    #[msg("Invalid remaining accounts layout")]
+    InvalidRemainingAccounts,
+    #[msg("Invalid emission schedule")]
+    InvalidEmissionSchedule,
+    #[msg("Invalid daily limits - must be greater than 0")]
+    InvalidLimits,
+    #[msg("Invalid circuit breaker configuration")]
+    InvalidCircuitBreakerConfig,
+    #[msg("Circuit breaker is already tripped")]
+    CircuitBreakerAlreadyTripped,
+    #[msg("This action requires an approved proposal")]
+    ApprovalRequired,
+    #[msg("Invalid approval threshold")]
+    InvalidApprovalThreshold,
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    #[msg("Approver has already approved this proposal")]
+    AlreadyApproved,
+    #[msg("Proposal has reached the maximum number of approvals")]
+    TooManyApprovals,
+    #[msg("Proposal does not have enough approvals")]
+    InsufficientApprovals,
+    #[msg("A config change is already pending")]
+    ConfigChangePending,
+    #[msg("No config change is currently pending")]
+    NoConfigChangePending,
+    #[msg("Config change must update at least one value")]
+    InvalidConfigChange,
+    #[msg("Role has expired")]
+    RoleExpired,
+    #[msg("Role expiry must be in the future")]
+    InvalidRoleExpiry,
+    #[msg("Cannot revoke your own role")]
+    CannotRevokeSelf,
+    #[msg("Role has already been revoked")]
+    RoleAlreadyRevoked,
+    #[msg("User is suspended")]
+    UserSuspended,
+    #[msg("User is already suspended")]
+    UserAlreadySuspended,
+    #[msg("User is not suspended")]
+    UserNotSuspended,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("Emergency mode is already in the requested state")]
+    EmergencyModeUnchanged,
+    #[msg("NFTs are still staked")]
+    NftsStillStaked,
+    #[msg("Rewards must be claimed first")]
+    UnclaimedRewards,
+    #[msg("Vault has been decommissioned")]
+    VaultDecommissioned,
+    #[msg("Vault has not been decommissioned")]
+    VaultNotDecommissioned,
+    #[msg("A decommission is already pending")]
+    DecommissionPending,
+    #[msg("No decommission is currently pending")]
+    NoDecommissionPending,
+    #[msg("A mint authority reclaim is already pending")]
+    MintAuthorityReclaimPending,
+    #[msg("No mint authority reclaim pending")]
+    NoMintAuthorityReclaimPending,
+    #[msg("Migration program is not configured for this vault")]
+    InvalidMigrationProgram,
+    #[msg("Account must be migrated with migrate_account first")]
+    AccountNotMigrated,
+    #[msg("Account is already on the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Account does not have a recognized layout")]
+    InvalidAccountLayout,
+    #[msg("Stake record does not belong to this user")]
+    StakeRecordMismatch,
+    #[msg("Invalid loyalty bonus configuration")]
+    InvalidLoyaltyConfig,
+    #[msg("Invalid streak bonus configuration")]
+    InvalidStreakConfig,
+    #[msg("Invalid oracle configuration")]
+    InvalidOracleConfig,
+    #[msg("Reward supply cap is below rewards already minted")]
+    InvalidRewardSupplyCap,
+    #[msg("Reward supply cap has been reached")]
+    RewardSupplyExhausted,
+    #[msg("Invalid vesting configuration")]
+    InvalidVestingConfig,
+    #[msg("Vesting position account is required while vesting is enabled")]
+    VestingPositionRequired,
+    #[msg("Nothing has vested yet")]
+    NothingVested,
+    #[msg("Oracle-driven rate is not configured")]
+    OracleNotConfigured,
+    #[msg("Oracle price is stale or for the wrong feed")]
+    StaleOraclePrice,
+    #[msg("Oracle price must be positive")]
+    InvalidOraclePrice,
+    #[msg("Oracle confidence interval too wide")]
+    OracleConfidenceTooWide,
+    #[msg("Invalid level configuration")]
+    InvalidLevelConfig,
+    #[msg("Invalid set bonus configuration")]
+    InvalidSetBonusConfig,
+    #[msg("Invalid NFT group")]
+    InvalidNftGroup,
+    #[msg("NFT is currently staked")]
+    NftCurrentlyStaked,
+    #[msg("Governance realm and governing token mint must be set together")]
+    InvalidGovernanceConfig,
+    #[msg("Invalid rate voting configuration")]
+    InvalidRateVoteConfig,
+    #[msg("Staker rate voting is disabled")]
+    RateVotingDisabled,
+    #[msg("Proposed rate is outside the allowed bounds")]
+    RateOutOfBounds,
+    #[msg("Voting on this proposal has closed")]
+    VotingClosed,
+    #[msg("Voting on this proposal has not ended")]
+    VotingNotEnded,
+    #[msg("Voter must have been staking since before the proposal opened")]
+    NotEligibleToVote,
+    #[msg("Proposal did not pass")]
+    ProposalRejected,
+    #[msg("Proposal execution window has passed")]
+    ProposalExpired,
+    #[msg("Invalid distribution parameters")]
+    InvalidDistribution,
+    #[msg("Merkle proof does not match the distribution root")]
+    InvalidMerkleProof,
+    #[msg("Distribution already claimed for this index")]
+    DistributionAlreadyClaimed,
+    #[msg("Invalid raffle parameters")]
+    InvalidRaffle,
+    #[msg("Raffle entries have closed")]
+    RaffleClosed,
+    #[msg("Raffle entries are still open")]
+    RaffleStillOpen,
+    #[msg("No raffle tickets available")]
+    NoRaffleTickets,
+    #[msg("Raffle has already been drawn")]
+    RaffleAlreadyDrawn,
+    #[msg("Raffle has not been drawn")]
+    RaffleNotDrawn,
+    #[msg("Raffle has entries and cannot be cancelled")]
+    RaffleHasEntries,
+    #[msg("Entry does not hold the winning ticket")]
+    NotRaffleWinner,
+    #[msg("Raffle prize already settled")]
+    PrizeAlreadyClaimed,
+    #[msg("Randomness has not been requested")]
+    RandomnessNotRequested,
+    #[msg("Randomness has not been fulfilled yet")]
+    RandomnessNotFulfilled,
+    #[msg("Invalid mission parameters")]
+    InvalidMission,
+    #[msg("Mission is inactive or has expired")]
+    MissionInactive,
+    #[msg("Mission requirement not met")]
+    MissionRequirementNotMet,
+    #[msg("Coupons are not enabled")]
+    CouponsDisabled,
+    #[msg("Coupon has expired")]
+    CouponExpired,
+    #[msg("Missing or invalid Ed25519 coupon signature instruction")]
+    InvalidCouponSignature,
+    #[msg("Warm-up period is out of range")]
+    InvalidWarmup,
+    #[msg("Unstake cooldown is out of range")]
+    InvalidUnstakeCooldown,
+    #[msg("Exit queue is not enabled for this vault")]
+    UnstakeQueueDisabled,
+    #[msg("Use request_unstake while the exit queue is enabled")]
+    UnstakeRequestRequired,
+    #[msg("Unstake has already been requested for this NFT")]
+    UnstakeAlreadyRequested,
+    #[msg("No unstake request for this NFT")]
+    NoUnstakeRequest,
+    #[msg("Unstake cooldown has not elapsed yet")]
+    UnstakeCooldownActive,
+    #[msg("Position is locked; use emergency_unstake to exit early")]
+    StakeLocked,
+    #[msg("Position is not locked")]
+    StakeNotLocked,
+    #[msg("Position is already locked")]
+    LockAlreadyActive,
+    #[msg("Position has no active lock")]
+    LockNotActive,
+    #[msg("Lock extension must end later at an equal or higher boost")]
+    InvalidLockExtension,
+    #[msg("Token boost is not enabled")]
+    TokenBoostDisabled,
+    #[msg("Invalid token boost configuration")]
+    InvalidTokenBoostConfig,
+    #[msg("Boost mint cannot change while boost tokens are staked")]
+    BoostTokensStaked,
+    #[msg("Invalid booster collection configuration")]
+    InvalidBoosterConfig,
+    #[msg("Booster collection is not active")]
+    BoosterCollectionInactive,
+    #[msg("Invalid lock tier")]
+    InvalidLockTier,
+    #[msg("Invalid early-unstake penalty configuration")]
+    InvalidPenaltyConfig,
+    #[msg("Treasury account does not match")]
+    InvalidTreasuryAccount,
+    #[msg("Insufficient treasury funds")]
+    InsufficientTreasuryFunds,
+    #[msg("A treasury withdrawal is already pending")]
+    TreasuryWithdrawalPending,
+    #[msg("No treasury withdrawal is currently pending")]
+    NoTreasuryWithdrawalPending,
+    #[msg("Referral bonus exceeds the maximum")]
+    InvalidReferralConfig,
+    #[msg("Users cannot refer themselves")]
+    SelfReferral,
+    #[msg("Referral account or referrer token account is missing or invalid")]
+    ReferralAccountRequired,
+    #[msg("Signer is neither the staker, their claim delegate nor a session key")]
+    UnauthorizedClaimer,
+    #[msg("Unsupported session scope")]
+    InvalidSessionScope,
+    #[msg("Session expiry must be in the future and within the maximum duration")]
+    InvalidSessionExpiry,
+    #[msg("Session has expired or does not cover this action")]
+    SessionNotValid,
+    #[msg("Rent must be refunded to the account that paid it")]
+    InvalidRentReceiver,
+    #[msg("Payout destination is not allowed for this claim")]
+    InvalidPayoutDestination,
+    #[msg("The user pinned a payout address; pass it as the destination")]
+    PayoutDestinationRequired,
+    #[msg("Invalid reward split: up to 4 non-zero shares totalling at most 100%")]
+    InvalidRewardSplit,
+    #[msg("Squad name must be 1-32 bytes")]
+    InvalidSquadName,
+    #[msg("Squad is full")]
+    SquadFull,
+    #[msg("Already a member of a squad")]
+    AlreadyInSquad,
+    #[msg("Not a member of this squad")]
+    NotInSquad,
+    #[msg("Rewards go to the squad pot while in a squad")]
+    RewardsGoToSquad,
+    #[msg("Signer is neither the position owner nor its recovery delegate")]
+    UnauthorizedRecovery,
+    #[msg("Position must move to a different wallet")]
+    InvalidStakeTransfer,
+    #[msg("Detach the booster and leave any squad before transferring")]
+    PositionNotTransferable,
+    #[msg("Every recorded NFT must be passed; unstake editions first")]
+    IncompleteStakeTransfer,
+    #[msg("Reward adjustment is zero or above the configured maximum")]
+    RewardAdjustmentTooLarge,
+    #[msg("Snapshots can only be taken for the current day")]
+    InvalidSnapshotDay,
+    #[msg("Positions can no longer be recorded into this snapshot")]
+    SnapshotWindowClosed,
+    #[msg("Snapshot entry address does not match the position")]
+    InvalidSnapshotEntry,
+    #[msg("Only whitelisted wallets can stake before the public start")]
+    NotWhitelisted,
+    #[msg("Whitelist entry address does not match the user")]
+    InvalidWhitelistEntry,
+    #[msg("User has reached the per-user stake cap")]
+    UserStakeCapReached,
+    #[msg("Vault has reached its total stake cap")]
+    VaultStakeCapReached,
+    #[msg("Invalid season")]
+    InvalidSeason,
+    #[msg("A season is active")]
+    SeasonStillActive,
+    #[msg("No season is active")]
+    NoActiveSeason,
+    #[msg("Position must roll over its previous season first")]
+    SeasonRolloverRequired,
+    #[msg("Vault shard does not match the user")]
+    WrongVaultShard,
+    #[msg("Shard's share of the reward supply is used up until it is folded")]
+    ShardAllowanceExhausted,
+    #[msg("The position's receipt token account is missing or invalid")]
+    ReceiptAccountRequired,
+    #[msg("Invalid protocol fee configuration")]
+    InvalidFeeConfig,
+    #[msg("Fee destination does not match the vault configuration")]
+    InvalidFeeDestination,
+    #[msg("Token has a stake record and can only leave through unstaking")]
+    TokenStillStaked,
+    #[msg("The reward mint, secondary reward mints and staked mints cannot be swept")]
+    MintNotSweepable,
+    #[msg("A sweep is already pending")]
+    SweepPending,
+    #[msg("No sweep is pending")]
+    NoSweepPending,
+    #[msg("Sweep accounts do not match the pending sweep")]
+    InvalidSweepAccount,
+    #[msg("The vault holds less than the sweep amount")]
+    InsufficientSweepFunds,
+    #[msg("Daily claim limit reached")]
+    DailyClaimLimitExceeded,
+    #[msg("Claim exceeds the rewards left under today's limit")]
+    DailyRewardLimitExceeded,
+    #[msg("Anomaly claim share cannot exceed 100%")]
+    InvalidAnomalyRules,
+    #[msg("Wallet rate limit reached; wait for the bucket to refill")]
+    RateLimited,
+    #[msg("Rate limit refill interval must be positive")]
+    InvalidRateLimit,
+    #[msg("Role registry is full; revoke a role first")]
+    RoleRegistryFull,
+    #[msg("Account is not a role account of this program")]
+    InvalidRoleAccount,
+    #[msg("Custom roles must grant at least one known permission")]
+    InvalidPermissions,
+    #[msg("Upgrade buffer does not match the proposal")]
+    UpgradeBufferMismatch,
+    #[msg("Stakers cannot veto this upgrade")]
+    UpgradeVetoDisabled,
+    #[msg("Upgrade veto must be on and at most 50% of staked NFTs")]
+    InvalidUpgradeVeto,
+    #[msg("Heartbeat timeout must be at least 7 days")]
+    InvalidHeartbeatTimeout,
+    #[msg("Signer is not the vault's recovery key")]
+    NotRecoveryKey,
+    #[msg("Admins sent a heartbeat within the timeout")]
+    AdminsStillActive,
+    #[msg("Insurance pool account is required when an insurance fee is set")]
+    InsurancePoolRequired,
+    #[msg("Insurance fee share cannot exceed 100%")]
+    InvalidInsuranceFee,
+    #[msg("An insurance claim is already pending")]
+    InsuranceClaimPending,
+    #[msg("No insurance claim is pending")]
+    NoInsuranceClaimPending,
+    #[msg("Insurance claim recipient does not match the proposal")]
+    InvalidInsuranceRecipient,
+    #[msg("Insurance pool holds less than the claim")]
+    InsufficientInsuranceFunds,
+    #[msg("NFT multiplier must be between 1x and 5x")]
+    InvalidNftMultiplier,
+    #[msg("NFT has not been verified against the vault's trait gate")]
+    TraitNotVerified,
+    #[msg("Vault has no trait gate")]
+    TraitGateDisabled,
+    #[msg("Missing or invalid trait attestation")]
+    InvalidTraitAttestation,
+    #[msg("Burn boost needs a collection and a per-burn boost within a cap of at most 100%")]
+    InvalidBurnBoostConfig,
+    #[msg("Burning for a boost is disabled")]
+    BurnBoostDisabled,
+    #[msg("Position already has the maximum burn boost")]
+    BurnBoostCapReached,
+    #[msg("A pool withdrawal is already pending")]
+    PoolWithdrawalPending,
+    #[msg("No pool withdrawal is pending")]
+    NoPoolWithdrawalPending,
+    #[msg("Accounts do not match the pending pool withdrawal")]
+    InvalidPoolWithdrawalAccount,
+    #[msg("An emission schedule change is already pending")]
+    EmissionSchedulePending,
+    #[msg("No emission schedule change is pending")]
+    NoEmissionSchedulePending,
+    #[msg("NFT has no stake record; legacy NFTs must be backfilled first")]
+    StakeRecordRequired,
+    #[msg("Legacy stake root is already set")]
+    LegacyStakeRootAlreadySet,
+    #[msg("Position has no legacy NFTs without a stake record")]
+    NoUnrecordedNfts,
+    #[msg("Legacy NFTs without stake records remain in custody")]
+    LegacyStakesUnrecorded,
+    #[msg("Token is an attached booster and can only leave through detach_booster")]
+    TokenIsAttachedBooster,
+    #[msg("Vesting position passed while vesting is disabled")]
+    VestingDisabled,
+}
@@ -0,0 +1,1473 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+// Events
+#[event]
+pub struct NftStaked {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    /// User's NFT count after this event
+    pub staked_nfts: u32,
+    pub pending_rewards: u64,
+    /// Vault's NFT count after this event
+    pub total_staked: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftUnstaked {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    /// User's NFT count after this event
+    pub staked_nfts: u32,
+    pub pending_rewards: u64,
+    /// Vault's NFT count after this event
+    pub total_staked: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingConfigUpdated {
+    pub duration_seconds: i64,
+    pub cliff_seconds: i64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingPositionCreated {
+    pub user: Pubkey,
+    pub id: u64,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub cliff_at: i64,
+    pub end_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestedRewardsClaimed {
+    pub user: Pubkey,
+    pub id: u64,
+    pub amount: u64,
+    pub released_amount: u64,
+    pub total_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardMintCreated {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardMetadataCreated {
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub created_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardCapReached {
+    pub max_reward_supply: u64,
+    pub total_rewards_minted: u64,
+    /// Part of the folded claims that did not fit under the cap
+    pub forfeited: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultShardFolded {
+    pub index: u8,
+    pub staked_delta: i64,
+    pub rewards_minted: u64,
+    pub total_staked: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardSupplyCapUpdated {
+    pub max_reward_supply: u64,
+    pub total_rewards_minted: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub user: Pubkey,
+    pub claimed_by: Pubkey,
+    /// Token account paid, or the vesting position when vesting is on
+    pub destination: Pubkey,
+    /// Gross amount claimed, including `fee`
+    pub amount: u64,
+    pub fee: u64,
+    pub staked_nfts: u32,
+    pub pending_rewards: u64,
+    pub total_staked: u32,
+    /// Per-NFT rate including the compound boost
+    pub reward_rate_per_second: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserStakeClosed {
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsCompounded {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub total_compounded: u64,
+    pub boost_bps: u64,
+    pub locked_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CompoundedWithdrawn {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardConfigAdded {
+    pub reward_mint: Pubkey,
+    pub reward_rate_per_second: u64,
+    pub added_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardConfigUpdated {
+    pub reward_mint: Pubkey,
+    pub reward_rate_per_second: u64,
+    pub active: bool,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SecondaryRewardsClaimed {
+    pub user: Pubkey,
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SeasonStarted {
+    pub id: u32,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub reward_rate_per_second: u64,
+    pub boost_bps: u16,
+    pub started_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SeasonEnded {
+    pub id: u32,
+    pub end_timestamp: i64,
+    pub ended_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SeasonRolledOver {
+    pub user: Pubkey,
+    pub from_season: u32,
+    pub to_season: u32,
+    pub amount: u64,
+    pub pending_rewards: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeCapsUpdated {
+    pub max_nfts_per_user: u32,
+    pub max_total_staked: u32,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LimitsUpdated {
+    pub max_stakes_per_day: u32,
+    pub max_claims_per_day: u32,
+    pub max_total_rewards_per_day: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CircuitBreakerConfigUpdated {
+    pub failure_threshold: u32,
+    pub reset_timeout: i64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveConfigUpdated {
+    pub total_emission_per_second: u64,
+    pub reward_index: u128,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmissionScheduleProposed {
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub halving_interval: i64,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmissionScheduleCancelled {
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmissionScheduleUpdated {
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub halving_interval: i64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsFunded {
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub pool_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolWithdrawalProposed {
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolWithdrawalExecuted {
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub pool_balance: u64,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolWithdrawalCancelled {
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnstakedTokenRecovered {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub recovered_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SweepProposed {
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SweepExecuted {
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SweepCancelled {
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsurancePoolInitialized {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub initialized_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceFeeUpdated {
+    pub insurance_fee_bps: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceClaimProposed {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub reason: AdjustmentReason,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceClaimPaid {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub reason: AdjustmentReason,
+    pub pool_balance: u64,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsurancePoolFunded {
+    pub amount: u64,
+    pub still_owed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceClaimCancelled {
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AutoPauseTriggered {
+    pub kind: AnomalyKind,
+    pub observed: u64,
+    pub threshold: u64,
+    pub shard_index: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AnomalyRulesUpdated {
+    pub max_claim_bps: u64,
+    pub max_daily_staked_drop: u32,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserRateLimitUpdated {
+    pub capacity: u32,
+    pub refill_interval_seconds: i64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultPaused {
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultUnpaused {
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmergencyModeUpdated {
+    pub enabled: bool,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseFlagsUpdated {
+    pub pause_flags: u8,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApprovalThresholdUpdated {
+    pub threshold: u8,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionProposed {
+    pub proposal_id: u64,
+    pub action: GovernanceAction,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionApproved {
+    pub proposal_id: u64,
+    pub approver: Pubkey,
+    pub approvals: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionExecuted {
+    pub proposal_id: u64,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub previous_len: u32,
+    pub version: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryInitialized {
+    pub treasury: Pubkey,
+    pub initialized_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryDeposited {
+    pub depositor: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryWithdrawalProposed {
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryWithdrawalCancelled {
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecoveryDelegateUpdated {
+    pub user: Pubkey,
+    pub delegate: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeOwnershipTransferred {
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+    /// The old wallet or its recovery delegate
+    pub authority: Pubkey,
+    pub staked_nfts: u32,
+    pub pending_rewards: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WhitelistPhaseUpdated {
+    pub public_start_timestamp: i64,
+    pub merkle_root: Option<[u8; 32]>,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WhitelistEntriesAdded {
+    pub count: u32,
+    pub added_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WhitelistJoined {
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LegacyStakeRootSet {
+    pub merkle_root: [u8; 32],
+    pub nft_count: u32,
+    pub set_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeRecordBackfilled {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub staked_at: i64,
+    pub unrecorded_nfts: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TraitGateUpdated {
+    pub trait_hash: [u8; 32],
+    pub merkle_root: Option<[u8; 32]>,
+    pub attestor: Option<Pubkey>,
+    pub version: u32,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftTraitVerified {
+    pub nft_mint: Pubkey,
+    pub gate_version: u32,
+    /// Verified by attestation rather than merkle proof
+    pub attested: bool,
+    pub verified_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SnapshotTaken {
+    pub snapshot: Pubkey,
+    pub day: u32,
+    pub total_staked: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SnapshotPositionsRecorded {
+    pub snapshot: Pubkey,
+    /// Recorded by this call
+    pub positions: u32,
+    pub positions_recorded: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SquadCreated {
+    pub squad: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SquadJoined {
+    pub squad: Pubkey,
+    pub user: Pubkey,
+    pub contribution: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SquadLeft {
+    pub squad: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SquadRewardsContributed {
+    pub squad: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub contribution: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SquadRewardsClaimed {
+    pub squad: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardSplitUpdated {
+    pub user: Pubkey,
+    pub shares: Vec<RewardShare>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PayoutAddressUpdated {
+    pub user: Pubkey,
+    pub payout_address: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SessionCreated {
+    pub user: Pubkey,
+    pub session_key: Pubkey,
+    pub scope: u8,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SessionRevoked {
+    pub user: Pubkey,
+    pub session_key: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClaimDelegateUpdated {
+    pub user: Pubkey,
+    pub delegate: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EditionStaked {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub edition_amount: u64,
+    pub staked_nfts: u32,
+    pub total_staked: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EditionUnstaked {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub edition_amount: u64,
+    pub staked_nfts: u32,
+    pub total_staked: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensStaked {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub staked_tokens: u64,
+    pub boost_active: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensUnstaked {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub staked_tokens: u64,
+    pub boost_active: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenBoostConfigUpdated {
+    pub mint: Pubkey,
+    pub required_amount: u64,
+    pub boost_bps: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeLockExtended {
+    pub user: Pubkey,
+    pub tier_index: u8,
+    pub previous_locked_until: i64,
+    pub previous_boost_bps: u64,
+    pub locked_until: i64,
+    pub boost_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeLocked {
+    pub user: Pubkey,
+    pub tier_index: u8,
+    pub locked_until: i64,
+    pub boost_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EarlyUnstakePenalty {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub penalty: u64,
+    /// False when the penalty was burned
+    pub redistributed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralRegistered {
+    pub referrer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralRecorded {
+    pub referrer: Pubkey,
+    pub user: Pubkey,
+    pub referred_users: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralBonusPaid {
+    pub referrer: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub total_bonus_paid: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralConfigUpdated {
+    pub referral_bonus_bps: u64,
+    pub referral_bonus_cap: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsAccrued {
+    pub user: Pubkey,
+    pub cranker: Pubkey,
+    pub amount: u64,
+    pub pending_rewards: u64,
+    pub bounty: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleConfigUpdated {
+    pub oracle: Option<OracleConfig>,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleRateRefreshed {
+    pub price: i64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub old_reward_rate: u64,
+    pub new_reward_rate: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardRateFractionUpdated {
+    pub reward_rate_per_second: u64,
+    pub reward_rate_fraction: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PartnerProgramConfigured {
+    pub program_id: Pubkey,
+    pub can_stake: bool,
+    pub can_claim: bool,
+    pub active: bool,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CpiCallerAllowlistUpdated {
+    pub programs: Vec<Pubkey>,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MinStakeSlotsUpdated {
+    pub min_stake_slots: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CooldownsUpdated {
+    pub action_cooldown_seconds: i64,
+    pub claim_cooldown_seconds: i64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CrankBountyUpdated {
+    pub crank_bounty: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeConfigUpdated {
+    pub fee_bps: u64,
+    pub fee_destination: Option<Pubkey>,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LockTiersUpdated {
+    pub tier_count: u8,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EarlyUnstakePenaltyUpdated {
+    pub penalty_bps: u64,
+    pub penalty_mode: PenaltyMode,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnstakeRequested {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub unlocks_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnstakeFinalized {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnstakeCooldownUpdated {
+    pub unstake_cooldown_seconds: i64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WarmupUpdated {
+    pub warmup_seconds: i64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RaffleCreated {
+    pub raffle_id: u64,
+    pub prize_mint: Pubkey,
+    pub prize_amount: u64,
+    pub entries_close_at: i64,
+    pub created_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RaffleEntered {
+    pub raffle_id: u64,
+    pub user: Pubkey,
+    pub first_ticket: u64,
+    pub tickets: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RandomnessRequested {
+    pub raffle_id: u64,
+    pub randomness: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RaffleDrawn {
+    pub raffle_id: u64,
+    pub winning_ticket: u64,
+    pub total_tickets: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RafflePrizeClaimed {
+    pub raffle_id: u64,
+    pub winner: Pubkey,
+    pub prize_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RaffleCancelled {
+    pub raffle_id: u64,
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MissionCreated {
+    pub mission_id: u64,
+    pub requirement: MissionRequirement,
+    pub reward: u64,
+    pub expires_at: Option<i64>,
+    pub created_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MissionStatusUpdated {
+    pub mission_id: u64,
+    pub active: bool,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MissionCompleted {
+    pub mission_id: u64,
+    pub user: Pubkey,
+    pub reward: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouponSignerUpdated {
+    pub coupon_signer: Option<Pubkey>,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouponRedeemed {
+    pub user: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DistributionCreated {
+    pub distribution_id: u64,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub num_recipients: u32,
+    pub created_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DistributionClaimed {
+    pub distribution_id: u64,
+    pub claimant: Pubkey,
+    pub index: u32,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RateVoteBoundsUpdated {
+    pub min_rate: u64,
+    pub max_rate: u64,
+    pub quorum: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RateProposalCreated {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub new_rate: u64,
+    pub voting_ends_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RateVoteCast {
+    pub proposal_id: u64,
+    pub voter: Pubkey,
+    pub approve: bool,
+    pub weight: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RateProposalExecuted {
+    pub proposal_id: u64,
+    pub old_reward_rate: u64,
+    pub new_reward_rate: u64,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoterWeightConfigured {
+    pub governance_realm: Option<Pubkey>,
+    pub governing_token_mint: Option<Pubkey>,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoterWeightUpdated {
+    pub user: Pubkey,
+    pub voter_weight: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BoosterCollectionConfigured {
+    pub collection_mint: Pubkey,
+    pub boost_bps: u64,
+    pub active: bool,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BoosterAttached {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub collection_mint: Pubkey,
+    pub boost_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BurnBoostUpdated {
+    pub collection_mint: Pubkey,
+    pub boost_bps_per_burn: u64,
+    pub max_boost_bps: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftBurnedForBoost {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub collection_mint: Pubkey,
+    /// The position's burn boost after this burn
+    pub burn_boost_bps: u64,
+    pub burned_nfts: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BoosterDetached {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SetBonusConfigUpdated {
+    pub group_count: u8,
+    pub completion_bonus_bps: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftGroupTagged {
+    pub nft_mint: Pubkey,
+    pub group: u8,
+    pub tagged_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftMultiplierSet {
+    pub nft_mint: Pubkey,
+    pub multiplier_bps: u64,
+    pub set_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct XpUpdated {
+    pub user: Pubkey,
+    pub xp: u64,
+    pub level: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LevelConfigUpdated {
+    pub xp_per_nft_day: u64,
+    pub xp_per_claim: u64,
+    pub level_count: u8,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StreakUpdated {
+    pub user: Pubkey,
+    pub current_streak_days: u32,
+    pub streak_started_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StreakConfigUpdated {
+    pub bonus_bps_per_day: u64,
+    pub max_bonus_bps: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LoyaltyConfigUpdated {
+    pub bonus_bps_per_period: u64,
+    pub period_seconds: i64,
+    pub max_bonus_bps: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MigrationConfigured {
+    pub migration_target: Option<Pubkey>,
+    pub migration_source: Option<Pubkey>,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeMigrated {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub target_program: Pubkey,
+    pub pending_rewards: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakeMigratedIn {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub source_program: Pubkey,
+    pub pending_rewards: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MaxRewardAdjustmentUpdated {
+    pub max_reward_adjustment: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardAdjustmentProposed {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub reason: AdjustmentReason,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardAdjustmentCancelled {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PendingRewardsAdjusted {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub reason: AdjustmentReason,
+    pub proposer: Pubkey,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DecommissionProposed {
+    pub mint_authority_recipient: Option<Pubkey>,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DecommissionCancelled {
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintAuthorityReclaimProposed {
+    pub recipient: Pubkey,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintAuthorityReclaimCancelled {
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintAuthorityReclaimed {
+    pub recipient: Pubkey,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultDecommissioned {
+    pub mint_authority_recipient: Option<Pubkey>,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultClosed {
+    pub closed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Why `claim_rewards` paid nothing. Rejections still succeed, so the
+/// breaker failure they record is kept; this event is how callers tell
+/// them apart from a payout, which emits `RewardsClaimed`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ClaimRejection {
+    /// More than the position could have earned; see `claim_bound`
+    ExceedsBound,
+    /// Above `AnomalyRules::claim_ceiling`; stops the shard
+    AnomalyCeiling,
+}
+
+#[event]
+pub struct ClaimRejected {
+    pub user: Pubkey,
+    pub reason: ClaimRejection,
+    pub requested: u64,
+    pub allowed: u64,
+    pub failure_count: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserSuspended {
+    pub user: Pubkey,
+    pub suspended_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserUnsuspended {
+    pub user: Pubkey,
+    pub unsuspended_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub tripped_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CircuitBreakerReset {
+    pub reset_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub user: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+    pub expires_at: Option<i64>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleRenewed {
+    pub user: Pubkey,
+    pub expires_at: Option<i64>,
+    pub renewed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub user: Pubkey,
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminHeartbeatRecorded {
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DeadManSwitchUpdated {
+    pub recovery_key: Option<Pubkey>,
+    pub timeout_seconds: i64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DeadManSwitchTriggered {
+    pub recovery_key: Pubkey,
+    pub last_heartbeat: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleRegistryInitialized {
+    pub entries: u32,
+    pub initialized_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpgradeProposed {
+    pub new_version: u32,
+    pub buffer: Pubkey,
+    pub buffer_hash: [u8; 32],
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpgradeObjected {
+    pub upgrade_id: u64,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub objections: u64,
+    pub veto_threshold: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpgradeVetoed {
+    pub upgrade_id: u64,
+    pub new_version: u32,
+    pub objections: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpgradeVetoUpdated {
+    pub veto_bps: u64,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpgradeApproved {
+    pub new_version: u32,
+    pub approved_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpgradeExecuted {
+    pub new_version: u32,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpgradeCancelled {
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UpgradesLocked {
+    pub locked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeProposed {
+    pub old_reward_rate: u64,
+    pub new_reward_rate: Option<u64>,
+    pub old_collection_mint: Pubkey,
+    pub new_collection_mint: Option<Pubkey>,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub old_reward_rate: u64,
+    pub new_reward_rate: u64,
+    pub old_collection_mint: Pubkey,
+    pub new_collection_mint: Pubkey,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub new_reward_rate: Option<u64>,
+    pub new_collection_mint: Option<Pubkey>,
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
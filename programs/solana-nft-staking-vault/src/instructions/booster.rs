@@ -0,0 +1,552 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{self, Mint, Token, TokenAccount, Transfer, CloseAccount},
+    metadata::{Metadata, MetadataAccount},
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{accrue_user_rewards, BPS_DENOMINATOR};
+
+#[derive(Accounts)]
+pub struct ConfigureBoosterCollection<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// CHECK: Collection NFT mint; only used as a seed.
+    pub collection_mint: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = updater,
+        space = 8 + BoosterCollection::INIT_SPACE,
+        seeds = [b"booster_collection", collection_mint.key().as_ref()],
+        bump
+    )]
+    pub booster_collection: Account<'info, BoosterCollection>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigurePartnerProgram<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// CHECK: Partner program id; only used as a seed.
+    pub partner_program: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = updater,
+        space = 8 + PartnerProgram::INIT_SPACE,
+        seeds = [b"partner", partner_program.key().as_ref()],
+        bump
+    )]
+    pub partner: Account<'info, PartnerProgram>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttachBooster<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"booster_collection", booster_collection.collection_mint.as_ref()],
+        bump = booster_collection.bump
+    )]
+    pub booster_collection: Account<'info, BoosterCollection>,
+
+    /// `init` also rejects a second booster while one is attached.
+    #[account(
+        init,
+        payer = user,
+        space = 8 + BoosterRecord::INIT_SPACE,
+        seeds = [b"booster", user.key().as_ref()],
+        bump
+    )]
+    pub booster: Account<'info, BoosterRecord>,
+
+    pub booster_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            booster_mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub booster_metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        mut,
+        constraint = user_booster_token_account.mint == booster_mint.key(),
+        constraint = user_booster_token_account.owner == user.key()
+    )]
+    pub user_booster_token_account: Account<'info, TokenAccount>,
+
+    /// Keyed by the booster's mint rather than the vault's associated token
+    /// account, which `unstake_nft` and recovery pay staked NFTs out of.
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"booster_escrow", booster_mint.key().as_ref()],
+        bump,
+        token::mint = booster_mint,
+        token::authority = vault
+    )]
+    pub booster_escrow: Account<'info, TokenAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DetachBooster<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"booster", user.key().as_ref()],
+        bump = booster.bump,
+        has_one = user
+    )]
+    pub booster: Account<'info, BoosterRecord>,
+
+    #[account(address = booster.nft_mint)]
+    pub booster_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_booster_token_account.mint == booster_mint.key(),
+        constraint = user_booster_token_account.owner == user.key()
+    )]
+    pub user_booster_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"booster_escrow", booster_mint.key().as_ref()],
+        bump
+    )]
+    pub booster_escrow: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureSetBonus<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = updater,
+        space = 8 + SetBonusConfig::INIT_SPACE,
+        seeds = [b"set_bonus"],
+        bump
+    )]
+    pub set_bonus: Account<'info, SetBonusConfig>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TagNftGroup<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Must be empty, i.e. the NFT is not currently staked.
+    #[account(seeds = [b"stake_record", nft_mint.key().as_ref()], bump)]
+    pub stake_record: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = updater,
+        space = 8 + NftGroupTag::INIT_SPACE,
+        seeds = [b"nft_group", nft_mint.key().as_ref()],
+        bump
+    )]
+    pub nft_group_tag: Account<'info, NftGroupTag>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetNftMultiplier<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Must be empty, i.e. the NFT is not currently staked.
+    #[account(seeds = [b"stake_record", nft_mint.key().as_ref()], bump)]
+    pub stake_record: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = updater,
+        space = 8 + NftMultiplier::INIT_SPACE,
+        seeds = [b"nft_multiplier", nft_mint.key().as_ref()],
+        bump
+    )]
+    pub nft_multiplier: Account<'info, NftMultiplier>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn configure_set_bonus(
+    ctx: Context<ConfigureSetBonus>,
+    group_count: u8,
+    completion_bonus_bps: u64,
+) -> Result<()> {
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        group_count <= NftGroupTag::MAX_GROUPS
+            && completion_bonus_bps <= SetBonusConfig::MAX_BONUS_BPS,
+        ErrorCode::InvalidSetBonusConfig
+    );
+
+    let set_bonus = &mut ctx.accounts.set_bonus;
+    set_bonus.group_count = group_count;
+    set_bonus.completion_bonus_bps = completion_bonus_bps;
+    set_bonus.bump = ctx.bumps.set_bonus;
+
+    emit!(SetBonusConfigUpdated {
+        group_count,
+        completion_bonus_bps,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn configure_booster_collection(
+    ctx: Context<ConfigureBoosterCollection>,
+    boost_bps: u64,
+    active: bool,
+) -> Result<()> {
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        boost_bps > 0 && boost_bps <= BoosterCollection::MAX_BOOST_BPS,
+        ErrorCode::InvalidBoosterConfig
+    );
+    require!(
+        ctx.accounts.collection_mint.key() != ctx.accounts.vault.collection_mint,
+        ErrorCode::InvalidBoosterConfig
+    );
+
+    let booster_collection = &mut ctx.accounts.booster_collection;
+    booster_collection.collection_mint = ctx.accounts.collection_mint.key();
+    booster_collection.boost_bps = boost_bps;
+    booster_collection.active = active;
+    booster_collection.bump = ctx.bumps.booster_collection;
+
+    emit!(BoosterCollectionConfigured {
+        collection_mint: booster_collection.collection_mint,
+        boost_bps,
+        active,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn configure_partner_program(
+    ctx: Context<ConfigurePartnerProgram>,
+    can_stake: bool,
+    can_claim: bool,
+    active: bool,
+) -> Result<()> {
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        ctx.accounts.partner_program.key() != crate::ID,
+        ErrorCode::InvalidPartnerProgram
+    );
+
+    let partner = &mut ctx.accounts.partner;
+    partner.program_id = ctx.accounts.partner_program.key();
+    partner.can_stake = can_stake;
+    partner.can_claim = can_claim;
+    partner.active = active;
+    partner.bump = ctx.bumps.partner;
+
+    emit!(PartnerProgramConfigured {
+        program_id: partner.program_id,
+        can_stake,
+        can_claim,
+        active,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn attach_booster(ctx: Context<AttachBooster>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let booster_collection = &ctx.accounts.booster_collection;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(!config.is_paused(VaultConfig::PAUSE_STAKE), ErrorCode::VaultPaused);
+    require!(booster_collection.active, ErrorCode::BoosterCollectionInactive);
+    require!(
+        ctx.accounts.booster_mint.decimals == 0
+            && ctx.accounts.user_booster_token_account.amount == 1,
+        ErrorCode::InvalidNft
+    );
+
+    let collection = ctx.accounts.booster_metadata.collection
+        .as_ref()
+        .ok_or(ErrorCode::NoCollectionFound)?;
+    require!(collection.verified, ErrorCode::CollectionNotVerified);
+    require!(
+        collection.key == booster_collection.collection_mint,
+        ErrorCode::WrongCollection
+    );
+
+    // Settle at the boost held so far
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.user_booster_token_account.to_account_info(),
+            to: ctx.accounts.booster_escrow.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    let booster = &mut ctx.accounts.booster;
+    booster.user = ctx.accounts.user.key();
+    booster.nft_mint = ctx.accounts.booster_mint.key();
+    booster.collection_mint = booster_collection.collection_mint;
+    booster.boost_bps = booster_collection.boost_bps;
+    booster.attached_at = clock.unix_timestamp;
+    booster.bump = ctx.bumps.booster;
+    user_stake.booster_boost_bps = booster.boost_bps;
+
+    emit!(BoosterAttached {
+        user: booster.user,
+        nft_mint: booster.nft_mint,
+        collection_mint: booster.collection_mint,
+        boost_bps: booster.boost_bps,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn detach_booster(ctx: Context<DetachBooster>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    if !vault.emergency_mode {
+        require!(!config.is_paused(VaultConfig::PAUSE_UNSTAKE), ErrorCode::VaultPaused);
+    }
+
+    let rewards_earned = if vault.emergency_mode {
+        accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp).unwrap_or(0)
+    } else {
+        accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?
+    };
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+    user_stake.booster_boost_bps = 0;
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.booster_escrow.to_account_info(),
+            to: ctx.accounts.user_booster_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    let close_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.booster_escrow.to_account_info(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::close_account(close_ctx)?;
+
+    emit!(BoosterDetached {
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.booster_mint.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn tag_nft_group(ctx: Context<TagNftGroup>, group: u8) -> Result<()> {
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(group < NftGroupTag::MAX_GROUPS, ErrorCode::InvalidNftGroup);
+    require!(ctx.accounts.stake_record.data_is_empty(), ErrorCode::NftCurrentlyStaked);
+
+    let tag = &mut ctx.accounts.nft_group_tag;
+    tag.nft_mint = ctx.accounts.nft_mint.key();
+    tag.group = group;
+    tag.bump = ctx.bumps.nft_group_tag;
+
+    emit!(NftGroupTagged {
+        nft_mint: tag.nft_mint,
+        group,
+        tagged_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_nft_multiplier(ctx: Context<SetNftMultiplier>, multiplier_bps: u64) -> Result<()> {
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        (BPS_DENOMINATOR..=NftMultiplier::MAX_MULTIPLIER_BPS).contains(&multiplier_bps),
+        ErrorCode::InvalidNftMultiplier
+    );
+    // Only 1/1s staked through `stake_nft` carry the bonus; editions are
+    // staked by amount and never read it
+    let nft_mint = &ctx.accounts.nft_mint;
+    let (master_edition, _) = Pubkey::find_program_address(
+        &[b"metadata", Metadata::id().as_ref(), nft_mint.key().as_ref(), b"edition"],
+        &Metadata::id(),
+    );
+    require!(
+        nft_mint.supply == 1 && nft_mint.mint_authority.contains(&master_edition),
+        ErrorCode::InvalidMasterEdition
+    );
+    // Positions add the bonus on stake and take it off on unstake, so it
+    // must not change in between
+    require!(ctx.accounts.stake_record.data_is_empty(), ErrorCode::NftCurrentlyStaked);
+
+    let multiplier = &mut ctx.accounts.nft_multiplier;
+    multiplier.nft_mint = ctx.accounts.nft_mint.key();
+    multiplier.multiplier_bps = multiplier_bps;
+    multiplier.bump = ctx.bumps.nft_multiplier;
+
+    emit!(NftMultiplierSet {
+        nft_mint: multiplier.nft_mint,
+        multiplier_bps,
+        set_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{self, BurnNft, MasterEditionAccount, Metadata, MetadataAccount},
+    token::{Mint, Token, TokenAccount},
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::accrue_user_rewards;
+use super::config::UpdateConfig;
+
+#[derive(Accounts)]
+pub struct BurnForBoost<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            nft_mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub nft_metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            nft_mint.key().as_ref(),
+            b"edition"
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub nft_master_edition: Account<'info, MasterEditionAccount>,
+
+    #[account(
+        mut,
+        constraint = user_nft_token_account.mint == nft_mint.key(),
+        constraint = user_nft_token_account.owner == user.key(),
+        constraint = user_nft_token_account.amount == 1
+    )]
+    pub user_nft_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Metadata of the burn collection; Token Metadata updates its
+    /// size when a verified member is burned.
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            vault.burn_boost.collection_mint.as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn set_burn_boost(
+    ctx: Context<UpdateConfig>,
+    collection_mint: Pubkey,
+    boost_bps_per_burn: u64,
+    max_boost_bps: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    let burn_boost = BurnBoostConfig {
+        collection_mint,
+        boost_bps_per_burn,
+        max_boost_bps,
+    };
+    require!(burn_boost.is_valid(), ErrorCode::InvalidBurnBoostConfig);
+    vault.burn_boost = burn_boost;
+
+    emit!(BurnBoostUpdated {
+        collection_mint,
+        boost_bps_per_burn,
+        max_boost_bps,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Burns one of the user's NFTs from the burn collection for a permanent
+/// boost on their position. Lowering the cap later doesn't take boosts
+/// already earned away.
+pub(crate) fn burn_for_boost(ctx: Context<BurnForBoost>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let burn_boost = &vault.burn_boost;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(!vault.decommissioned, ErrorCode::VaultDecommissioned);
+    require!(!config.is_paused(VaultConfig::PAUSE_STAKE), ErrorCode::VaultPaused);
+    require!(burn_boost.is_enabled(), ErrorCode::BurnBoostDisabled);
+    require!(
+        user_stake.burn_boost_bps < burn_boost.max_boost_bps,
+        ErrorCode::BurnBoostCapReached
+    );
+    require!(ctx.accounts.nft_mint.decimals == 0, ErrorCode::InvalidNft);
+
+    let collection = ctx.accounts.nft_metadata.collection
+        .as_ref()
+        .ok_or(ErrorCode::NoCollectionFound)?;
+    require!(collection.verified, ErrorCode::CollectionNotVerified);
+    require!(
+        collection.key == burn_boost.collection_mint,
+        ErrorCode::WrongCollection
+    );
+
+    // Settle at the boost held so far
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    let burn_ctx = CpiContext::new(
+        ctx.accounts.metadata_program.to_account_info(),
+        BurnNft {
+            metadata: ctx.accounts.nft_metadata.to_account_info(),
+            owner: ctx.accounts.user.to_account_info(),
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            token: ctx.accounts.user_nft_token_account.to_account_info(),
+            edition: ctx.accounts.nft_master_edition.to_account_info(),
+            spl_token: ctx.accounts.token_program.to_account_info(),
+        },
+    )
+    .with_remaining_accounts(vec![ctx.accounts.collection_metadata.to_account_info()]);
+    metadata::burn_nft(burn_ctx, Some(ctx.accounts.collection_metadata.key()))?;
+
+    user_stake.burn_boost_bps = burn_boost.boost_after_burn(user_stake.burn_boost_bps);
+    user_stake.burned_nfts = user_stake.burned_nfts
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(NftBurnedForBoost {
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        collection_mint: burn_boost.collection_mint,
+        burn_boost_bps: user_stake.burn_boost_bps,
+        burned_nfts: user_stake.burned_nfts,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
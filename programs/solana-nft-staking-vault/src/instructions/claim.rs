@@ -0,0 +1,887 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAccount}};
+use anchor_lang::solana_program::sysvar;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{
+    BPS_DENOMINATOR, apply_boost_bps, accrue_user_rewards, claim_bound, pay_out_rewards,
+    reward_source, settle_secondary_rewards, ensure_allowed_caller, ensure_not_suspended,
+    ensure_cooldown_elapsed,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// This user's accumulator; see `VaultShard`.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump,
+        constraint = vault_shard.load()?.index == VaultShard::index_for(&user.key()) @ ErrorCode::WrongVaultShard
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    /// CHECK: The staker; only used to derive their accounts.
+    pub user: UncheckedAccount<'info>,
+
+    /// The staker, their `claim_delegate` or a session key; pays for any
+    /// accounts created.
+    #[account(
+        mut,
+        constraint = claimer.key() == user.key()
+            || user_stake.claim_delegate == Some(claimer.key())
+            || session_authority.is_some() @ ErrorCode::UnauthorizedClaimer
+    )]
+    pub claimer: Signer<'info>,
+
+    /// Only required when `claimer` is a session key; scope and expiry are
+    /// checked in the handler.
+    #[account(
+        seeds = [b"session", user.key().as_ref(), claimer.key().as_ref()],
+        bump = session_authority.bump
+    )]
+    pub session_authority: Option<Account<'info, SessionAuthority>>,
+
+    /// CHECK: Address is derived from the user; the account only exists
+    /// once a moderator has suspended (or unsuspended) this user.
+    #[account(seeds = [b"user_status", user.key().as_ref()], bump)]
+    pub user_status: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = reward_token_mint.key() == vault.reward_token_mint
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = user
+    )]
+    pub user_reward_token_account: Account<'info, TokenAccount>,
+
+    /// Receives the payout instead of `user_reward_token_account`. Required
+    /// when the user pinned a payout address.
+    #[account(
+        mut,
+        constraint = destination.mint == vault.reward_token_mint @ ErrorCode::InvalidPayoutDestination
+    )]
+    pub destination: Option<Account<'info, TokenAccount>>,
+
+    /// Only required when the vault pays rewards from a funded pool.
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+
+    /// Only required when a protocol fee is configured.
+    #[account(
+        mut,
+        constraint = vault.fee_destination == Some(fee_destination.key())
+            && fee_destination.mint == vault.reward_token_mint @ ErrorCode::InvalidFeeDestination
+    )]
+    pub fee_destination: Option<Account<'info, TokenAccount>>,
+
+    /// Only required when part of the protocol fee goes to insurance.
+    #[account(
+        mut,
+        seeds = [b"insurance_pool"],
+        bump
+    )]
+    pub insurance_pool: Option<Account<'info, TokenAccount>>,
+
+    /// Only required when the user was referred and referral bonuses are on.
+    #[account(
+        mut,
+        seeds = [b"referral", referral.referrer.as_ref()],
+        bump = referral.bump,
+        constraint = user_stake.referrer == Some(referral.referrer) @ ErrorCode::ReferralAccountRequired
+    )]
+    pub referral: Option<Account<'info, ReferralAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_stake.referrer == Some(referrer_reward_token_account.owner)
+            && referrer_reward_token_account.mint == vault.reward_token_mint
+            @ ErrorCode::ReferralAccountRequired
+    )]
+    pub referrer_reward_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Omit to claim without earning XP or the level bonus.
+    #[account(seeds = [b"level_config"], bump = level_config.bump)]
+    pub level_config: Option<Account<'info, LevelConfig>>,
+
+    /// Omit to claim without the set-completion bonus.
+    #[account(seeds = [b"set_bonus"], bump = set_bonus.bump)]
+    pub set_bonus: Option<Account<'info, SetBonusConfig>>,
+
+    /// Required when vesting is on and rejected otherwise; receives what
+    /// is left of the claim after any reward split.
+    #[account(
+        init,
+        payer = claimer,
+        space = 8 + VestingPosition::INIT_SPACE,
+        seeds = [
+            b"vesting",
+            user.key().as_ref(),
+            user_stake.vesting_position_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vesting_position: Option<Account<'info, VestingPosition>>,
+
+    /// CHECK: Instructions sysvar, used to identify CPI callers.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Only required when a registered partner program is the caller.
+    #[account(seeds = [b"partner", partner.program_id.as_ref()], bump = partner.bump)]
+    pub partner: Option<Account<'info, PartnerProgram>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            user.key().as_ref(),
+            vesting_position.id.to_le_bytes().as_ref()
+        ],
+        bump = vesting_position.bump,
+        has_one = user
+    )]
+    pub vesting_position: Account<'info, VestingPosition>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = reward_token_mint.key() == vault.reward_token_mint
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    /// The payout account the position was created for.
+    #[account(
+        mut,
+        address = vesting_position.destination @ ErrorCode::InvalidPayoutDestination
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    /// Only required when the vault pays rewards from a funded pool.
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct AccrueRewards<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user_stake.user.as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    /// The position's accumulator; the bounty counts against it.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump,
+        constraint = vault_shard.load()?.index == VaultShard::index_for(&user_stake.user) @ ErrorCode::WrongVaultShard
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = reward_token_mint.key() == vault.reward_token_mint
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    /// Receives the bounty; omit to crank without one.
+    #[account(mut)]
+    pub cranker_reward_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Only required when the vault pays rewards from a funded pool.
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetPendingRewards<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    /// CHECK: Only used to derive the stake account address
+    pub user: UncheckedAccount<'info>,
+}
+
+pub(crate) fn accrue_rewards(ctx: Context<AccrueRewards>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    require!(user_stake.staked_nfts > 0, ErrorCode::NoNftsStaked);
+    ensure_cooldown_elapsed(
+        user_stake.last_update_timestamp,
+        VaultAccount::CRANK_INTERVAL_SECONDS,
+        clock.unix_timestamp,
+        ErrorCode::TooFrequent,
+    )?;
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    // The bounty is minted like a claim, so it shrinks to what the supply
+    // cap, the shard allowance and today's limit leave, and stops while
+    // the breaker is open
+    shard.daily_limit.reset_if_new_day(clock.unix_timestamp);
+    let bounty = vault.crank_bounty
+        .min(shard.reward_allowance(vault))
+        .min(shard.daily_limit.remaining_rewards());
+    let bounty = match ctx.accounts.cranker_reward_token_account.as_ref() {
+        Some(destination)
+            if bounty > 0
+                && rewards_earned > 0
+                && shard.can_execute(config, clock.unix_timestamp) =>
+        {
+            require!(
+                destination.mint == vault.reward_token_mint,
+                ErrorCode::InvalidRewardDestination
+            );
+
+            let source = reward_source(
+                vault,
+                vault.reward_mode,
+                vault.reward_token_mint,
+                &ctx.accounts.reward_token_mint,
+                ctx.accounts.reward_pool.as_ref(),
+            )?;
+            pay_out_rewards(
+                vault,
+                vault.reward_mode,
+                source,
+                destination.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                bounty,
+            )?;
+            shard.record_rewards_minted(bounty, 0)?;
+            shard.daily_limit.record_payout(bounty);
+            bounty
+        }
+        _ => 0,
+    };
+
+    emit!(RewardsAccrued {
+        user: user_stake.user,
+        cranker: ctx.accounts.cranker.key(),
+        amount: rewards_earned,
+        pending_rewards: user_stake.pending_rewards,
+        bounty,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let position = &mut ctx.accounts.vesting_position;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+
+    let amount = position.releasable(now)?;
+    require!(amount > 0, ErrorCode::NothingVested);
+
+    let source = reward_source(
+        vault,
+        vault.reward_mode,
+        vault.reward_token_mint,
+        &ctx.accounts.reward_token_mint,
+        ctx.accounts.reward_pool.as_ref(),
+    )?;
+    pay_out_rewards(
+        vault,
+        vault.reward_mode,
+        source,
+        ctx.accounts.destination.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    position.released_amount = position.released_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(VestedRewardsClaimed {
+        user: position.user,
+        id: position.id,
+        amount,
+        released_amount: position.released_amount,
+        total_amount: position.total_amount,
+        timestamp: now,
+    });
+
+    if position.released_amount == position.total_amount {
+        position.close(ctx.accounts.user.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn claim_rewards<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>,
+) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+    require!(user_stake.squad.is_none(), ErrorCode::RewardsGoToSquad);
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    ensure_not_suspended(&ctx.accounts.user_status)?;
+    require!(
+        vault.vesting.is_enabled() || ctx.accounts.vesting_position.is_none(),
+        ErrorCode::VestingDisabled
+    );
+    shard.sync_config(config);
+    ensure_allowed_caller(
+        vault,
+        &ctx.accounts.instructions,
+        ctx.accounts.partner.as_deref(),
+        |partner| partner.can_claim,
+    )?;
+    if let Some(session) = &ctx.accounts.session_authority {
+        require!(
+            session.allows(SessionAuthority::SCOPE_CLAIM, clock.unix_timestamp),
+            ErrorCode::SessionNotValid
+        );
+    }
+
+    // A pinned payout address always wins; otherwise only the staker
+    // may redirect, so delegates and session keys pay the owner's ATA
+    let destination = match (ctx.accounts.destination.as_ref(), user_stake.payout_address) {
+        (Some(destination), Some(pinned)) => {
+            require!(destination.key() == pinned, ErrorCode::InvalidPayoutDestination);
+            destination.to_account_info()
+        }
+        (Some(destination), None) => {
+            require!(
+                ctx.accounts.claimer.key() == ctx.accounts.user.key(),
+                ErrorCode::InvalidPayoutDestination
+            );
+            destination.to_account_info()
+        }
+        (None, Some(_)) => return err!(ErrorCode::PayoutDestinationRequired),
+        (None, None) => ctx.accounts.user_reward_token_account.to_account_info(),
+    };
+    let split_len = user_stake.reward_split.len();
+    require!(
+        ctx.remaining_accounts.len() >= split_len,
+        ErrorCode::InvalidRemainingAccounts
+    );
+    let (split_accounts, secondary_accounts) = ctx.remaining_accounts.split_at(split_len);
+
+    // Circuit breaker check
+    require!(
+        shard.can_execute(config, clock.unix_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+
+    ensure_cooldown_elapsed(
+        user_stake.last_update_timestamp,
+        vault.claim_cooldown_seconds,
+        clock.unix_timestamp,
+        ErrorCode::TooFrequentClaim,
+    )?;
+
+    require!(
+        vault.min_stake_slots_elapsed(user_stake.last_stake_slot, clock.slot),
+        ErrorCode::StakedTooRecently
+    );
+
+    // The streak and XP are only recorded once the claim passes the
+    // checks below; until then the boosts are worked out without them
+    let streak_bps = vault.streak.bonus_bps(user_stake.streak_days_at(clock.unix_timestamp));
+    let level_bps = match ctx.accounts.level_config.as_ref() {
+        Some(level_config) => level_config.boost_bps(
+            user_stake.level_with_staking_xp(level_config, clock.unix_timestamp)?,
+        ),
+        None => 0,
+    };
+    let set_bps = ctx.accounts.set_bonus
+        .as_ref()
+        .map_or(0, |set_bonus| set_bonus.bonus_bps(user_stake));
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    let total_rewards = apply_boost_bps(
+        user_stake.pending_rewards
+            .checked_add(rewards_earned)
+            .ok_or(ErrorCode::MathOverflow)?,
+        streak_bps.saturating_add(level_bps).saturating_add(set_bps),
+    )?;
+    // Early-unstake penalties shared out to this user and credited
+    // rewards; not subject to the per-NFT emission ceiling below
+    let unbounded = user_stake.redistributed_rewards
+        .checked_add(user_stake.penalty_share(vault.penalty_index)?)
+        .and_then(|amount| amount.checked_add(user_stake.credited_rewards))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let payout = total_rewards
+        .checked_add(unbounded)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    require!(payout > 0, ErrorCode::NoRewardsToClaim);
+
+    // Daily limits check
+    shard.daily_limit.reset_if_new_day(clock.unix_timestamp);
+    shard.daily_limit.check_claim(payout)?;
+
+    let (effective_rate, allowed) = claim_bound(
+        vault,
+        config,
+        user_stake,
+        streak_bps.saturating_add(level_bps).saturating_add(set_bps),
+        clock.unix_timestamp,
+    )?;
+    if total_rewards > allowed {
+        // Reverting would also revert the failure count, so the claim is
+        // rejected by returning early without paying anything out;
+        // `ClaimRejected` tells the caller nothing was paid.
+        if user_stake.take_breaker_failure(shard.circuit_breaker.reset_timeout, clock.unix_timestamp) {
+            shard.circuit_breaker.on_failure(clock.unix_timestamp);
+        }
+        msg!(
+            "Excessive claim: requested {}, at most {} allowed; recorded as a circuit breaker failure",
+            total_rewards,
+            allowed
+        );
+
+        emit_cpi!(ClaimRejected {
+            user: ctx.accounts.user.key(),
+            reason: ClaimRejection::ExceedsBound,
+            requested: total_rewards,
+            allowed,
+            failure_count: shard.circuit_breaker.failure_count,
+            timestamp: clock.unix_timestamp,
+        });
+
+        return Ok(());
+    }
+
+    // Anomaly rule: a claim this large stops the shard at once and pauses
+    // the vault on the next fold. Returns early for the same reason as above.
+    if let Some(ceiling) = vault.anomaly_rules.claim_ceiling(config.daily_limit.max_total_rewards_per_day) {
+        if payout > ceiling {
+            msg!("Auto-pause: claim of {} is above the {} anomaly ceiling", payout, ceiling);
+            if user_stake.take_breaker_failure(shard.circuit_breaker.reset_timeout, clock.unix_timestamp) {
+                shard.circuit_breaker.on_failure(clock.unix_timestamp);
+                shard.circuit_breaker.trip(clock.unix_timestamp);
+                shard.auto_paused = 1;
+
+                emit_cpi!(AutoPauseTriggered {
+                    kind: AnomalyKind::LargeClaim,
+                    observed: payout,
+                    threshold: ceiling,
+                    shard_index: shard.index,
+                    timestamp: clock.unix_timestamp,
+                });
+            }
+
+            emit_cpi!(ClaimRejected {
+                user: ctx.accounts.user.key(),
+                reason: ClaimRejection::AnomalyCeiling,
+                requested: payout,
+                allowed: ceiling,
+                failure_count: shard.circuit_breaker.failure_count,
+                timestamp: clock.unix_timestamp,
+            });
+
+            return Ok(());
+        }
+    }
+
+    user_stake.take_rate_token(&vault.user_rate_limit, clock.unix_timestamp)?;
+    if user_stake.update_streak(clock.unix_timestamp) {
+        emit_cpi!(StreakUpdated {
+            user: user_stake.user,
+            current_streak_days: user_stake.current_streak_days,
+            streak_started_at: user_stake.streak_started_at,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+    if let Some(level_config) = ctx.accounts.level_config.as_ref() {
+        user_stake.credit_staking_xp(level_config, clock.unix_timestamp)?;
+    }
+
+    // Hard supply cap: pay out what is left and forfeit the rest
+    let remaining_supply = shard.remaining_reward_supply(vault);
+    require!(remaining_supply > 0, ErrorCode::RewardSupplyExhausted);
+    let forfeited = payout.saturating_sub(remaining_supply);
+    let payout = payout - forfeited;
+    // Supply left over may sit in other shards' allowances; folding
+    // them rebalances it
+    require!(
+        payout <= shard.reward_allowance(vault),
+        ErrorCode::ShardAllowanceExhausted
+    );
+
+    let fee = vault.protocol_fee(payout)?;
+    let net_payout = payout - fee;
+
+    let source = reward_source(
+        vault,
+        vault.reward_mode,
+        vault.reward_token_mint,
+        &ctx.accounts.reward_token_mint,
+        ctx.accounts.reward_pool.as_ref(),
+    )?;
+    let insurance_cut = vault.insurance_share(fee)?;
+    if insurance_cut > 0 {
+        let insurance_pool = ctx.accounts.insurance_pool
+            .as_ref()
+            .ok_or(ErrorCode::InsurancePoolRequired)?;
+
+        pay_out_rewards(
+            vault,
+            vault.reward_mode,
+            source.clone(),
+            insurance_pool.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            insurance_cut,
+        )?;
+    }
+    if fee > insurance_cut {
+        let fee_destination = ctx.accounts.fee_destination
+            .as_ref()
+            .ok_or(ErrorCode::InvalidFeeDestination)?;
+
+        pay_out_rewards(
+            vault,
+            vault.reward_mode,
+            source.clone(),
+            fee_destination.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            fee - insurance_cut,
+        )?;
+    }
+    // Split shares come off the top and are paid out now; the rest goes to
+    // `destination`, or is locked for it when vesting is on
+    let mut remainder = net_payout;
+    for (share, account) in user_stake.reward_split.iter().zip(split_accounts) {
+        require!(account.key() == share.destination, ErrorCode::InvalidRewardSplit);
+        let amount = share.amount_of(net_payout)?;
+        if amount == 0 {
+            continue;
+        }
+        remainder = remainder.checked_sub(amount).ok_or(ErrorCode::MathUnderflow)?;
+        pay_out_rewards(
+            vault,
+            vault.reward_mode,
+            source.clone(),
+            account.clone(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+    }
+    if vault.vesting.is_enabled() {
+        // Nothing more leaves the mint or pool until `claim_vested`
+        let position = ctx.accounts.vesting_position
+            .as_mut()
+            .ok_or(ErrorCode::VestingPositionRequired)?;
+        position.user = user_stake.user;
+        position.id = user_stake.vesting_position_count;
+        position.destination = destination.key();
+        position.total_amount = remainder;
+        position.released_amount = 0;
+        position.start_at = clock.unix_timestamp;
+        position.cliff_at = clock.unix_timestamp
+            .checked_add(vault.vesting.cliff_seconds)
+            .ok_or(ErrorCode::MathOverflow)?;
+        position.end_at = clock.unix_timestamp
+            .checked_add(vault.vesting.duration_seconds)
+            .ok_or(ErrorCode::MathOverflow)?;
+        position.bump = ctx.bumps.vesting_position.ok_or(ErrorCode::VestingPositionRequired)?;
+        user_stake.vesting_position_count = user_stake.vesting_position_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_cpi!(VestingPositionCreated {
+            user: position.user,
+            id: position.id,
+            destination: position.destination,
+            amount: remainder,
+            cliff_at: position.cliff_at,
+            end_at: position.end_at,
+            timestamp: clock.unix_timestamp,
+        });
+    } else if remainder > 0 {
+        pay_out_rewards(
+            vault,
+            vault.reward_mode,
+            source.clone(),
+            destination.clone(),
+            ctx.accounts.token_program.to_account_info(),
+            remainder,
+        )?;
+    }
+    shard.record_rewards_minted(payout, forfeited)?;
+    user_stake.record_claimed(net_payout)?;
+
+    // Referral bonus comes on top of the claim, from emissions only
+    if let Some(referrer) = user_stake.referrer.filter(|_| vault.referral_bonus_bps > 0) {
+        let referral = ctx.accounts.referral
+            .as_mut()
+            .ok_or(ErrorCode::ReferralAccountRequired)?;
+        let referrer_account = ctx.accounts.referrer_reward_token_account
+            .as_ref()
+            .ok_or(ErrorCode::ReferralAccountRequired)?;
+
+        let bonus = ((total_rewards as u128)
+            .checked_mul(vault.referral_bonus_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR as u128) as u64;
+        let bonus = bonus
+            .min(vault.referral_bonus_cap.saturating_sub(referral.total_bonus_paid))
+            .min(shard.reward_allowance(vault));
+
+        if bonus > 0 {
+            pay_out_rewards(
+                vault,
+                vault.reward_mode,
+                source,
+                referrer_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                bonus,
+            )?;
+            shard.record_rewards_minted(bonus, 0)?;
+
+            referral.total_bonus_paid = referral.total_bonus_paid
+                .checked_add(bonus)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            emit_cpi!(ReferralBonusPaid {
+                referrer,
+                user: user_stake.user,
+                amount: bonus,
+                total_bonus_paid: referral.total_bonus_paid,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+    }
+
+    user_stake.pending_rewards = 0;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+    user_stake.redistributed_rewards = 0;
+    user_stake.credited_rewards = 0;
+
+    if let Some(level_config) = ctx.accounts.level_config.as_ref() {
+        user_stake.add_xp(level_config.xp_per_claim, level_config);
+
+        emit_cpi!(XpUpdated {
+            user: user_stake.user,
+            xp: user_stake.xp,
+            level: user_stake.level,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    // Secondary rewards
+    for accounts in secondary_accounts.chunks(4) {
+        require!(accounts.len() == 4, ErrorCode::InvalidRemainingAccounts);
+
+        let mut reward_config: Account<RewardConfig> = Account::try_from(&accounts[0])?;
+        let mut user_reward: Account<UserRewardState> = Account::try_from(&accounts[1])?;
+        require!(
+            user_reward.user == user_stake.user
+                && user_reward.reward_mint == reward_config.reward_mint,
+            ErrorCode::InvalidRewardPosition
+        );
+
+        let destination = TokenAccount::try_deserialize(&mut &accounts[2].data.borrow()[..])?;
+        require!(
+            destination.mint == reward_config.reward_mint
+                && destination.owner == user_stake.user,
+            ErrorCode::InvalidRewardDestination
+        );
+
+        let amount = settle_secondary_rewards(
+            &reward_config,
+            &mut user_reward,
+            user_stake,
+            clock.unix_timestamp,
+        )?;
+
+        if amount > 0 {
+            check_reward_source(
+                vault,
+                reward_config.reward_mode,
+                reward_config.reward_mint,
+                &accounts[3],
+            )?;
+            pay_out_rewards(
+                vault,
+                reward_config.reward_mode,
+                accounts[3].clone(),
+                accounts[2].clone(),
+                ctx.accounts.token_program.to_account_info(),
+                amount,
+            )?;
+
+            reward_config.total_claimed = reward_config.total_claimed
+                .checked_add(amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            emit_cpi!(SecondaryRewardsClaimed {
+                user: user_stake.user,
+                reward_mint: reward_config.reward_mint,
+                amount,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        reward_config.exit(ctx.program_id)?;
+        user_reward.exit(ctx.program_id)?;
+    }
+
+    // Record successful claim
+    shard.record_claim(payout, clock.unix_timestamp)?;
+    shard.daily_limit.record_claim(payout);
+    shard.circuit_breaker.on_success();
+
+    let paid_to = match ctx.accounts.vesting_position.as_ref() {
+        Some(position) if vault.vesting.is_enabled() => position.key(),
+        _ => destination.key(),
+    };
+    emit_cpi!(RewardsClaimed {
+        user: ctx.accounts.user.key(),
+        claimed_by: ctx.accounts.claimer.key(),
+        destination: paid_to,
+        amount: payout,
+        fee,
+        staked_nfts: user_stake.staked_nfts,
+        pending_rewards: user_stake.pending_rewards,
+        total_staked: shard.projected_total_staked(vault),
+        reward_rate_per_second: effective_rate,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn get_pending_rewards(ctx: Context<GetPendingRewards>) -> Result<u64> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    let pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .and_then(|pending| pending.checked_add(user_stake.redistributed_rewards))
+        .and_then(|pending| pending.checked_add(user_stake.credited_rewards))
+        .and_then(|pending| {
+            pending.checked_add(user_stake.penalty_share(vault.penalty_index).ok()?)
+        })
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(pending_rewards)
+}
+
+/// `reward_source` for a raw source passed in `remaining_accounts`.
+fn check_reward_source(
+    vault: &Account<VaultAccount>,
+    reward_mode: RewardMode,
+    reward_mint: Pubkey,
+    source: &AccountInfo,
+) -> Result<()> {
+    match reward_mode {
+        RewardMode::Mint => {
+            require!(source.key() == reward_mint, ErrorCode::InvalidRewardMint);
+            let mint = Mint::try_deserialize(&mut &source.data.borrow()[..])?;
+            require!(
+                mint.mint_authority == COption::Some(vault.key()),
+                ErrorCode::InvalidMintAuthority
+            );
+        }
+        RewardMode::Pool => {
+            let pool = TokenAccount::try_deserialize(&mut &source.data.borrow()[..])?;
+            require!(
+                pool.mint == reward_mint && pool.owner == vault.key(),
+                ErrorCode::InvalidRewardPool
+            );
+        }
+    }
+    Ok(())
+}
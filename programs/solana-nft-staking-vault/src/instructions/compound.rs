@@ -0,0 +1,200 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{
+    accrue_user_rewards, claim_bound, pay_out_rewards, reward_source, ensure_not_suspended,
+    ensure_cooldown_elapsed,
+};
+use super::claim::ClaimRewards;
+
+#[derive(Accounts)]
+pub struct CompoundRewards<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// This user's accumulator; see `VaultShard`.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump,
+        constraint = vault_shard.load()?.index == VaultShard::index_for(&user.key()) @ ErrorCode::WrongVaultShard
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Address is derived from the user; the account only exists
+    /// once a moderator has suspended (or unsuspended) this user.
+    #[account(seeds = [b"user_status", user.key().as_ref()], bump)]
+    pub user_status: UncheckedAccount<'info>,
+}
+
+pub(crate) fn compound_rewards(ctx: Context<CompoundRewards>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+    require!(user_stake.squad.is_none(), ErrorCode::RewardsGoToSquad);
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    ensure_not_suspended(&ctx.accounts.user_status)?;
+    shard.sync_config(config);
+
+    // Circuit breaker check
+    require!(
+        shard.can_execute(config, clock.unix_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+
+    require!(user_stake.staked_nfts > 0, ErrorCode::NoNftsStaked);
+    ensure_cooldown_elapsed(
+        user_stake.last_update_timestamp,
+        vault.claim_cooldown_seconds,
+        clock.unix_timestamp,
+        ErrorCode::TooFrequentClaim,
+    )?;
+    require!(
+        vault.min_stake_slots_elapsed(user_stake.last_stake_slot, clock.slot),
+        ErrorCode::StakedTooRecently
+    );
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    let total_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    require!(total_rewards > 0, ErrorCode::NoRewardsToClaim);
+
+    // Compounding is a claim paid into the position, so it is held to the
+    // same daily limit and emission bound
+    shard.daily_limit.reset_if_new_day(clock.unix_timestamp);
+    shard.daily_limit.check_claim(total_rewards)?;
+    let (_, allowed) = claim_bound(vault, config, user_stake, 0, clock.unix_timestamp)?;
+    require!(total_rewards <= allowed, ErrorCode::ExcessiveRewardClaim);
+    user_stake.take_rate_token(&vault.user_rate_limit, clock.unix_timestamp)?;
+
+    // Rewards stay inside the position instead of being minted; the
+    // lock restarts on every compound so the boost can't be farmed
+    // and withdrawn in the same window.
+    let position = &mut user_stake.compound_position;
+    position.amount = position.amount
+        .checked_add(total_rewards)
+        .ok_or(ErrorCode::MathOverflow)?;
+    position.locked_until = clock.unix_timestamp
+        .checked_add(CompoundPosition::LOCK_SECONDS)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    user_stake.pending_rewards = 0;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    shard.daily_limit.record_claim(total_rewards);
+    shard.circuit_breaker.on_success();
+
+    emit!(RewardsCompounded {
+        user: ctx.accounts.user.key(),
+        amount: total_rewards,
+        total_compounded: user_stake.compound_position.amount,
+        boost_bps: user_stake.compound_position.boost_bps(config.reward_rate_per_second),
+        locked_until: user_stake.compound_position.locked_until,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn withdraw_compounded(ctx: Context<ClaimRewards>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    ensure_not_suspended(&ctx.accounts.user_status)?;
+    shard.sync_config(config);
+
+    // Circuit breaker check
+    require!(
+        shard.can_execute(config, clock.unix_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+
+    let amount = user_stake.compound_position.amount;
+    require!(amount > 0, ErrorCode::NoCompoundedRewards);
+    require!(
+        clock.unix_timestamp >= user_stake.compound_position.locked_until,
+        ErrorCode::CompoundPositionLocked
+    );
+
+    // Settle accrual at the boosted rate before the boost disappears
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    // Daily limits check
+    shard.daily_limit.reset_if_new_day(clock.unix_timestamp);
+    shard.daily_limit.check_claim(amount)?;
+    user_stake.take_rate_token(&vault.user_rate_limit, clock.unix_timestamp)?;
+
+    // Hard supply cap and shard allowance, as for a claim
+    let remaining_supply = shard.remaining_reward_supply(vault);
+    require!(remaining_supply > 0, ErrorCode::RewardSupplyExhausted);
+    let forfeited = amount.saturating_sub(remaining_supply);
+    let amount = amount - forfeited;
+    require!(
+        amount <= shard.reward_allowance(vault),
+        ErrorCode::ShardAllowanceExhausted
+    );
+
+    let source = reward_source(
+        vault,
+        vault.reward_mode,
+        vault.reward_token_mint,
+        &ctx.accounts.reward_token_mint,
+        ctx.accounts.reward_pool.as_ref(),
+    )?;
+    pay_out_rewards(
+        vault,
+        vault.reward_mode,
+        source,
+        ctx.accounts.user_reward_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    user_stake.compound_position = CompoundPosition::new();
+    user_stake.record_claimed(amount)?;
+
+    shard.record_rewards_minted(amount, forfeited)?;
+    shard.record_claim(amount, clock.unix_timestamp)?;
+    shard.daily_limit.record_claim(amount);
+    shard.circuit_breaker.on_success();
+
+    emit_cpi!(CompoundedWithdrawn {
+        user: ctx.accounts.user.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,899 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::BPS_DENOMINATOR;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVaultConfig<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureLevels<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = updater,
+        space = 8 + LevelConfig::INIT_SPACE,
+        seeds = [b"level_config"],
+        bump
+    )]
+    pub level_config: Account<'info, LevelConfig>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn propose_config_change(
+    ctx: Context<UpdateVaultConfig>,
+    new_reward_rate: Option<u64>,
+    new_collection_mint: Option<Pubkey>,
+    timelock_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let proposer_role = &ctx.accounts.updater_role;
+
+    proposer_role.require_permission(Role::can_update_config)?;
+    require!(vault.approval_threshold <= 1, ErrorCode::ApprovalRequired);
+    require!(
+        timelock_seconds >= PendingConfigChange::MIN_TIMELOCK_SECONDS,
+        ErrorCode::InvalidTimelock
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let scheduled_timestamp = vault.schedule_config_change(
+        config,
+        new_reward_rate,
+        new_collection_mint,
+        now,
+        timelock_seconds,
+        ctx.accounts.updater.key(),
+    )?;
+
+    emit!(ConfigChangeProposed {
+        old_reward_rate: config.reward_rate_per_second,
+        new_reward_rate,
+        old_collection_mint: vault.collection_mint,
+        new_collection_mint,
+        scheduled_timestamp,
+        proposer: ctx.accounts.updater.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn execute_config_change(ctx: Context<UpdateVaultConfig>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let executor_role = &ctx.accounts.updater_role;
+
+    executor_role.require_permission(Role::can_update_config)?;
+
+    let pending = vault.pending_config_change.clone()
+        .ok_or(ErrorCode::NoConfigChangePending)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= pending.scheduled_timestamp,
+        ErrorCode::TimelockNotExpired
+    );
+
+    let old_reward_rate = config.reward_rate_per_second;
+    let old_collection_mint = vault.collection_mint;
+
+    vault.apply_config_update(config, pending.new_reward_rate, pending.new_collection_mint)?;
+    vault.pending_config_change = None;
+
+    emit!(ConfigChangeExecuted {
+        old_reward_rate,
+        new_reward_rate: config.reward_rate_per_second,
+        old_collection_mint,
+        new_collection_mint: vault.collection_mint,
+        executor: ctx.accounts.updater.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_config_change(ctx: Context<UpdateConfig>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let canceller_role = &ctx.accounts.updater_role;
+
+    canceller_role.require_permission(Role::can_update_config)?;
+
+    let pending = vault.pending_config_change.take()
+        .ok_or(ErrorCode::NoConfigChangePending)?;
+
+    emit!(ConfigChangeCancelled {
+        new_reward_rate: pending.new_reward_rate,
+        new_collection_mint: pending.new_collection_mint,
+        cancelled_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn update_limits(
+    ctx: Context<UpdateVaultConfig>,
+    max_stakes_per_day: Option<u32>,
+    max_claims_per_day: Option<u32>,
+    max_total_rewards_per_day: Option<u64>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    if let Some(max_stakes) = max_stakes_per_day {
+        require!(max_stakes > 0, ErrorCode::InvalidLimits);
+        config.daily_limit.max_stakes_per_day = max_stakes;
+    }
+
+    if let Some(max_claims) = max_claims_per_day {
+        require!(max_claims > 0, ErrorCode::InvalidLimits);
+        config.daily_limit.max_claims_per_day = max_claims;
+    }
+
+    if let Some(max_rewards) = max_total_rewards_per_day {
+        require!(max_rewards > 0, ErrorCode::InvalidLimits);
+        config.daily_limit.max_total_rewards_per_day = vault.to_base_units(max_rewards)?;
+    }
+
+    emit!(LimitsUpdated {
+        max_stakes_per_day: config.daily_limit.max_stakes_per_day,
+        max_claims_per_day: config.daily_limit.max_claims_per_day,
+        max_total_rewards_per_day: config.daily_limit.max_total_rewards_per_day,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_stake_caps(
+    ctx: Context<UpdateConfig>,
+    max_nfts_per_user: Option<u32>,
+    max_total_staked: Option<u32>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    if let Some(max_nfts) = max_nfts_per_user {
+        vault.max_nfts_per_user = max_nfts;
+    }
+
+    if let Some(max_total) = max_total_staked {
+        vault.max_total_staked = max_total;
+    }
+
+    emit!(StakeCapsUpdated {
+        max_nfts_per_user: vault.max_nfts_per_user,
+        max_total_staked: vault.max_total_staked,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn update_circuit_breaker_config(
+    ctx: Context<UpdateVaultConfig>,
+    failure_threshold: Option<u32>,
+    reset_timeout: Option<i64>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    if let Some(threshold) = failure_threshold {
+        require!(threshold > 0, ErrorCode::InvalidCircuitBreakerConfig);
+        config.circuit_breaker.failure_threshold = threshold;
+    }
+
+    if let Some(timeout) = reset_timeout {
+        require!(timeout > 0, ErrorCode::InvalidCircuitBreakerConfig);
+        config.circuit_breaker.reset_timeout = timeout;
+    }
+
+    emit!(CircuitBreakerConfigUpdated {
+        failure_threshold: config.circuit_breaker.failure_threshold,
+        reset_timeout: config.circuit_breaker.reset_timeout,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn propose_emission_schedule(
+    ctx: Context<UpdateConfig>,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    halving_interval: i64,
+    timelock_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(!vault.season_active, ErrorCode::SeasonStillActive);
+    require!(
+        vault.pending_emission_schedule.is_none(),
+        ErrorCode::EmissionSchedulePending
+    );
+    require!(
+        timelock_seconds >= PendingEmissionSchedule::MIN_TIMELOCK_SECONDS,
+        ErrorCode::InvalidTimelock
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let scheduled_timestamp = now
+        .checked_add(timelock_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        end_timestamp == 0 || end_timestamp > start_timestamp.max(scheduled_timestamp),
+        ErrorCode::InvalidEmissionSchedule
+    );
+    require!(
+        halving_interval == 0 || halving_interval >= EmissionSchedule::MIN_HALVING_INTERVAL,
+        ErrorCode::InvalidEmissionSchedule
+    );
+
+    vault.pending_emission_schedule = Some(PendingEmissionSchedule {
+        schedule: EmissionSchedule {
+            start_timestamp,
+            end_timestamp,
+            halving_interval,
+        },
+        scheduled_timestamp,
+        proposer: ctx.accounts.updater.key(),
+    });
+
+    emit!(EmissionScheduleProposed {
+        start_timestamp,
+        end_timestamp,
+        halving_interval,
+        scheduled_timestamp,
+        proposer: ctx.accounts.updater.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn execute_emission_schedule(ctx: Context<UpdateConfig>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    // A season may have started during the timelock
+    require!(!vault.season_active, ErrorCode::SeasonStillActive);
+
+    let pending = vault.pending_emission_schedule.take()
+        .ok_or(ErrorCode::NoEmissionSchedulePending)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= pending.scheduled_timestamp,
+        ErrorCode::TimelockNotExpired
+    );
+
+    vault.update_reward_index(now)?;
+    vault.emission_schedule = pending.schedule;
+
+    emit!(EmissionScheduleUpdated {
+        start_timestamp: vault.emission_schedule.start_timestamp,
+        end_timestamp: vault.emission_schedule.end_timestamp,
+        halving_interval: vault.emission_schedule.halving_interval,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_emission_schedule(ctx: Context<UpdateConfig>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let canceller_role = &ctx.accounts.updater_role;
+
+    canceller_role.require_permission(Role::can_update_config)?;
+
+    vault.pending_emission_schedule.take()
+        .ok_or(ErrorCode::NoEmissionSchedulePending)?;
+
+    emit!(EmissionScheduleCancelled {
+        cancelled_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_curve_config(
+    ctx: Context<UpdateConfig>,
+    total_emission_per_second: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    vault.update_reward_index(now)?;
+    vault.curve = CurveConfig { total_emission_per_second };
+
+    emit!(CurveConfigUpdated {
+        total_emission_per_second,
+        reward_index: vault.reward_index,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_loyalty_config(
+    ctx: Context<UpdateConfig>,
+    bonus_bps_per_period: u64,
+    period_seconds: i64,
+    max_bonus_bps: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(period_seconds >= 86400, ErrorCode::InvalidLoyaltyConfig);
+    require!(
+        bonus_bps_per_period <= max_bonus_bps && max_bonus_bps <= LoyaltyConfig::MAX_BONUS_BPS,
+        ErrorCode::InvalidLoyaltyConfig
+    );
+
+    vault.loyalty = LoyaltyConfig {
+        bonus_bps_per_period,
+        period_seconds,
+        max_bonus_bps,
+    };
+
+    emit!(LoyaltyConfigUpdated {
+        bonus_bps_per_period,
+        period_seconds,
+        max_bonus_bps,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_streak_config(
+    ctx: Context<UpdateConfig>,
+    bonus_bps_per_day: u64,
+    max_bonus_bps: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        bonus_bps_per_day <= max_bonus_bps && max_bonus_bps <= StreakConfig::MAX_BONUS_BPS,
+        ErrorCode::InvalidStreakConfig
+    );
+
+    vault.streak = StreakConfig {
+        bonus_bps_per_day,
+        max_bonus_bps,
+    };
+
+    emit!(StreakConfigUpdated {
+        bonus_bps_per_day,
+        max_bonus_bps,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_fee_config(
+    ctx: Context<UpdateConfig>,
+    fee_bps: u64,
+    fee_destination: Option<Pubkey>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(fee_bps <= VaultAccount::MAX_FEE_BPS, ErrorCode::InvalidFeeConfig);
+    require!(
+        fee_bps == 0 || fee_destination.is_some(),
+        ErrorCode::InvalidFeeConfig
+    );
+
+    vault.fee_bps = fee_bps;
+    vault.fee_destination = fee_destination;
+
+    emit!(FeeConfigUpdated {
+        fee_bps,
+        fee_destination,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_referral_config(
+    ctx: Context<UpdateConfig>,
+    referral_bonus_bps: u64,
+    referral_bonus_cap: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        referral_bonus_bps <= VaultAccount::MAX_REFERRAL_BONUS_BPS,
+        ErrorCode::InvalidReferralConfig
+    );
+
+    vault.referral_bonus_bps = referral_bonus_bps;
+    vault.referral_bonus_cap = referral_bonus_cap;
+
+    emit!(ReferralConfigUpdated {
+        referral_bonus_bps,
+        referral_bonus_cap,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_reward_supply_cap(
+    ctx: Context<UpdateConfig>,
+    max_reward_supply: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        max_reward_supply == 0 || max_reward_supply >= vault.total_rewards_minted,
+        ErrorCode::InvalidRewardSupplyCap
+    );
+
+    vault.max_reward_supply = max_reward_supply;
+
+    emit!(RewardSupplyCapUpdated {
+        max_reward_supply,
+        total_rewards_minted: vault.total_rewards_minted,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_vesting_config(
+    ctx: Context<UpdateConfig>,
+    duration_seconds: i64,
+    cliff_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    let vesting = VestingConfig { duration_seconds, cliff_seconds };
+    require!(vesting.is_valid(), ErrorCode::InvalidVestingConfig);
+    vault.vesting = vesting;
+
+    emit!(VestingConfigUpdated {
+        duration_seconds,
+        cliff_seconds,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_token_boost_config(
+    ctx: Context<UpdateConfig>,
+    mint: Pubkey,
+    required_amount: u64,
+    boost_bps: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        mint == vault.token_boost.mint || vault.total_boost_tokens_staked == 0,
+        ErrorCode::BoostTokensStaked
+    );
+
+    let token_boost = TokenBoostConfig { mint, required_amount, boost_bps };
+    require!(token_boost.is_valid(), ErrorCode::InvalidTokenBoostConfig);
+    vault.token_boost = token_boost;
+
+    emit!(TokenBoostConfigUpdated {
+        mint,
+        required_amount,
+        boost_bps,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_cooldowns(
+    ctx: Context<UpdateConfig>,
+    action_cooldown_seconds: i64,
+    claim_cooldown_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        VaultAccount::cooldowns_valid(action_cooldown_seconds, claim_cooldown_seconds),
+        ErrorCode::InvalidCooldown
+    );
+
+    vault.action_cooldown_seconds = action_cooldown_seconds;
+    vault.claim_cooldown_seconds = claim_cooldown_seconds;
+
+    emit!(CooldownsUpdated {
+        action_cooldown_seconds,
+        claim_cooldown_seconds,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_reward_rate_fraction(
+    ctx: Context<UpdateVaultConfig>,
+    reward_rate_fraction: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let updater_role = &ctx.accounts.updater_role;
+    let clock = Clock::get()?;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(!vault.season_active, ErrorCode::SeasonStillActive);
+    require!(
+        (reward_rate_fraction as u128) < VaultAccount::RATE_PRECISION
+            && (config.reward_rate_per_second > 0 || reward_rate_fraction > 0),
+        ErrorCode::InvalidRewardRate
+    );
+
+    vault.update_reward_index(clock.unix_timestamp)?;
+    config.reward_rate_fraction = reward_rate_fraction;
+
+    emit!(RewardRateFractionUpdated {
+        reward_rate_per_second: config.reward_rate_per_second,
+        reward_rate_fraction,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_min_stake_slots(ctx: Context<UpdateConfig>, min_stake_slots: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        min_stake_slots <= VaultAccount::MAX_MIN_STAKE_SLOTS,
+        ErrorCode::InvalidMinStakeSlots
+    );
+
+    vault.min_stake_slots = min_stake_slots;
+
+    emit!(MinStakeSlotsUpdated {
+        min_stake_slots,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_crank_bounty(ctx: Context<UpdateConfig>, crank_bounty: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    vault.crank_bounty = crank_bounty;
+
+    emit!(CrankBountyUpdated {
+        crank_bounty,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_level_config(
+    ctx: Context<ConfigureLevels>,
+    xp_per_nft_day: u64,
+    xp_per_claim: u64,
+    levels: Vec<LevelTier>,
+) -> Result<()> {
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(levels.len() <= LevelConfig::MAX_LEVELS, ErrorCode::InvalidLevelConfig);
+    for (i, tier) in levels.iter().enumerate() {
+        require!(tier.boost_bps <= LevelConfig::MAX_BOOST_BPS, ErrorCode::InvalidLevelConfig);
+        if i > 0 {
+            let previous = &levels[i - 1];
+            require!(
+                tier.xp_threshold > previous.xp_threshold
+                    && tier.boost_bps >= previous.boost_bps,
+                ErrorCode::InvalidLevelConfig
+            );
+        }
+    }
+
+    let level_config = &mut ctx.accounts.level_config;
+    level_config.xp_per_nft_day = xp_per_nft_day;
+    level_config.xp_per_claim = xp_per_claim;
+    level_config.levels = levels;
+    level_config.bump = ctx.bumps.level_config;
+
+    emit!(LevelConfigUpdated {
+        xp_per_nft_day,
+        xp_per_claim,
+        level_count: level_config.levels.len() as u8,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_cpi_caller_allowlist(
+    ctx: Context<UpdateConfig>,
+    programs: Vec<Pubkey>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        programs.len() <= VaultAccount::MAX_CPI_CALLERS
+            && !programs.contains(&crate::ID),
+        ErrorCode::InvalidCpiCallerAllowlist
+    );
+
+    vault.cpi_caller_allowlist = programs;
+
+    emit!(CpiCallerAllowlistUpdated {
+        programs: vault.cpi_caller_allowlist.clone(),
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_lock_tiers(ctx: Context<UpdateConfig>, lock_tiers: Vec<LockTier>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        !lock_tiers.is_empty() && lock_tiers.len() <= LockTier::MAX_TIERS,
+        ErrorCode::InvalidLockTier
+    );
+    for (i, tier) in lock_tiers.iter().enumerate() {
+        require!(
+            tier.duration_seconds > 0
+                && tier.duration_seconds <= LockTier::MAX_DURATION_SECONDS
+                && tier.boost_bps <= LockTier::MAX_BOOST_BPS,
+            ErrorCode::InvalidLockTier
+        );
+        if i > 0 {
+            let previous = &lock_tiers[i - 1];
+            require!(
+                tier.duration_seconds > previous.duration_seconds
+                    && tier.boost_bps >= previous.boost_bps,
+                ErrorCode::InvalidLockTier
+            );
+        }
+    }
+
+    vault.lock_tiers = lock_tiers;
+
+    emit!(LockTiersUpdated {
+        tier_count: vault.lock_tiers.len() as u8,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_early_unstake_penalty(
+    ctx: Context<UpdateConfig>,
+    penalty_bps: u64,
+    penalty_mode: PenaltyMode,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(penalty_bps <= BPS_DENOMINATOR, ErrorCode::InvalidPenaltyConfig);
+
+    vault.early_unstake_penalty_bps = penalty_bps;
+    vault.penalty_mode = penalty_mode;
+
+    emit!(EarlyUnstakePenaltyUpdated {
+        penalty_bps,
+        penalty_mode,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_unstake_cooldown(
+    ctx: Context<UpdateConfig>,
+    unstake_cooldown_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        (0..=VaultAccount::MAX_UNSTAKE_COOLDOWN_SECONDS).contains(&unstake_cooldown_seconds),
+        ErrorCode::InvalidUnstakeCooldown
+    );
+
+    vault.unstake_cooldown_seconds = unstake_cooldown_seconds;
+
+    emit!(UnstakeCooldownUpdated {
+        unstake_cooldown_seconds,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_warmup_seconds(ctx: Context<UpdateConfig>, warmup_seconds: i64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        (0..=VaultAccount::MAX_WARMUP_SECONDS).contains(&warmup_seconds),
+        ErrorCode::InvalidWarmup
+    );
+
+    vault.warmup_seconds = warmup_seconds;
+
+    emit!(WarmupUpdated {
+        warmup_seconds,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Sets the rules that pause the vault on their own; 0 turns one off.
+/// `max_claim_bps` is a share of the daily reward limit.
+pub(crate) fn set_anomaly_rules(
+    ctx: Context<UpdateConfig>,
+    max_claim_bps: u64,
+    max_daily_staked_drop: u32,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(max_claim_bps <= BPS_DENOMINATOR, ErrorCode::InvalidAnomalyRules);
+
+    vault.anomaly_rules = AnomalyRules {
+        max_claim_bps,
+        max_daily_staked_drop,
+    };
+
+    emit!(AnomalyRulesUpdated {
+        max_claim_bps,
+        max_daily_staked_drop,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_user_rate_limit(
+    ctx: Context<UpdateConfig>,
+    capacity: u32,
+    refill_interval_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        capacity == 0 || refill_interval_seconds > 0,
+        ErrorCode::InvalidRateLimit
+    );
+
+    vault.user_rate_limit = RateLimitConfig {
+        capacity,
+        refill_interval_seconds,
+    };
+
+    emit!(UserRateLimitUpdated {
+        capacity,
+        refill_interval_seconds,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
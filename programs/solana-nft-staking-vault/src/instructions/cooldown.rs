@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::utils::cooldown_remaining;
+
+#[derive(Accounts)]
+pub struct GetCooldowns<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// Absent for users who have never staked.
+    #[account(seeds = [b"user_stake", user.key().as_ref()], bump)]
+    pub user_stake: Option<Account<'info, UserStakeAccount>>,
+
+    /// CHECK: Only used to derive the user's accounts
+    pub user: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"whitelist", user.key().as_ref()], bump = whitelist_entry.bump)]
+    pub whitelist_entry: Option<Account<'info, WhitelistEntry>>,
+
+    /// The NFT whose unstake wait to report; without it only the position
+    /// lock counts.
+    #[account(
+        seeds = [b"stake_record", stake_record.nft_mint.as_ref()],
+        bump = stake_record.bump,
+        constraint = stake_record.user == user.key() @ ErrorCode::StakeRecordMismatch
+    )]
+    pub stake_record: Option<Account<'info, StakeRecord>>,
+}
+
+/// Seconds until each action's time-based checks pass; 0 means now. Pause
+/// flags, limits and slot guards are not included.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub struct Cooldowns {
+    pub stake_seconds: i64,
+    pub unstake_seconds: i64,
+    pub claim_seconds: i64,
+}
+
+pub(crate) fn get_cooldowns(ctx: Context<GetCooldowns>) -> Result<Cooldowns> {
+    let vault = &ctx.accounts.vault;
+    let now = Clock::get()?.unix_timestamp;
+
+    // Only the whitelist phase holds stakes back
+    let stake_seconds = if ctx.accounts.whitelist_entry.is_some() {
+        0
+    } else {
+        vault.public_start_timestamp.saturating_sub(now).max(0)
+    };
+
+    let (lock_seconds, claim_seconds) = match ctx.accounts.user_stake.as_ref() {
+        Some(user_stake) if user_stake.staked_nfts > 0 => (
+            user_stake.locked_until.saturating_sub(now).max(0),
+            cooldown_remaining(user_stake.last_update_timestamp, vault.claim_cooldown_seconds, now),
+        ),
+        _ => (0, 0),
+    };
+
+    let nft_seconds = match ctx.accounts.stake_record.as_ref() {
+        // Queued by `request_unstake`; waits for `finalize_unstake`
+        Some(record) if record.unlocks_at > 0 => record.unlocks_at.saturating_sub(now).max(0),
+        Some(record) => cooldown_remaining(record.staked_at, vault.action_cooldown_seconds, now),
+        None => 0,
+    };
+
+    Ok(Cooldowns {
+        stake_seconds,
+        unstake_seconds: lock_seconds.max(nft_seconds),
+        claim_seconds,
+    })
+}
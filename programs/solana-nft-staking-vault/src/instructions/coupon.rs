@@ -0,0 +1,262 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAccount}};
+use anchor_lang::solana_program::{
+    ed25519_program,
+    instruction::Instruction,
+    sysvar::{self, instructions::{load_current_index_checked, load_instruction_at_checked}},
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{book_primary_payout, pay_out_rewards, reward_source};
+use super::config::UpdateConfig;
+
+#[derive(Accounts)]
+#[instruction(amount: u64, nonce: u64)]
+pub struct RedeemCoupon<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// This user's accumulator; see `VaultShard`.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump,
+        constraint = vault_shard.load()?.index == VaultShard::index_for(&user.key()) @ ErrorCode::WrongVaultShard
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + CouponRedemption::INIT_SPACE,
+        seeds = [b"coupon", user.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub coupon_redemption: Account<'info, CouponRedemption>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = reward_token_mint.key() == vault.reward_token_mint
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = user
+    )]
+    pub user_reward_token_account: Account<'info, TokenAccount>,
+
+    /// Only required when the vault pays rewards from a funded pool.
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Instructions sysvar, used to find the Ed25519 verification.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn set_coupon_signer(
+    ctx: Context<UpdateConfig>,
+    coupon_signer: Option<Pubkey>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    vault.coupon_signer = coupon_signer;
+
+    emit!(CouponSignerUpdated {
+        coupon_signer,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn redeem_coupon(
+    ctx: Context<RedeemCoupon>,
+    amount: u64,
+    nonce: u64,
+    expires_at: i64,
+) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let user = ctx.accounts.user.key();
+    let clock = Clock::get()?;
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(clock.unix_timestamp < expires_at, ErrorCode::CouponExpired);
+    let coupon_signer = vault.coupon_signer.ok_or(ErrorCode::CouponsDisabled)?;
+
+    let instructions = ctx.accounts.instructions.to_account_info();
+    let current_index = load_current_index_checked(&instructions)?;
+    require!(current_index > 0, ErrorCode::InvalidCouponSignature);
+    let ed25519_ix = load_instruction_at_checked(current_index as usize - 1, &instructions)?;
+    verify_ed25519_instruction(
+        &ed25519_ix,
+        &coupon_signer,
+        &coupon_message(&user, amount, nonce, expires_at),
+    )?;
+
+    // The nonce account's `init` is what makes each coupon single-use
+    let redemption = &mut ctx.accounts.coupon_redemption;
+    redemption.user = user;
+    redemption.nonce = nonce;
+    redemption.amount = amount;
+    redemption.redeemed_at = clock.unix_timestamp;
+    redemption.bump = ctx.bumps.coupon_redemption;
+
+    book_primary_payout(vault, config, shard, amount, clock.unix_timestamp)?;
+
+    let source = reward_source(
+        vault,
+        vault.reward_mode,
+        vault.reward_token_mint,
+        &ctx.accounts.reward_token_mint,
+        ctx.accounts.reward_pool.as_ref(),
+    )?;
+    pay_out_rewards(
+        vault,
+        vault.reward_mode,
+        source,
+        ctx.accounts.user_reward_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    emit!(CouponRedeemed {
+        user,
+        nonce,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Bytes the coupon signer signs for `redeem_coupon`. Bound to this program
+/// so coupons cannot be replayed against another deployment.
+fn coupon_message(user: &Pubkey, amount: u64, nonce: u64, expires_at: i64) -> Vec<u8> {
+    [
+        b"nft-vault-coupon".as_ref(),
+        crate::ID.as_ref(),
+        user.as_ref(),
+        &amount.to_le_bytes(),
+        &nonce.to_le_bytes(),
+        &expires_at.to_le_bytes(),
+    ]
+    .concat()
+}
+
+/// Checks that `ix` is an Ed25519 program instruction verifying exactly one
+/// signature by `signer` over `message`, with all data inline.
+pub(super) fn verify_ed25519_instruction(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    const HEADER_LEN: usize = 16; // count, padding, 7 u16 offsets
+    const PUBKEY_LEN: usize = 32;
+    const SIGNATURE_LEN: usize = 64;
+
+    require!(
+        ix.program_id == ed25519_program::ID && ix.accounts.is_empty(),
+        ErrorCode::InvalidCouponSignature
+    );
+    let data = &ix.data;
+    require!(data.len() >= HEADER_LEN && data[0] == 1, ErrorCode::InvalidCouponSignature);
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_offset = read_u16(2) as usize;
+    let signature_ix_index = read_u16(4);
+    let pubkey_offset = read_u16(6) as usize;
+    let pubkey_ix_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix_index = read_u16(14);
+
+    // u16::MAX means "this instruction"; anything else could point the
+    // precompile at data we are not checking here
+    require!(
+        signature_ix_index == u16::MAX
+            && pubkey_ix_index == u16::MAX
+            && message_ix_index == u16::MAX,
+        ErrorCode::InvalidCouponSignature
+    );
+    require!(
+        signature_offset + SIGNATURE_LEN <= data.len()
+            && pubkey_offset + PUBKEY_LEN <= data.len()
+            && message_offset + message_size <= data.len(),
+        ErrorCode::InvalidCouponSignature
+    );
+    require!(
+        data[pubkey_offset..pubkey_offset + PUBKEY_LEN] == signer.to_bytes()
+            && data[message_offset..message_offset + message_size] == *message,
+        ErrorCode::InvalidCouponSignature
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ed25519_instruction(signer: &Pubkey, message: &[u8]) -> Instruction {
+        // Header, then pubkey, signature and message inline
+        let pubkey_offset = 16u16;
+        let signature_offset = pubkey_offset + 32;
+        let message_offset = signature_offset + 64;
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset,
+            u16::MAX,
+            pubkey_offset,
+            u16::MAX,
+            message_offset,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[0; 64]);
+        data.extend_from_slice(message);
+
+        Instruction {
+            program_id: ed25519_program::ID,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    #[test]
+    fn coupon_instruction_must_match_signer_and_message() {
+        let signer = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let message = coupon_message(&user, 500, 7, 1_000);
+        let ix = ed25519_instruction(&signer, &message);
+
+        assert!(verify_ed25519_instruction(&ix, &signer, &message).is_ok());
+        assert!(verify_ed25519_instruction(&ix, &Pubkey::new_unique(), &message).is_err());
+        assert!(verify_ed25519_instruction(&ix, &signer, &coupon_message(&user, 501, 7, 1_000)).is_err());
+    }
+}
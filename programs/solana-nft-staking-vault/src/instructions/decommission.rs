@@ -0,0 +1,291 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, SetAuthority};
+use spl_token::instruction::AuthorityType;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::governance::ManageGovernance;
+
+#[derive(Accounts)]
+pub struct DecommissionVault<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+
+    #[account(
+        mut,
+        constraint = reward_token_mint.key() == vault.reward_token_mint
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault"],
+        bump = vault.bump,
+        close = admin
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+}
+
+pub(crate) fn propose_decommission(
+    ctx: Context<ManageGovernance>,
+    mint_authority_recipient: Option<Pubkey>,
+    timelock_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+    require!(!vault.decommissioned, ErrorCode::VaultDecommissioned);
+    require!(vault.pending_decommission.is_none(), ErrorCode::DecommissionPending);
+    require!(
+        timelock_seconds >= PendingDecommission::MIN_TIMELOCK_SECONDS,
+        ErrorCode::InvalidTimelock
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let scheduled_timestamp = now
+        .checked_add(timelock_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    vault.pending_decommission = Some(PendingDecommission {
+        mint_authority_recipient,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+    });
+
+    emit!(DecommissionProposed {
+        mint_authority_recipient,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_decommission(ctx: Context<ManageGovernance>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+    require!(vault.pending_decommission.is_some(), ErrorCode::NoDecommissionPending);
+
+    vault.pending_decommission = None;
+
+    emit!(DecommissionCancelled {
+        cancelled_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn decommission_vault(ctx: Context<DecommissionVault>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+
+    let pending = vault.pending_decommission.clone()
+        .ok_or(ErrorCode::NoDecommissionPending)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= pending.scheduled_timestamp,
+        ErrorCode::TimelockNotExpired
+    );
+
+    // Existing stakers can still claim and unstake; only new stakes stop
+    vault.decommissioned = true;
+    config.pause_flags |= VaultConfig::PAUSE_STAKE;
+    vault.pending_decommission = None;
+
+    if let Some(recipient) = pending.mint_authority_recipient {
+        require!(vault.reward_mode == RewardMode::Mint, ErrorCode::InvalidRewardMode);
+
+        let seeds = &[b"vault".as_ref(), &[vault.bump]];
+        let signer = &[&seeds[..]];
+
+        let set_authority_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                account_or_mint: ctx.accounts.reward_token_mint.to_account_info(),
+                current_authority: vault.to_account_info(),
+            },
+            signer,
+        );
+        token::set_authority(
+            set_authority_ctx,
+            AuthorityType::MintTokens,
+            Some(recipient)
+        )?;
+    }
+
+    emit!(VaultDecommissioned {
+        mint_authority_recipient: pending.mint_authority_recipient,
+        executor: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn propose_mint_authority_reclaim(
+    ctx: Context<ManageGovernance>,
+    recipient: Pubkey,
+    timelock_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+    require!(vault.decommissioned, ErrorCode::VaultNotDecommissioned);
+    require!(vault.reward_mode == RewardMode::Mint, ErrorCode::InvalidRewardMode);
+    require!(
+        vault.pending_mint_authority_reclaim.is_none(),
+        ErrorCode::MintAuthorityReclaimPending
+    );
+    require!(
+        timelock_seconds >= PendingDecommission::MIN_TIMELOCK_SECONDS,
+        ErrorCode::InvalidTimelock
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let scheduled_timestamp = now
+        .checked_add(timelock_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    vault.pending_mint_authority_reclaim = Some(PendingMintAuthorityReclaim {
+        recipient,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+    });
+
+    emit!(MintAuthorityReclaimProposed {
+        recipient,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_mint_authority_reclaim(ctx: Context<ManageGovernance>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+    require!(
+        vault.pending_mint_authority_reclaim.is_some(),
+        ErrorCode::NoMintAuthorityReclaimPending
+    );
+
+    vault.pending_mint_authority_reclaim = None;
+
+    emit!(MintAuthorityReclaimCancelled {
+        cancelled_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn reclaim_mint_authority(ctx: Context<DecommissionVault>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+
+    let pending = vault.pending_mint_authority_reclaim.clone()
+        .ok_or(ErrorCode::NoMintAuthorityReclaimPending)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= pending.scheduled_timestamp,
+        ErrorCode::TimelockNotExpired
+    );
+
+    let reward_token_mint = &ctx.accounts.reward_token_mint;
+    require!(
+        reward_token_mint.mint_authority == COption::Some(vault.key()),
+        ErrorCode::InvalidMintAuthority
+    );
+
+    vault.pending_mint_authority_reclaim = None;
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    // Vault-created mints also have the vault as freeze authority
+    let mut authority_types = vec![AuthorityType::MintTokens];
+    if reward_token_mint.freeze_authority == COption::Some(vault.key()) {
+        authority_types.push(AuthorityType::FreezeAccount);
+    }
+    for authority_type in authority_types {
+        let set_authority_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                account_or_mint: reward_token_mint.to_account_info(),
+                current_authority: vault.to_account_info(),
+            },
+            signer,
+        );
+        token::set_authority(set_authority_ctx, authority_type, Some(pending.recipient))?;
+    }
+
+    emit!(MintAuthorityReclaimed {
+        recipient: pending.recipient,
+        executor: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+    require!(vault.decommissioned, ErrorCode::VaultNotDecommissioned);
+    require!(
+        vault.total_staked == 0 && vault.pending_unstakes == 0,
+        ErrorCode::NftsStillStaked
+    );
+
+    emit!(VaultClosed {
+        closed_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAccount}};
+use anchor_lang::solana_program::keccak;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{book_primary_payout, pay_out_rewards, reward_source, verify_merkle_proof};
+
+#[derive(Accounts)]
+#[instruction(merkle_root: [u8; 32], total_amount: u64, num_recipients: u32)]
+pub struct CreateDistribution<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Distribution::INIT_SPACE,
+        seeds = [b"distribution", vault.distribution_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ClaimedBitmap::space(num_recipients),
+        seeds = [b"claimed_bitmap", distribution.key().as_ref()],
+        bump
+    )]
+    pub claimed_bitmap: Account<'info, ClaimedBitmap>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDistribution<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// This claimant's accumulator; see `VaultShard`.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump,
+        constraint = vault_shard.load()?.index == VaultShard::index_for(&claimant.key()) @ ErrorCode::WrongVaultShard
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(
+        mut,
+        seeds = [b"distribution", distribution.id.to_le_bytes().as_ref()],
+        bump = distribution.bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+
+    #[account(
+        mut,
+        seeds = [b"claimed_bitmap", distribution.key().as_ref()],
+        bump = claimed_bitmap.bump
+    )]
+    pub claimed_bitmap: Account<'info, ClaimedBitmap>,
+
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = reward_token_mint.key() == vault.reward_token_mint
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = claimant
+    )]
+    pub claimant_reward_token_account: Account<'info, TokenAccount>,
+
+    /// Only required when the vault pays rewards from a funded pool.
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn create_distribution(
+    ctx: Context<CreateDistribution>,
+    merkle_root: [u8; 32],
+    total_amount: u64,
+    num_recipients: u32,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+    let now = Clock::get()?.unix_timestamp;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+    require!(total_amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        num_recipients > 0 && num_recipients <= Distribution::MAX_RECIPIENTS,
+        ErrorCode::InvalidDistribution
+    );
+
+    let distribution = &mut ctx.accounts.distribution;
+    distribution.id = vault.distribution_count;
+    distribution.merkle_root = merkle_root;
+    distribution.total_amount = total_amount;
+    distribution.claimed_amount = 0;
+    distribution.num_recipients = num_recipients;
+    distribution.created_by = ctx.accounts.admin.key();
+    distribution.created_at = now;
+    distribution.bump = ctx.bumps.distribution;
+
+    let bitmap = &mut ctx.accounts.claimed_bitmap;
+    bitmap.distribution = distribution.key();
+    bitmap.bits = vec![0; Distribution::bitmap_len(num_recipients)];
+    bitmap.bump = ctx.bumps.claimed_bitmap;
+
+    vault.distribution_count = vault.distribution_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(DistributionCreated {
+        distribution_id: distribution.id,
+        merkle_root,
+        total_amount,
+        num_recipients,
+        created_by: distribution.created_by,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn claim_distribution(
+    ctx: Context<ClaimDistribution>,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let distribution = &mut ctx.accounts.distribution;
+    let bitmap = &mut ctx.accounts.claimed_bitmap;
+    let claimant = ctx.accounts.claimant.key();
+    let clock = Clock::get()?;
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    require!(index < distribution.num_recipients, ErrorCode::InvalidMerkleProof);
+    require!(!bitmap.is_claimed(index), ErrorCode::DistributionAlreadyClaimed);
+
+    let leaf = distribution_leaf(index, &claimant, amount);
+    require!(
+        verify_merkle_proof(&proof, distribution.merkle_root, leaf),
+        ErrorCode::InvalidMerkleProof
+    );
+
+    distribution.claimed_amount = distribution.claimed_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        distribution.claimed_amount <= distribution.total_amount,
+        ErrorCode::InvalidDistribution
+    );
+    bitmap.set_claimed(index);
+
+    book_primary_payout(vault, config, shard, amount, clock.unix_timestamp)?;
+
+    let source = reward_source(
+        vault,
+        vault.reward_mode,
+        vault.reward_token_mint,
+        &ctx.accounts.reward_token_mint,
+        ctx.accounts.reward_pool.as_ref(),
+    )?;
+    pay_out_rewards(
+        vault,
+        vault.reward_mode,
+        source,
+        ctx.accounts.claimant_reward_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    emit!(DistributionClaimed {
+        distribution_id: distribution.id,
+        claimant,
+        index,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Leaf hash for `claim_distribution`. Leaves and inner nodes use distinct
+/// prefixes so an inner node can never be passed off as a leaf.
+fn distribution_leaf(index: u32, wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &[0u8],
+        &index.to_le_bytes(),
+        wallet.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merkle_proof_accepts_leaf_and_rejects_tampering() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let leaf_a = distribution_leaf(0, &alice, 100);
+        let leaf_b = distribution_leaf(1, &bob, 250);
+        let (left, right) = if leaf_a <= leaf_b { (leaf_a, leaf_b) } else { (leaf_b, leaf_a) };
+        let root = keccak::hashv(&[&[1u8], &left, &right]).to_bytes();
+
+        assert!(verify_merkle_proof(&[leaf_b], root, leaf_a));
+        assert!(verify_merkle_proof(&[leaf_a], root, leaf_b));
+        assert!(!verify_merkle_proof(&[leaf_b], root, distribution_leaf(0, &alice, 101)));
+        assert!(!verify_merkle_proof(&[leaf_a], root, distribution_leaf(1, &alice, 250)));
+    }
+}
@@ -0,0 +1,401 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{self, Mint, Token, TokenAccount, Transfer, CloseAccount},
+    metadata::{Metadata, MetadataAccount},
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{
+    accrue_user_rewards, ensure_whitelisted, ensure_not_suspended, sync_voter_weight,
+    ensure_cooldown_elapsed, ensure_trait_verified,
+};
+
+#[derive(Accounts)]
+pub struct StakeEdition<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(mut, seeds = [b"vault_stats"], bump = vault_stats.bump)]
+    pub vault_stats: Account<'info, VaultStats>,
+
+    #[account(mut, seeds = [b"vault_metrics"], bump = vault_metrics.bump)]
+    pub vault_metrics: Account<'info, VaultMetrics>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStakeAccount::INIT_SPACE,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Address is derived from the user; the account only exists
+    /// once a moderator has suspended (or unsuspended) this user.
+    #[account(seeds = [b"user_status", user.key().as_ref()], bump)]
+    pub user_status: UncheckedAccount<'info>,
+
+    /// Required to stake before `vault.public_start_timestamp`.
+    #[account(seeds = [b"whitelist", user.key().as_ref()], bump = whitelist_entry.bump)]
+    pub whitelist_entry: Option<Account<'info, WhitelistEntry>>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// Required while the vault has a trait gate.
+    #[account(seeds = [b"trait_pass", nft_mint.key().as_ref()], bump = trait_pass.bump)]
+    pub trait_pass: Option<Account<'info, TraitPass>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + EditionStake::INIT_SPACE,
+        seeds = [b"edition_stake", nft_mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub edition_stake: Account<'info, EditionStake>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"edition_escrow", nft_mint.key().as_ref(), user.key().as_ref()],
+        bump,
+        token::mint = nft_mint,
+        token::authority = vault
+    )]
+    pub edition_escrow: Account<'info, TokenAccount>,
+
+    /// CHECK: The user's voter-weight record; may not exist.
+    #[account(mut, seeds = [b"voter_weight_record", user.key().as_ref()], bump)]
+    pub voter_weight_record: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            nft_mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub nft_metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        mut,
+        constraint = user_nft_token_account.mint == nft_mint.key(),
+        constraint = user_nft_token_account.owner == user.key()
+    )]
+    pub user_nft_token_account: Account<'info, TokenAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeEdition<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(mut, seeds = [b"vault_stats"], bump = vault_stats.bump)]
+    pub vault_stats: Account<'info, VaultStats>,
+
+    #[account(mut, seeds = [b"vault_metrics"], bump = vault_metrics.bump)]
+    pub vault_metrics: Account<'info, VaultMetrics>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"edition_stake", nft_mint.key().as_ref(), user.key().as_ref()],
+        bump = edition_stake.bump,
+        has_one = user
+    )]
+    pub edition_stake: Account<'info, EditionStake>,
+
+    #[account(
+        mut,
+        seeds = [b"edition_escrow", nft_mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub edition_escrow: Account<'info, TokenAccount>,
+
+    /// CHECK: The user's voter-weight record; may not exist.
+    #[account(mut, seeds = [b"voter_weight_record", user.key().as_ref()], bump)]
+    pub voter_weight_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_nft_token_account.mint == nft_mint.key(),
+        constraint = user_nft_token_account.owner == user.key()
+    )]
+    pub user_nft_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn stake_edition(ctx: Context<StakeEdition>, amount: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    let is_new_staker = user_stake.user == Pubkey::default();
+    if is_new_staker {
+        user_stake.version = UserStakeAccount::CURRENT_VERSION;
+    }
+    user_stake.require_current_version()?;
+
+    require!(!vault.decommissioned, ErrorCode::VaultDecommissioned);
+    require!(!config.is_paused(VaultConfig::PAUSE_STAKE), ErrorCode::VaultPaused);
+    ensure_not_suspended(&ctx.accounts.user_status)?;
+    ensure_whitelisted(vault, ctx.accounts.whitelist_entry.as_deref(), clock.unix_timestamp)?;
+    require!(
+        config.circuit_breaker.can_execute(clock.unix_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+    config.daily_limit.reset_if_new_day(clock.unix_timestamp);
+    config.daily_limit.check_stake()?;
+    user_stake.take_rate_token(&vault.user_rate_limit, clock.unix_timestamp)?;
+
+    let weight = u32::try_from(amount).map_err(|_| ErrorCode::InvalidAmount)?;
+    require!(weight > 0, ErrorCode::InvalidAmount);
+    vault.check_stake_caps(user_stake.staked_nfts, weight)?;
+    require!(ctx.accounts.nft_mint.decimals == 0, ErrorCode::InvalidNft);
+    require!(
+        ctx.accounts.user_nft_token_account.amount >= amount,
+        ErrorCode::InvalidNft
+    );
+
+    let collection = ctx.accounts.nft_metadata.collection
+        .as_ref()
+        .ok_or(ErrorCode::NoCollectionFound)?;
+    require!(collection.verified, ErrorCode::CollectionNotVerified);
+    require!(collection.key == vault.collection_mint, ErrorCode::WrongCollection);
+    ensure_trait_verified(vault, ctx.accounts.trait_pass.as_deref())?;
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.user_nft_token_account.to_account_info(),
+            to: ctx.accounts.edition_escrow.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, amount)?;
+
+    user_stake.user = ctx.accounts.user.key();
+    user_stake.record_first_stake(clock.unix_timestamp);
+    if user_stake.staked_nfts == 0 {
+        user_stake.start_streak(clock.unix_timestamp);
+
+        emit!(StreakUpdated {
+            user: user_stake.user,
+            current_streak_days: 0,
+            streak_started_at: user_stake.streak_started_at,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+    user_stake.staked_nfts = user_stake.staked_nfts
+        .checked_add(weight)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.add_staked_at(
+        clock.unix_timestamp
+            .checked_mul(weight as i64)
+            .ok_or(ErrorCode::MathOverflow)?,
+    )?;
+    user_stake.start_warmup(vault.warmup_seconds, weight, clock.unix_timestamp)?;
+
+    let edition_stake = &mut ctx.accounts.edition_stake;
+    if edition_stake.amount == 0 {
+        edition_stake.user = ctx.accounts.user.key();
+        edition_stake.nft_mint = ctx.accounts.nft_mint.key();
+        edition_stake.staked_at = clock.unix_timestamp;
+        edition_stake.bump = ctx.bumps.edition_stake;
+    } else {
+        edition_stake.staked_at = edition_stake.average_staked_at(amount, clock.unix_timestamp)?;
+    }
+    edition_stake.last_staked_at = clock.unix_timestamp;
+    edition_stake.last_staked_slot = clock.slot;
+    user_stake.last_stake_slot = clock.slot;
+    edition_stake.amount = edition_stake.amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    vault.update_reward_index(clock.unix_timestamp)?;
+    vault.total_staked = vault.total_staked
+        .checked_add(weight)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    config.daily_limit.record_stake();
+    config.circuit_breaker.on_success();
+    sync_voter_weight(&ctx.accounts.voter_weight_record, user_stake.staked_nfts)?;
+    ctx.accounts.vault_stats.record_stake(
+        is_new_staker,
+        vault.total_staked,
+        clock.unix_timestamp,
+    )?;
+    ctx.accounts.vault_metrics.update(vault.total_staked, 0, clock.unix_timestamp)?;
+
+    emit!(EditionStaked {
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        amount,
+        edition_amount: edition_stake.amount,
+        staked_nfts: user_stake.staked_nfts,
+        total_staked: vault.total_staked,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn unstake_edition(ctx: Context<UnstakeEdition>, amount: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let edition_stake = &mut ctx.accounts.edition_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(
+        amount > 0 && amount <= edition_stake.amount,
+        ErrorCode::InvalidAmount
+    );
+    let weight = amount as u32;
+
+    // Same emergency escape hatch as `unstake_nft`
+    if !vault.emergency_mode {
+        require!(!config.is_paused(VaultConfig::PAUSE_UNSTAKE), ErrorCode::VaultPaused);
+        require!(
+            config.circuit_breaker.can_execute(clock.unix_timestamp),
+            ErrorCode::CircuitBreakerActive
+        );
+        require!(
+            clock.unix_timestamp >= user_stake.locked_until,
+            ErrorCode::StakeLocked
+        );
+        ensure_cooldown_elapsed(
+            edition_stake.last_staked_at,
+            vault.action_cooldown_seconds,
+            clock.unix_timestamp,
+            ErrorCode::TooFrequent,
+        )?;
+    }
+    require!(
+        vault.min_stake_slots_elapsed(edition_stake.last_staked_slot, clock.slot),
+        ErrorCode::StakedTooRecently
+    );
+
+    let rewards_earned = if vault.emergency_mode {
+        accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp).unwrap_or(0)
+    } else {
+        accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?
+    };
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.edition_escrow.to_account_info(),
+            to: ctx.accounts.user_nft_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, amount)?;
+
+    edition_stake.amount -= amount;
+    user_stake.remove_staked_at(
+        edition_stake.staked_at
+            .checked_mul(weight as i64)
+            .ok_or(ErrorCode::MathOverflow)?,
+    )?;
+    user_stake.staked_nfts = user_stake.staked_nfts
+        .checked_sub(weight)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    if edition_stake.staked_at.saturating_add(vault.warmup_seconds) > clock.unix_timestamp {
+        user_stake.warming_nfts = user_stake.warming_nfts.saturating_sub(weight);
+    }
+    user_stake.warming_nfts = user_stake.warming_nfts.min(user_stake.staked_nfts);
+
+    if user_stake.staked_nfts == 0 {
+        user_stake.clear_lock();
+        user_stake.end_streak();
+
+        emit!(StreakUpdated {
+            user: user_stake.user,
+            current_streak_days: 0,
+            streak_started_at: 0,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    vault.update_reward_index(clock.unix_timestamp)?;
+    vault.total_staked = vault.total_staked
+        .checked_sub(weight)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    sync_voter_weight(&ctx.accounts.voter_weight_record, user_stake.staked_nfts)?;
+    ctx.accounts.vault_stats.record_unstake()?;
+    ctx.accounts.vault_metrics.update(vault.total_staked, 0, clock.unix_timestamp)?;
+
+    emit!(EditionUnstaked {
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        amount,
+        edition_amount: edition_stake.amount,
+        staked_nfts: user_stake.staked_nfts,
+        total_staked: vault.total_staked,
+        timestamp: clock.unix_timestamp,
+    });
+
+    if edition_stake.amount == 0 {
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.edition_escrow.to_account_info(),
+                destination: ctx.accounts.user.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            signer,
+        );
+        token::close_account(close_ctx)?;
+        edition_stake.close(ctx.accounts.user.to_account_info())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,276 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", funder.key().as_ref()],
+        bump
+    )]
+    pub funder_role: Account<'info, AccountRole>,
+
+    pub reward_token_mint: Account<'info, Mint>,
+
+    /// Required when funding a secondary reward configured via `RewardConfig`.
+    #[account(
+        seeds = [b"reward_config", reward_token_mint.key().as_ref()],
+        bump = reward_config.bump
+    )]
+    pub reward_config: Option<Account<'info, RewardConfig>>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == reward_token_mint.key(),
+        constraint = funder_token_account.owner == funder.key()
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManagePoolWithdrawal<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct ExecutePoolWithdrawal<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+
+    pub reward_token_mint: Account<'info, Mint>,
+
+    /// Required when withdrawing a secondary reward configured via `RewardConfig`.
+    #[account(
+        seeds = [b"reward_config", reward_token_mint.key().as_ref()],
+        bump = reward_config.bump
+    )]
+    pub reward_config: Option<Account<'info, RewardConfig>>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == reward_token_mint.key()
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let funder_role = &ctx.accounts.funder_role;
+
+    funder_role.require_permission(Role::can_manage_treasury)?;
+    let reward_mode = reward_mode_for_mint(
+        vault,
+        ctx.accounts.reward_config.as_ref(),
+        ctx.accounts.reward_token_mint.key(),
+    )?;
+    require!(reward_mode == RewardMode::Pool, ErrorCode::InvalidRewardMode);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.funder_token_account.to_account_info(),
+            to: ctx.accounts.reward_pool.to_account_info(),
+            authority: ctx.accounts.funder.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, amount)?;
+
+    ctx.accounts.reward_pool.reload()?;
+
+    emit!(RewardsFunded {
+        funder: ctx.accounts.funder.key(),
+        amount,
+        pool_balance: ctx.accounts.reward_pool.amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Schedules taking `amount` back out of the `reward_mint` pool. The pool
+/// backs rewards stakers have accrued but not claimed, which aren't tracked
+/// on-chain, so the timelock gives them the chance to claim first.
+pub(crate) fn propose_pool_withdrawal(
+    ctx: Context<ManagePoolWithdrawal>,
+    reward_mint: Pubkey,
+    amount: u64,
+    destination: Pubkey,
+    timelock_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        vault.pending_pool_withdrawal.is_none(),
+        ErrorCode::PoolWithdrawalPending
+    );
+    require!(
+        timelock_seconds >= PendingPoolWithdrawal::MIN_TIMELOCK_SECONDS,
+        ErrorCode::InvalidTimelock
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let scheduled_timestamp = now
+        .checked_add(timelock_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    vault.pending_pool_withdrawal = Some(PendingPoolWithdrawal {
+        reward_mint,
+        amount,
+        destination,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+    });
+
+    emit!(PoolWithdrawalProposed {
+        reward_mint,
+        amount,
+        destination,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn execute_pool_withdrawal(ctx: Context<ExecutePoolWithdrawal>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+
+    let pending = vault.pending_pool_withdrawal
+        .clone()
+        .ok_or(ErrorCode::NoPoolWithdrawalPending)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= pending.scheduled_timestamp, ErrorCode::TimelockNotExpired);
+    require!(
+        ctx.accounts.reward_token_mint.key() == pending.reward_mint
+            && ctx.accounts.destination.key() == pending.destination,
+        ErrorCode::InvalidPoolWithdrawalAccount
+    );
+    let reward_mode = reward_mode_for_mint(
+        vault,
+        ctx.accounts.reward_config.as_ref(),
+        pending.reward_mint,
+    )?;
+    require!(reward_mode == RewardMode::Pool, ErrorCode::InvalidRewardMode);
+    require!(
+        ctx.accounts.reward_pool.amount >= pending.amount,
+        ErrorCode::InsufficientRewardFunds
+    );
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.reward_pool.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, pending.amount)?;
+
+    vault.pending_pool_withdrawal = None;
+
+    emit!(PoolWithdrawalExecuted {
+        reward_mint: pending.reward_mint,
+        amount: pending.amount,
+        destination: pending.destination,
+        pool_balance: ctx.accounts.reward_pool.amount - pending.amount,
+        executor: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_pool_withdrawal(ctx: Context<ManagePoolWithdrawal>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+    require!(
+        vault.pending_pool_withdrawal.is_some(),
+        ErrorCode::NoPoolWithdrawalPending
+    );
+
+    vault.pending_pool_withdrawal = None;
+
+    emit!(PoolWithdrawalCancelled {
+        cancelled_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+fn reward_mode_for_mint(
+    vault: &VaultAccount,
+    reward_config: Option<&Account<RewardConfig>>,
+    reward_mint: Pubkey,
+) -> Result<RewardMode> {
+    if reward_mint == vault.reward_token_mint {
+        return Ok(vault.reward_mode);
+    }
+
+    match reward_config {
+        Some(config) if config.reward_mint == reward_mint => Ok(config.reward_mode),
+        _ => err!(ErrorCode::InvalidRewardMint),
+    }
+}
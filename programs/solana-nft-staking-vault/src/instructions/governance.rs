@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ManageGovernance<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAction<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", proposer.key().as_ref()],
+        bump
+    )]
+    pub proposer_role: Account<'info, AccountRole>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + ActionProposal::INIT_SPACE,
+        seeds = [b"proposal", vault.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, ActionProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveAction<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub approver: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", approver.key().as_ref()],
+        bump
+    )]
+    pub approver_role: Account<'info, AccountRole>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, ActionProposal>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", executor.key().as_ref()],
+        bump
+    )]
+    pub executor_role: Account<'info, AccountRole>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, ActionProposal>,
+}
+
+pub(crate) fn set_approval_threshold(ctx: Context<ManageGovernance>, threshold: u8) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+    // Once multi-approval is enabled, changing it requires a proposal
+    require!(vault.approval_threshold <= 1, ErrorCode::ApprovalRequired);
+
+    vault.apply_approval_threshold(threshold)?;
+
+    emit!(ApprovalThresholdUpdated {
+        threshold,
+        updated_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn propose_action(ctx: Context<ProposeAction>, action: GovernanceAction) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let proposer_role = &ctx.accounts.proposer_role;
+
+    proposer_role.require_permission(|role| action.is_permitted(role))?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.id = vault.proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.action = action;
+    // Proposing counts as the first approval
+    proposal.approvals = vec![ctx.accounts.proposer.key()];
+    proposal.created_at = now;
+    proposal.executed = false;
+    proposal.bump = ctx.bumps.proposal;
+
+    vault.proposal_count = vault.proposal_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(ActionProposed {
+        proposal_id: proposal.id,
+        action: proposal.action.clone(),
+        proposer: proposal.proposer,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn approve_action(ctx: Context<ApproveAction>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let approver_role = &ctx.accounts.approver_role;
+    let approver = ctx.accounts.approver.key();
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(!proposal.executed, ErrorCode::ProposalAlreadyExecuted);
+    require!(!proposal.is_expired(now), ErrorCode::ProposalExpired);
+    approver_role.require_permission(|role| proposal.action.is_permitted(role))?;
+    require!(
+        !proposal.approvals.contains(&approver),
+        ErrorCode::AlreadyApproved
+    );
+    require!(
+        proposal.approvals.len() < ActionProposal::MAX_APPROVALS,
+        ErrorCode::TooManyApprovals
+    );
+
+    proposal.approvals.push(approver);
+
+    emit!(ActionApproved {
+        proposal_id: proposal.id,
+        approver,
+        approvals: proposal.approvals.len() as u8,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// `remaining_accounts` are the approvers' role accounts, in approval
+/// order; approvers whose role has since lapsed or been revoked don't count.
+pub(crate) fn execute_action<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteAction<'info>>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let proposal = &mut ctx.accounts.proposal;
+    let executor_role = &ctx.accounts.executor_role;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(!proposal.executed, ErrorCode::ProposalAlreadyExecuted);
+    require!(!proposal.is_expired(now), ErrorCode::ProposalExpired);
+    executor_role.require_permission(|role| proposal.action.is_permitted(role))?;
+    require!(
+        count_current_approvals(proposal, ctx.remaining_accounts, now)?
+            >= vault.approval_threshold.max(1) as usize,
+        ErrorCode::InsufficientApprovals
+    );
+
+    match proposal.action.clone() {
+        GovernanceAction::ExecuteUpgrade => {
+            // The loader CPI needs accounts this instruction doesn't take;
+            // `execute_upgrade` performs it once approved
+            let new_version = vault.approve_upgrade()?;
+
+            emit!(UpgradeApproved {
+                new_version,
+                approved_by: ctx.accounts.executor.key(),
+                timestamp: now,
+            });
+        }
+        GovernanceAction::UpdateConfig { new_reward_rate, new_collection_mint } => {
+            // Approved config changes still go through the timelock
+            let scheduled_timestamp = vault.schedule_config_change(
+                config,
+                new_reward_rate,
+                new_collection_mint,
+                now,
+                PendingConfigChange::MIN_TIMELOCK_SECONDS,
+                proposal.proposer,
+            )?;
+
+            emit!(ConfigChangeProposed {
+                old_reward_rate: config.reward_rate_per_second,
+                new_reward_rate,
+                old_collection_mint: vault.collection_mint,
+                new_collection_mint,
+                scheduled_timestamp,
+                proposer: proposal.proposer,
+                timestamp: now,
+            });
+        }
+        GovernanceAction::UnpauseVault => {
+            require!(config.pause_flags != 0, ErrorCode::NotPaused);
+            config.pause_flags = 0;
+
+            emit!(VaultUnpaused {
+                authority: ctx.accounts.executor.key(),
+                timestamp: now,
+            });
+        }
+        GovernanceAction::SetApprovalThreshold { threshold } => {
+            vault.apply_approval_threshold(threshold)?;
+
+            emit!(ApprovalThresholdUpdated {
+                threshold,
+                updated_by: ctx.accounts.executor.key(),
+                timestamp: now,
+            });
+        }
+    }
+
+    proposal.executed = true;
+
+    emit!(ActionExecuted {
+        proposal_id: proposal.id,
+        executor: ctx.accounts.executor.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Approvals whose role is still active and still permits the action.
+fn count_current_approvals<'info>(
+    proposal: &ActionProposal,
+    role_accounts: &'info [AccountInfo<'info>],
+    current_timestamp: i64,
+) -> Result<usize> {
+    require!(
+        role_accounts.len() <= proposal.approvals.len(),
+        ErrorCode::InvalidRoleAccount
+    );
+
+    let mut current = 0;
+    for (approver, account_info) in proposal.approvals.iter().zip(role_accounts) {
+        let (expected, _) = Pubkey::find_program_address(&[b"role", approver.as_ref()], &crate::ID);
+        require!(account_info.key() == expected, ErrorCode::InvalidRoleAccount);
+
+        let role_account: Account<AccountRole> = Account::try_from(account_info)?;
+        if role_account.is_active(current_timestamp)
+            && proposal.action.is_permitted(&role_account.role)
+        {
+            current += 1;
+        }
+    }
+    Ok(current)
+}
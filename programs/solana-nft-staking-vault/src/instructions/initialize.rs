@@ -0,0 +1,466 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::{self, Mint, Token, SetAuthority},
+    metadata::{self, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3, Metadata},
+};
+use spl_token::instruction::AuthorityType;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::roles::backfill_role_registry;
+
+#[derive(Accounts)]
+#[instruction(reward_tokens_per_day: u64, collection_mint: Pubkey, reward_mode: RewardMode)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VaultAccount::INIT_SPACE,
+        seeds = [b"vault"],
+        bump
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<VaultConfig>(),
+        seeds = [b"vault_config"],
+        bump
+    )]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VaultStats::INIT_SPACE,
+        seeds = [b"vault_stats"],
+        bump
+    )]
+    pub vault_stats: Account<'info, VaultStats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The initializer becomes the first SuperAdmin.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AccountRole::INIT_SPACE,
+        seeds = [b"role", authority.key().as_ref()],
+        bump
+    )]
+    pub authority_role: Account<'info, AccountRole>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RoleRegistry::INIT_SPACE,
+        seeds = [b"role_registry"],
+        bump
+    )]
+    pub role_registry: Account<'info, RoleRegistry>,
+
+    /// In `Mint` mode the authority hands its mint authority to the vault.
+    #[account(
+        mut,
+        constraint = reward_mode == RewardMode::Pool
+            || reward_token_mint.mint_authority == COption::Some(authority.key())
+            @ ErrorCode::InvalidMintAuthority
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(reward_decimals: u8)]
+pub struct InitializeVaultWithMint<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VaultAccount::INIT_SPACE,
+        seeds = [b"vault"],
+        bump
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<VaultConfig>(),
+        seeds = [b"vault_config"],
+        bump
+    )]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VaultStats::INIT_SPACE,
+        seeds = [b"vault_stats"],
+        bump
+    )]
+    pub vault_stats: Account<'info, VaultStats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The initializer becomes the first SuperAdmin.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AccountRole::INIT_SPACE,
+        seeds = [b"role", authority.key().as_ref()],
+        bump
+    )]
+    pub authority_role: Account<'info, AccountRole>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RoleRegistry::INIT_SPACE,
+        seeds = [b"role_registry"],
+        bump
+    )]
+    pub role_registry: Account<'info, RoleRegistry>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reward_mint"],
+        bump,
+        mint::decimals = reward_decimals,
+        mint::authority = vault,
+        mint::freeze_authority = vault
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitSuperAdmin<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump, has_one = authority)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Must not exist yet, so a revoked or downgraded authority can't
+    /// restore itself this way.
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AccountRole::INIT_SPACE,
+        seeds = [b"role", authority.key().as_ref()],
+        bump
+    )]
+    pub authority_role: Account<'info, AccountRole>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RoleRegistry::INIT_SPACE,
+        seeds = [b"role_registry"],
+        bump
+    )]
+    pub role_registry: Account<'info, RoleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRewardMetadata<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(address = vault.reward_token_mint)]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    /// CHECK: Created by the Token Metadata program at its PDA for the mint.
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            reward_token_mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub reward_metadata: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaultMetrics<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + VaultMetrics::INIT_SPACE,
+        seeds = [b"vault_metrics"],
+        bump
+    )]
+    pub vault_metrics: Account<'info, VaultMetrics>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn initialize_vault(
+    ctx: Context<InitializeVault>,
+    reward_tokens_per_day: u64,
+    collection_mint: Pubkey,
+    reward_mode: RewardMode,
+    max_stakes_per_day: u32,
+    max_claims_per_day: u32,
+    max_total_rewards_per_day: u64,
+    failure_threshold: u32,
+    reset_timeout: i64,
+    action_cooldown_seconds: i64,
+    claim_cooldown_seconds: i64,
+) -> Result<()> {
+    let params = VaultInitParams {
+        reward_tokens_per_day,
+        collection_mint,
+        max_stakes_per_day,
+        max_claims_per_day,
+        max_total_rewards_per_day,
+        failure_threshold,
+        reset_timeout,
+        action_cooldown_seconds,
+        claim_cooldown_seconds,
+    };
+    let vault = &mut ctx.accounts.vault;
+    vault.initialize(
+        &params,
+        ctx.accounts.authority.key(),
+        ctx.accounts.reward_token_mint.key(),
+        ctx.accounts.reward_token_mint.decimals,
+        ctx.bumps.vault,
+    )?;
+    ctx.accounts.vault_config.load_init()?.initialize(&params, vault, ctx.bumps.vault_config)?;
+    ctx.accounts.vault_stats.bump = ctx.bumps.vault_stats;
+    ctx.accounts.role_registry.bump = ctx.bumps.role_registry;
+    grant_super_admin(
+        &mut ctx.accounts.authority_role,
+        &mut ctx.accounts.role_registry,
+        ctx.accounts.authority.key(),
+        vault.last_update_timestamp,
+    )?;
+
+    // Pool-funded vaults pay out of a pre-funded token account and never
+    // take over the mint authority.
+    vault.reward_mode = reward_mode;
+    if reward_mode == RewardMode::Pool {
+        return Ok(());
+    }
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let set_authority_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        SetAuthority {
+            account_or_mint: ctx.accounts.reward_token_mint.to_account_info(),
+            current_authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer,
+    );
+
+    // The token program only succeeds once the vault holds the authority
+    token::set_authority(
+        set_authority_ctx, 
+        AuthorityType::MintTokens, 
+        Some(vault.key())
+    )?;
+
+    Ok(())
+}
+
+/// Gives a vault created before `initialize_vault` granted roles its
+/// first SuperAdmin: the vault authority, and only if it has no role yet.
+/// Creates the registry if it doesn't exist yet; other existing role
+/// accounts passed as remaining accounts are listed in it, since
+/// `initialize_role_registry` can no longer run afterwards.
+pub(crate) fn init_super_admin<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InitSuperAdmin<'info>>,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.role_registry;
+    registry.bump = ctx.bumps.role_registry;
+    backfill_role_registry(registry, ctx.remaining_accounts)?;
+
+    grant_super_admin(
+        &mut ctx.accounts.authority_role,
+        registry,
+        ctx.accounts.authority.key(),
+        Clock::get()?.unix_timestamp,
+    )
+}
+
+/// Writes a non-expiring SuperAdmin role granted by `authority` to itself.
+fn grant_super_admin(
+    role_account: &mut AccountRole,
+    registry: &mut RoleRegistry,
+    authority: Pubkey,
+    now: i64,
+) -> Result<()> {
+    role_account.user = authority;
+    role_account.role = Role::SuperAdmin;
+    role_account.granted_by = authority;
+    role_account.granted_at = now;
+    role_account.expires_at = None;
+    registry.upsert(role_account)?;
+
+    emit!(RoleGranted {
+        user: authority,
+        role: Role::SuperAdmin,
+        granted_by: authority,
+        expires_at: None,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn create_reward_metadata(
+    ctx: Context<CreateRewardMetadata>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        vault.reward_mode == RewardMode::Mint,
+        ErrorCode::InvalidMintAuthority
+    );
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let metadata_ctx = CpiContext::new_with_signer(
+        ctx.accounts.metadata_program.to_account_info(),
+        CreateMetadataAccountsV3 {
+            metadata: ctx.accounts.reward_metadata.to_account_info(),
+            mint: ctx.accounts.reward_token_mint.to_account_info(),
+            mint_authority: vault.to_account_info(),
+            payer: ctx.accounts.updater.to_account_info(),
+            update_authority: vault.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        },
+        signer,
+    );
+    let data = DataV2 {
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    };
+    metadata::create_metadata_accounts_v3(metadata_ctx, data, true, true, None)?;
+
+    emit!(RewardMetadataCreated {
+        mint: vault.reward_token_mint,
+        name,
+        symbol,
+        uri,
+        created_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn initialize_vault_with_mint(
+    ctx: Context<InitializeVaultWithMint>,
+    reward_decimals: u8,
+    reward_tokens_per_day: u64,
+    collection_mint: Pubkey,
+    max_stakes_per_day: u32,
+    max_claims_per_day: u32,
+    max_total_rewards_per_day: u64,
+    failure_threshold: u32,
+    reset_timeout: i64,
+    action_cooldown_seconds: i64,
+    claim_cooldown_seconds: i64,
+) -> Result<()> {
+    let params = VaultInitParams {
+        reward_tokens_per_day,
+        collection_mint,
+        max_stakes_per_day,
+        max_claims_per_day,
+        max_total_rewards_per_day,
+        failure_threshold,
+        reset_timeout,
+        action_cooldown_seconds,
+        claim_cooldown_seconds,
+    };
+    let vault = &mut ctx.accounts.vault;
+    vault.initialize(
+        &params,
+        ctx.accounts.authority.key(),
+        ctx.accounts.reward_token_mint.key(),
+        reward_decimals,
+        ctx.bumps.vault,
+    )?;
+    vault.reward_mode = RewardMode::Mint;
+    ctx.accounts.vault_config.load_init()?.initialize(&params, vault, ctx.bumps.vault_config)?;
+    ctx.accounts.vault_stats.bump = ctx.bumps.vault_stats;
+    ctx.accounts.role_registry.bump = ctx.bumps.role_registry;
+    grant_super_admin(
+        &mut ctx.accounts.authority_role,
+        &mut ctx.accounts.role_registry,
+        ctx.accounts.authority.key(),
+        vault.last_update_timestamp,
+    )?;
+
+    emit!(RewardMintCreated {
+        mint: vault.reward_token_mint,
+        decimals: reward_decimals,
+        timestamp: vault.last_update_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn initialize_vault_metrics(ctx: Context<InitializeVaultMetrics>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let metrics = &mut ctx.accounts.vault_metrics;
+
+    metrics.staked_ema = ctx.accounts.vault.total_staked as u128 * VaultMetrics::EMA_SCALE;
+    metrics.last_total_staked = ctx.accounts.vault.total_staked;
+    metrics.updated_at = now;
+    metrics.current_epoch = (now / VaultMetrics::EPOCH_SECONDS) as u64;
+    metrics.epoch_rewards = 0;
+    metrics.previous_epoch_rewards = 0;
+    metrics.bump = ctx.bumps.vault_metrics;
+
+    Ok(())
+}
@@ -0,0 +1,301 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{BPS_DENOMINATOR, pay_out_rewards, reward_source};
+
+use super::config::UpdateConfig;
+
+#[derive(Accounts)]
+pub struct InitializeInsurancePool<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+
+    #[account(address = vault.reward_token_mint)]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"insurance_pool"],
+        bump,
+        token::mint = reward_token_mint,
+        token::authority = vault
+    )]
+    pub insurance_pool: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageInsuranceClaim<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct PayInsuranceClaim<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance_pool"],
+        bump
+    )]
+    pub insurance_pool: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = recipient.mint == vault.reward_token_mint @ ErrorCode::InvalidInsuranceRecipient
+    )]
+    pub recipient: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FundInsurancePool<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// Any shard; the payout is held to its share of the supply cap.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(mut, address = vault.reward_token_mint)]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    /// Only required when the vault pays rewards from a funded pool.
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance_pool"],
+        bump
+    )]
+    pub insurance_pool: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Creates the reward-mint token account that collects the insurance share
+/// of claim fees. Must exist before `set_insurance_fee` turns the share on.
+pub(crate) fn initialize_insurance_pool(ctx: Context<InitializeInsurancePool>) -> Result<()> {
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+
+    emit!(InsurancePoolInitialized {
+        pool: ctx.accounts.insurance_pool.key(),
+        mint: ctx.accounts.reward_token_mint.key(),
+        initialized_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_insurance_fee(ctx: Context<UpdateConfig>, insurance_fee_bps: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        insurance_fee_bps <= BPS_DENOMINATOR,
+        ErrorCode::InvalidInsuranceFee
+    );
+
+    vault.insurance_fee_bps = insurance_fee_bps;
+
+    emit!(InsuranceFeeUpdated {
+        insurance_fee_bps,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Schedules a payout of `amount` from the insurance pool to `recipient`, a
+/// reward-mint token account, executable once the timelock has passed.
+pub(crate) fn propose_insurance_claim(
+    ctx: Context<ManageInsuranceClaim>,
+    recipient: Pubkey,
+    amount: u64,
+    reason: AdjustmentReason,
+    timelock_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+    require!(
+        vault.pending_insurance_claim.is_none(),
+        ErrorCode::InsuranceClaimPending
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let pending = PendingInsuranceClaim::schedule(
+        recipient,
+        amount,
+        reason.clone(),
+        ctx.accounts.admin.key(),
+        now,
+        timelock_seconds,
+    )?;
+    let scheduled_timestamp = pending.scheduled_timestamp;
+    vault.pending_insurance_claim = Some(pending);
+
+    emit!(InsuranceClaimProposed {
+        recipient,
+        amount,
+        reason,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn pay_insurance_claim(ctx: Context<PayInsuranceClaim>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+
+    let pending = vault.pending_insurance_claim
+        .clone()
+        .ok_or(ErrorCode::NoInsuranceClaimPending)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(pending.is_payable(now), ErrorCode::TimelockNotExpired);
+    require!(
+        ctx.accounts.recipient.key() == pending.recipient,
+        ErrorCode::InvalidInsuranceRecipient
+    );
+    require!(
+        ctx.accounts.insurance_pool.amount >= pending.amount,
+        ErrorCode::InsufficientInsuranceFunds
+    );
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.insurance_pool.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, pending.amount)?;
+
+    vault.pending_insurance_claim = None;
+
+    emit!(InsuranceClaimPaid {
+        recipient: pending.recipient,
+        amount: pending.amount,
+        reason: pending.reason,
+        pool_balance: ctx.accounts.insurance_pool.amount - pending.amount,
+        executor: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Moves the insurance share of early-unstake penalties, booked when
+/// shards are folded, into the pool. Anyone may crank it. Counts against
+/// the reward supply cap like any other payout, through the given shard's
+/// allowance; whatever doesn't fit stays owed for the next crank.
+pub(crate) fn fund_insurance_pool(ctx: Context<FundInsurancePool>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+
+    let amount = vault.insurance_penalties_owed
+        .min(shard.remaining_reward_supply(vault))
+        .min(shard.reward_allowance(vault));
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let source = reward_source(
+        vault,
+        vault.reward_mode,
+        vault.reward_token_mint,
+        &ctx.accounts.reward_token_mint,
+        ctx.accounts.reward_pool.as_ref(),
+    )?;
+    pay_out_rewards(
+        vault,
+        vault.reward_mode,
+        source,
+        ctx.accounts.insurance_pool.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    vault.insurance_penalties_owed -= amount;
+    shard.record_rewards_minted(amount, 0)?;
+
+    emit!(InsurancePoolFunded {
+        amount,
+        still_owed: vault.insurance_penalties_owed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_insurance_claim(ctx: Context<ManageInsuranceClaim>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+    require!(
+        vault.pending_insurance_claim.is_some(),
+        ErrorCode::NoInsuranceClaimPending
+    );
+
+    vault.pending_insurance_claim = None;
+
+    emit!(InsuranceClaimCancelled {
+        cancelled_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{accrue_user_rewards, nft_group, nft_bonus_bps, verify_merkle_proof};
+use super::config::UpdateConfig;
+
+#[derive(Accounts)]
+pub struct BackfillStakeRecord<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + StakeRecord::INIT_SPACE,
+        seeds = [b"stake_record", nft_mint.key().as_ref()],
+        bump
+    )]
+    pub stake_record: Account<'info, StakeRecord>,
+
+    #[account(
+        associated_token::mint = nft_mint,
+        associated_token::authority = vault,
+        constraint = vault_nft_token_account.amount == 1 @ ErrorCode::InvalidNft
+    )]
+    pub vault_nft_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Set-bonus tag; may be uninitialized for untagged NFTs.
+    #[account(seeds = [b"nft_group", nft_mint.key().as_ref()], bump)]
+    pub nft_group_tag: UncheckedAccount<'info>,
+
+    /// CHECK: Reward multiplier; may be uninitialized for plain NFTs.
+    #[account(seeds = [b"nft_multiplier", nft_mint.key().as_ref()], bump)]
+    pub nft_multiplier: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn set_legacy_stake_root(
+    ctx: Context<UpdateConfig>,
+    merkle_root: [u8; 32],
+    nft_count: u32,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.updater_role.require_permission(Role::can_update_config)?;
+    // Replacing the root could hand someone else's legacy NFT to a new leaf
+    require!(vault.legacy_stake_root.is_none(), ErrorCode::LegacyStakeRootAlreadySet);
+    require!(nft_count > 0, ErrorCode::InvalidAmount);
+
+    vault.legacy_stake_root = Some(merkle_root);
+    vault.unrecorded_legacy_nfts = nft_count;
+
+    emit!(LegacyStakeRootSet {
+        merkle_root,
+        nft_count,
+        set_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn backfill_stake_record(
+    ctx: Context<BackfillStakeRecord>,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+    require!(user_stake.unrecorded_nfts > 0, ErrorCode::NoUnrecordedNfts);
+
+    let user = ctx.accounts.user.key();
+    let nft_mint = ctx.accounts.nft_mint.key();
+    let root = vault.legacy_stake_root.ok_or(ErrorCode::InvalidMerkleProof)?;
+    require!(
+        verify_merkle_proof(&proof, root, legacy_stake_leaf(&user, &nft_mint)),
+        ErrorCode::InvalidMerkleProof
+    );
+
+    // Settle at the current boosts; from here the NFT counts toward its
+    // group and multiplier like any other, and unstaking takes them off
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    if let Some(group) = nft_group(&ctx.accounts.nft_group_tag)? {
+        user_stake.join_group(group)?;
+    }
+    user_stake.add_nft_bonus(nft_bonus_bps(&ctx.accounts.nft_multiplier)?)?;
+    user_stake.unrecorded_nfts -= 1;
+    vault.unrecorded_legacy_nfts = vault.unrecorded_legacy_nfts.saturating_sub(1);
+
+    // `staked_at_sum` already counts the NFT at `unrecorded_staked_at`
+    let stake_record = &mut ctx.accounts.stake_record;
+    stake_record.user = user;
+    stake_record.nft_mint = nft_mint;
+    stake_record.staked_at = user_stake.unrecorded_staked_at;
+    stake_record.rent_payer = user;
+    stake_record.bump = ctx.bumps.stake_record;
+
+    emit!(StakeRecordBackfilled {
+        user,
+        nft_mint,
+        staked_at: stake_record.staked_at,
+        unrecorded_nfts: user_stake.unrecorded_nfts,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Leaf hash for `backfill_stake_record`, prefixed like `whitelist_leaf`.
+fn legacy_stake_leaf(user: &Pubkey, nft_mint: &Pubkey) -> [u8; 32] {
+    keccak::hashv(&[&[0u8], b"legacy_stake", user.as_ref(), nft_mint.as_ref()]).to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_proof_binds_user_to_mint() {
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (alice_nft, bob_nft) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let alice_leaf = legacy_stake_leaf(&alice, &alice_nft);
+        let bob_leaf = legacy_stake_leaf(&bob, &bob_nft);
+        let (left, right) = if alice_leaf <= bob_leaf {
+            (alice_leaf, bob_leaf)
+        } else {
+            (bob_leaf, alice_leaf)
+        };
+        let root = keccak::hashv(&[&[1u8], &left, &right]).to_bytes();
+
+        assert!(verify_merkle_proof(&[bob_leaf], root, alice_leaf));
+        // Bob can't claim Alice's NFT, nor Alice Bob's
+        assert!(!verify_merkle_proof(&[alice_leaf], root, legacy_stake_leaf(&bob, &alice_nft)));
+        assert!(!verify_merkle_proof(&[bob_leaf], root, legacy_stake_leaf(&alice, &bob_nft)));
+    }
+}
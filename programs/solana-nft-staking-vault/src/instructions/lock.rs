@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::accrue_user_rewards;
+
+#[derive(Accounts)]
+pub struct LockStake<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    pub user: Signer<'info>,
+}
+
+pub(crate) fn lock_stake(ctx: Context<LockStake>, tier_index: u8) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(user_stake.staked_nfts > 0, ErrorCode::NoNftsStaked);
+    require!(
+        clock.unix_timestamp >= user_stake.locked_until,
+        ErrorCode::LockAlreadyActive
+    );
+    let tier = vault.lock_tiers
+        .get(tier_index as usize)
+        .cloned()
+        .ok_or(ErrorCode::InvalidLockTier)?;
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    user_stake.locked_until = clock.unix_timestamp
+        .checked_add(tier.duration_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.lock_boost_bps = tier.boost_bps;
+
+    emit!(StakeLocked {
+        user: ctx.accounts.user.key(),
+        tier_index,
+        locked_until: user_stake.locked_until,
+        boost_bps: tier.boost_bps,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn extend_lock(ctx: Context<LockStake>, tier_index: u8) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(
+        clock.unix_timestamp < user_stake.locked_until,
+        ErrorCode::LockNotActive
+    );
+    let tier = vault.lock_tiers
+        .get(tier_index as usize)
+        .cloned()
+        .ok_or(ErrorCode::InvalidLockTier)?;
+    let locked_until = clock.unix_timestamp
+        .checked_add(tier.duration_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        locked_until > user_stake.locked_until && tier.boost_bps >= user_stake.lock_boost_bps,
+        ErrorCode::InvalidLockExtension
+    );
+
+    let previous_locked_until = user_stake.locked_until;
+    let previous_boost_bps = user_stake.lock_boost_bps;
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    user_stake.locked_until = locked_until;
+    user_stake.lock_boost_bps = tier.boost_bps;
+
+    emit!(StakeLockExtended {
+        user: ctx.accounts.user.key(),
+        tier_index,
+        previous_locked_until,
+        previous_boost_bps,
+        locked_until,
+        boost_bps: tier.boost_bps,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
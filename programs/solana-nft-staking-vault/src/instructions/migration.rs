@@ -0,0 +1,485 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer, CloseAccount},
+    metadata::{Metadata, MetadataAccount},
+};
+use anchor_lang::InstructionData;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke_signed};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{
+    accrue_user_rewards, release_stake_record, load_stake_record, sync_voter_weight, nft_group,
+    nft_bonus_bps, burn_receipt, ensure_trait_verified,
+};
+use super::governance::ManageGovernance;
+
+#[derive(Accounts)]
+pub struct MigrateStake<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Whoever funded the NFT's accounts at stake time; receives
+    /// their rent. Checked against the stake record in the handler.
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Closed by the handler. Legacy NFTs staked before per-NFT
+    /// records existed need `backfill_stake_record` first.
+    #[account(mut, seeds = [b"stake_record", nft_mint.key().as_ref()], bump)]
+    pub stake_record: UncheckedAccount<'info>,
+
+    /// CHECK: Uninitialized for NFTs staked before receipts were issued;
+    /// validated in `burn_receipt`.
+    #[account(mut, seeds = [b"receipt_mint", nft_mint.key().as_ref()], bump)]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// Required whenever a receipt was issued for this NFT.
+    #[account(mut)]
+    pub user_receipt_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Set-bonus tag; may be uninitialized for untagged NFTs.
+    #[account(seeds = [b"nft_group", nft_mint.key().as_ref()], bump)]
+    pub nft_group_tag: UncheckedAccount<'info>,
+
+    /// CHECK: Reward multiplier; may be uninitialized for plain NFTs.
+    #[account(seeds = [b"nft_multiplier", nft_mint.key().as_ref()], bump)]
+    pub nft_multiplier: UncheckedAccount<'info>,
+
+    /// CHECK: The user's voter-weight record; may not exist.
+    #[account(mut, seeds = [b"voter_weight_record", user.key().as_ref()], bump)]
+    pub voter_weight_record: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            nft_mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub nft_metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_nft_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Must match `vault.migration_target`, checked in the handler.
+    #[account(executable)]
+    pub target_program: UncheckedAccount<'info>,
+
+    /// CHECK: Vault PDA of the successor deployment.
+    #[account(
+        mut,
+        seeds = [b"vault"],
+        seeds::program = target_program.key(),
+        bump
+    )]
+    pub target_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Config of the successor deployment, read during the CPI.
+    #[account(
+        seeds = [b"vault_config"],
+        seeds::program = target_program.key(),
+        bump
+    )]
+    pub target_vault_config: UncheckedAccount<'info>,
+
+    /// CHECK: Initialized by the successor deployment during the CPI.
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        seeds::program = target_program.key(),
+        bump
+    )]
+    pub target_user_stake: UncheckedAccount<'info>,
+
+    /// CHECK: Initialized by the successor deployment during the CPI.
+    #[account(
+        mut,
+        seeds = [b"stake_record", nft_mint.key().as_ref()],
+        seeds::program = target_program.key(),
+        bump
+    )]
+    pub target_stake_record: UncheckedAccount<'info>,
+
+    /// CHECK: The NFT's trait pass in the successor deployment; required
+    /// while its vault has a trait gate.
+    #[account(
+        seeds = [b"trait_pass", nft_mint.key().as_ref()],
+        seeds::program = target_program.key(),
+        bump
+    )]
+    pub target_trait_pass: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = nft_mint,
+        associated_token::authority = target_vault
+    )]
+    pub target_vault_nft_token_account: Account<'info, TokenAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptMigratedStake<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// Vault PDA of the predecessor deployment, signing via CPI
+    pub source_vault: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStakeAccount::INIT_SPACE,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + StakeRecord::INIT_SPACE,
+        seeds = [b"stake_record", nft_mint.key().as_ref()],
+        bump
+    )]
+    pub stake_record: Account<'info, StakeRecord>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// Required while the vault has a trait gate.
+    #[account(seeds = [b"trait_pass", nft_mint.key().as_ref()], bump = trait_pass.bump)]
+    pub trait_pass: Option<Account<'info, TraitPass>>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            nft_mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub nft_metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        associated_token::mint = nft_mint,
+        associated_token::authority = vault,
+        constraint = vault_nft_token_account.amount == 1 @ ErrorCode::InvalidNft
+    )]
+    pub vault_nft_token_account: Account<'info, TokenAccount>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn configure_migration(
+    ctx: Context<ManageGovernance>,
+    migration_target: Option<Pubkey>,
+    migration_source: Option<Pubkey>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_roles)?;
+    require!(
+        migration_target != Some(crate::ID) && migration_source != Some(crate::ID),
+        ErrorCode::InvalidMigrationProgram
+    );
+
+    vault.migration_target = migration_target;
+    vault.migration_source = migration_source;
+
+    emit!(MigrationConfigured {
+        migration_target,
+        migration_source,
+        updated_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn migrate_stake(ctx: Context<MigrateStake>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(
+        vault.migration_target == Some(ctx.accounts.target_program.key()),
+        ErrorCode::InvalidMigrationProgram
+    );
+    require!(user_stake.staked_nfts > 0, ErrorCode::NoNftsStaked);
+    require!(
+        ctx.accounts.rent_receiver.key()
+            == load_stake_record(&ctx.accounts.stake_record)?
+                .ok_or(ErrorCode::StakeRecordRequired)?
+                .rent_receiver(),
+        ErrorCode::InvalidRentReceiver
+    );
+    if !vault.emergency_mode {
+        require!(!config.is_paused(VaultConfig::PAUSE_UNSTAKE), ErrorCode::VaultPaused);
+        require!(clock.unix_timestamp >= user_stake.locked_until, ErrorCode::StakeLocked);
+    }
+
+    // Settle up to now so the successor starts accruing from this moment
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    let pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .and_then(|pending| pending.checked_add(user_stake.credited_rewards))
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.pending_rewards = 0;
+    user_stake.credited_rewards = 0;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault_nft_token_account.to_account_info(),
+            to: ctx.accounts.target_vault_nft_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    let close_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault_nft_token_account.to_account_info(),
+            destination: ctx.accounts.rent_receiver.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::close_account(close_ctx)?;
+
+    burn_receipt(
+        &vault.to_account_info(),
+        signer,
+        &ctx.accounts.receipt_mint,
+        ctx.accounts.user_receipt_token_account.as_ref(),
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.rent_receiver.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    if let Some(group) = nft_group(&ctx.accounts.nft_group_tag)? {
+        user_stake.leave_group(group);
+    }
+    user_stake.remove_nft_bonus(nft_bonus_bps(&ctx.accounts.nft_multiplier)?);
+    let staked_at = release_stake_record(
+        &ctx.accounts.stake_record,
+        user_stake,
+        &ctx.accounts.rent_receiver.to_account_info(),
+    )?;
+    user_stake.staked_nfts = user_stake.staked_nfts
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    user_stake.end_warmup_for(staked_at, vault.warmup_seconds, clock.unix_timestamp);
+    if user_stake.staked_nfts == 0 {
+        user_stake.clear_lock();
+        user_stake.end_streak();
+
+        emit!(StreakUpdated {
+            user: user_stake.user,
+            current_streak_days: 0,
+            streak_started_at: 0,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    vault.update_reward_index(clock.unix_timestamp)?;
+    vault.total_staked = vault.total_staked
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    sync_voter_weight(&ctx.accounts.voter_weight_record, user_stake.staked_nfts)?;
+
+    // An absent optional account is passed as the successor's program ID
+    let target_trait_pass = ctx.accounts.target_trait_pass
+        .as_ref()
+        .map_or(ctx.accounts.target_program.to_account_info(), |pass| pass.to_account_info());
+
+    // The successor runs this same program, so its instruction and
+    // account layouts are the ones generated for this crate.
+    let accept_accounts = crate::accounts::AcceptMigratedStake {
+        vault: ctx.accounts.target_vault.key(),
+        vault_config: ctx.accounts.target_vault_config.key(),
+        source_vault: vault.key(),
+        user_stake: ctx.accounts.target_user_stake.key(),
+        stake_record: ctx.accounts.target_stake_record.key(),
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        trait_pass: Some(target_trait_pass.key()),
+        nft_metadata: ctx.accounts.nft_metadata.key(),
+        vault_nft_token_account: ctx.accounts.target_vault_nft_token_account.key(),
+        metadata_program: ctx.accounts.metadata_program.key(),
+        system_program: ctx.accounts.system_program.key(),
+    };
+    let accept_ix = Instruction {
+        program_id: ctx.accounts.target_program.key(),
+        accounts: accept_accounts.to_account_metas(None),
+        data: crate::instruction::AcceptMigratedStake {
+            pending_rewards,
+            reward_token_mint: vault.reward_token_mint,
+            staked_at,
+        }
+        .data(),
+    };
+    invoke_signed(
+        &accept_ix,
+        &[
+            ctx.accounts.target_vault.to_account_info(),
+            ctx.accounts.target_vault_config.to_account_info(),
+            vault.to_account_info(),
+            ctx.accounts.target_user_stake.to_account_info(),
+            ctx.accounts.target_stake_record.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.nft_mint.to_account_info(),
+            target_trait_pass,
+            ctx.accounts.nft_metadata.to_account_info(),
+            ctx.accounts.target_vault_nft_token_account.to_account_info(),
+            ctx.accounts.metadata_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        signer,
+    )?;
+
+    emit!(StakeMigrated {
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        target_program: ctx.accounts.target_program.key(),
+        pending_rewards,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn accept_migrated_stake(
+    ctx: Context<AcceptMigratedStake>,
+    pending_rewards: u64,
+    reward_token_mint: Pubkey,
+    staked_at: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    if user_stake.user == Pubkey::default() {
+        user_stake.version = UserStakeAccount::CURRENT_VERSION;
+    }
+    user_stake.require_current_version()?;
+
+    let source_program = vault.migration_source
+        .ok_or(ErrorCode::InvalidMigrationProgram)?;
+    let (expected_source_vault, _) =
+        Pubkey::find_program_address(&[b"vault"], &source_program);
+    require!(
+        ctx.accounts.source_vault.key() == expected_source_vault,
+        ErrorCode::InvalidMigrationProgram
+    );
+    require!(!vault.decommissioned, ErrorCode::VaultDecommissioned);
+
+    let metadata_account = &ctx.accounts.nft_metadata;
+    let collection = metadata_account.collection.as_ref()
+        .ok_or(ErrorCode::NoCollectionFound)?;
+    require!(collection.verified, ErrorCode::CollectionNotVerified);
+    require!(
+        collection.key == vault.collection_mint,
+        ErrorCode::WrongCollection
+    );
+    ensure_trait_verified(vault, ctx.accounts.trait_pass.as_deref())?;
+
+    // Rewards denominated in a different mint can't be carried over
+    require!(
+        pending_rewards == 0 || reward_token_mint == vault.reward_token_mint,
+        ErrorCode::InvalidRewardMint
+    );
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+    // Accrued under the source vault, so outside this one's emission bound
+    user_stake.credited_rewards = user_stake.credited_rewards
+        .checked_add(pending_rewards)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    user_stake.user = ctx.accounts.user.key();
+    user_stake.record_first_stake(clock.unix_timestamp);
+    user_stake.staked_nfts = user_stake.staked_nfts
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // Loyalty keeps counting from when the NFT was first staked
+    let staked_at = staked_at.min(clock.unix_timestamp);
+    user_stake.add_staked_at(staked_at)?;
+
+    let stake_record = &mut ctx.accounts.stake_record;
+    stake_record.user = ctx.accounts.user.key();
+    stake_record.nft_mint = ctx.accounts.nft_mint.key();
+    stake_record.staked_at = staked_at;
+    stake_record.staked_slot = clock.slot;
+    stake_record.rent_payer = ctx.accounts.user.key();
+    stake_record.bump = ctx.bumps.stake_record;
+    user_stake.last_stake_slot = clock.slot;
+
+    vault.update_reward_index(clock.unix_timestamp)?;
+    vault.total_staked = vault.total_staked
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(StakeMigratedIn {
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        source_program,
+        pending_rewards,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
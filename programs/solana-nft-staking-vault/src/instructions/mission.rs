@@ -0,0 +1,241 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAccount}};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{book_primary_payout, pay_out_rewards, reward_source, ensure_not_suspended};
+
+#[derive(Accounts)]
+pub struct CreateMission<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = updater,
+        space = 8 + Mission::INIT_SPACE,
+        seeds = [b"mission", vault.mission_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub mission: Account<'info, Mission>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMission<'info> {
+    #[account(
+        mut,
+        seeds = [b"mission", mission.id.to_le_bytes().as_ref()],
+        bump = mission.bump
+    )]
+    pub mission: Account<'info, Mission>,
+
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteMission<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// This user's accumulator; see `VaultShard`.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump,
+        constraint = vault_shard.load()?.index == VaultShard::index_for(&user.key()) @ ErrorCode::WrongVaultShard
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(
+        mut,
+        seeds = [b"mission", mission.id.to_le_bytes().as_ref()],
+        bump = mission.bump
+    )]
+    pub mission: Account<'info, Mission>,
+
+    /// One per user and mission, so each reward is paid once.
+    #[account(
+        init,
+        payer = user,
+        space = 8 + MissionCompletion::INIT_SPACE,
+        seeds = [b"mission_completion", mission.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub mission_completion: Account<'info, MissionCompletion>,
+
+    #[account(seeds = [b"user_stake", user.key().as_ref()], bump)]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Address is derived from the user; the account only exists
+    /// once a moderator has suspended (or unsuspended) this user.
+    #[account(seeds = [b"user_status", user.key().as_ref()], bump)]
+    pub user_status: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = reward_token_mint.key() == vault.reward_token_mint
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = user
+    )]
+    pub user_reward_token_account: Account<'info, TokenAccount>,
+
+    /// Only required when the vault pays rewards from a funded pool.
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn create_mission(
+    ctx: Context<CreateMission>,
+    requirement: MissionRequirement,
+    reward: u64,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+    let now = Clock::get()?.unix_timestamp;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(reward > 0, ErrorCode::InvalidAmount);
+    require!(requirement.is_valid(), ErrorCode::InvalidMission);
+    require!(expires_at.map_or(true, |at| at > now), ErrorCode::InvalidMission);
+
+    let mission = &mut ctx.accounts.mission;
+    mission.id = vault.mission_count;
+    mission.requirement = requirement;
+    mission.reward = reward;
+    mission.expires_at = expires_at;
+    mission.active = true;
+    mission.completions = 0;
+    mission.bump = ctx.bumps.mission;
+
+    vault.mission_count = vault.mission_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(MissionCreated {
+        mission_id: mission.id,
+        requirement: mission.requirement.clone(),
+        reward,
+        expires_at,
+        created_by: ctx.accounts.updater.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_mission_active(ctx: Context<UpdateMission>, active: bool) -> Result<()> {
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    let mission = &mut ctx.accounts.mission;
+    mission.active = active;
+
+    emit!(MissionStatusUpdated {
+        mission_id: mission.id,
+        active,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn complete_mission(ctx: Context<CompleteMission>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let user_stake = &ctx.accounts.user_stake;
+    let mission = &mut ctx.accounts.mission;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    ensure_not_suspended(&ctx.accounts.user_status)?;
+    require!(mission.active, ErrorCode::MissionInactive);
+    require!(
+        mission.expires_at.map_or(true, |at| clock.unix_timestamp < at),
+        ErrorCode::MissionInactive
+    );
+    require!(
+        mission.requirement.is_met(user_stake, clock.unix_timestamp),
+        ErrorCode::MissionRequirementNotMet
+    );
+
+    let completion = &mut ctx.accounts.mission_completion;
+    completion.mission_id = mission.id;
+    completion.user = user_stake.user;
+    completion.completed_at = clock.unix_timestamp;
+    completion.bump = ctx.bumps.mission_completion;
+
+    mission.completions = mission.completions
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    book_primary_payout(vault, config, shard, mission.reward, clock.unix_timestamp)?;
+
+    let source = reward_source(
+        vault,
+        vault.reward_mode,
+        vault.reward_token_mint,
+        &ctx.accounts.reward_token_mint,
+        ctx.accounts.reward_pool.as_ref(),
+    )?;
+    pay_out_rewards(
+        vault,
+        vault.reward_mode,
+        source,
+        ctx.accounts.user_reward_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        mission.reward,
+    )?;
+
+    emit!(MissionCompleted {
+        mission_id: mission.id,
+        user: user_stake.user,
+        reward: mission.reward,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+pub(crate) mod booster;
+pub(crate) mod burn_boost;
+pub(crate) mod claim;
+pub(crate) mod compound;
+pub(crate) mod config;
+pub(crate) mod cooldown;
+pub(crate) mod coupon;
+pub(crate) mod decommission;
+pub(crate) mod distribution;
+pub(crate) mod edition;
+pub(crate) mod funding;
+pub(crate) mod governance;
+pub(crate) mod initialize;
+pub(crate) mod insurance;
+pub(crate) mod legacy;
+pub(crate) mod lock;
+pub(crate) mod migration;
+pub(crate) mod mission;
+pub(crate) mod moderation;
+pub(crate) mod oracle;
+pub(crate) mod pause;
+pub(crate) mod position;
+pub(crate) mod raffle;
+pub(crate) mod recovery;
+pub(crate) mod referral;
+pub(crate) mod reward_adjustment;
+pub(crate) mod roles;
+pub(crate) mod season;
+pub(crate) mod secondary_rewards;
+pub(crate) mod session;
+pub(crate) mod shard;
+pub(crate) mod snapshot;
+pub(crate) mod squad;
+pub(crate) mod stake;
+pub(crate) mod token_boost;
+pub(crate) mod trait_gate;
+pub(crate) mod treasury;
+pub(crate) mod unstake;
+pub(crate) mod upgrade;
+pub(crate) mod voting;
+pub(crate) mod whitelist;
+
+pub use booster::*;
+pub use burn_boost::*;
+pub use claim::*;
+pub use compound::*;
+pub use config::*;
+pub use cooldown::*;
+pub use coupon::*;
+pub use decommission::*;
+pub use distribution::*;
+pub use edition::*;
+pub use funding::*;
+pub use governance::*;
+pub use initialize::*;
+pub use insurance::*;
+pub use legacy::*;
+pub use lock::*;
+pub use migration::*;
+pub use mission::*;
+pub use moderation::*;
+pub use oracle::*;
+pub use pause::*;
+pub use position::*;
+pub use raffle::*;
+pub use recovery::*;
+pub use referral::*;
+pub use reward_adjustment::*;
+pub use roles::*;
+pub use season::*;
+pub use secondary_rewards::*;
+pub use session::*;
+pub use shard::*;
+pub use snapshot::*;
+pub use squad::*;
+pub use stake::*;
+pub use token_boost::*;
+pub use trait_gate::*;
+pub use treasury::*;
+pub use unstake::*;
+pub use upgrade::*;
+pub use voting::*;
+pub use whitelist::*;
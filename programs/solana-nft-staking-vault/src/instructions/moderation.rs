@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct ModerateUser<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub moderator: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", moderator.key().as_ref()],
+        bump
+    )]
+    pub moderator_role: Account<'info, AccountRole>,
+
+    #[account(
+        init_if_needed,
+        payer = moderator,
+        space = 8 + UserStatus::INIT_SPACE,
+        seeds = [b"user_status", user.as_ref()],
+        bump
+    )]
+    pub user_status: Account<'info, UserStatus>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn suspend_user(ctx: Context<ModerateUser>, user: Pubkey) -> Result<()> {
+    let moderator_role = &ctx.accounts.moderator_role;
+
+    moderator_role.require_permission(Role::can_moderate_users)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let user_status = &mut ctx.accounts.user_status;
+    require!(!user_status.suspended, ErrorCode::UserAlreadySuspended);
+
+    user_status.user = user;
+    user_status.suspended = true;
+    user_status.updated_by = ctx.accounts.moderator.key();
+    user_status.updated_at = now;
+    user_status.bump = ctx.bumps.user_status;
+
+    emit!(UserSuspended {
+        user,
+        suspended_by: ctx.accounts.moderator.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn unsuspend_user(ctx: Context<ModerateUser>, user: Pubkey) -> Result<()> {
+    let moderator_role = &ctx.accounts.moderator_role;
+
+    moderator_role.require_permission(Role::can_moderate_users)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let user_status = &mut ctx.accounts.user_status;
+    require!(user_status.suspended, ErrorCode::UserNotSuspended);
+
+    user_status.suspended = false;
+    user_status.updated_by = ctx.accounts.moderator.key();
+    user_status.updated_at = now;
+
+    emit!(UserUnsuspended {
+        user,
+        unsuspended_by: ctx.accounts.moderator.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::BPS_DENOMINATOR;
+use super::config::UpdateConfig;
+
+#[derive(Accounts)]
+pub struct RefreshOracleRate<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(address = vault.reward_token_mint)]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    /// Owner is checked by the Pyth receiver type; the feed id is checked
+    /// against `vault.oracle` when the price is read.
+    pub price_update: Account<'info, PriceUpdateV2>,
+}
+
+pub(crate) fn set_oracle_config(
+    ctx: Context<UpdateConfig>,
+    oracle: Option<OracleConfig>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    if let Some(config) = &oracle {
+        require!(config.is_valid(), ErrorCode::InvalidOracleConfig);
+    }
+
+    vault.oracle = oracle.clone();
+
+    emit!(OracleConfigUpdated {
+        oracle,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn refresh_oracle_rate(ctx: Context<RefreshOracleRate>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let oracle = vault.oracle.clone().ok_or(ErrorCode::OracleNotConfigured)?;
+    let clock = Clock::get()?;
+
+    let price = ctx.accounts.price_update
+        .get_price_no_older_than(&clock, oracle.max_staleness_seconds, &oracle.feed_id)
+        .map_err(|_| ErrorCode::StaleOraclePrice)?;
+    require!(price.price > 0, ErrorCode::InvalidOraclePrice);
+    require!(
+        (price.conf as u128) * BPS_DENOMINATOR as u128
+            <= (price.price as u128) * oracle.max_confidence_bps as u128,
+        ErrorCode::OracleConfidenceTooWide
+    );
+
+    let old_reward_rate = config.reward_rate_per_second;
+    let new_reward_rate = oracle.rate_for_price(
+        price.price as u64,
+        price.exponent,
+        ctx.accounts.reward_token_mint.decimals,
+    )?;
+    vault.apply_config_update(config, Some(new_reward_rate), None)?;
+
+    emit!(OracleRateRefreshed {
+        price: price.price,
+        exponent: price.exponent,
+        publish_time: price.publish_time,
+        old_reward_rate,
+        new_reward_rate,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct PauseVault<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", authority.key().as_ref()],
+        bump
+    )]
+    pub user_role: Account<'info, AccountRole>,
+}
+
+pub(crate) fn pause_vault(ctx: Context<PauseVault>) -> Result<()> {
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let pauser_role = &ctx.accounts.user_role;
+    
+    require!(config.pause_flags != VaultConfig::PAUSE_ALL, ErrorCode::AlreadyPaused);
+    pauser_role.require_permission(Role::can_pause_vault)?;
+    
+    config.pause_flags = VaultConfig::PAUSE_ALL;
+    
+    emit!(VaultPaused {
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub(crate) fn unpause_vault(ctx: Context<PauseVault>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let unpauser_role = &ctx.accounts.user_role;
+    
+    require!(config.pause_flags != 0, ErrorCode::NotPaused);
+    unpauser_role.require_permission(Role::can_pause_vault)?;
+    require!(vault.approval_threshold <= 1, ErrorCode::ApprovalRequired);
+    
+    config.pause_flags = 0;
+    
+    emit!(VaultUnpaused {
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub(crate) fn set_pause_flags(ctx: Context<PauseVault>, pause_flags: u8) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let pauser_role = &ctx.accounts.user_role;
+
+    pauser_role.require_permission(Role::can_pause_vault)?;
+    require!(
+        pause_flags & !VaultConfig::PAUSE_ALL == 0,
+        ErrorCode::InvalidPauseFlags
+    );
+
+    // Lifting any pause is an unpause and follows the same approval rule
+    let lifted = config.pause_flags & !pause_flags;
+    if lifted != 0 {
+        require!(vault.approval_threshold <= 1, ErrorCode::ApprovalRequired);
+    }
+
+    config.pause_flags = pause_flags;
+
+    emit!(PauseFlagsUpdated {
+        pause_flags,
+        updated_by: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_emergency_mode(ctx: Context<PauseVault>, enabled: bool) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.user_role;
+
+    admin_role.require_permission(Role::can_update_config)?;
+    require!(vault.emergency_mode != enabled, ErrorCode::EmergencyModeUnchanged);
+
+    vault.emergency_mode = enabled;
+
+    emit!(EmergencyModeUpdated {
+        enabled,
+        updated_by: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn trip_circuit_breaker(ctx: Context<PauseVault>) -> Result<()> {
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let tripper_role = &ctx.accounts.user_role;
+
+    tripper_role.require_permission(Role::can_pause_vault)?;
+    require!(
+        config.circuit_breaker.manually_tripped == 0,
+        ErrorCode::CircuitBreakerAlreadyTripped
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    config.circuit_breaker.trip(now);
+
+    emit!(CircuitBreakerTripped {
+        tripped_by: ctx.accounts.authority.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn reset_circuit_breaker(ctx: Context<PauseVault>) -> Result<()> {
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let resetter_role = &ctx.accounts.user_role;
+
+    resetter_role.require_permission(Role::can_pause_vault)?;
+
+    config.circuit_breaker.reset();
+    config.circuit_breaker_resets = config.circuit_breaker_resets.wrapping_add(1);
+
+    emit!(CircuitBreakerReset {
+        reset_by: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,486 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Burn, FreezeAccount, ThawAccount};
+use anchor_lang::system_program;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{BPS_DENOMINATOR, ensure_not_suspended, sync_voter_weight};
+
+#[derive(Accounts)]
+pub struct SetClaimDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRecoveryDelegate<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferStakeOwnership<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", old_owner.key().as_ref()],
+        bump,
+        close = rent_receiver
+    )]
+    pub old_user_stake: Account<'info, UserStakeAccount>,
+
+    /// CHECK: The wallet giving up the position; only used to derive its accounts.
+    pub old_owner: UncheckedAccount<'info>,
+
+    /// The old wallet itself or its recovery delegate.
+    #[account(
+        constraint = authority.key() == old_owner.key()
+            || old_user_stake.recovery_delegate == Some(authority.key())
+            @ ErrorCode::UnauthorizedRecovery
+    )]
+    pub authority: Signer<'info>,
+
+    /// Must not hold a position yet; pays for the new one.
+    #[account(
+        init,
+        payer = new_owner,
+        space = 8 + UserStakeAccount::INIT_SPACE,
+        seeds = [b"user_stake", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+
+    /// CHECK: Gets the old position's rent: its sponsor, if any, otherwise
+    /// the new wallet.
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == old_user_stake.rent_payer.unwrap_or(new_owner.key())
+            @ ErrorCode::InvalidRentReceiver
+    )]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    /// CHECK: Address is derived from the old wallet; the account only
+    /// exists once a moderator has suspended (or unsuspended) it.
+    #[account(seeds = [b"user_status", old_owner.key().as_ref()], bump)]
+    pub old_user_status: UncheckedAccount<'info>,
+
+    /// CHECK: The old wallet's voter-weight record; may not exist.
+    #[account(mut, seeds = [b"voter_weight_record", old_owner.key().as_ref()], bump)]
+    pub old_voter_weight_record: UncheckedAccount<'info>,
+
+    /// CHECK: The new wallet's voter-weight record; may not exist.
+    #[account(mut, seeds = [b"voter_weight_record", new_owner.key().as_ref()], bump)]
+    pub new_voter_weight_record: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardSplit<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayoutAddress<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        constraint = payout_account.mint == vault.reward_token_mint @ ErrorCode::InvalidPayoutDestination
+    )]
+    pub payout_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUserStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump,
+        close = rent_receiver
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: The sponsor that paid for `user_stake`, or the user.
+    #[account(
+        mut,
+        constraint = rent_receiver.key()
+            == user_stake.rent_payer.unwrap_or(user.key()) @ ErrorCode::InvalidRentReceiver
+    )]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// CHECK: May still be on an old layout that `Account` can't
+    /// deserialize; owner and discriminator are checked in the handler.
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn set_claim_delegate(
+    ctx: Context<SetClaimDelegate>,
+    delegate: Option<Pubkey>,
+) -> Result<()> {
+    let user_stake = &mut ctx.accounts.user_stake;
+
+    user_stake.require_current_version()?;
+    user_stake.claim_delegate = delegate;
+
+    emit!(ClaimDelegateUpdated {
+        user: ctx.accounts.user.key(),
+        delegate,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_recovery_delegate(
+    ctx: Context<SetRecoveryDelegate>,
+    delegate: Option<Pubkey>,
+) -> Result<()> {
+    let user_stake = &mut ctx.accounts.user_stake;
+
+    user_stake.require_current_version()?;
+    user_stake.recovery_delegate = delegate;
+
+    emit!(RecoveryDelegateUpdated {
+        user: ctx.accounts.user.key(),
+        delegate,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn transfer_stake_ownership<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TransferStakeOwnership<'info>>,
+) -> Result<()> {
+    let old_stake = &ctx.accounts.old_user_stake;
+    let old_owner = ctx.accounts.old_owner.key();
+    let new_owner = ctx.accounts.new_owner.key();
+    let clock = Clock::get()?;
+
+    old_stake.require_current_version()?;
+    ensure_not_suspended(&ctx.accounts.old_user_status)?;
+    require!(old_owner != new_owner, ErrorCode::InvalidStakeTransfer);
+    require!(
+        old_stake.booster_boost_bps == 0 && old_stake.squad.is_none(),
+        ErrorCode::PositionNotTransferable
+    );
+    // Legacy proofs name the old wallet, so their records must exist first
+    require!(old_stake.unrecorded_nfts == 0, ErrorCode::StakeRecordRequired);
+    require!(
+        ctx.remaining_accounts.len() % 4 == 0
+            && (ctx.remaining_accounts.len() / 4) as u32 == old_stake.staked_nfts,
+        ErrorCode::IncompleteStakeTransfer
+    );
+
+    let owner_signed = ctx.accounts.authority.key() == old_owner;
+    let vault_info = ctx.accounts.vault.to_account_info();
+    let seeds = &[b"vault".as_ref(), &[ctx.accounts.vault.bump]];
+    let signer = &[&seeds[..]];
+
+    for accounts in ctx.remaining_accounts.chunks(4) {
+        let mut stake_record: Account<StakeRecord> = Account::try_from(&accounts[0])?;
+        require!(stake_record.user == old_owner, ErrorCode::IncompleteStakeTransfer);
+
+        stake_record.user = new_owner;
+        // Rent the old wallet paid itself follows the position
+        if stake_record.rent_payer == old_owner {
+            stake_record.rent_payer = new_owner;
+        }
+        stake_record.exit(ctx.program_id)?;
+
+        move_receipt(
+            &vault_info,
+            signer,
+            &stake_record.nft_mint,
+            &accounts[1],
+            &accounts[2],
+            &accounts[3],
+            &ctx.accounts.authority.to_account_info(),
+            owner_signed,
+            old_owner,
+            new_owner,
+            &ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
+
+    let mut position = old_stake.clone().into_inner();
+    position.user = new_owner;
+    position.rent_payer = None;
+    // Anything the old wallet may have redirected is reset
+    position.claim_delegate = None;
+    position.recovery_delegate = None;
+    position.payout_address = None;
+    position.reward_split.clear();
+    // Secondary rewards up to now stay with the old wallet's records
+    position.secondary_nft_seconds_floor = position.nft_seconds_at(clock.unix_timestamp)?;
+    ctx.accounts.new_user_stake.set_inner(position);
+
+    sync_voter_weight(&ctx.accounts.old_voter_weight_record, 0)?;
+    sync_voter_weight(&ctx.accounts.new_voter_weight_record, old_stake.staked_nfts)?;
+
+    emit!(StakeOwnershipTransferred {
+        old_owner,
+        new_owner,
+        authority: ctx.accounts.authority.key(),
+        staked_nfts: old_stake.staked_nfts,
+        pending_rewards: old_stake.pending_rewards,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_payout_address(ctx: Context<SetPayoutAddress>) -> Result<()> {
+    let user_stake = &mut ctx.accounts.user_stake;
+
+    user_stake.require_current_version()?;
+    user_stake.payout_address = ctx.accounts.payout_account.as_ref().map(|account| account.key());
+
+    emit!(PayoutAddressUpdated {
+        user: ctx.accounts.user.key(),
+        payout_address: user_stake.payout_address,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_reward_split(ctx: Context<SetRewardSplit>, shares: Vec<RewardShare>) -> Result<()> {
+    let user_stake = &mut ctx.accounts.user_stake;
+
+    user_stake.require_current_version()?;
+    require!(shares.len() <= RewardShare::MAX_SHARES, ErrorCode::InvalidRewardSplit);
+    let mut total_bps: u64 = 0;
+    for share in &shares {
+        require!(share.bps > 0, ErrorCode::InvalidRewardSplit);
+        total_bps += share.bps as u64;
+    }
+    require!(total_bps <= BPS_DENOMINATOR, ErrorCode::InvalidRewardSplit);
+
+    user_stake.reward_split = shares;
+
+    emit!(RewardSplitUpdated {
+        user: ctx.accounts.user.key(),
+        shares: user_stake.reward_split.clone(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn close_user_stake(ctx: Context<CloseUserStake>) -> Result<()> {
+    let user_stake = &ctx.accounts.user_stake;
+
+    require!(user_stake.staked_nfts == 0, ErrorCode::NftsStillStaked);
+    require!(
+        user_stake.pending_rewards == 0
+            && user_stake.redistributed_rewards == 0
+            && user_stake.credited_rewards == 0,
+        ErrorCode::UnclaimedRewards
+    );
+    require!(
+        user_stake.compound_position.amount == 0,
+        ErrorCode::UnclaimedRewards
+    );
+
+    emit!(UserStakeClosed {
+        user: ctx.accounts.user.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+    let user_stake = &ctx.accounts.user_stake;
+
+    require!(user_stake.owner == &crate::ID, ErrorCode::InvalidAccountLayout);
+    require!(
+        user_stake.try_borrow_data()?.starts_with(UserStakeAccount::DISCRIMINATOR),
+        ErrorCode::InvalidAccountLayout
+    );
+
+    let new_len = 8 + UserStakeAccount::INIT_SPACE;
+    let current_len = user_stake.data_len();
+    require!(current_len <= new_len, ErrorCode::InvalidAccountLayout);
+
+    // Top up rent for the larger layout before growing the account
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(user_stake.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: user_stake.to_account_info(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    // Newly added trailing fields read back as zero
+    user_stake.resize(new_len)?;
+
+    let mut account =
+        UserStakeAccount::try_deserialize(&mut &user_stake.try_borrow_data()?[..])?;
+    require!(
+        account.version < UserStakeAccount::CURRENT_VERSION,
+        ErrorCode::AccountAlreadyMigrated
+    );
+    account.upgrade(&ctx.accounts.vault);
+    account.try_serialize(&mut &mut user_stake.try_borrow_mut_data()?[..])?;
+
+    emit!(AccountMigrated {
+        account: user_stake.key(),
+        previous_len: current_len as u32,
+        version: UserStakeAccount::CURRENT_VERSION,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Re-issues an NFT's receipt to the new owner of its position. The old
+/// receipt is burned when the old wallet signed; a recovery delegate cannot
+/// burn it, so it is left frozen where it is.
+#[allow(clippy::too_many_arguments)]
+fn move_receipt<'info>(
+    vault: &AccountInfo<'info>,
+    vault_signer: &[&[&[u8]]],
+    nft_mint: &Pubkey,
+    receipt_mint: &AccountInfo<'info>,
+    old_receipt_account: &AccountInfo<'info>,
+    new_receipt_account: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    owner_signed: bool,
+    old_owner: Pubkey,
+    new_owner: Pubkey,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let (expected_mint, _) = Pubkey::find_program_address(
+        &[b"receipt_mint", nft_mint.as_ref()],
+        &crate::ID,
+    );
+    require!(receipt_mint.key() == expected_mint, ErrorCode::ReceiptAccountRequired);
+    if receipt_mint.owner != &token::ID || receipt_mint.data_is_empty() {
+        return Ok(());
+    }
+    let mint = Mint::try_deserialize(&mut &receipt_mint.data.borrow()[..])?;
+    if mint.supply == 0 {
+        return Ok(());
+    }
+
+    let old_receipt = TokenAccount::try_deserialize(&mut &old_receipt_account.data.borrow()[..])?;
+    let new_receipt = TokenAccount::try_deserialize(&mut &new_receipt_account.data.borrow()[..])?;
+    require!(
+        old_receipt.mint == expected_mint
+            && old_receipt.owner == old_owner
+            && old_receipt.amount == 1
+            && new_receipt.mint == expected_mint
+            && new_receipt.owner == new_owner,
+        ErrorCode::ReceiptAccountRequired
+    );
+
+    if owner_signed {
+        let thaw_ctx = CpiContext::new_with_signer(
+            token_program.clone(),
+            ThawAccount {
+                account: old_receipt_account.clone(),
+                mint: receipt_mint.clone(),
+                authority: vault.clone(),
+            },
+            vault_signer,
+        );
+        token::thaw_account(thaw_ctx)?;
+
+        let burn_ctx = CpiContext::new(
+            token_program.clone(),
+            Burn {
+                mint: receipt_mint.clone(),
+                from: old_receipt_account.clone(),
+                authority: authority.clone(),
+            },
+        );
+        token::burn(burn_ctx, 1)?;
+    }
+
+    let mint_ctx = CpiContext::new_with_signer(
+        token_program.clone(),
+        MintTo {
+            mint: receipt_mint.clone(),
+            to: new_receipt_account.clone(),
+            authority: vault.clone(),
+        },
+        vault_signer,
+    );
+    token::mint_to(mint_ctx, 1)?;
+
+    let freeze_ctx = CpiContext::new_with_signer(
+        token_program.clone(),
+        FreezeAccount {
+            account: new_receipt_account.clone(),
+            mint: receipt_mint.clone(),
+            authority: vault.clone(),
+        },
+        vault_signer,
+    );
+    token::freeze_account(freeze_ctx)
+}
@@ -0,0 +1,469 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use orao_solana_vrf::{
+    program::OraoVrf,
+    state::{NetworkState, RandomnessAccountData},
+    CONFIG_ACCOUNT_SEED,
+    RANDOMNESS_ACCOUNT_SEED,
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::ensure_not_suspended;
+
+#[derive(Accounts)]
+pub struct CreateRaffle<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = updater,
+        space = 8 + Raffle::INIT_SPACE,
+        seeds = [b"raffle", vault.raffle_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    pub prize_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = updater,
+        seeds = [b"raffle_escrow", raffle.key().as_ref()],
+        bump,
+        token::mint = prize_mint,
+        token::authority = vault
+    )]
+    pub prize_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == prize_mint.key(),
+        constraint = creator_token_account.owner == updater.key()
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnterRaffle<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + RaffleEntry::INIT_SPACE,
+        seeds = [b"raffle_entry", raffle.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, RaffleEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Address is derived from the user; the account only exists
+    /// once a moderator has suspended (or unsuspended) this user.
+    #[account(seeds = [b"user_status", user.key().as_ref()], bump)]
+    pub user_status: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRaffleRandomness<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG_ACCOUNT_SEED],
+        seeds::program = vrf_program.key(),
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
+
+    /// CHECK: Fee receiver named by the VRF network config.
+    #[account(mut, address = network_state.config.treasury)]
+    pub vrf_treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Request account created by the VRF program for this raffle's seed.
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_ACCOUNT_SEED, raffle.randomness_seed().as_ref()],
+        seeds::program = vrf_program.key(),
+        bump
+    )]
+    pub randomness: UncheckedAccount<'info>,
+
+    pub vrf_program: Program<'info, OraoVrf>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DrawRaffle<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    /// CHECK: Derived from the raffle's seed under the VRF program, so only the
+    /// oracle's fulfillment for this raffle is accepted.
+    #[account(
+        seeds = [RANDOMNESS_ACCOUNT_SEED, raffle.randomness_seed().as_ref()],
+        seeds::program = orao_solana_vrf::ID,
+        bump,
+        owner = orao_solana_vrf::ID
+    )]
+    pub randomness: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPrize<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    #[account(
+        seeds = [b"raffle_entry", raffle.key().as_ref(), winner.key().as_ref()],
+        bump = entry.bump
+    )]
+    pub entry: Account<'info, RaffleEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle_escrow", raffle.key().as_ref()],
+        bump
+    )]
+    pub prize_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = winner_token_account.mint == raffle.prize_mint,
+        constraint = winner_token_account.owner == winner.key()
+    )]
+    pub winner_token_account: Account<'info, TokenAccount>,
+
+    pub winner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRaffle<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle.id.to_le_bytes().as_ref()],
+        bump = raffle.bump
+    )]
+    pub raffle: Account<'info, Raffle>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle_escrow", raffle.key().as_ref()],
+        bump
+    )]
+    pub prize_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == raffle.prize_mint,
+        constraint = creator_token_account.owner == raffle.creator
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn create_raffle(
+    ctx: Context<CreateRaffle>,
+    prize_amount: u64,
+    entries_close_at: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+    let now = Clock::get()?.unix_timestamp;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(prize_amount > 0, ErrorCode::InvalidAmount);
+    require!(entries_close_at > now, ErrorCode::InvalidRaffle);
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.creator_token_account.to_account_info(),
+            to: ctx.accounts.prize_escrow.to_account_info(),
+            authority: ctx.accounts.updater.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, prize_amount)?;
+
+    let raffle = &mut ctx.accounts.raffle;
+    raffle.id = vault.raffle_count;
+    raffle.creator = ctx.accounts.updater.key();
+    raffle.prize_mint = ctx.accounts.prize_mint.key();
+    raffle.prize_amount = prize_amount;
+    raffle.entries_close_at = entries_close_at;
+    raffle.total_tickets = 0;
+    raffle.winning_ticket = None;
+    raffle.randomness_requested = false;
+    raffle.settled = false;
+    raffle.bump = ctx.bumps.raffle;
+
+    vault.raffle_count = vault.raffle_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(RaffleCreated {
+        raffle_id: raffle.id,
+        prize_mint: raffle.prize_mint,
+        prize_amount,
+        entries_close_at,
+        created_by: raffle.creator,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn enter_raffle(ctx: Context<EnterRaffle>) -> Result<()> {
+    let raffle = &mut ctx.accounts.raffle;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(clock.unix_timestamp < raffle.entries_close_at, ErrorCode::RaffleClosed);
+    ensure_not_suspended(&ctx.accounts.user_status)?;
+
+    let tickets = user_stake.take_raffle_tickets(clock.unix_timestamp)?;
+    require!(tickets > 0, ErrorCode::NoRaffleTickets);
+
+    let entry = &mut ctx.accounts.entry;
+    entry.raffle_id = raffle.id;
+    entry.user = user_stake.user;
+    entry.first_ticket = raffle.total_tickets;
+    entry.tickets = tickets;
+    entry.bump = ctx.bumps.entry;
+
+    raffle.total_tickets = raffle.total_tickets
+        .checked_add(tickets)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(RaffleEntered {
+        raffle_id: raffle.id,
+        user: entry.user,
+        first_ticket: entry.first_ticket,
+        tickets,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn request_raffle_randomness(ctx: Context<RequestRaffleRandomness>) -> Result<()> {
+    let raffle = &mut ctx.accounts.raffle;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(now >= raffle.entries_close_at, ErrorCode::RaffleStillOpen);
+    require!(!raffle.randomness_requested, ErrorCode::RaffleAlreadyDrawn);
+    require!(raffle.total_tickets > 0, ErrorCode::NoRaffleTickets);
+
+    request_randomness(
+        &ctx.accounts.vrf_program,
+        &ctx.accounts.payer,
+        &ctx.accounts.network_state,
+        &ctx.accounts.vrf_treasury,
+        &ctx.accounts.randomness,
+        &ctx.accounts.system_program,
+        raffle.randomness_seed(),
+    )?;
+    raffle.randomness_requested = true;
+
+    emit!(RandomnessRequested {
+        raffle_id: raffle.id,
+        randomness: ctx.accounts.randomness.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn draw_raffle(ctx: Context<DrawRaffle>) -> Result<()> {
+    let raffle = &mut ctx.accounts.raffle;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(raffle.randomness_requested, ErrorCode::RandomnessNotRequested);
+    require!(raffle.winning_ticket.is_none(), ErrorCode::RaffleAlreadyDrawn);
+
+    let randomness = fulfilled_randomness(&ctx.accounts.randomness)?;
+    let random = u64::from_le_bytes(randomness[..8].try_into().unwrap());
+    let winning_ticket = random % raffle.total_tickets;
+    raffle.winning_ticket = Some(winning_ticket);
+
+    emit!(RaffleDrawn {
+        raffle_id: raffle.id,
+        winning_ticket,
+        total_tickets: raffle.total_tickets,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn claim_prize(ctx: Context<ClaimPrize>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let raffle = &mut ctx.accounts.raffle;
+    let entry = &ctx.accounts.entry;
+
+    require!(!raffle.settled, ErrorCode::PrizeAlreadyClaimed);
+    let winning_ticket = raffle.winning_ticket.ok_or(ErrorCode::RaffleNotDrawn)?;
+    require!(
+        winning_ticket >= entry.first_ticket
+            && winning_ticket - entry.first_ticket < entry.tickets,
+        ErrorCode::NotRaffleWinner
+    );
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.prize_escrow.to_account_info(),
+            to: ctx.accounts.winner_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, raffle.prize_amount)?;
+    raffle.settled = true;
+
+    emit!(RafflePrizeClaimed {
+        raffle_id: raffle.id,
+        winner: entry.user,
+        prize_amount: raffle.prize_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_raffle(ctx: Context<CancelRaffle>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let raffle = &mut ctx.accounts.raffle;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(!raffle.settled, ErrorCode::PrizeAlreadyClaimed);
+    require!(
+        Clock::get()?.unix_timestamp >= raffle.entries_close_at,
+        ErrorCode::RaffleStillOpen
+    );
+    require!(raffle.total_tickets == 0, ErrorCode::RaffleHasEntries);
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.prize_escrow.to_account_info(),
+            to: ctx.accounts.creator_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, raffle.prize_amount)?;
+    raffle.settled = true;
+
+    emit!(RaffleCancelled {
+        raffle_id: raffle.id,
+        cancelled_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Requests ORAO VRF randomness for `seed`; the oracle fulfills it into the
+/// `randomness` account a few slots later.
+fn request_randomness<'info>(
+    vrf_program: &Program<'info, OraoVrf>,
+    payer: &Signer<'info>,
+    network_state: &Account<'info, NetworkState>,
+    vrf_treasury: &UncheckedAccount<'info>,
+    randomness: &UncheckedAccount<'info>,
+    system_program: &Program<'info, System>,
+    seed: [u8; 32],
+) -> Result<()> {
+    let request_ctx = CpiContext::new(
+        vrf_program.to_account_info(),
+        orao_solana_vrf::cpi::accounts::RequestV2 {
+            payer: payer.to_account_info(),
+            network_state: network_state.to_account_info(),
+            treasury: vrf_treasury.to_account_info(),
+            request: randomness.to_account_info(),
+            system_program: system_program.to_account_info(),
+        },
+    );
+    orao_solana_vrf::cpi::request_v2(request_ctx, seed)
+}
+
+/// The VRF output once the oracle has fulfilled the request.
+fn fulfilled_randomness(randomness: &UncheckedAccount) -> Result<[u8; 64]> {
+    let data = RandomnessAccountData::try_deserialize(&mut &randomness.data.borrow()[..])?;
+    data.fulfilled_randomness()
+        .copied()
+        .ok_or(ErrorCode::RandomnessNotFulfilled.into())
+}
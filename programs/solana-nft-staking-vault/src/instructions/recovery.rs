@@ -0,0 +1,350 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{load_stake_record, is_attached_booster};
+
+#[derive(Accounts)]
+pub struct RecoverUnstakedToken<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub recoverer: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", recoverer.key().as_ref()],
+        bump
+    )]
+    pub recoverer_role: Account<'info, AccountRole>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Must not exist; an initialized record means the NFT is staked.
+    #[account(seeds = [b"stake_record", nft_mint.key().as_ref()], bump)]
+    pub stake_record: UncheckedAccount<'info>,
+
+    /// CHECK: Must not exist; an initialized escrow means the NFT is an
+    /// attached booster.
+    #[account(seeds = [b"booster_escrow", nft_mint.key().as_ref()], bump)]
+    pub booster_escrow: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_nft_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Wallet the NFT is returned to; only used to check `destination`.
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == nft_mint.key(),
+        constraint = destination.owner == owner.key()
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ManageSweep<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSweep<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+
+    /// CHECK: Must match the pending sweep's destination; a wallet for SOL
+    /// or a token account for SPL sweeps.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    /// The vault's associated token account for the swept mint.
+    #[account(mut)]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: The swept mint's stake record address; must not exist.
+    pub stake_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The swept mint's `RewardConfig` address; must not exist.
+    pub reward_config: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The swept mint's booster escrow address; must not exist.
+    pub booster_escrow: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Returns an NFT that was sent straight to the vault's token account
+/// instead of through `stake_nft`. Attached boosters and legacy NFTs have
+/// no stake record either, so boosters are rejected and nothing is
+/// recovered until every legacy NFT has been backfilled.
+pub(crate) fn recover_unstaked_token(ctx: Context<RecoverUnstakedToken>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+
+    ctx.accounts.recoverer_role.require_permission(Role::can_manage_treasury)?;
+    require!(
+        load_stake_record(&ctx.accounts.stake_record)?.is_none(),
+        ErrorCode::TokenStillStaked
+    );
+    require!(
+        !is_attached_booster(&ctx.accounts.booster_escrow),
+        ErrorCode::TokenIsAttachedBooster
+    );
+    require!(vault.unrecorded_legacy_nfts == 0, ErrorCode::LegacyStakesUnrecorded);
+    require!(
+        ctx.accounts.nft_mint.key() != vault.reward_token_mint
+            && ctx.accounts.nft_mint.decimals == 0,
+        ErrorCode::InvalidNft
+    );
+
+    let amount = ctx.accounts.vault_nft_token_account.amount;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault_nft_token_account.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, amount)?;
+
+    emit!(UnstakedTokenRecovered {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        owner: ctx.accounts.owner.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        recovered_by: ctx.accounts.recoverer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Schedules a sweep of `amount` SOL (`mint == None`) or SPL tokens stuck at
+/// the vault PDA to `destination`.
+pub(crate) fn propose_sweep(
+    ctx: Context<ManageSweep>,
+    mint: Option<Pubkey>,
+    amount: u64,
+    destination: Pubkey,
+    timelock_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(vault.pending_sweep.is_none(), ErrorCode::SweepPending);
+    require!(
+        mint != Some(vault.reward_token_mint),
+        ErrorCode::MintNotSweepable
+    );
+    require!(
+        timelock_seconds >= PendingSweep::MIN_TIMELOCK_SECONDS,
+        ErrorCode::InvalidTimelock
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let scheduled_timestamp = now
+        .checked_add(timelock_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    vault.pending_sweep = Some(PendingSweep {
+        mint,
+        amount,
+        destination,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+    });
+
+    emit!(SweepProposed {
+        mint,
+        amount,
+        destination,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn execute_sweep(ctx: Context<ExecuteSweep>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+
+    let pending = vault.pending_sweep.clone().ok_or(ErrorCode::NoSweepPending)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= pending.scheduled_timestamp, ErrorCode::TimelockNotExpired);
+    require!(
+        ctx.accounts.destination.key() == pending.destination,
+        ErrorCode::InvalidSweepAccount
+    );
+
+    match pending.mint {
+        Some(mint) => {
+            // Checked again here since the mint may have been staked or
+            // configured as a reward during the timelock
+            ensure_sweepable(
+                vault,
+                mint,
+                ctx.accounts.stake_record.as_ref(),
+                ctx.accounts.reward_config.as_ref(),
+                ctx.accounts.booster_escrow.as_ref(),
+            )?;
+
+            let vault_token_account = ctx.accounts.vault_token_account
+                .as_ref()
+                .ok_or(ErrorCode::InvalidSweepAccount)?;
+            require!(
+                vault_token_account.key() == get_associated_token_address(&vault.key(), &mint),
+                ErrorCode::InvalidSweepAccount
+            );
+            require!(
+                vault_token_account.amount >= pending.amount,
+                ErrorCode::InsufficientSweepFunds
+            );
+
+            let seeds = &[b"vault".as_ref(), &[vault.bump]];
+            let signer = &[&seeds[..]];
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: vault_token_account.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(transfer_ctx, pending.amount)?;
+        }
+        None => {
+            // Keep the vault account itself rent exempt
+            let vault_info = vault.to_account_info();
+            let available = vault_info
+                .lamports()
+                .saturating_sub(Rent::get()?.minimum_balance(vault_info.data_len()));
+            require!(available >= pending.amount, ErrorCode::InsufficientSweepFunds);
+
+            let destination = ctx.accounts.destination.to_account_info();
+            **vault_info.try_borrow_mut_lamports()? -= pending.amount;
+            **destination.try_borrow_mut_lamports()? = destination
+                .lamports()
+                .checked_add(pending.amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+    }
+
+    vault.pending_sweep = None;
+
+    emit!(SweepExecuted {
+        mint: pending.mint,
+        amount: pending.amount,
+        destination: pending.destination,
+        executor: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_sweep(ctx: Context<ManageSweep>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    ctx.accounts.admin_role.require_permission(Role::can_manage_treasury)?;
+    require!(vault.pending_sweep.is_some(), ErrorCode::NoSweepPending);
+
+    vault.pending_sweep = None;
+
+    emit!(SweepCancelled {
+        cancelled_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Rejects the reward mint, secondary reward mints, attached boosters and
+/// any mint with a stake record, and every mint while legacy NFTs have no
+/// record yet. All three PDAs must be passed so their absence can be
+/// checked.
+fn ensure_sweepable(
+    vault: &VaultAccount,
+    mint: Pubkey,
+    stake_record: Option<&UncheckedAccount>,
+    reward_config: Option<&UncheckedAccount>,
+    booster_escrow: Option<&UncheckedAccount>,
+) -> Result<()> {
+    require!(mint != vault.reward_token_mint, ErrorCode::MintNotSweepable);
+    require!(vault.unrecorded_legacy_nfts == 0, ErrorCode::LegacyStakesUnrecorded);
+
+    let stake_record = stake_record.ok_or(ErrorCode::InvalidSweepAccount)?;
+    let (stake_record_address, _) =
+        Pubkey::find_program_address(&[b"stake_record", mint.as_ref()], &crate::ID);
+    require!(
+        stake_record.key() == stake_record_address,
+        ErrorCode::InvalidSweepAccount
+    );
+    require!(
+        load_stake_record(stake_record)?.is_none(),
+        ErrorCode::MintNotSweepable
+    );
+
+    let reward_config = reward_config.ok_or(ErrorCode::InvalidSweepAccount)?;
+    let (reward_config_address, _) =
+        Pubkey::find_program_address(&[b"reward_config", mint.as_ref()], &crate::ID);
+    require!(
+        reward_config.key() == reward_config_address,
+        ErrorCode::InvalidSweepAccount
+    );
+    require!(
+        reward_config.owner != &crate::ID || reward_config.data_is_empty(),
+        ErrorCode::MintNotSweepable
+    );
+
+    let booster_escrow = booster_escrow.ok_or(ErrorCode::InvalidSweepAccount)?;
+    let (booster_escrow_address, _) =
+        Pubkey::find_program_address(&[b"booster_escrow", mint.as_ref()], &crate::ID);
+    require!(
+        booster_escrow.key() == booster_escrow_address,
+        ErrorCode::InvalidSweepAccount
+    );
+    require!(!is_attached_booster(booster_escrow), ErrorCode::TokenIsAttachedBooster);
+
+    Ok(())
+}
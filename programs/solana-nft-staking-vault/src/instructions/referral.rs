@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::events::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RegisterReferral<'info> {
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + ReferralAccount::INIT_SPACE,
+        seeds = [b"referral", referrer.key().as_ref()],
+        bump
+    )]
+    pub referral: Account<'info, ReferralAccount>,
+
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn register_referral(ctx: Context<RegisterReferral>) -> Result<()> {
+    let referral = &mut ctx.accounts.referral;
+    let clock = Clock::get()?;
+
+    referral.referrer = ctx.accounts.referrer.key();
+    referral.referred_users = 0;
+    referral.total_bonus_paid = 0;
+    referral.created_at = clock.unix_timestamp;
+    referral.bump = ctx.bumps.referral;
+
+    emit!(ReferralRegistered {
+        referrer: referral.referrer,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
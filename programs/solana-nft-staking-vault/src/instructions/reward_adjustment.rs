@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::config::UpdateConfig;
+
+#[derive(Accounts)]
+pub struct ProposeRewardAdjustment<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// CHECK: The user being compensated; must already have a position.
+    pub user: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"user_stake", user.key().as_ref()], bump)]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    /// One pending adjustment per user at a time.
+    #[account(
+        init,
+        payer = updater,
+        space = 8 + RewardAdjustment::INIT_SPACE,
+        seeds = [b"reward_adjustment", user.key().as_ref()],
+        bump
+    )]
+    pub adjustment: Account<'info, RewardAdjustment>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Shared by `adjust_pending_rewards` and `cancel_reward_adjustment`; both
+/// close the proposal and refund its rent to the proposer.
+#[derive(Accounts)]
+pub struct ExecuteRewardAdjustment<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", adjustment.user.as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_adjustment", adjustment.user.as_ref()],
+        bump = adjustment.bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub adjustment: Account<'info, RewardAdjustment>,
+
+    /// CHECK: Receives the proposal's rent; checked against `adjustment.proposer`.
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+}
+
+pub(crate) fn set_max_reward_adjustment(
+    ctx: Context<UpdateConfig>,
+    max_reward_adjustment: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    vault.max_reward_adjustment = max_reward_adjustment;
+
+    emit!(MaxRewardAdjustmentUpdated {
+        max_reward_adjustment,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn propose_reward_adjustment(
+    ctx: Context<ProposeRewardAdjustment>,
+    amount: u64,
+    reason: AdjustmentReason,
+    timelock_seconds: i64,
+) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        amount > 0 && amount <= vault.max_reward_adjustment,
+        ErrorCode::RewardAdjustmentTooLarge
+    );
+    require!(
+        timelock_seconds >= RewardAdjustment::MIN_TIMELOCK_SECONDS,
+        ErrorCode::InvalidTimelock
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let scheduled_timestamp = now
+        .checked_add(timelock_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let adjustment = &mut ctx.accounts.adjustment;
+    adjustment.user = ctx.accounts.user.key();
+    adjustment.amount = amount;
+    adjustment.reason = reason.clone();
+    adjustment.scheduled_timestamp = scheduled_timestamp;
+    adjustment.proposer = ctx.accounts.updater.key();
+    adjustment.bump = ctx.bumps.adjustment;
+
+    emit!(RewardAdjustmentProposed {
+        user: adjustment.user,
+        amount,
+        reason,
+        scheduled_timestamp,
+        proposer: adjustment.proposer,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_reward_adjustment(ctx: Context<ExecuteRewardAdjustment>) -> Result<()> {
+    ctx.accounts.updater_role.require_permission(Role::can_update_config)?;
+
+    emit!(RewardAdjustmentCancelled {
+        user: ctx.accounts.adjustment.user,
+        amount: ctx.accounts.adjustment.amount,
+        cancelled_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn adjust_pending_rewards(ctx: Context<ExecuteRewardAdjustment>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let adjustment = &ctx.accounts.adjustment;
+
+    ctx.accounts.updater_role.require_permission(Role::can_update_config)?;
+    user_stake.require_current_version()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= adjustment.scheduled_timestamp,
+        ErrorCode::TimelockNotExpired
+    );
+    // The cap may have been lowered since the proposal
+    require!(
+        adjustment.amount <= vault.max_reward_adjustment,
+        ErrorCode::RewardAdjustmentTooLarge
+    );
+
+    // Credited outside the emission bound, which only covers accrual
+    user_stake.credited_rewards = user_stake.credited_rewards
+        .checked_add(adjustment.amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(PendingRewardsAdjusted {
+        user: adjustment.user,
+        amount: adjustment.amount,
+        reason: adjustment.reason.clone(),
+        proposer: adjustment.proposer,
+        executor: ctx.accounts.updater.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
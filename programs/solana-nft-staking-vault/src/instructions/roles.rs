@@ -0,0 +1,348 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ManageRole<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub granter: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", granter.key().as_ref()],
+        bump
+    )]
+    pub granter_role: Account<'info, AccountRole>,
+
+    #[account(
+        init_if_needed,
+        payer = granter,
+        space = 8 + AccountRole::INIT_SPACE,
+        seeds = [b"role", user_role.user.as_ref()],
+        bump
+    )]
+    pub user_role: Account<'info, AccountRole>,
+
+    #[account(mut, seeds = [b"role_registry"], bump = role_registry.bump)]
+    pub role_registry: Account<'info, RoleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminHeartbeat<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSuperAdmin<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub recovery_key: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = recovery_key,
+        space = 8 + AccountRole::INIT_SPACE,
+        seeds = [b"role", recovery_key.key().as_ref()],
+        bump
+    )]
+    pub recovery_role: Account<'info, AccountRole>,
+
+    #[account(mut, seeds = [b"role_registry"], bump = role_registry.bump)]
+    pub role_registry: Account<'info, RoleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRoleRegistry<'info> {
+    #[account(mut)]
+    pub granter: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", granter.key().as_ref()],
+        bump
+    )]
+    pub granter_role: Account<'info, AccountRole>,
+
+    #[account(
+        init,
+        payer = granter,
+        space = 8 + RoleRegistry::INIT_SPACE,
+        seeds = [b"role_registry"],
+        bump
+    )]
+    pub role_registry: Account<'info, RoleRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the registry for a deployment whose roles predate it. Existing
+/// `AccountRole` accounts passed as remaining accounts are listed right
+/// away; revoked ones are skipped.
+pub(crate) fn initialize_role_registry<'info>(
+    ctx: Context<'_, '_, 'info, 'info, InitializeRoleRegistry<'info>>,
+) -> Result<()> {
+    ctx.accounts.granter_role.require_permission(Role::can_manage_roles)?;
+
+    let registry = &mut ctx.accounts.role_registry;
+    registry.bump = ctx.bumps.role_registry;
+    registry.upsert(&ctx.accounts.granter_role)?;
+    backfill_role_registry(registry, ctx.remaining_accounts)?;
+
+    emit!(RoleRegistryInitialized {
+        entries: registry.entries.len() as u32,
+        initialized_by: ctx.accounts.granter.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Lists the `AccountRole` accounts in `role_accounts`; revoked ones are
+/// skipped.
+pub(super) fn backfill_role_registry<'info>(
+    registry: &mut RoleRegistry,
+    role_accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    for account_info in role_accounts {
+        let role_account: Account<AccountRole> = Account::try_from(account_info)?;
+        let (expected, _) =
+            Pubkey::find_program_address(&[b"role", role_account.user.as_ref()], &crate::ID);
+        require!(account_info.key() == expected, ErrorCode::InvalidRoleAccount);
+        if role_account.role != Role::Revoked {
+            registry.upsert(&role_account)?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn grant_role(
+    ctx: Context<ManageRole>, 
+    user: Pubkey,
+    role: Role,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    let granter_role_account = &ctx.accounts.granter_role;
+    
+    // Only SuperAdmin can grant roles
+    granter_role_account.require_permission(Role::can_manage_roles)?;
+
+    require!(role.is_valid(), ErrorCode::InvalidPermissions);
+
+    let now = Clock::get()?.unix_timestamp;
+    if let Some(expiry) = expires_at {
+        require!(expiry > now, ErrorCode::InvalidRoleExpiry);
+    }
+
+    // Role accounts created before `Role::Custom` are too small to hold one
+    grow_role_account(
+        &ctx.accounts.user_role,
+        &ctx.accounts.granter,
+        &ctx.accounts.system_program,
+    )?;
+
+    let role_account = &mut ctx.accounts.user_role;
+    role_account.user = user;
+    role_account.role = role.clone();
+    role_account.granted_by = ctx.accounts.granter.key();
+    role_account.granted_at = now;
+    role_account.expires_at = expires_at;
+    ctx.accounts.role_registry.upsert(role_account)?;
+
+    emit!(RoleGranted {
+        user,
+        role,
+        granted_by: ctx.accounts.granter.key(),
+        expires_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn renew_role(ctx: Context<ManageRole>, expires_at: Option<i64>) -> Result<()> {
+    let granter_role_account = &ctx.accounts.granter_role;
+
+    granter_role_account.require_permission(Role::can_manage_roles)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if let Some(expiry) = expires_at {
+        require!(expiry > now, ErrorCode::InvalidRoleExpiry);
+    }
+
+    let role_account = &mut ctx.accounts.user_role;
+    role_account.expires_at = expires_at;
+    ctx.accounts.role_registry.upsert(role_account)?;
+
+    emit!(RoleRenewed {
+        user: role_account.user,
+        expires_at,
+        renewed_by: ctx.accounts.granter.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn revoke_role(ctx: Context<ManageRole>) -> Result<()> {
+    let granter_role_account = &ctx.accounts.granter_role;
+    
+    granter_role_account.require_permission(Role::can_manage_roles)?;
+
+    let role_account = &mut ctx.accounts.user_role;
+    let user = role_account.user;
+
+    // Prevent the last SuperAdmin from locking everyone out by accident
+    require!(user != ctx.accounts.granter.key(), ErrorCode::CannotRevokeSelf);
+    require!(role_account.role != Role::Revoked, ErrorCode::RoleAlreadyRevoked);
+
+    role_account.role = Role::Revoked;
+    role_account.expires_at = None;
+    ctx.accounts.role_registry.remove(&user);
+
+    emit!(RoleRevoked {
+        user,
+        revoked_by: ctx.accounts.granter.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Proves the role managers still hold their keys, pushing back the
+/// dead-man switch.
+pub(crate) fn admin_heartbeat(ctx: Context<AdminHeartbeat>) -> Result<()> {
+    ctx.accounts.admin_role.require_permission(Role::can_manage_roles)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.vault.last_admin_heartbeat = now;
+
+    emit!(AdminHeartbeatRecorded {
+        admin: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Arms the dead-man switch, or disarms it with `recovery_key == None`.
+/// Counts as a heartbeat.
+pub(crate) fn set_dead_man_switch(
+    ctx: Context<AdminHeartbeat>,
+    recovery_key: Option<Pubkey>,
+    timeout_seconds: i64,
+) -> Result<()> {
+    ctx.accounts.admin_role.require_permission(Role::can_manage_roles)?;
+    require!(
+        recovery_key.is_none() || timeout_seconds >= VaultAccount::MIN_HEARTBEAT_TIMEOUT_SECONDS,
+        ErrorCode::InvalidHeartbeatTimeout
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault;
+    vault.recovery_key = recovery_key;
+    vault.heartbeat_timeout_seconds = timeout_seconds;
+    vault.last_admin_heartbeat = now;
+
+    emit!(DeadManSwitchUpdated {
+        recovery_key,
+        timeout_seconds,
+        updated_by: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Makes the recovery key SuperAdmin once the admins have missed the
+/// heartbeat timeout. Existing roles are left alone; the new SuperAdmin
+/// can revoke them.
+pub(crate) fn claim_super_admin(ctx: Context<ClaimSuperAdmin>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let recovery_key = ctx.accounts.recovery_key.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        vault.recovery_key == Some(recovery_key),
+        ErrorCode::NotRecoveryKey
+    );
+    require!(vault.admins_unresponsive(now), ErrorCode::AdminsStillActive);
+
+    let role_account = &mut ctx.accounts.recovery_role;
+    role_account.user = recovery_key;
+    role_account.role = Role::SuperAdmin;
+    role_account.granted_by = recovery_key;
+    role_account.granted_at = now;
+    role_account.expires_at = None;
+    ctx.accounts.role_registry.upsert(role_account)?;
+
+    // The new SuperAdmin's silence is measured from here
+    let last_heartbeat = vault.last_admin_heartbeat;
+    vault.last_admin_heartbeat = now;
+
+    emit!(DeadManSwitchTriggered {
+        recovery_key,
+        last_heartbeat,
+        timestamp: now,
+    });
+    emit!(RoleGranted {
+        user: recovery_key,
+        role: Role::SuperAdmin,
+        granted_by: recovery_key,
+        expires_at: None,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Reallocates a role account to the current layout, with `payer`
+/// topping up rent. A no-op for accounts that are already large enough.
+fn grow_role_account<'info>(
+    role_account: &Account<'info, AccountRole>,
+    payer: &Signer<'info>,
+    system: &Program<'info, System>,
+) -> Result<()> {
+    let role_info = role_account.to_account_info();
+    let new_len = 8 + AccountRole::INIT_SPACE;
+    if role_info.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(role_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: role_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    role_info.resize(new_len)?;
+
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::accrue_user_rewards;
+
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct StartSeason<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// The season being replaced; created as season 0 on the first start.
+    #[account(
+        init_if_needed,
+        payer = updater,
+        space = 8 + Season::INIT_SPACE,
+        seeds = [b"season", vault.current_season_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub previous_season: Account<'info, Season>,
+
+    #[account(
+        init,
+        payer = updater,
+        space = 8 + Season::INIT_SPACE,
+        seeds = [b"season", id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub season: Account<'info, Season>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EndSeason<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"season", vault.current_season_id.to_le_bytes().as_ref()],
+        bump = season.bump
+    )]
+    pub season: Account<'info, Season>,
+
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct RollOverSeason<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user_stake.user.as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    /// The season the position last accrued in.
+    #[account(
+        seeds = [b"season", user_stake.season_id.to_le_bytes().as_ref()],
+        bump = season.bump
+    )]
+    pub season: Account<'info, Season>,
+}
+
+pub(crate) fn start_season(
+    ctx: Context<StartSeason>,
+    id: u32,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    reward_rate_per_second: u64,
+    boost_bps: u16,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let clock = Clock::get()?;
+
+    ctx.accounts.updater_role.require_permission(Role::can_update_config)?;
+    require!(!vault.season_active, ErrorCode::SeasonStillActive);
+    require!(
+        Some(id) == vault.current_season_id.checked_add(1),
+        ErrorCode::InvalidSeason
+    );
+    require!(
+        start_timestamp >= clock.unix_timestamp && end_timestamp > start_timestamp,
+        ErrorCode::InvalidSeason
+    );
+    require!(reward_rate_per_second > 0, ErrorCode::InvalidRewardRate);
+    // The curve's shared index can't be split per season
+    require!(!vault.curve.is_enabled(), ErrorCode::InvalidSeason);
+
+    vault.update_reward_index(clock.unix_timestamp)?;
+
+    if vault.current_season_id == 0 {
+        let schedule = &vault.emission_schedule;
+        let ended_at = if schedule.end_timestamp > 0 {
+            schedule.end_timestamp.min(clock.unix_timestamp)
+        } else {
+            clock.unix_timestamp
+        };
+
+        let legacy = &mut ctx.accounts.previous_season;
+        legacy.id = 0;
+        legacy.schedule = EmissionSchedule {
+            end_timestamp: ended_at,
+            ..schedule.clone()
+        };
+        legacy.reward_rate_per_second = config.reward_rate_per_second;
+        legacy.reward_rate_fraction = config.reward_rate_fraction;
+        legacy.boost_bps = 0;
+        legacy.ended_at = clock.unix_timestamp;
+        legacy.bump = ctx.bumps.previous_season;
+    }
+
+    let season = &mut ctx.accounts.season;
+    season.id = id;
+    season.schedule = EmissionSchedule {
+        start_timestamp,
+        end_timestamp,
+        halving_interval: 0,
+    };
+    season.reward_rate_per_second = reward_rate_per_second;
+    season.reward_rate_fraction = 0;
+    season.boost_bps = boost_bps;
+    season.ended_at = 0;
+    season.bump = ctx.bumps.season;
+
+    season.apply_to(vault, config);
+    vault.current_season_id = id;
+    vault.season_active = true;
+
+    emit!(SeasonStarted {
+        id,
+        start_timestamp,
+        end_timestamp,
+        reward_rate_per_second,
+        boost_bps,
+        started_by: ctx.accounts.updater.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn end_season(ctx: Context<EndSeason>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let season = &mut ctx.accounts.season;
+    let clock = Clock::get()?;
+
+    ctx.accounts.updater_role.require_permission(Role::can_update_config)?;
+    require!(vault.season_active, ErrorCode::NoActiveSeason);
+
+    vault.update_reward_index(clock.unix_timestamp)?;
+    season.schedule.end_timestamp = season.schedule.end_timestamp.min(clock.unix_timestamp);
+    season.ended_at = clock.unix_timestamp;
+    vault.emission_schedule.end_timestamp = season.schedule.end_timestamp;
+    vault.season_active = false;
+
+    emit!(SeasonEnded {
+        id: season.id,
+        end_timestamp: season.schedule.end_timestamp,
+        ended_by: ctx.accounts.updater.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn roll_over_season(ctx: Context<RollOverSeason>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let season = &ctx.accounts.season;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+    require!(user_stake.season_id < vault.current_season_id, ErrorCode::InvalidSeason);
+
+    let mut season_vault = VaultAccount::clone(vault);
+    let mut season_config = *ctx.accounts.vault_config.load()?;
+    season.apply_to(&mut season_vault, &mut season_config);
+    season_vault.current_season_id = season.id;
+
+    // Settle no further than the season's end, so the time after it
+    // still accrues under the seasons that follow
+    let settled_at = clock.unix_timestamp
+        .min(season.schedule.end_timestamp)
+        .max(user_stake.last_update_timestamp);
+    let rolled_over = accrue_user_rewards(&season_vault, &season_config, user_stake, settled_at)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rolled_over)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, settled_at)?;
+    if user_stake.staked_nfts > 0 {
+        user_stake.season_id = season.id + 1;
+    }
+
+    emit!(SeasonRolledOver {
+        user: user_stake.user,
+        from_season: season.id,
+        to_season: user_stake.season_id,
+        amount: rolled_over,
+        pending_rewards: user_stake.pending_rewards,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
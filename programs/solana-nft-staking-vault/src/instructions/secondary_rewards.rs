@@ -0,0 +1,278 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, SetAuthority},
+};
+use spl_token::instruction::AuthorityType;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{pay_out_rewards, reward_source, settle_secondary_rewards, ensure_not_suspended};
+
+#[derive(Accounts)]
+pub struct AddRewardConfig<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+
+    #[account(mut)]
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + RewardConfig::INIT_SPACE,
+        seeds = [b"reward_config", reward_mint.key().as_ref()],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewardConfig<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_config", reward_config.reward_mint.as_ref()],
+        bump = reward_config.bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewardToken<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Address is derived from the user; the account only exists
+    /// once a moderator has suspended (or unsuspended) this user.
+    #[account(seeds = [b"user_status", user.key().as_ref()], bump)]
+    pub user_status: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_config", reward_mint.key().as_ref()],
+        bump = reward_config.bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserRewardState::INIT_SPACE,
+        seeds = [b"user_reward", reward_mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_reward: Account<'info, UserRewardState>,
+
+    #[account(mut)]
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user
+    )]
+    pub user_reward_token_account: Account<'info, TokenAccount>,
+
+    /// Only required when this reward is paid from a funded pool.
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn add_reward_config(
+    ctx: Context<AddRewardConfig>,
+    reward_rate_per_second: u64,
+    reward_mode: RewardMode,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_update_config)?;
+    require!(reward_rate_per_second > 0, ErrorCode::InvalidRewardRate);
+    require!(
+        ctx.accounts.reward_mint.key() != vault.reward_token_mint,
+        ErrorCode::InvalidRewardMint
+    );
+
+    if reward_mode == RewardMode::Mint {
+        let set_authority_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                account_or_mint: ctx.accounts.reward_mint.to_account_info(),
+                current_authority: ctx.accounts.admin.to_account_info(),
+            },
+        );
+        token::set_authority(
+            set_authority_ctx,
+            AuthorityType::MintTokens,
+            Some(vault.key())
+        )?;
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    // Positions touched from now on checkpoint their NFT-seconds at launch
+    vault.record_secondary_launch(now);
+
+    let reward_config = &mut ctx.accounts.reward_config;
+    reward_config.reward_mint = ctx.accounts.reward_mint.key();
+    reward_config.reward_rate_per_second = reward_rate_per_second;
+    reward_config.reward_mode = reward_mode;
+    reward_config.start_timestamp = now;
+    reward_config.active = true;
+    reward_config.total_claimed = 0;
+    reward_config.bump = ctx.bumps.reward_config;
+
+    emit!(RewardConfigAdded {
+        reward_mint: reward_config.reward_mint,
+        reward_rate_per_second,
+        added_by: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn update_reward_config(
+    ctx: Context<UpdateRewardConfig>,
+    new_reward_rate: Option<u64>,
+    active: Option<bool>,
+) -> Result<()> {
+    let reward_config = &mut ctx.accounts.reward_config;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    if let Some(rate) = new_reward_rate {
+        require!(rate > 0, ErrorCode::InvalidRewardRate);
+        reward_config.reward_rate_per_second = rate;
+    }
+
+    if let Some(active) = active {
+        reward_config.active = active;
+    }
+
+    emit!(RewardConfigUpdated {
+        reward_mint: reward_config.reward_mint,
+        reward_rate_per_second: reward_config.reward_rate_per_second,
+        active: reward_config.active,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn claim_reward_token(ctx: Context<ClaimRewardToken>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let user_stake = &ctx.accounts.user_stake;
+    let reward_config = &mut ctx.accounts.reward_config;
+    let user_reward = &mut ctx.accounts.user_reward;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    ensure_not_suspended(&ctx.accounts.user_status)?;
+
+    // Circuit breaker check
+    require!(
+        config.circuit_breaker.can_execute(clock.unix_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+
+    // First claim: only NFT-seconds from the reward's launch onwards count
+    if user_reward.user == Pubkey::default() {
+        user_reward.user = ctx.accounts.user.key();
+        user_reward.reward_mint = reward_config.reward_mint;
+        user_reward.nft_seconds_checkpoint = user_stake
+            .nft_seconds_at_launch(reward_config.start_timestamp)?
+            .max(user_stake.secondary_nft_seconds_floor);
+        user_reward.total_claimed = 0;
+        user_reward.bump = ctx.bumps.user_reward;
+    }
+
+    let amount = settle_secondary_rewards(
+        reward_config,
+        user_reward,
+        user_stake,
+        clock.unix_timestamp,
+    )?;
+    require!(amount > 0, ErrorCode::NoRewardsToClaim);
+
+    let source = reward_source(
+        vault,
+        reward_config.reward_mode,
+        reward_config.reward_mint,
+        &ctx.accounts.reward_mint,
+        ctx.accounts.reward_pool.as_ref(),
+    )?;
+    pay_out_rewards(
+        vault,
+        reward_config.reward_mode,
+        source,
+        ctx.accounts.user_reward_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    reward_config.total_claimed = reward_config.total_claimed
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    config.circuit_breaker.on_success();
+
+    emit!(SecondaryRewardsClaimed {
+        user: ctx.accounts.user.key(),
+        reward_mint: reward_config.reward_mint,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
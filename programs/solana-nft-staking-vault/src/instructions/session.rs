@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateSession<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + SessionAuthority::INIT_SPACE,
+        seeds = [b"session", user.key().as_ref(), session_key.as_ref()],
+        bump
+    )]
+    pub session_authority: Account<'info, SessionAuthority>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(
+        mut,
+        seeds = [b"session", user.key().as_ref(), session_authority.session_key.as_ref()],
+        bump = session_authority.bump,
+        close = user
+    )]
+    pub session_authority: Account<'info, SessionAuthority>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+pub(crate) fn create_session(
+    ctx: Context<CreateSession>,
+    session_key: Pubkey,
+    scope: u8,
+    expires_at: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        scope != 0 && scope & !SessionAuthority::SUPPORTED_SCOPES == 0,
+        ErrorCode::InvalidSessionScope
+    );
+    require!(
+        expires_at > clock.unix_timestamp
+            && expires_at - clock.unix_timestamp <= SessionAuthority::MAX_DURATION_SECONDS,
+        ErrorCode::InvalidSessionExpiry
+    );
+
+    let session = &mut ctx.accounts.session_authority;
+    session.user = ctx.accounts.user.key();
+    session.session_key = session_key;
+    session.scope = scope;
+    session.expires_at = expires_at;
+    session.bump = ctx.bumps.session_authority;
+
+    emit!(SessionCreated {
+        user: session.user,
+        session_key,
+        scope,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+    emit!(SessionRevoked {
+        user: ctx.accounts.user.key(),
+        session_key: ctx.accounts.session_authority.session_key,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct InitializeVaultShard<'info> {
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<VaultShard>(),
+        seeds = [b"vault_shard", index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FoldVaultShard<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// Written only when an anomaly rule pauses the vault
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(mut, seeds = [b"vault_stats"], bump = vault_stats.bump)]
+    pub vault_stats: Account<'info, VaultStats>,
+
+    #[account(mut, seeds = [b"vault_metrics"], bump = vault_metrics.bump)]
+    pub vault_metrics: Account<'info, VaultMetrics>,
+
+    #[account(mut, seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()], bump = vault_shard.load()?.bump)]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+}
+
+pub(crate) fn initialize_vault_shard(ctx: Context<InitializeVaultShard>, index: u8) -> Result<()> {
+    require!(index < VaultShard::COUNT, ErrorCode::WrongVaultShard);
+
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_init()?;
+    shard.index = index;
+    // Limits and thresholds are filled in by `sync_config`
+    shard.daily_limit = DailyLimits::new(0, 0, 0);
+    shard.circuit_breaker = CircuitBreakerState::new(0, 0);
+    shard.circuit_breaker_resets = config.circuit_breaker_resets;
+    shard.sync_config(config);
+    shard.folded_at = Clock::get()?.unix_timestamp;
+    shard.bump = ctx.bumps.vault_shard;
+
+    Ok(())
+}
+
+pub(crate) fn fold_vault_shard(ctx: Context<FoldVaultShard>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    vault.update_reward_index(now)?;
+    let previous_total = vault.total_staked;
+    vault.total_staked = u32::try_from(vault.total_staked as i64 + shard.staked_delta)
+        .map_err(|_| ErrorCode::MathUnderflow)?;
+    vault.record_rewards_minted(shard.rewards_minted)?;
+    if shard.pending_penalties > 0 {
+        vault.apply_penalty(shard.pending_penalties)?;
+    }
+
+    ctx.accounts.vault_stats.record_shard(shard, vault.total_staked)?;
+    ctx.accounts.vault_metrics.update(vault.total_staked, shard.rewards_distributed, now)?;
+
+    if vault.max_reward_supply > 0 && shard.rewards_minted > 0 && vault.remaining_reward_supply() == 0 {
+        emit!(RewardCapReached {
+            max_reward_supply: vault.max_reward_supply,
+            total_rewards_minted: vault.total_rewards_minted,
+            forfeited: shard.rewards_forfeited,
+            timestamp: now,
+        });
+    }
+
+    // Anomaly rules: a shard tripped by a claim, or a sharp fall in
+    // `total_staked`, pauses everything until an admin unpauses. The
+    // claim emitted its own `AutoPauseTriggered`.
+    let staked_drop = vault.staked_drop(previous_total, now);
+    let drop_exceeded = vault.anomaly_rules.staked_drop_exceeded(staked_drop);
+    if shard.auto_paused != 0 || drop_exceeded {
+        let config = &mut ctx.accounts.vault_config.load_mut()?;
+        config.pause_flags = VaultConfig::PAUSE_ALL;
+        config.circuit_breaker.on_failure(now);
+        shard.auto_paused = 0;
+    }
+    if drop_exceeded {
+        vault.reset_staked_window(now);
+        msg!(
+            "Auto-pause: total_staked fell {} below today's high, limit {}",
+            staked_drop,
+            vault.anomaly_rules.max_daily_staked_drop
+        );
+        emit!(AutoPauseTriggered {
+            kind: AnomalyKind::StakedDrop,
+            observed: staked_drop as u64,
+            threshold: vault.anomaly_rules.max_daily_staked_drop as u64,
+            shard_index: shard.index,
+            timestamp: now,
+        });
+    }
+
+    emit!(VaultShardFolded {
+        index: shard.index,
+        staked_delta: shard.staked_delta,
+        rewards_minted: shard.rewards_minted,
+        total_staked: vault.total_staked,
+        timestamp: now,
+    });
+
+    shard.clear(now);
+
+    Ok(())
+}
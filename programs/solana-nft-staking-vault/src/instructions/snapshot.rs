@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::create_pda_account;
+
+#[derive(Accounts)]
+#[instruction(day: u32)]
+pub struct TakeSnapshot<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = 8 + Snapshot::INIT_SPACE,
+        seeds = [b"snapshot", day.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub snapshot: Account<'info, Snapshot>,
+
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn take_snapshot<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TakeSnapshot<'info>>,
+    day: u32,
+) -> Result<()> {
+    let snapshot = &mut ctx.accounts.snapshot;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(day as i64 == now / Snapshot::SECONDS_PER_DAY, ErrorCode::InvalidSnapshotDay);
+    if snapshot.taken_at == 0 {
+        snapshot.day = day;
+        snapshot.taken_at = now;
+        snapshot.total_staked = ctx.accounts.vault.total_staked;
+        snapshot.bump = ctx.bumps.snapshot;
+
+        emit!(SnapshotTaken {
+            snapshot: snapshot.key(),
+            day,
+            total_staked: snapshot.total_staked,
+            timestamp: now,
+        });
+    }
+
+    require!(ctx.remaining_accounts.len() % 2 == 0, ErrorCode::InvalidRemainingAccounts);
+    if ctx.remaining_accounts.is_empty() {
+        return Ok(());
+    }
+    require!(
+        now < snapshot.taken_at + Snapshot::RECORD_WINDOW_SECONDS,
+        ErrorCode::SnapshotWindowClosed
+    );
+
+    let mut recorded = 0u32;
+    for accounts in ctx.remaining_accounts.chunks(2) {
+        let user_stake: Account<UserStakeAccount> = Account::try_from(&accounts[0])?;
+        if user_stake.staked_nfts == 0 {
+            continue;
+        }
+        let entry = SnapshotEntry {
+            snapshot: snapshot.key(),
+            user: user_stake.user,
+            staked_nfts: user_stake.staked_nfts,
+            accrued_nft_seconds: user_stake.nft_seconds_at(now)?,
+            recorded_at: now,
+            bump: 0,
+        };
+        if create_snapshot_entry(
+            entry,
+            &accounts[1],
+            &ctx.accounts.cranker.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )? {
+            recorded += 1;
+            snapshot.nfts_recorded = snapshot.nfts_recorded
+                .checked_add(user_stake.staked_nfts as u64)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+    }
+    snapshot.positions_recorded = snapshot.positions_recorded
+        .checked_add(recorded)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(SnapshotPositionsRecorded {
+        snapshot: snapshot.key(),
+        positions: recorded,
+        positions_recorded: snapshot.positions_recorded,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Creates `entry`'s PDA at `account`. Returns false, leaving the account
+/// alone, if the position was already recorded.
+fn create_snapshot_entry<'info>(
+    mut entry: SnapshotEntry,
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<bool> {
+    let (address, bump) = Pubkey::find_program_address(
+        &[b"snapshot_entry", entry.snapshot.as_ref(), entry.user.as_ref()],
+        &crate::ID,
+    );
+    require!(account.key() == address, ErrorCode::InvalidSnapshotEntry);
+    if account.owner == &crate::ID {
+        return Ok(false);
+    }
+
+    create_pda_account(
+        account,
+        &[b"snapshot_entry", entry.snapshot.as_ref(), entry.user.as_ref(), &[bump]],
+        8 + SnapshotEntry::INIT_SPACE,
+        payer,
+        system_program,
+    )?;
+    entry.bump = bump;
+    entry.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    Ok(true)
+}
@@ -0,0 +1,373 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{accrue_user_rewards, pay_out_rewards, reward_source};
+
+#[derive(Accounts)]
+pub struct CreateSquad<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Squad::INIT_SPACE,
+        seeds = [b"squad", creator.key().as_ref()],
+        bump
+    )]
+    pub squad: Account<'info, Squad>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinSquad<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut, seeds = [b"squad", squad.creator.as_ref()], bump = squad.bump)]
+    pub squad: Account<'info, Squad>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + SquadMember::INIT_SPACE,
+        seeds = [b"squad_member", squad.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub squad_member: Account<'info, SquadMember>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Shared by `contribute_squad_rewards` (permissionless) and `leave_squad`
+/// (which requires `user` to sign).
+#[derive(Accounts)]
+pub struct ContributeSquadRewards<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump,
+        constraint = user_stake.squad == Some(squad.key()) @ ErrorCode::NotInSquad
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    /// CHECK: The member; only used to derive their accounts.
+    pub user: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"squad", squad.creator.as_ref()], bump = squad.bump)]
+    pub squad: Account<'info, Squad>,
+
+    #[account(
+        mut,
+        seeds = [b"squad_member", squad.key().as_ref(), user.key().as_ref()],
+        bump = squad_member.bump
+    )]
+    pub squad_member: Account<'info, SquadMember>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSquadRewards<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// This user's accumulator; see `VaultShard`.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump,
+        constraint = vault_shard.load()?.index == VaultShard::index_for(&user.key()) @ ErrorCode::WrongVaultShard
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(mut, seeds = [b"squad", squad.creator.as_ref()], bump = squad.bump)]
+    pub squad: Account<'info, Squad>,
+
+    #[account(
+        mut,
+        seeds = [b"squad_member", squad.key().as_ref(), user.key().as_ref()],
+        bump = squad_member.bump
+    )]
+    pub squad_member: Account<'info, SquadMember>,
+
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = reward_token_mint.key() == vault.reward_token_mint
+    )]
+    pub reward_token_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = user
+    )]
+    pub user_reward_token_account: Account<'info, TokenAccount>,
+
+    /// Only required when the vault pays rewards from a funded pool.
+    #[account(
+        mut,
+        associated_token::mint = reward_token_mint,
+        associated_token::authority = vault
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn create_squad(ctx: Context<CreateSquad>, name: String) -> Result<()> {
+    require!(
+        !name.is_empty() && name.len() <= Squad::MAX_NAME_LEN,
+        ErrorCode::InvalidSquadName
+    );
+
+    let squad = &mut ctx.accounts.squad;
+    squad.creator = ctx.accounts.creator.key();
+    squad.name = name;
+    squad.member_count = 0;
+    squad.total_contribution = 0;
+    squad.reward_index = 0;
+    squad.pot = 0;
+    squad.bump = ctx.bumps.squad;
+
+    emit!(SquadCreated {
+        squad: squad.key(),
+        creator: squad.creator,
+        name: squad.name.clone(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn join_squad(ctx: Context<JoinSquad>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let squad = &mut ctx.accounts.squad;
+    let member = &mut ctx.accounts.squad_member;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+    require!(user_stake.squad.is_none(), ErrorCode::AlreadyInSquad);
+    require!(squad.member_count < Squad::MAX_MEMBERS, ErrorCode::SquadFull);
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    let own_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.pending_rewards = 0;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    // A returning member keeps any pot share not yet claimed
+    member.pending_rewards = member.pending_rewards
+        .checked_add(own_rewards)
+        .ok_or(ErrorCode::MathOverflow)?;
+    squad.pot = squad.pot.checked_add(own_rewards).ok_or(ErrorCode::MathOverflow)?;
+    member.squad = squad.key();
+    member.user = ctx.accounts.user.key();
+    member.contribution = user_stake.staked_nfts as u64;
+    member.reward_index_checkpoint = squad.reward_index;
+    member.bump = ctx.bumps.squad_member;
+
+    squad.member_count += 1;
+    squad.total_contribution = squad.total_contribution
+        .checked_add(member.contribution)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.squad = Some(squad.key());
+
+    emit!(SquadJoined {
+        squad: squad.key(),
+        user: member.user,
+        contribution: member.contribution,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn contribute_squad_rewards(ctx: Context<ContributeSquadRewards>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    require!(
+        config.circuit_breaker.can_execute(clock.unix_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+
+    let amount = contribute_to_squad(
+        vault,
+        config,
+        user_stake,
+        &mut ctx.accounts.squad,
+        &mut ctx.accounts.squad_member,
+        clock.unix_timestamp,
+    )?;
+
+    emit!(SquadRewardsContributed {
+        squad: ctx.accounts.squad.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+        contribution: ctx.accounts.squad_member.contribution,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn leave_squad(ctx: Context<ContributeSquadRewards>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let squad = &mut ctx.accounts.squad;
+    let member = &mut ctx.accounts.squad_member;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+    require!(ctx.accounts.user.is_signer, ErrorCode::UnauthorizedClaimer);
+
+    contribute_to_squad(vault, config, user_stake, squad, member, clock.unix_timestamp)?;
+    member.settle(squad)?;
+    squad.total_contribution = squad.total_contribution
+        .checked_sub(member.contribution)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    member.contribution = 0;
+    squad.member_count -= 1;
+    user_stake.squad = None;
+
+    emit!(SquadLeft {
+        squad: squad.key(),
+        user: member.user,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn claim_squad_rewards(ctx: Context<ClaimSquadRewards>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let squad = &mut ctx.accounts.squad;
+    let member = &mut ctx.accounts.squad_member;
+    let clock = Clock::get()?;
+
+    require!(!config.is_paused(VaultConfig::PAUSE_CLAIM), ErrorCode::VaultPaused);
+    shard.sync_config(config);
+    require!(
+        shard.can_execute(config, clock.unix_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+
+    member.settle(squad)?;
+    // Whatever the supply cap or the shard's allowance cuts stays pending
+    // for later
+    let amount = member.pending_rewards
+        .min(shard.remaining_reward_supply(vault))
+        .min(shard.reward_allowance(vault));
+    require!(member.pending_rewards > 0, ErrorCode::NoRewardsToClaim);
+    require!(amount > 0, ErrorCode::RewardSupplyExhausted);
+
+    shard.daily_limit.reset_if_new_day(clock.unix_timestamp);
+    shard.daily_limit.check_claim(amount)?;
+
+    let source = reward_source(
+        vault,
+        vault.reward_mode,
+        vault.reward_token_mint,
+        &ctx.accounts.reward_token_mint,
+        ctx.accounts.reward_pool.as_ref(),
+    )?;
+    pay_out_rewards(
+        vault,
+        vault.reward_mode,
+        source,
+        ctx.accounts.user_reward_token_account.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    member.pending_rewards -= amount;
+    squad.pot = squad.pot
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    shard.record_rewards_minted(amount, 0)?;
+    shard.record_claim(amount, clock.unix_timestamp)?;
+    shard.daily_limit.record_claim(amount);
+    shard.circuit_breaker.on_success();
+
+    emit!(SquadRewardsClaimed {
+        squad: squad.key(),
+        user: member.user,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Sweeps the member's accrued and pending rewards into the squad pot and
+/// re-weights them by their current staked NFTs. Returns the amount moved.
+fn contribute_to_squad(
+    vault: &VaultAccount,
+    config: &VaultConfig,
+    user_stake: &mut UserStakeAccount,
+    squad: &mut Squad,
+    member: &mut SquadMember,
+    current_timestamp: i64,
+) -> Result<u64> {
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, current_timestamp)?;
+    let amount = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.pending_rewards = 0;
+    user_stake.touch(vault, current_timestamp)?;
+
+    member.settle(squad)?;
+    squad.total_contribution = squad.total_contribution
+        .checked_sub(member.contribution)
+        .and_then(|total| total.checked_add(user_stake.staked_nfts as u64))
+        .ok_or(ErrorCode::MathOverflow)?;
+    member.contribution = user_stake.staked_nfts as u64;
+
+    if squad.total_contribution == 0 {
+        // Nobody to share with; the contributor keeps it
+        member.pending_rewards = member.pending_rewards
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        squad.pot = squad.pot.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    } else {
+        squad.deposit(amount)?;
+    }
+    Ok(amount)
+}
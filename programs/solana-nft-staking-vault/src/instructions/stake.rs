@@ -0,0 +1,352 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer, MintTo, FreezeAccount},
+    metadata::{MasterEditionAccount, Metadata, MetadataAccount},
+};
+use anchor_lang::solana_program::sysvar;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{
+    accrue_user_rewards, ensure_allowed_caller, ensure_whitelisted, ensure_not_suspended,
+    ensure_trait_verified, sync_voter_weight, nft_group, nft_bonus_bps,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StakeNft<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// This user's accumulator; see `VaultShard`.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump,
+        constraint = vault_shard.load()?.index == VaultShard::index_for(&user.key()) @ ErrorCode::WrongVaultShard
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserStakeAccount::INIT_SPACE,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    /// May be a PDA of a partner program, signing through CPI.
+    pub user: Signer<'info>,
+
+    /// Funds the accounts created here; the user themselves unless a project
+    /// sponsors onboarding, or the user is a PDA that can't pay for them.
+    /// Gets the rent back when they are closed.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Address is derived from the user; the account only exists
+    /// once a moderator has suspended (or unsuspended) this user.
+    #[account(seeds = [b"user_status", user.key().as_ref()], bump)]
+    pub user_status: UncheckedAccount<'info>,
+
+    /// Required to stake before `vault.public_start_timestamp`.
+    #[account(seeds = [b"whitelist", user.key().as_ref()], bump = whitelist_entry.bump)]
+    pub whitelist_entry: Option<Account<'info, WhitelistEntry>>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// Required while the vault has a trait gate.
+    #[account(seeds = [b"trait_pass", nft_mint.key().as_ref()], bump = trait_pass.bump)]
+    pub trait_pass: Option<Account<'info, TraitPass>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + StakeRecord::INIT_SPACE,
+        seeds = [b"stake_record", nft_mint.key().as_ref()],
+        bump
+    )]
+    pub stake_record: Account<'info, StakeRecord>,
+
+    /// Referrer for a first-time staker; ignored afterwards.
+    #[account(mut, seeds = [b"referral", referral.referrer.as_ref()], bump = referral.bump)]
+    pub referral: Option<Account<'info, ReferralAccount>>,
+
+    /// CHECK: Set-bonus tag; may be uninitialized for untagged NFTs.
+    #[account(seeds = [b"nft_group", nft_mint.key().as_ref()], bump)]
+    pub nft_group_tag: UncheckedAccount<'info>,
+
+    /// CHECK: Reward multiplier; may be uninitialized for plain NFTs.
+    #[account(seeds = [b"nft_multiplier", nft_mint.key().as_ref()], bump)]
+    pub nft_multiplier: UncheckedAccount<'info>,
+
+    /// CHECK: The user's voter-weight record; may not exist.
+    #[account(mut, seeds = [b"voter_weight_record", user.key().as_ref()], bump)]
+    pub voter_weight_record: UncheckedAccount<'info>,
+
+    /// Non-transferable receipt for this NFT's position. Reused across
+    /// stakes of the same NFT.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"receipt_mint", nft_mint.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = vault,
+        mint::freeze_authority = vault
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = user
+    )]
+    pub user_receipt_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            nft_mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub nft_metadata: Account<'info, MetadataAccount>,
+
+    /// Deserializing as a Master Edition rejects printed editions, which
+    /// live at the same address with a different account key.
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            nft_mint.key().as_ref(),
+            b"edition"
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub nft_master_edition: Account<'info, MasterEditionAccount>,
+
+    #[account(
+        mut,
+        constraint = user_nft_token_account.mint == nft_mint.key(),
+        constraint = user_nft_token_account.owner == user.key(),
+        constraint = user_nft_token_account.amount == 1
+    )]
+    pub user_nft_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_nft_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Instructions sysvar, used to identify CPI callers.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Only required when a registered partner program is the caller.
+    #[account(seeds = [b"partner", partner.program_id.as_ref()], bump = partner.bump)]
+    pub partner: Option<Account<'info, PartnerProgram>>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn stake_nft(ctx: Context<StakeNft>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    let is_new_staker = user_stake.user == Pubkey::default();
+    if is_new_staker {
+        user_stake.version = UserStakeAccount::CURRENT_VERSION;
+    }
+    user_stake.require_current_version()?;
+
+    require!(!vault.decommissioned, ErrorCode::VaultDecommissioned);
+    require!(!config.is_paused(VaultConfig::PAUSE_STAKE), ErrorCode::VaultPaused);
+    ensure_not_suspended(&ctx.accounts.user_status)?;
+    ensure_whitelisted(vault, ctx.accounts.whitelist_entry.as_deref(), clock.unix_timestamp)?;
+    ensure_allowed_caller(
+        vault,
+        &ctx.accounts.instructions,
+        ctx.accounts.partner.as_deref(),
+        |partner| partner.can_stake,
+    )?;
+
+    shard.sync_config(config);
+
+    // Circuit breaker check
+    require!(
+        shard.can_execute(config, clock.unix_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+
+    // Daily limits check
+    shard.daily_limit.reset_if_new_day(clock.unix_timestamp);
+    shard.daily_limit.check_stake()?;
+    user_stake.take_rate_token(&vault.user_rate_limit, clock.unix_timestamp)?;
+    shard.check_stake_caps(vault, user_stake.staked_nfts, 1)?;
+    require!(
+        ctx.accounts.nft_mint.decimals == 0,
+        ErrorCode::InvalidNft
+    );
+    require!(
+        ctx.accounts.user_nft_token_account.amount == 1,
+        ErrorCode::InvalidNft
+    );
+
+    let metadata_account = &ctx.accounts.nft_metadata;
+    require!(
+        metadata_account.collection.is_some(),
+        ErrorCode::NoCollectionFound
+    );
+    
+    let collection = metadata_account.collection.as_ref().unwrap();
+    require!(
+        collection.verified,
+        ErrorCode::CollectionNotVerified
+    );
+    require!(
+        collection.key == vault.collection_mint,
+        ErrorCode::WrongCollection
+    );
+    ensure_trait_verified(vault, ctx.accounts.trait_pass.as_deref())?;
+
+    // A genuine 1/1 hands both authorities to its Master Edition, so no
+    // one can mint a second copy or freeze it
+    let master_edition = ctx.accounts.nft_master_edition.key();
+    require!(
+        ctx.accounts.nft_mint.mint_authority == COption::Some(master_edition)
+            && ctx.accounts.nft_mint.freeze_authority == COption::Some(master_edition)
+            && ctx.accounts.nft_mint.supply == 1,
+        ErrorCode::InvalidMasterEdition
+    );
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.user_nft_token_account.to_account_info(),
+            to: ctx.accounts.vault_nft_token_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    user_stake.user = ctx.accounts.user.key();
+    user_stake.record_first_stake(clock.unix_timestamp);
+    if is_new_staker && ctx.accounts.payer.key() != user_stake.user {
+        user_stake.rent_payer = Some(ctx.accounts.payer.key());
+    }
+    // Referrals only count for a user's first stake
+    if let (true, Some(referral)) = (is_new_staker, ctx.accounts.referral.as_mut()) {
+        require!(referral.referrer != user_stake.user, ErrorCode::SelfReferral);
+
+        user_stake.referrer = Some(referral.referrer);
+        referral.referred_users = referral.referred_users
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit_cpi!(ReferralRecorded {
+            referrer: referral.referrer,
+            user: user_stake.user,
+            referred_users: referral.referred_users,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+    if let Some(group) = nft_group(&ctx.accounts.nft_group_tag)? {
+        user_stake.join_group(group)?;
+    }
+    user_stake.add_nft_bonus(nft_bonus_bps(&ctx.accounts.nft_multiplier)?)?;
+    if user_stake.staked_nfts == 0 {
+        user_stake.start_streak(clock.unix_timestamp);
+
+        emit_cpi!(StreakUpdated {
+            user: user_stake.user,
+            current_streak_days: 0,
+            streak_started_at: user_stake.streak_started_at,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+    user_stake.staked_nfts = user_stake.staked_nfts
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.add_staked_at(clock.unix_timestamp)?;
+    user_stake.start_warmup(vault.warmup_seconds, 1, clock.unix_timestamp)?;
+
+    let stake_record = &mut ctx.accounts.stake_record;
+    stake_record.user = ctx.accounts.user.key();
+    stake_record.nft_mint = ctx.accounts.nft_mint.key();
+    stake_record.staked_at = clock.unix_timestamp;
+    stake_record.staked_slot = clock.slot;
+    stake_record.rent_payer = ctx.accounts.payer.key();
+    stake_record.bump = ctx.bumps.stake_record;
+    user_stake.last_stake_slot = clock.slot;
+
+    // Issue a receipt for the position and freeze it so it stays
+    // non-transferable; it is thawed and burned on exit.
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let mint_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.receipt_mint.to_account_info(),
+            to: ctx.accounts.user_receipt_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::mint_to(mint_ctx, 1)?;
+
+    let freeze_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        FreezeAccount {
+            account: ctx.accounts.user_receipt_token_account.to_account_info(),
+            mint: ctx.accounts.receipt_mint.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::freeze_account(freeze_ctx)?;
+
+    // Record successful stake; `fold_vault_shard` moves it into the
+    // vault's totals
+    shard.record_stake(is_new_staker, 1, clock.unix_timestamp)?;
+    shard.daily_limit.record_stake();
+    shard.circuit_breaker.on_success();
+    sync_voter_weight(&ctx.accounts.voter_weight_record, user_stake.staked_nfts)?;
+
+    emit_cpi!(NftStaked {
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        staked_nfts: user_stake.staked_nfts,
+        pending_rewards: user_stake.pending_rewards,
+        total_staked: shard.projected_total_staked(vault),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
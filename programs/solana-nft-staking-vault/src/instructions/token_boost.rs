@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::accrue_user_rewards;
+
+#[derive(Accounts)]
+pub struct StakeTokens<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(address = vault.token_boost.mint)]
+    pub boost_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = boost_mint,
+        token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"token_boost_escrow", boost_mint.key().as_ref()],
+        bump,
+        token::mint = boost_mint,
+        token::authority = vault
+    )]
+    pub token_escrow: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeTokens<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    pub user: Signer<'info>,
+
+    #[account(address = vault.token_boost.mint)]
+    pub boost_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = boost_mint,
+        token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"token_boost_escrow", boost_mint.key().as_ref()],
+        bump
+    )]
+    pub token_escrow: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn stake_tokens(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(!vault.decommissioned, ErrorCode::VaultDecommissioned);
+    require!(!config.is_paused(VaultConfig::PAUSE_STAKE), ErrorCode::VaultPaused);
+    require!(vault.token_boost.is_enabled(), ErrorCode::TokenBoostDisabled);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    // Settle at the boost held so far
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            to: ctx.accounts.token_escrow.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, amount)?;
+
+    user_stake.staked_tokens = user_stake.staked_tokens
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    vault.total_boost_tokens_staked = vault.total_boost_tokens_staked
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(TokensStaked {
+        user: ctx.accounts.user.key(),
+        amount,
+        staked_tokens: user_stake.staked_tokens,
+        boost_active: vault.token_boost.is_met(user_stake.staked_tokens),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(
+        amount > 0 && amount <= user_stake.staked_tokens,
+        ErrorCode::InvalidAmount
+    );
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.token_escrow.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, amount)?;
+
+    user_stake.staked_tokens -= amount;
+    vault.total_boost_tokens_staked = vault.total_boost_tokens_staked
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    emit!(TokensUnstaked {
+        user: ctx.accounts.user.key(),
+        amount,
+        staked_tokens: user_stake.staked_tokens,
+        boost_active: vault.token_boost.is_met(user_stake.staked_tokens),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    keccak,
+    sysvar::{self, instructions::{load_current_index_checked, load_instruction_at_checked}},
+};
+use anchor_spl::token::Mint;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::verify_merkle_proof;
+use super::config::UpdateConfig;
+use super::coupon::verify_ed25519_instruction;
+
+#[derive(Accounts)]
+pub struct VerifyNftTrait<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// Reused when the gate changes; re-verifying moves it to the new version.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TraitPass::INIT_SPACE,
+        seeds = [b"trait_pass", nft_mint.key().as_ref()],
+        bump
+    )]
+    pub trait_pass: Account<'info, TraitPass>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Instructions sysvar, used to find the Ed25519 attestation.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn set_trait_gate(
+    ctx: Context<UpdateConfig>,
+    trait_hash: [u8; 32],
+    merkle_root: Option<[u8; 32]>,
+    attestor: Option<Pubkey>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    let version = vault.trait_gate.version
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    vault.trait_gate = TraitGate {
+        trait_hash,
+        merkle_root,
+        attestor,
+        version,
+    };
+
+    emit!(TraitGateUpdated {
+        trait_hash,
+        merkle_root,
+        attestor,
+        version,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Anyone may verify an NFT; the proof or attestation is what's checked.
+pub(crate) fn verify_nft_trait(ctx: Context<VerifyNftTrait>, proof: Option<Vec<[u8; 32]>>) -> Result<()> {
+    let gate = &ctx.accounts.vault.trait_gate;
+    let nft_mint = ctx.accounts.nft_mint.key();
+
+    require!(gate.is_enabled(), ErrorCode::TraitGateDisabled);
+
+    let attested = match proof {
+        Some(proof) => {
+            let root = gate.merkle_root.ok_or(ErrorCode::InvalidMerkleProof)?;
+            require!(
+                verify_merkle_proof(&proof, root, trait_leaf(&nft_mint, &gate.trait_hash)),
+                ErrorCode::InvalidMerkleProof
+            );
+            false
+        }
+        None => {
+            let attestor = gate.attestor.ok_or(ErrorCode::InvalidTraitAttestation)?;
+            let instructions = ctx.accounts.instructions.to_account_info();
+            let current_index = load_current_index_checked(&instructions)?;
+            require!(current_index > 0, ErrorCode::InvalidTraitAttestation);
+            let ed25519_ix =
+                load_instruction_at_checked(current_index as usize - 1, &instructions)?;
+            verify_ed25519_instruction(
+                &ed25519_ix,
+                &attestor,
+                &trait_attestation_message(&nft_mint, &gate.trait_hash, gate.version),
+            )
+            .map_err(|_| ErrorCode::InvalidTraitAttestation)?;
+            true
+        }
+    };
+
+    let gate_version = gate.version;
+    let pass = &mut ctx.accounts.trait_pass;
+    pass.nft_mint = nft_mint;
+    pass.gate_version = gate_version;
+    pass.bump = ctx.bumps.trait_pass;
+
+    emit!(NftTraitVerified {
+        nft_mint,
+        gate_version,
+        attested,
+        verified_by: ctx.accounts.payer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Leaf hash for `verify_nft_trait`, prefixed like `distribution_leaf`.
+fn trait_leaf(nft_mint: &Pubkey, trait_hash: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[&[0u8], b"trait", nft_mint.as_ref(), trait_hash]).to_bytes()
+}
+
+/// Bytes the attestor signs for `verify_nft_trait`. Includes the gate
+/// version so attestations don't carry over to a changed gate.
+fn trait_attestation_message(
+    nft_mint: &Pubkey,
+    trait_hash: &[u8; 32],
+    gate_version: u32,
+) -> Vec<u8> {
+    [
+        b"nft-vault-trait".as_ref(),
+        crate::ID.as_ref(),
+        nft_mint.as_ref(),
+        trait_hash,
+        &gate_version.to_le_bytes(),
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trait_proof_binds_mint_and_trait() {
+        let generation_one = keccak::hash(b"Generation:1").to_bytes();
+        let mint = Pubkey::new_unique();
+        let eligible = trait_leaf(&mint, &generation_one);
+        let other = trait_leaf(&Pubkey::new_unique(), &generation_one);
+        let (left, right) = if eligible <= other { (eligible, other) } else { (other, eligible) };
+        let root = keccak::hashv(&[&[1u8], &left, &right]).to_bytes();
+
+        assert!(verify_merkle_proof(&[other], root, eligible));
+        let generation_two = keccak::hash(b"Generation:2").to_bytes();
+        assert!(!verify_merkle_proof(&[other], root, trait_leaf(&mint, &generation_two)));
+        let outsider = trait_leaf(&Pubkey::new_unique(), &generation_one);
+        assert!(!verify_merkle_proof(&[other], root, outsider));
+    }
+}
@@ -0,0 +1,314 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::system_program;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositTreasury<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", depositor.key().as_ref()],
+        bump
+    )]
+    pub depositor_role: Account<'info, AccountRole>,
+
+    #[account(
+        mut,
+        constraint = depositor_token_account.owner == depositor.key()
+    )]
+    pub depositor_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = treasury_token_account.owner == treasury.key() @ ErrorCode::InvalidTreasuryAccount
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageTreasury<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", admin.key().as_ref()],
+        bump
+    )]
+    pub admin_role: Account<'info, AccountRole>,
+
+    /// CHECK: Must match the pending withdrawal's destination; a wallet for
+    /// SOL or a token account for SPL withdrawals.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = treasury_token_account.owner == treasury.key() @ ErrorCode::InvalidTreasuryAccount
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+    let admin_role = &ctx.accounts.admin_role;
+    admin_role.require_permission(Role::can_manage_treasury)?;
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.pending_withdrawal = None;
+    treasury.bump = ctx.bumps.treasury;
+
+    emit!(TreasuryInitialized {
+        treasury: treasury.key(),
+        initialized_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn deposit_treasury(ctx: Context<DepositTreasury>, amount: u64) -> Result<()> {
+    let depositor_role = &ctx.accounts.depositor_role;
+
+    depositor_role.require_permission(Role::can_manage_treasury)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let mint = match (
+        &ctx.accounts.depositor_token_account,
+        &ctx.accounts.treasury_token_account,
+    ) {
+        (Some(from), Some(to)) => {
+            require!(from.mint == to.mint, ErrorCode::InvalidTreasuryAccount);
+
+            let transfer_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.depositor.to_account_info(),
+                },
+            );
+            token::transfer(transfer_ctx, amount)?;
+            Some(to.mint)
+        }
+        (None, None) => {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.depositor.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+            None
+        }
+        _ => return err!(ErrorCode::InvalidTreasuryAccount),
+    };
+
+    emit!(TreasuryDeposited {
+        depositor: ctx.accounts.depositor.key(),
+        mint,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn propose_treasury_withdrawal(
+    ctx: Context<ManageTreasury>,
+    mint: Option<Pubkey>,
+    amount: u64,
+    destination: Pubkey,
+    timelock_seconds: i64,
+) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_treasury)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        treasury.pending_withdrawal.is_none(),
+        ErrorCode::TreasuryWithdrawalPending
+    );
+    require!(
+        timelock_seconds >= Treasury::MIN_TIMELOCK_SECONDS,
+        ErrorCode::InvalidTimelock
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let scheduled_timestamp = now
+        .checked_add(timelock_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    treasury.pending_withdrawal = Some(PendingTreasuryWithdrawal {
+        mint,
+        amount,
+        destination,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+    });
+
+    emit!(TreasuryWithdrawalProposed {
+        mint,
+        amount,
+        destination,
+        scheduled_timestamp,
+        proposer: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn withdraw_treasury(ctx: Context<WithdrawTreasury>) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_treasury)?;
+
+    let pending = treasury.pending_withdrawal.clone()
+        .ok_or(ErrorCode::NoTreasuryWithdrawalPending)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= pending.scheduled_timestamp,
+        ErrorCode::TimelockNotExpired
+    );
+    require!(
+        ctx.accounts.destination.key() == pending.destination,
+        ErrorCode::InvalidTreasuryAccount
+    );
+
+    match pending.mint {
+        Some(mint) => {
+            let treasury_token_account = ctx.accounts.treasury_token_account
+                .as_ref()
+                .ok_or(ErrorCode::InvalidTreasuryAccount)?;
+            require!(treasury_token_account.mint == mint, ErrorCode::InvalidTreasuryAccount);
+            require!(
+                treasury_token_account.amount >= pending.amount,
+                ErrorCode::InsufficientTreasuryFunds
+            );
+
+            let seeds = &[b"treasury".as_ref(), &[treasury.bump]];
+            let signer = &[&seeds[..]];
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: treasury_token_account.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: treasury.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(transfer_ctx, pending.amount)?;
+        }
+        None => {
+            // Keep the treasury account itself rent exempt
+            let treasury_info = treasury.to_account_info();
+            let available = treasury_info
+                .lamports()
+                .saturating_sub(Rent::get()?.minimum_balance(treasury_info.data_len()));
+            require!(
+                available >= pending.amount,
+                ErrorCode::InsufficientTreasuryFunds
+            );
+
+            let destination = ctx.accounts.destination.to_account_info();
+            **treasury_info.try_borrow_mut_lamports()? -= pending.amount;
+            **destination.try_borrow_mut_lamports()? = destination
+                .lamports()
+                .checked_add(pending.amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+    }
+
+    treasury.pending_withdrawal = None;
+
+    emit!(TreasuryWithdrawn {
+        mint: pending.mint,
+        amount: pending.amount,
+        destination: pending.destination,
+        executor: ctx.accounts.admin.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_treasury_withdrawal(ctx: Context<ManageTreasury>) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+    let admin_role = &ctx.accounts.admin_role;
+
+    admin_role.require_permission(Role::can_manage_treasury)?;
+    require!(
+        treasury.pending_withdrawal.is_some(),
+        ErrorCode::NoTreasuryWithdrawalPending
+    );
+
+    treasury.pending_withdrawal = None;
+
+    emit!(TreasuryWithdrawalCancelled {
+        cancelled_by: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,531 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer, CloseAccount};
+use anchor_lang::solana_program::sysvar;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{
+    BPS_DENOMINATOR, accrue_user_rewards, ensure_allowed_caller, release_stake_record,
+    load_stake_record, sync_voter_weight, nft_group, nft_bonus_bps, burn_receipt,
+    ensure_cooldown_elapsed,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UnstakeNft<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    /// This user's accumulator; see `VaultShard`.
+    #[account(
+        mut,
+        seeds = [b"vault_shard", vault_shard.load()?.index.to_le_bytes().as_ref()],
+        bump = vault_shard.load()?.bump,
+        constraint = vault_shard.load()?.index == VaultShard::index_for(&user.key()) @ ErrorCode::WrongVaultShard
+    )]
+    pub vault_shard: AccountLoader<'info, VaultShard>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Whoever funded the NFT's accounts at stake time; receives
+    /// their rent. Checked against the stake record in the handler.
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Closed by the handler. Legacy NFTs staked before per-NFT
+    /// records existed need `backfill_stake_record` first.
+    #[account(mut, seeds = [b"stake_record", nft_mint.key().as_ref()], bump)]
+    pub stake_record: UncheckedAccount<'info>,
+
+    /// CHECK: Uninitialized for NFTs staked before receipts were issued;
+    /// validated in `burn_receipt`.
+    #[account(mut, seeds = [b"receipt_mint", nft_mint.key().as_ref()], bump)]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// Required whenever a receipt was issued for this NFT.
+    #[account(mut)]
+    pub user_receipt_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Set-bonus tag; may be uninitialized for untagged NFTs.
+    #[account(seeds = [b"nft_group", nft_mint.key().as_ref()], bump)]
+    pub nft_group_tag: UncheckedAccount<'info>,
+
+    /// CHECK: Reward multiplier; may be uninitialized for plain NFTs.
+    #[account(seeds = [b"nft_multiplier", nft_mint.key().as_ref()], bump)]
+    pub nft_multiplier: UncheckedAccount<'info>,
+
+    /// CHECK: The user's voter-weight record; may not exist.
+    #[account(mut, seeds = [b"voter_weight_record", user.key().as_ref()], bump)]
+    pub voter_weight_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_nft_token_account.mint == nft_mint.key(),
+        constraint = user_nft_token_account.owner == user.key()
+    )]
+    pub user_nft_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_nft_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Instructions sysvar, used to identify CPI callers.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Only required when a registered partner program is the caller.
+    #[account(seeds = [b"partner", partner.program_id.as_ref()], bump = partner.bump)]
+    pub partner: Option<Account<'info, PartnerProgram>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_record", stake_record.nft_mint.as_ref()],
+        bump = stake_record.bump,
+        constraint = stake_record.user == user.key() @ ErrorCode::StakeRecordMismatch
+    )]
+    pub stake_record: Account<'info, StakeRecord>,
+
+    /// CHECK: Set-bonus tag; may be uninitialized for untagged NFTs.
+    #[account(seeds = [b"nft_group", stake_record.nft_mint.as_ref()], bump)]
+    pub nft_group_tag: UncheckedAccount<'info>,
+
+    /// CHECK: Reward multiplier; may be uninitialized for plain NFTs.
+    #[account(seeds = [b"nft_multiplier", stake_record.nft_mint.as_ref()], bump)]
+    pub nft_multiplier: UncheckedAccount<'info>,
+
+    /// CHECK: The user's voter-weight record; may not exist.
+    #[account(mut, seeds = [b"voter_weight_record", user.key().as_ref()], bump)]
+    pub voter_weight_record: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeUnstake<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(mut, seeds = [b"vault_stats"], bump = vault_stats.bump)]
+    pub vault_stats: Account<'info, VaultStats>,
+
+    #[account(mut, seeds = [b"vault_metrics"], bump = vault_metrics.bump)]
+    pub vault_metrics: Account<'info, VaultMetrics>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_record", nft_mint.key().as_ref()],
+        bump = stake_record.bump,
+        constraint = stake_record.user == user.key() @ ErrorCode::StakeRecordMismatch,
+        close = rent_receiver
+    )]
+    pub stake_record: Account<'info, StakeRecord>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: Whoever funded the NFT's accounts at stake time; receives
+    /// their rent.
+    #[account(
+        mut,
+        constraint = rent_receiver.key() == stake_record.rent_receiver() @ ErrorCode::InvalidRentReceiver
+    )]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Uninitialized for NFTs staked before receipts were issued;
+    /// validated in `burn_receipt`.
+    #[account(mut, seeds = [b"receipt_mint", nft_mint.key().as_ref()], bump)]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// Required whenever a receipt was issued for this NFT.
+    #[account(mut)]
+    pub user_receipt_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_nft_token_account.mint == nft_mint.key(),
+        constraint = user_nft_token_account.owner == user.key()
+    )]
+    pub user_nft_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_nft_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn unstake_nft(ctx: Context<UnstakeNft>) -> Result<()> {
+    process_unstake(ctx, false)
+}
+
+pub(crate) fn emergency_unstake(ctx: Context<UnstakeNft>) -> Result<()> {
+    process_unstake(ctx, true)
+}
+
+pub(crate) fn request_unstake(ctx: Context<RequestUnstake>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let stake_record = &mut ctx.accounts.stake_record;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(vault.unstake_cooldown_seconds > 0, ErrorCode::UnstakeQueueDisabled);
+    require!(!config.is_paused(VaultConfig::PAUSE_UNSTAKE), ErrorCode::VaultPaused);
+    require!(clock.unix_timestamp >= user_stake.locked_until, ErrorCode::StakeLocked);
+    require!(
+        config.circuit_breaker.can_execute(clock.unix_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+    require!(stake_record.unlocks_at == 0, ErrorCode::UnstakeAlreadyRequested);
+    require!(
+        vault.min_stake_slots_elapsed(stake_record.staked_slot, clock.slot),
+        ErrorCode::StakedTooRecently
+    );
+
+    let rewards_earned = accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    user_stake.remove_staked_at(stake_record.staked_at)?;
+    if let Some(group) = nft_group(&ctx.accounts.nft_group_tag)? {
+        user_stake.leave_group(group);
+    }
+    user_stake.remove_nft_bonus(nft_bonus_bps(&ctx.accounts.nft_multiplier)?);
+    user_stake.staked_nfts = user_stake.staked_nfts
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    user_stake.end_warmup_for(
+        stake_record.staked_at,
+        vault.warmup_seconds,
+        clock.unix_timestamp,
+    );
+    if user_stake.staked_nfts == 0 {
+        user_stake.clear_lock();
+        user_stake.end_streak();
+
+        emit_cpi!(StreakUpdated {
+            user: user_stake.user,
+            current_streak_days: 0,
+            streak_started_at: 0,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    vault.update_reward_index(clock.unix_timestamp)?;
+    vault.total_staked = vault.total_staked
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    vault.pending_unstakes = vault.pending_unstakes
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    stake_record.unlocks_at = clock.unix_timestamp
+        .checked_add(vault.unstake_cooldown_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    sync_voter_weight(&ctx.accounts.voter_weight_record, user_stake.staked_nfts)?;
+
+    emit_cpi!(UnstakeRequested {
+        user: ctx.accounts.user.key(),
+        nft_mint: stake_record.nft_mint,
+        unlocks_at: stake_record.unlocks_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn finalize_unstake(ctx: Context<FinalizeUnstake>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let stake_record = &ctx.accounts.stake_record;
+    let clock = Clock::get()?;
+
+    require!(stake_record.unlocks_at > 0, ErrorCode::NoUnstakeRequest);
+    if !vault.emergency_mode {
+        require!(!config.is_paused(VaultConfig::PAUSE_UNSTAKE), ErrorCode::VaultPaused);
+        require!(
+            clock.unix_timestamp >= stake_record.unlocks_at,
+            ErrorCode::UnstakeCooldownActive
+        );
+    }
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault_nft_token_account.to_account_info(),
+            to: ctx.accounts.user_nft_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    let close_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault_nft_token_account.to_account_info(),
+            destination: ctx.accounts.rent_receiver.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::close_account(close_ctx)?;
+
+    burn_receipt(
+        &vault.to_account_info(),
+        signer,
+        &ctx.accounts.receipt_mint,
+        ctx.accounts.user_receipt_token_account.as_ref(),
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.rent_receiver.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    vault.pending_unstakes = vault.pending_unstakes
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    ctx.accounts.vault_stats.record_unstake()?;
+    ctx.accounts.vault_metrics.update(vault.total_staked, 0, clock.unix_timestamp)?;
+
+    emit_cpi!(UnstakeFinalized {
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Shared body of `unstake_nft` and `emergency_unstake`; `early` applies
+/// the lock penalty instead of requiring the lock to have expired.
+fn process_unstake(ctx: Context<UnstakeNft>, early: bool) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let config = &ctx.accounts.vault_config.load()?;
+    let shard = &mut ctx.accounts.vault_shard.load_mut()?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+    ensure_allowed_caller(
+        vault,
+        &ctx.accounts.instructions,
+        ctx.accounts.partner.as_deref(),
+        |partner| partner.can_stake,
+    )?;
+
+    require!(user_stake.staked_nfts > 0, ErrorCode::NoNftsStaked);
+
+    let locked = clock.unix_timestamp < user_stake.locked_until;
+    if early {
+        require!(locked, ErrorCode::StakeNotLocked);
+    }
+
+    let record = load_stake_record(&ctx.accounts.stake_record)?
+        .ok_or(ErrorCode::StakeRecordRequired)?;
+    let staked_at = record.staked_at;
+    // Unlike the cooldown, the slot guard also holds in emergency mode: it
+    // only blocks atomic stake-and-exit, never a real exit
+    require!(
+        vault.min_stake_slots_elapsed(record.staked_slot, clock.slot),
+        ErrorCode::StakedTooRecently
+    );
+    require!(
+        ctx.accounts.rent_receiver.key() == record.rent_receiver(),
+        ErrorCode::InvalidRentReceiver
+    );
+
+    shard.sync_config(config);
+
+    // Emergency mode guarantees users can always exit custody, so the
+    // pause flag, circuit breaker, cooldown and lock are skipped.
+    if !vault.emergency_mode {
+        require!(!config.is_paused(VaultConfig::PAUSE_UNSTAKE), ErrorCode::VaultPaused);
+        require!(
+            shard.can_execute(config, clock.unix_timestamp),
+            ErrorCode::CircuitBreakerActive
+        );
+        if !early {
+            require!(!locked, ErrorCode::StakeLocked);
+            // Cooldown runs per NFT, so other NFTs of the same wallet can
+            // move freely
+            ensure_cooldown_elapsed(
+                staked_at,
+                vault.action_cooldown_seconds,
+                clock.unix_timestamp,
+                ErrorCode::TooFrequent,
+            )?;
+            // With an exit queue configured, recorded NFTs must go through
+            // `request_unstake` / `finalize_unstake`
+            require!(
+                vault.unstake_cooldown_seconds == 0 || ctx.accounts.stake_record.data_is_empty(),
+                ErrorCode::UnstakeRequestRequired
+            );
+        }
+    }
+
+    let rewards_earned = if vault.emergency_mode {
+        // Never let reward math block an emergency exit
+        accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp).unwrap_or(0)
+    } else {
+        accrue_user_rewards(vault, config, user_stake, clock.unix_timestamp)?
+    };
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(rewards_earned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.touch(vault, clock.unix_timestamp)?;
+
+    let penalty = if early {
+        let penalty = (user_stake.pending_rewards as u128)
+            .checked_mul(vault.early_unstake_penalty_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        let penalty = penalty as u64;
+        user_stake.pending_rewards -= penalty;
+        penalty
+    } else {
+        0
+    };
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault_nft_token_account.to_account_info(),
+            to: ctx.accounts.user_nft_token_account.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, 1)?;
+
+    // The per-mint ATA is empty now; return its rent to whoever funded it
+    let close_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault_nft_token_account.to_account_info(),
+            destination: ctx.accounts.rent_receiver.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token::close_account(close_ctx)?;
+
+    burn_receipt(
+        &vault.to_account_info(),
+        signer,
+        &ctx.accounts.receipt_mint,
+        ctx.accounts.user_receipt_token_account.as_ref(),
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.rent_receiver.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    if let Some(group) = nft_group(&ctx.accounts.nft_group_tag)? {
+        user_stake.leave_group(group);
+    }
+    user_stake.remove_nft_bonus(nft_bonus_bps(&ctx.accounts.nft_multiplier)?);
+    let staked_at = release_stake_record(
+        &ctx.accounts.stake_record,
+        user_stake,
+        &ctx.accounts.rent_receiver.to_account_info(),
+    )?;
+    user_stake.staked_nfts = user_stake.staked_nfts
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    user_stake.end_warmup_for(staked_at, vault.warmup_seconds, clock.unix_timestamp);
+
+    if user_stake.staked_nfts == 0 {
+        user_stake.clear_lock();
+        user_stake.end_streak();
+
+        emit_cpi!(StreakUpdated {
+            user: user_stake.user,
+            current_streak_days: 0,
+            streak_started_at: 0,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    shard.record_unstake(1, penalty)?;
+    sync_voter_weight(&ctx.accounts.voter_weight_record, user_stake.staked_nfts)?;
+
+    if penalty > 0 {
+        // Booked against the vault when the shard is folded
+        let redistributed = vault.penalty_mode == PenaltyMode::Redistribute;
+
+        emit_cpi!(EarlyUnstakePenalty {
+            user: ctx.accounts.user.key(),
+            nft_mint: ctx.accounts.nft_mint.key(),
+            penalty,
+            redistributed,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    emit_cpi!(NftUnstaked {
+        user: ctx.accounts.user.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        staked_nfts: user_stake.staked_nfts,
+        pending_rewards: user_stake.pending_rewards,
+        total_staked: shard.projected_total_staked(vault),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
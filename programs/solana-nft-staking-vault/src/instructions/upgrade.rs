@@ -0,0 +1,377 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, program::invoke_signed};
+use solana_loader_v3_interface::{instruction as loader_v3, state::UpgradeableLoaderState};
+use solana_sdk_ids::bpf_loader_upgradeable;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::config::UpdateConfig;
+
+#[derive(Accounts)]
+pub struct ProposeUpgrade<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", proposer.key().as_ref()],
+        bump
+    )]
+    pub proposer_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteUpgrade<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", executor.key().as_ref()],
+        bump
+    )]
+    pub executor_role: Account<'info, AccountRole>,
+
+    /// CHECK: This program's own account
+    #[account(mut, address = crate::ID)]
+    pub program: UncheckedAccount<'info>,
+
+    /// CHECK: The program's data account; the loader checks the vault is
+    /// its upgrade authority.
+    #[account(
+        mut,
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    /// CHECK: Must be the proposal's buffer; its contents are hashed.
+    #[account(mut)]
+    pub buffer: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the buffer's lamports once it is consumed
+    #[account(mut)]
+    pub spill: UncheckedAccount<'info>,
+
+    /// CHECK: The BPF upgradeable loader
+    #[account(address = bpf_loader_upgradeable::ID)]
+    pub bpf_loader_upgradeable: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct ObjectToUpgrade<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"user_stake", voter.key().as_ref()], bump)]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + UpgradeObjection::INIT_SPACE,
+        seeds = [
+            b"upgrade_objection",
+            vault.pending_upgrade.as_ref().map_or(0, |upgrade| upgrade.id).to_le_bytes().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub objection: Account<'info, UpgradeObjection>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelUpgrade<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub canceller: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", canceller.key().as_ref()],
+        bump
+    )]
+    pub canceller_role: Account<'info, AccountRole>,
+}
+
+#[derive(Accounts)]
+pub struct LockUpgrades<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub locker: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", locker.key().as_ref()],
+        bump
+    )]
+    pub locker_role: Account<'info, AccountRole>,
+
+    /// CHECK: The program's data account; its upgrade authority is cleared.
+    #[account(
+        mut,
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID
+    )]
+    pub program_data: UncheckedAccount<'info>,
+
+    /// CHECK: The BPF upgradeable loader
+    #[account(address = bpf_loader_upgradeable::ID)]
+    pub bpf_loader_upgradeable: UncheckedAccount<'info>,
+}
+
+/// Schedules an upgrade to the program in `buffer`. The buffer's authority
+/// must already be the vault PDA, which must also be the program's upgrade
+/// authority.
+pub(crate) fn propose_upgrade(
+    ctx: Context<ProposeUpgrade>,
+    new_version: u32,
+    buffer: Pubkey,
+    buffer_hash: [u8; 32],
+    timelock_seconds: i64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let proposer_role = &ctx.accounts.proposer_role;
+
+    require!(!vault.upgrade_locked, ErrorCode::UpgradesLocked);
+    require!(vault.pending_upgrade.is_none(), ErrorCode::UpgradePending);
+    proposer_role.require_permission(Role::can_manage_upgrades)?;
+    require!(new_version > vault.version, ErrorCode::InvalidVersion);
+    require!(
+        timelock_seconds >= 3600, // Minimum 1 hour
+        ErrorCode::InvalidTimelock
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let scheduled_timestamp = now + timelock_seconds;
+    let id = vault.upgrade_proposal_count;
+    vault.upgrade_proposal_count = id.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+
+    vault.pending_upgrade = Some(PendingUpgrade {
+        new_version,
+        scheduled_timestamp,
+        proposer: ctx.accounts.proposer.key(),
+        buffer,
+        buffer_hash,
+        approved: false,
+        id,
+        proposed_at: now,
+        objections: 0,
+        veto_threshold: vault.upgrade_veto_threshold(),
+    });
+
+    emit!(UpgradeProposed {
+        new_version,
+        buffer,
+        buffer_hash,
+        scheduled_timestamp,
+        proposer: ctx.accounts.proposer.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Upgrades the program from the proposal's buffer through the loader,
+/// signing as the vault PDA, then records the new version.
+pub(crate) fn execute_upgrade(ctx: Context<ExecuteUpgrade>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let executor_role = &ctx.accounts.executor_role;
+    
+    executor_role.require_permission(Role::can_manage_upgrades)?;
+
+    let pending = vault.pending_upgrade.clone().ok_or(ErrorCode::NoUpgradePending)?;
+    require!(
+        vault.approval_threshold <= 1 || pending.approved,
+        ErrorCode::ApprovalRequired
+    );
+    require!(
+        ctx.accounts.buffer.key() == pending.buffer,
+        ErrorCode::UpgradeBufferMismatch
+    );
+    {
+        // Guards against the buffer being rewritten during the timelock
+        let data = ctx.accounts.buffer.try_borrow_data()?;
+        let offset = UpgradeableLoaderState::size_of_buffer_metadata();
+        require!(
+            data.len() > offset && hash(&data[offset..]).to_bytes() == pending.buffer_hash,
+            ErrorCode::UpgradeBufferMismatch
+        );
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    vault.apply_upgrade(now)?;
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    invoke_signed(
+        &loader_v3::upgrade(
+            &crate::ID,
+            &pending.buffer,
+            &vault.key(),
+            &ctx.accounts.spill.key(),
+        ),
+        &[
+            ctx.accounts.program_data.to_account_info(),
+            ctx.accounts.program.to_account_info(),
+            ctx.accounts.buffer.to_account_info(),
+            ctx.accounts.spill.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            vault.to_account_info(),
+            ctx.accounts.bpf_loader_upgradeable.to_account_info(),
+        ],
+        &[&seeds[..]],
+    )?;
+
+    emit!(UpgradeExecuted {
+        new_version: vault.version,
+        executor: ctx.accounts.executor.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Registers the staker's objection, weighted by the NFTs they had staked
+/// when it was proposed, to the pending upgrade. Enough objections before
+/// the timelock ends cancel it. As with rate votes, only positions staked
+/// since before the proposal count.
+pub(crate) fn object_to_upgrade(ctx: Context<ObjectToUpgrade>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let user_stake = &ctx.accounts.user_stake;
+    let now = Clock::get()?.unix_timestamp;
+
+    user_stake.require_current_version()?;
+
+    let pending = vault.pending_upgrade.as_mut().ok_or(ErrorCode::NoUpgradePending)?;
+    require!(pending.veto_threshold > 0, ErrorCode::UpgradeVetoDisabled);
+    require!(now < pending.scheduled_timestamp, ErrorCode::VotingClosed);
+    require!(
+        user_stake.staked_nfts > 0
+            && user_stake.streak_started_at > 0
+            && user_stake.streak_started_at <= pending.proposed_at,
+        ErrorCode::NotEligibleToVote
+    );
+
+    let weight = user_stake.nfts_staked_at_proposal(pending.proposed_at) as u64;
+    require!(weight > 0, ErrorCode::NotEligibleToVote);
+    pending.objections = pending.objections
+        .checked_add(weight)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let objection = &mut ctx.accounts.objection;
+    objection.upgrade_id = pending.id;
+    objection.voter = ctx.accounts.voter.key();
+    objection.weight = weight;
+    objection.bump = ctx.bumps.objection;
+
+    emit!(UpgradeObjected {
+        upgrade_id: pending.id,
+        voter: objection.voter,
+        weight,
+        objections: pending.objections,
+        veto_threshold: pending.veto_threshold,
+        timestamp: now,
+    });
+
+    if pending.objections >= pending.veto_threshold {
+        emit!(UpgradeVetoed {
+            upgrade_id: pending.id,
+            new_version: pending.new_version,
+            objections: pending.objections,
+            timestamp: now,
+        });
+        vault.pending_upgrade = None;
+    }
+
+    Ok(())
+}
+
+/// Once on, the veto can't be turned off again, and changes only apply to
+/// upgrades proposed afterwards.
+pub(crate) fn set_upgrade_veto(ctx: Context<UpdateConfig>, veto_bps: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        veto_bps <= VaultAccount::MAX_UPGRADE_VETO_BPS
+            && (veto_bps > 0 || vault.upgrade_veto_bps == 0),
+        ErrorCode::InvalidUpgradeVeto
+    );
+
+    vault.upgrade_veto_bps = veto_bps;
+
+    emit!(UpgradeVetoUpdated {
+        veto_bps,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_upgrade(ctx: Context<CancelUpgrade>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let canceller_role = &ctx.accounts.canceller_role;
+    
+    canceller_role.require_permission(Role::can_manage_upgrades)?;
+    require!(vault.pending_upgrade.is_some(), ErrorCode::NoUpgradePending);
+
+    vault.pending_upgrade = None;
+
+    emit!(UpgradeCancelled {
+        cancelled_by: ctx.accounts.canceller.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Makes the program immutable by clearing its upgrade authority. Final:
+/// not even a new vault version can undo it.
+pub(crate) fn lock_upgrades(ctx: Context<LockUpgrades>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let locker_role = &ctx.accounts.locker_role;
+    
+    locker_role.require_permission(Role::can_manage_upgrades)?;
+    require!(!vault.upgrade_locked, ErrorCode::UpgradesAlreadyLocked);
+
+    vault.upgrade_locked = true;
+    vault.pending_upgrade = None;
+
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    invoke_signed(
+        &loader_v3::set_upgrade_authority(&crate::ID, &vault.key(), None),
+        &[
+            ctx.accounts.program_data.to_account_info(),
+            vault.to_account_info(),
+            ctx.accounts.bpf_loader_upgradeable.to_account_info(),
+        ],
+        &[&seeds[..]],
+    )?;
+
+    emit!(UpgradesLocked {
+        locked_by: ctx.accounts.locker.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,308 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::config::UpdateConfig;
+
+#[derive(Accounts)]
+pub struct CreateRateProposal<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"user_stake", proposer.key().as_ref()], bump)]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + RateProposal::INIT_SPACE,
+        seeds = [b"rate_proposal", vault.rate_proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, RateProposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastRateVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"rate_proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, RateProposal>,
+
+    #[account(seeds = [b"user_stake", voter.key().as_ref()], bump)]
+    pub user_stake: Account<'info, UserStakeAccount>,
+
+    /// One per voter and proposal, so each wallet votes once.
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + RateVoteRecord::INIT_SPACE,
+        seeds = [b"rate_vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, RateVoteRecord>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRateProposal<'info> {
+    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut, seeds = [b"vault_config"], bump = vault_config.load()?.bump)]
+    pub vault_config: AccountLoader<'info, VaultConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"rate_proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, RateProposal>,
+
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVoterWeightRecord<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(seeds = [b"user_stake", user.key().as_ref()], bump)]
+    pub user_stake: Option<Account<'info, UserStakeAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + VoterWeightRecord::INIT_SPACE,
+        seeds = [b"voter_weight_record", user.key().as_ref()],
+        bump
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn configure_voter_weight(
+    ctx: Context<UpdateConfig>,
+    governance_realm: Option<Pubkey>,
+    governing_token_mint: Option<Pubkey>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        governance_realm.is_some() == governing_token_mint.is_some(),
+        ErrorCode::InvalidGovernanceConfig
+    );
+
+    vault.governance_realm = governance_realm;
+    vault.governing_token_mint = governing_token_mint;
+
+    emit!(VoterWeightConfigured {
+        governance_realm,
+        governing_token_mint,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn create_voter_weight_record(ctx: Context<CreateVoterWeightRecord>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let record = &mut ctx.accounts.voter_weight_record;
+
+    let (Some(realm), Some(governing_token_mint)) =
+        (vault.governance_realm, vault.governing_token_mint)
+    else {
+        return err!(ErrorCode::InvalidGovernanceConfig);
+    };
+
+    record.realm = realm;
+    record.governing_token_mint = governing_token_mint;
+    record.governing_token_owner = ctx.accounts.user.key();
+    record.voter_weight = ctx.accounts.user_stake
+        .as_ref()
+        .map_or(0, |user_stake| user_stake.staked_nfts as u64);
+    record.voter_weight_expiry = None;
+    record.weight_action = None;
+    record.weight_action_target = None;
+
+    emit!(VoterWeightUpdated {
+        user: record.governing_token_owner,
+        voter_weight: record.voter_weight,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_rate_vote_bounds(
+    ctx: Context<UpdateConfig>,
+    min_rate: u64,
+    max_rate: u64,
+    quorum: u64,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        max_rate == 0 || (min_rate > 0 && min_rate <= max_rate && quorum > 0),
+        ErrorCode::InvalidRateVoteConfig
+    );
+
+    vault.rate_vote_min = min_rate;
+    vault.rate_vote_max = max_rate;
+    vault.rate_vote_quorum = quorum;
+
+    emit!(RateVoteBoundsUpdated {
+        min_rate,
+        max_rate,
+        quorum,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn create_proposal(ctx: Context<CreateRateProposal>, new_rate: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let user_stake = &ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    require!(vault.rate_vote_max > 0, ErrorCode::RateVotingDisabled);
+    require!(
+        new_rate >= vault.rate_vote_min && new_rate <= vault.rate_vote_max,
+        ErrorCode::RateOutOfBounds
+    );
+    require!(user_stake.staked_nfts > 0, ErrorCode::NoNftsStaked);
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.id = vault.rate_proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.new_rate = new_rate;
+    proposal.yes_votes = 0;
+    proposal.no_votes = 0;
+    proposal.created_at = clock.unix_timestamp;
+    proposal.voting_ends_at = clock.unix_timestamp
+        .checked_add(RateProposal::VOTING_PERIOD_SECONDS)
+        .ok_or(ErrorCode::MathOverflow)?;
+    proposal.executed = false;
+    proposal.bump = ctx.bumps.proposal;
+
+    vault.rate_proposal_count = vault.rate_proposal_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(RateProposalCreated {
+        proposal_id: proposal.id,
+        proposer: proposal.proposer,
+        new_rate,
+        voting_ends_at: proposal.voting_ends_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cast_vote(ctx: Context<CastRateVote>, approve: bool) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    let user_stake = &ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    user_stake.require_current_version()?;
+
+    require!(clock.unix_timestamp < proposal.voting_ends_at, ErrorCode::VotingClosed);
+    require!(
+        user_stake.staked_nfts > 0
+            && user_stake.streak_started_at > 0
+            && user_stake.streak_started_at <= proposal.created_at,
+        ErrorCode::NotEligibleToVote
+    );
+
+    let weight = user_stake.staked_nfts as u64;
+    if approve {
+        proposal.yes_votes = proposal.yes_votes
+            .checked_add(weight)
+            .ok_or(ErrorCode::MathOverflow)?;
+    } else {
+        proposal.no_votes = proposal.no_votes
+            .checked_add(weight)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    let vote_record = &mut ctx.accounts.vote_record;
+    vote_record.proposal_id = proposal.id;
+    vote_record.voter = ctx.accounts.voter.key();
+    vote_record.weight = weight;
+    vote_record.approve = approve;
+    vote_record.bump = ctx.bumps.vote_record;
+
+    emit!(RateVoteCast {
+        proposal_id: proposal.id,
+        voter: vote_record.voter,
+        approve,
+        weight,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn execute_proposal(ctx: Context<ExecuteRateProposal>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let config = &mut ctx.accounts.vault_config.load_mut()?;
+    let proposal = &mut ctx.accounts.proposal;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(!proposal.executed, ErrorCode::ProposalAlreadyExecuted);
+    require!(now >= proposal.voting_ends_at, ErrorCode::VotingNotEnded);
+    require!(
+        now - proposal.voting_ends_at <= RateProposal::EXECUTION_WINDOW_SECONDS,
+        ErrorCode::ProposalExpired
+    );
+    require!(
+        proposal.yes_votes > proposal.no_votes
+            && proposal.yes_votes >= vault.rate_vote_quorum,
+        ErrorCode::ProposalRejected
+    );
+    // Bounds may have been tightened since the proposal was created
+    require!(
+        vault.rate_vote_max > 0
+            && proposal.new_rate >= vault.rate_vote_min
+            && proposal.new_rate <= vault.rate_vote_max,
+        ErrorCode::RateOutOfBounds
+    );
+
+    let old_reward_rate = config.reward_rate_per_second;
+    vault.apply_config_update(config, Some(proposal.new_rate), None)?;
+    proposal.executed = true;
+
+    emit!(RateProposalExecuted {
+        proposal_id: proposal.id,
+        old_reward_rate,
+        new_reward_rate: config.reward_rate_per_second,
+        executor: ctx.accounts.executor.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use crate::utils::{create_pda_account, verify_merkle_proof};
+use super::config::UpdateConfig;
+
+#[derive(Accounts)]
+pub struct AddWhitelistEntries<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(mut)]
+    pub updater: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", updater.key().as_ref()],
+        bump
+    )]
+    pub updater_role: Account<'info, AccountRole>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinWhitelist<'info> {
+    #[account(seeds = [b"vault"], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + WhitelistEntry::INIT_SPACE,
+        seeds = [b"whitelist", user.key().as_ref()],
+        bump
+    )]
+    pub whitelist_entry: Account<'info, WhitelistEntry>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn set_whitelist_phase(
+    ctx: Context<UpdateConfig>,
+    public_start_timestamp: i64,
+    merkle_root: Option<[u8; 32]>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let updater_role = &ctx.accounts.updater_role;
+
+    updater_role.require_permission(Role::can_update_config)?;
+
+    vault.public_start_timestamp = public_start_timestamp;
+    vault.whitelist_merkle_root = merkle_root;
+
+    emit!(WhitelistPhaseUpdated {
+        public_start_timestamp,
+        merkle_root,
+        updated_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn add_whitelist_entries<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AddWhitelistEntries<'info>>,
+    users: Vec<Pubkey>,
+) -> Result<()> {
+    ctx.accounts.updater_role.require_permission(Role::can_update_config)?;
+    require!(
+        !users.is_empty() && users.len() == ctx.remaining_accounts.len(),
+        ErrorCode::InvalidRemainingAccounts
+    );
+
+    let mut added = 0u32;
+    for (user, account) in users.iter().zip(ctx.remaining_accounts) {
+        let (address, bump) = Pubkey::find_program_address(
+            &[b"whitelist", user.as_ref()],
+            ctx.program_id,
+        );
+        require!(account.key() == address, ErrorCode::InvalidWhitelistEntry);
+        if account.owner == ctx.program_id {
+            continue;
+        }
+
+        create_pda_account(
+            account,
+            &[b"whitelist", user.as_ref(), &[bump]],
+            8 + WhitelistEntry::INIT_SPACE,
+            &ctx.accounts.updater.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        let entry = WhitelistEntry { user: *user, bump };
+        entry.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+        added += 1;
+    }
+
+    emit!(WhitelistEntriesAdded {
+        count: added,
+        added_by: ctx.accounts.updater.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn join_whitelist(ctx: Context<JoinWhitelist>, proof: Vec<[u8; 32]>) -> Result<()> {
+    let root = ctx.accounts.vault.whitelist_merkle_root
+        .ok_or(ErrorCode::InvalidMerkleProof)?;
+    let user = ctx.accounts.user.key();
+    require!(
+        verify_merkle_proof(&proof, root, whitelist_leaf(&user)),
+        ErrorCode::InvalidMerkleProof
+    );
+
+    let entry = &mut ctx.accounts.whitelist_entry;
+    entry.user = user;
+    entry.bump = ctx.bumps.whitelist_entry;
+
+    emit!(WhitelistJoined {
+        user,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Leaf hash for `join_whitelist`, prefixed like `distribution_leaf`.
+fn whitelist_leaf(wallet: &Pubkey) -> [u8; 32] {
+    keccak::hashv(&[&[0u8], b"whitelist", wallet.as_ref()]).to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitelist_proof_admits_listed_wallets_only() {
+        let alice = whitelist_leaf(&Pubkey::new_unique());
+        let bob = whitelist_leaf(&Pubkey::new_unique());
+        let (left, right) = if alice <= bob { (alice, bob) } else { (bob, alice) };
+        let root = keccak::hashv(&[&[1u8], &left, &right]).to_bytes();
+
+        assert!(verify_merkle_proof(&[bob], root, alice));
+        assert!(verify_merkle_proof(&[alice], root, bob));
+        assert!(!verify_merkle_proof(&[bob], root, whitelist_leaf(&Pubkey::new_unique())));
+    }
+}
@@ -1,13 +1,16 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer, MintTo, SetAuthority},
-    metadata::{
-        mpl_token_metadata::types::{CollectionDetails, DataV2},
-        Metadata, MetadataAccount,
-    },
-};
-use spl_token::instruction::AuthorityType;
+
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub mod state;
+pub mod utils;
+
+pub use errors::ErrorCode;
+pub use events::*;
+pub use instructions::*;
+pub use state::*;
+pub use utils::math::BPS_DENOMINATOR;
 
 declare_id!("B8XmBimHbyZkzL1hsaYJM5BHwbPV2vVGf9eWtWc1zQ9P");
 
@@ -15,1155 +18,1331 @@ declare_id!("B8XmBimHbyZkzL1hsaYJM5BHwbPV2vVGf9eWtWc1zQ9P");
 pub mod solana_nft_staking_vault {
     use super::*;
 
+    /// `reward_tokens_per_day` and `max_total_rewards_per_day` are in whole
+    /// reward tokens and converted with the reward mint's decimals. The
+    /// initializer is made SuperAdmin.
     pub fn initialize_vault(
         ctx: Context<InitializeVault>,
-        reward_rate_per_second: u64,
+        reward_tokens_per_day: u64,
         collection_mint: Pubkey,
+        reward_mode: RewardMode,
+        max_stakes_per_day: u32,
+        max_claims_per_day: u32,
+        max_total_rewards_per_day: u64,
+        failure_threshold: u32,
+        reset_timeout: i64,
+        action_cooldown_seconds: i64,
+        claim_cooldown_seconds: i64,
+    ) -> Result<()> {
+        instructions::initialize::initialize_vault(
+            ctx,
+            reward_tokens_per_day,
+            collection_mint,
+            reward_mode,
+            max_stakes_per_day,
+            max_claims_per_day,
+            max_total_rewards_per_day,
+            failure_threshold,
+            reset_timeout,
+            action_cooldown_seconds,
+            claim_cooldown_seconds,
+        )
+    }
+
+    /// For vaults created before `initialize_vault` granted roles: makes
+    /// the vault authority SuperAdmin, provided it holds no role yet. Other
+    /// existing role accounts go in as remaining accounts.
+    pub fn init_super_admin<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitSuperAdmin<'info>>,
     ) -> Result<()> {
-        require!(reward_rate_per_second > 0, ErrorCode::InvalidRewardRate);
-        
-        let vault = &mut ctx.accounts.vault;
-        vault.authority = ctx.accounts.authority.key();
-        vault.total_staked = 0;
-        vault.reward_token_mint = ctx.accounts.reward_token_mint.key();
-        vault.reward_rate_per_second = reward_rate_per_second;
-        vault.collection_mint = collection_mint;
-        vault.bump = ctx.bumps.vault;
-        vault.paused = false;
-        vault.last_update_timestamp = Clock::get()?.unix_timestamp;
-        
-        // Initialize RBAC & Governance
-        vault.upgrade_authority = ctx.accounts.authority.key();
-        vault.version = 1;
-        vault.upgrade_locked = false;
-        vault.pending_upgrade = None;
-
-        // Initialize Circuit Breaker & Security
-        vault.circuit_breaker = CircuitBreakerState::new();
-        vault.daily_limit = DailyLimits::new();
-
-        let seeds = &[b"vault".as_ref(), &[vault.bump]];
-        let signer = &[&seeds[..]];
-
-        let set_authority_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            SetAuthority {
-                account_or_mint: ctx.accounts.reward_token_mint.to_account_info(),
-                current_authority: ctx.accounts.authority.to_account_info(),
-            },
-            signer,
-        );
-
-        token::set_authority(
-            set_authority_ctx, 
-            AuthorityType::MintTokens, 
-            Some(vault.key())
-        )?;
-
-        // Verify that mint authority was transferred successfully
-        let mint_info = ctx.accounts.reward_token_mint.to_account_info();
-        let mint_account = Mint::try_deserialize(&mut &mint_info.data.borrow()[..])?;
-        require!(
-            mint_account.mint_authority == anchor_lang::prelude::COption::Some(vault.key()),
-            ErrorCode::MintAuthorityTransferFailed
-        );
-
-        Ok(())
+        instructions::initialize::init_super_admin(ctx)
+    }
+
+    /// Creates Token Metadata for the reward mint so wallets show its name,
+    /// symbol and logo. The vault PDA signs as mint authority and stays the
+    /// update authority.
+    pub fn create_reward_metadata(
+        ctx: Context<CreateRewardMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::initialize::create_reward_metadata(ctx, name, symbol, uri)
+    }
+
+    /// Same as `initialize_vault` in `Mint` mode, but the vault creates the
+    /// reward mint at a PDA with itself as mint and freeze authority, so
+    /// there is no authority handoff that can fail.
+    pub fn initialize_vault_with_mint(
+        ctx: Context<InitializeVaultWithMint>,
+        reward_decimals: u8,
+        reward_tokens_per_day: u64,
+        collection_mint: Pubkey,
+        max_stakes_per_day: u32,
+        max_claims_per_day: u32,
+        max_total_rewards_per_day: u64,
+        failure_threshold: u32,
+        reset_timeout: i64,
+        action_cooldown_seconds: i64,
+        claim_cooldown_seconds: i64,
+    ) -> Result<()> {
+        instructions::initialize::initialize_vault_with_mint(
+            ctx,
+            reward_decimals,
+            reward_tokens_per_day,
+            collection_mint,
+            max_stakes_per_day,
+            max_claims_per_day,
+            max_total_rewards_per_day,
+            failure_threshold,
+            reset_timeout,
+            action_cooldown_seconds,
+            claim_cooldown_seconds,
+        )
     }
 
     pub fn stake_nft(ctx: Context<StakeNft>) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let user_stake = &mut ctx.accounts.user_stake;
-        let clock = Clock::get()?;
-
-        require!(!vault.paused, ErrorCode::VaultPaused);
-
-        // Circuit breaker check
-        require!(
-            vault.circuit_breaker.can_execute(clock.unix_timestamp),
-            ErrorCode::CircuitBreakerActive
-        );
-
-        // Daily limits check
-        vault.daily_limit.reset_if_new_day(clock.unix_timestamp);
-        require!(
-            vault.daily_limit.can_stake(),
-            ErrorCode::DailyLimitExceeded
-        );
-        require!(
-            ctx.accounts.nft_mint.decimals == 0,
-            ErrorCode::InvalidNft
-        );
-        require!(
-            ctx.accounts.user_nft_token_account.amount == 1,
-            ErrorCode::InvalidNft
-        );
-
-        let metadata_account = &ctx.accounts.nft_metadata;
-        require!(
-            metadata_account.collection.is_some(),
-            ErrorCode::NoCollectionFound
-        );
-        
-        let collection = metadata_account.collection.as_ref().unwrap();
-        require!(
-            collection.verified,
-            ErrorCode::CollectionNotVerified
-        );
-        require!(
-            collection.key == vault.collection_mint,
-            ErrorCode::WrongCollection
-        );
-
-        if user_stake.last_update_timestamp > 0 {
-            require!(
-                clock.unix_timestamp - user_stake.last_update_timestamp >= 300, // 5 minutes
-                ErrorCode::TooFrequent
-            );
-        }
-
-        if user_stake.staked_nfts > 0 {
-            let time_elapsed = clock.unix_timestamp - user_stake.last_update_timestamp;
-            let rewards_earned = calculate_rewards(
-                time_elapsed, 
-                vault.reward_rate_per_second, 
-                user_stake.staked_nfts as u64
-            )?;
-            
-            user_stake.pending_rewards = user_stake.pending_rewards
-                .checked_add(rewards_earned)
-                .ok_or(ErrorCode::MathOverflow)?;
-        }
-
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_nft_token_account.to_account_info(),
-                to: ctx.accounts.vault_nft_token_account.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        );
-        token::transfer(transfer_ctx, 1)?;
-
-        user_stake.user = ctx.accounts.user.key();
-        user_stake.staked_nfts = user_stake.staked_nfts
-            .checked_add(1)
-            .ok_or(ErrorCode::MathOverflow)?;
-        user_stake.last_update_timestamp = clock.unix_timestamp;
-
-        vault.total_staked = vault.total_staked
-            .checked_add(1)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        // Record successful stake
-        vault.daily_limit.record_stake();
-        vault.circuit_breaker.on_success();
-
-        emit!(NftStaked {
-            user: ctx.accounts.user.key(),
-            nft_mint: ctx.accounts.nft_mint.key(),
-            timestamp: clock.unix_timestamp,
-        });
-
-        Ok(())
+        instructions::stake::stake_nft(ctx)
     }
 
     pub fn unstake_nft(ctx: Context<UnstakeNft>) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let user_stake = &mut ctx.accounts.user_stake;
-        let clock = Clock::get()?;
-
-        require!(!vault.paused, ErrorCode::VaultPaused);
-        require!(user_stake.staked_nfts > 0, ErrorCode::NoNftsStaked);
-        require!(
-            clock.unix_timestamp - user_stake.last_update_timestamp >= 300, // 5 minutes
-            ErrorCode::TooFrequent
-        );
-
-        let time_elapsed = clock.unix_timestamp - user_stake.last_update_timestamp;
-        let rewards_earned = calculate_rewards(
-            time_elapsed, 
-            vault.reward_rate_per_second, 
-            user_stake.staked_nfts as u64
-        )?;
-        
-        user_stake.pending_rewards = user_stake.pending_rewards
-            .checked_add(rewards_earned)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        let seeds = &[b"vault".as_ref(), &[vault.bump]];
-        let signer = &[&seeds[..]];
-
-        let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.vault_nft_token_account.to_account_info(),
-                to: ctx.accounts.user_nft_token_account.to_account_info(),
-                authority: ctx.accounts.vault.to_account_info(),
-            },
-            signer,
-        );
-        token::transfer(transfer_ctx, 1)?;
-
-        user_stake.staked_nfts = user_stake.staked_nfts
-            .checked_sub(1)
-            .ok_or(ErrorCode::MathUnderflow)?;
-        user_stake.last_update_timestamp = clock.unix_timestamp;
-
-        vault.total_staked = vault.total_staked
-            .checked_sub(1)
-            .ok_or(ErrorCode::MathUnderflow)?;
-
-        emit!(NftUnstaked {
-            user: ctx.accounts.user.key(),
-            nft_mint: ctx.accounts.nft_mint.key(),
-            timestamp: clock.unix_timestamp,
-        });
-
-        Ok(())
-    }
-
-    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let user_stake = &mut ctx.accounts.user_stake;
-        let clock = Clock::get()?;
-
-        require!(!vault.paused, ErrorCode::VaultPaused);
-
-        // Circuit breaker check
-        require!(
-            vault.circuit_breaker.can_execute(clock.unix_timestamp),
-            ErrorCode::CircuitBreakerActive
-        );
-
-        require!(
-            clock.unix_timestamp - user_stake.last_update_timestamp >= 60,
-            ErrorCode::TooFrequentClaim
-        );
-
-        let time_elapsed = clock.unix_timestamp - user_stake.last_update_timestamp;
-        let rewards_earned = calculate_rewards(
-            time_elapsed, 
-            vault.reward_rate_per_second, 
-            user_stake.staked_nfts as u64
-        )?;
-        
-        let total_rewards = user_stake.pending_rewards
-            .checked_add(rewards_earned)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        require!(total_rewards > 0, ErrorCode::NoRewardsToClaim);
-
-        // Daily limits check
-        vault.daily_limit.reset_if_new_day(clock.unix_timestamp);
-        require!(
-            vault.daily_limit.can_claim(total_rewards),
-            ErrorCode::DailyLimitExceeded
-        );
-
-        // Anti-exploitation: Maximum reward per day per NFT
-        let max_reward_per_nft_per_day = vault.reward_rate_per_second
-            .checked_mul(86400)
-            .ok_or(ErrorCode::MathOverflow)?;
-        
-        let max_total_reward = max_reward_per_nft_per_day
-            .checked_mul(user_stake.staked_nfts as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        
-        require!(total_rewards <= max_total_reward, ErrorCode::ExcessiveRewardClaim);
-
-        // Additional safety: Check if reward amount seems reasonable
-        let time_since_init = clock.unix_timestamp - vault.last_update_timestamp;
-        let theoretical_max = vault.reward_rate_per_second
-            .checked_mul(time_since_init as u64)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_mul(user_stake.staked_nfts as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        
-        require!(total_rewards <= theoretical_max, ErrorCode::ExcessiveRewardClaim);
-
-        // Verify mint has sufficient authority
-        let mint_info = ctx.accounts.reward_token_mint.to_account_info();
-        let mint_account = Mint::try_deserialize(&mut &mint_info.data.borrow()[..])?;
-        require!(
-            mint_account.mint_authority == anchor_lang::prelude::COption::Some(vault.key()),
-            ErrorCode::InvalidMintAuthority
-        );
-
-        let seeds = &[b"vault".as_ref(), &[vault.bump]];
-        let signer = &[&seeds[..]];
-
-        let mint_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                mint: ctx.accounts.reward_token_mint.to_account_info(),
-                to: ctx.accounts.user_reward_token_account.to_account_info(),
-                authority: ctx.accounts.vault.to_account_info(),
-            },
-            signer,
-        );
-        token::mint_to(mint_ctx, total_rewards)?;
-
-        user_stake.pending_rewards = 0;
-        user_stake.last_update_timestamp = clock.unix_timestamp;
-
-        // Record successful claim
-        vault.daily_limit.record_claim(total_rewards);
-        vault.circuit_breaker.on_success();
-
-        emit!(RewardsClaimed {
-            user: ctx.accounts.user.key(),
-            amount: total_rewards,
-            timestamp: clock.unix_timestamp,
-        });
-
-        Ok(())
+        instructions::unstake::unstake_nft(ctx)
+    }
+
+    /// Stakes `amount` copies of a semi-fungible edition, each weighing the
+    /// same as one NFT. Editions are held per user rather than per mint, so
+    /// they get no receipt, set-bonus group or exit queue.
+    pub fn stake_edition(ctx: Context<StakeEdition>, amount: u64) -> Result<()> {
+        instructions::edition::stake_edition(ctx, amount)
+    }
+
+    /// Withdraws `amount` copies of a staked edition, closing the escrow
+    /// and record once the last copy leaves.
+    pub fn unstake_edition(ctx: Context<UnstakeEdition>, amount: u64) -> Result<()> {
+        instructions::edition::unstake_edition(ctx, amount)
+    }
+
+    /// Exits a locked position early. A share of the user's pending rewards
+    /// is forfeited and burned or redistributed per `vault.penalty_mode`.
+    pub fn emergency_unstake(ctx: Context<UnstakeNft>) -> Result<()> {
+        instructions::unstake::emergency_unstake(ctx)
+    }
+
+    /// Locks the user's whole position, including NFTs staked later, for the
+    /// tier's duration in exchange for its reward boost.
+    pub fn lock_stake(ctx: Context<LockStake>, tier_index: u8) -> Result<()> {
+        instructions::lock::lock_stake(ctx, tier_index)
+    }
+
+    /// Moves an active lock up to `tier_index`. The lock end can only move
+    /// later and the boost only up; time so far is settled at the old boost.
+    pub fn extend_lock(ctx: Context<LockStake>, tier_index: u8) -> Result<()> {
+        instructions::lock::extend_lock(ctx, tier_index)
+    }
+
+    /// Deposits boost tokens into the vault escrow. The position earns
+    /// `token_boost.boost_bps` while it holds at least `required_amount`.
+    pub fn stake_tokens(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
+        instructions::token_boost::stake_tokens(ctx, amount)
+    }
+
+    /// Withdraws boost tokens from the vault escrow. Allowed while paused
+    /// or after the boost is switched off.
+    pub fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
+        instructions::token_boost::unstake_tokens(ctx, amount)
+    }
+
+    /// Opens a referral account so new stakers can name the signer as their
+    /// referrer in `stake_nft`.
+    pub fn register_referral(ctx: Context<RegisterReferral>) -> Result<()> {
+        instructions::referral::register_referral(ctx)
+    }
+
+    /// Lets `delegate` claim on the user's behalf; rewards still go to the
+    /// user's own token accounts. Pass `None` to revoke.
+    pub fn set_claim_delegate(
+        ctx: Context<SetClaimDelegate>,
+        delegate: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::position::set_claim_delegate(ctx, delegate)
+    }
+
+    /// Lets `delegate` move the position to a new wallet with
+    /// `transfer_stake_ownership` if this one is lost. Pass `None` to revoke.
+    pub fn set_recovery_delegate(
+        ctx: Context<SetRecoveryDelegate>,
+        delegate: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::position::set_recovery_delegate(ctx, delegate)
+    }
+
+    /// Moves the whole position, its stake records, receipts and pending
+    /// rewards to `new_owner` without unstaking, e.g. after a wallet
+    /// compromise. Signed by the old wallet or its recovery delegate.
+    ///
+    /// `remaining_accounts` holds four accounts per staked NFT: its stake
+    /// record, receipt mint, the old wallet's receipt account and the new
+    /// wallet's (already created) receipt account. Editions, an attached
+    /// booster or squad membership must be unwound, and legacy NFTs
+    /// backfilled, first.
+    pub fn transfer_stake_ownership<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TransferStakeOwnership<'info>>,
+    ) -> Result<()> {
+        instructions::position::transfer_stake_ownership(ctx)
+    }
+
+    /// Pins the token account every claim must pay, e.g. a hot wallet for
+    /// a cold-wallet staker. Omit `payout_account` to unpin.
+    pub fn set_payout_address(ctx: Context<SetPayoutAddress>) -> Result<()> {
+        instructions::position::set_payout_address(ctx)
+    }
+
+    /// Splits future claims by basis points among up to
+    /// `RewardShare::MAX_SHARES` token accounts; whatever the shares leave
+    /// goes to the usual destination. With vesting on, the shares are paid
+    /// at claim time and only the rest vests. Pass an empty list to stop
+    /// splitting.
+    pub fn set_reward_split(ctx: Context<SetRewardSplit>, shares: Vec<RewardShare>) -> Result<()> {
+        instructions::position::set_reward_split(ctx, shares)
+    }
+
+    /// Authorizes `session_key` to act for the user within `scope` until
+    /// `expires_at`, e.g. so a bot can submit claims without the user
+    /// signing each one. Re-creating an existing session is rejected;
+    /// revoke it first.
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_key: Pubkey,
+        scope: u8,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::session::create_session(ctx, session_key, scope, expires_at)
+    }
+
+    /// Closes a session early, returning its rent to the user.
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        instructions::session::revoke_session(ctx)
+    }
+
+    /// Starts the exit cooldown for one NFT. It stops earning immediately and
+    /// can be withdrawn with `finalize_unstake` once the cooldown has passed.
+    pub fn request_unstake(ctx: Context<RequestUnstake>) -> Result<()> {
+        instructions::unstake::request_unstake(ctx)
+    }
+
+    pub fn finalize_unstake(ctx: Context<FinalizeUnstake>) -> Result<()> {
+        instructions::unstake::finalize_unstake(ctx)
+    }
+
+    /// Permissionless crank that rolls a position's elapsed time into
+    /// `pending_rewards`, so idle positions never outgrow the accrual window.
+    /// The cranker earns up to `vault.crank_bounty` if they pass a reward
+    /// account, counted against the reward supply like a claim.
+    pub fn accrue_rewards(ctx: Context<AccrueRewards>) -> Result<()> {
+        instructions::claim::accrue_rewards(ctx)
+    }
+
+    /// Creates the `VaultMetrics` account; anyone may pay for it. Shard folds
+    /// and the stake, unstake and claim paths that bypass shards require it
+    /// from then on.
+    pub fn initialize_vault_metrics(ctx: Context<InitializeVaultMetrics>) -> Result<()> {
+        instructions::initialize::initialize_vault_metrics(ctx)
+    }
+
+    /// Creates `VaultShard` number `index`; anyone may pay for it. Users
+    /// whose key maps to a shard can't stake, unstake or claim until it
+    /// exists.
+    pub fn initialize_vault_shard(ctx: Context<InitializeVaultShard>, index: u8) -> Result<()> {
+        instructions::shard::initialize_vault_shard(ctx, index)
+    }
+
+    /// Permissionless crank that moves a shard's counters into the vault,
+    /// `VaultStats` and `VaultMetrics`. Until then `total_staked`, the supply
+    /// cap and the stats lag behind; curve-mode emissions are split by the
+    /// last folded `total_staked`, so frequent folds keep them accurate.
+    pub fn fold_vault_shard(ctx: Context<FoldVaultShard>) -> Result<()> {
+        instructions::shard::fold_vault_shard(ctx)
+    }
+
+    /// Permissionless crank that opens today's `Snapshot` with the vault's
+    /// `total_staked` and records positions into it. `remaining_accounts`
+    /// holds pairs of a `UserStakeAccount` and its `SnapshotEntry` address;
+    /// the cranker pays for new entries. Positions already recorded or with
+    /// nothing staked are skipped.
+    pub fn take_snapshot<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TakeSnapshot<'info>>,
+        day: u32,
+    ) -> Result<()> {
+        instructions::snapshot::take_snapshot(ctx, day)
+    }
+
+    /// Withdraws the unlocked part of a vesting position to the payout
+    /// account it was created for, closing it once fully released.
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        instructions::claim::claim_vested(ctx)
+    }
+
+    /// Claims the primary reward. Secondary rewards can be claimed in the
+    /// same call by passing `[reward_config, user_reward, destination, source]`
+    /// groups as remaining accounts, where `source` is the reward mint in
+    /// `Mint` mode or the vault-owned pool in `Pool` mode. With a reward
+    /// split configured, its destinations come first, in split order.
+    ///
+    /// A claim over its bound or the anomaly ceiling pays nothing but still
+    /// succeeds, so the circuit-breaker failure it records is kept. It emits
+    /// `ClaimRejected` rather than `RewardsClaimed`.
+    pub fn claim_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>,
+    ) -> Result<()> {
+        instructions::claim::claim_rewards(ctx)
+    }
+
+    /// Read-only quote of what `claim_rewards` would pay right now, returned
+    /// as instruction return data for clients to simulate.
+    pub fn get_pending_rewards(ctx: Context<GetPendingRewards>) -> Result<u64> {
+        instructions::claim::get_pending_rewards(ctx)
+    }
+
+    /// Read-only seconds left before `user` may stake, unstake the NFT of
+    /// the passed stake record, and claim, returned as instruction return
+    /// data.
+    pub fn get_cooldowns(ctx: Context<GetCooldowns>) -> Result<Cooldowns> {
+        instructions::cooldown::get_cooldowns(ctx)
+    }
+
+    pub fn compound_rewards(ctx: Context<CompoundRewards>) -> Result<()> {
+        instructions::compound::compound_rewards(ctx)
+    }
+
+    pub fn withdraw_compounded(ctx: Context<ClaimRewards>) -> Result<()> {
+        instructions::compound::withdraw_compounded(ctx)
+    }
+
+    pub fn create_squad(ctx: Context<CreateSquad>, name: String) -> Result<()> {
+        instructions::squad::create_squad(ctx, name)
+    }
+
+    /// Joins a squad. From now on the member's staking rewards go to the
+    /// squad pot, shared pro-rata by staked NFTs. Rewards earned before
+    /// joining stay the member's own and are claimed with their pot share.
+    pub fn join_squad(ctx: Context<JoinSquad>) -> Result<()> {
+        instructions::squad::join_squad(ctx)
+    }
+
+    /// Moves a member's accrued rewards into the squad pot and refreshes
+    /// their contribution to their current staked NFTs. Anyone may crank it.
+    pub fn contribute_squad_rewards(ctx: Context<ContributeSquadRewards>) -> Result<()> {
+        instructions::squad::contribute_squad_rewards(ctx)
+    }
+
+    /// Leaves the squad after a final contribution. The member's pot share
+    /// stays claimable through `claim_squad_rewards`.
+    pub fn leave_squad(ctx: Context<ContributeSquadRewards>) -> Result<()> {
+        instructions::squad::leave_squad(ctx)
+    }
+
+    /// Pays out the member's share of the squad pot, held to the member's
+    /// shard allowance like `claim_rewards`; the rest stays claimable.
+    pub fn claim_squad_rewards(ctx: Context<ClaimSquadRewards>) -> Result<()> {
+        instructions::squad::claim_squad_rewards(ctx)
+    }
+
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        instructions::funding::fund_rewards(ctx, amount)
+    }
+
+    /// Schedules a withdrawal of `amount` from the `reward_mint` pool to
+    /// `destination`, executable once the timelock has passed.
+    pub fn propose_pool_withdrawal(
+        ctx: Context<ManagePoolWithdrawal>,
+        reward_mint: Pubkey,
+        amount: u64,
+        destination: Pubkey,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::funding::propose_pool_withdrawal(
+            ctx,
+            reward_mint,
+            amount,
+            destination,
+            timelock_seconds,
+        )
+    }
+
+    pub fn execute_pool_withdrawal(ctx: Context<ExecutePoolWithdrawal>) -> Result<()> {
+        instructions::funding::execute_pool_withdrawal(ctx)
+    }
+
+    pub fn cancel_pool_withdrawal(ctx: Context<ManagePoolWithdrawal>) -> Result<()> {
+        instructions::funding::cancel_pool_withdrawal(ctx)
+    }
+
+    /// Returns an NFT sent directly to the vault, with no stake record, to
+    /// `owner`. Refused for attached boosters and while legacy NFTs are
+    /// still waiting to be backfilled.
+    pub fn recover_unstaked_token(ctx: Context<RecoverUnstakedToken>) -> Result<()> {
+        instructions::recovery::recover_unstaked_token(ctx)
+    }
+
+    /// Schedules a sweep of SOL (`mint == None`) or SPL tokens stuck at the
+    /// vault PDA to `destination`, executable once the timelock has passed.
+    pub fn propose_sweep(
+        ctx: Context<ManageSweep>,
+        mint: Option<Pubkey>,
+        amount: u64,
+        destination: Pubkey,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::recovery::propose_sweep(ctx, mint, amount, destination, timelock_seconds)
+    }
+
+    pub fn execute_sweep(ctx: Context<ExecuteSweep>) -> Result<()> {
+        instructions::recovery::execute_sweep(ctx)
+    }
+
+    pub fn cancel_sweep(ctx: Context<ManageSweep>) -> Result<()> {
+        instructions::recovery::cancel_sweep(ctx)
+    }
+
+    /// Creates the `insurance_pool` token account for the reward mint.
+    pub fn initialize_insurance_pool(ctx: Context<InitializeInsurancePool>) -> Result<()> {
+        instructions::insurance::initialize_insurance_pool(ctx)
+    }
+
+    /// Share of each claim fee and early-unstake penalty, in bps, paid into
+    /// the insurance pool.
+    pub fn set_insurance_fee(ctx: Context<UpdateConfig>, insurance_fee_bps: u64) -> Result<()> {
+        instructions::insurance::set_insurance_fee(ctx, insurance_fee_bps)
+    }
+
+    /// Schedules a payout from the insurance pool to a staker's reward
+    /// token account, executable once the timelock has passed.
+    pub fn propose_insurance_claim(
+        ctx: Context<ManageInsuranceClaim>,
+        recipient: Pubkey,
+        amount: u64,
+        reason: AdjustmentReason,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::insurance::propose_insurance_claim(
+            ctx,
+            recipient,
+            amount,
+            reason,
+            timelock_seconds,
+        )
+    }
+
+    pub fn pay_insurance_claim(ctx: Context<PayInsuranceClaim>) -> Result<()> {
+        instructions::insurance::pay_insurance_claim(ctx)
+    }
+
+    /// Moves the insurance share of early-unstake penalties into the pool,
+    /// up to the allowance of the shard passed.
+    pub fn fund_insurance_pool(ctx: Context<FundInsurancePool>) -> Result<()> {
+        instructions::insurance::fund_insurance_pool(ctx)
+    }
+
+    pub fn cancel_insurance_claim(ctx: Context<ManageInsuranceClaim>) -> Result<()> {
+        instructions::insurance::cancel_insurance_claim(ctx)
+    }
+
+    /// Secondary rewards should be claimed first; their checkpoints restart
+    /// from zero if the stake account is recreated.
+    pub fn close_user_stake(ctx: Context<CloseUserStake>) -> Result<()> {
+        instructions::position::close_user_stake(ctx)
+    }
+
+    /// Upgrades a `UserStakeAccount` written by an older program version to
+    /// the current layout. Fields added since then start zeroed.
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::position::migrate_account(ctx)
+    }
+
+    /// Commits to the `(user, nft_mint)` pairs staked before per-NFT records
+    /// existed. Can only be set once; `nft_count` is the number of leaves.
+    pub fn set_legacy_stake_root(
+        ctx: Context<UpdateConfig>,
+        merkle_root: [u8; 32],
+        nft_count: u32,
+    ) -> Result<()> {
+        instructions::legacy::set_legacy_stake_root(ctx, merkle_root, nft_count)
+    }
+
+    /// Creates the stake record of a legacy NFT the user proves against
+    /// the legacy stake root. Legacy NFTs can't be unstaked, migrated or
+    /// transferred until their records exist.
+    pub fn backfill_stake_record(
+        ctx: Context<BackfillStakeRecord>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::legacy::backfill_stake_record(ctx, proof)
+    }
+
+    pub fn add_reward_config(
+        ctx: Context<AddRewardConfig>,
+        reward_rate_per_second: u64,
+        reward_mode: RewardMode,
+    ) -> Result<()> {
+        instructions::secondary_rewards::add_reward_config(ctx, reward_rate_per_second, reward_mode)
+    }
+
+    /// Rate changes apply to NFT-seconds that users have not claimed yet.
+    pub fn update_reward_config(
+        ctx: Context<UpdateRewardConfig>,
+        new_reward_rate: Option<u64>,
+        active: Option<bool>,
+    ) -> Result<()> {
+        instructions::secondary_rewards::update_reward_config(ctx, new_reward_rate, active)
+    }
+
+    pub fn claim_reward_token(ctx: Context<ClaimRewardToken>) -> Result<()> {
+        instructions::secondary_rewards::claim_reward_token(ctx)
     }
 
     pub fn pause_vault(ctx: Context<PauseVault>) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let pauser_role = &ctx.accounts.user_role;
-        
-        require!(!vault.paused, ErrorCode::AlreadyPaused);
-        require!(
-            pauser_role.role.can_pause_vault(),
-            ErrorCode::InsufficientPermissions
-        );
-        
-        vault.paused = true;
-        
-        emit!(VaultPaused {
-            authority: ctx.accounts.authority.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-        
-        Ok(())
+        instructions::pause::pause_vault(ctx)
     }
 
     pub fn unpause_vault(ctx: Context<PauseVault>) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let unpauser_role = &ctx.accounts.user_role;
-        
-        require!(vault.paused, ErrorCode::NotPaused);
-        require!(
-            unpauser_role.role.can_pause_vault(),
-            ErrorCode::InsufficientPermissions
-        );
-        
-        vault.paused = false;
-        
-        emit!(VaultUnpaused {
-            authority: ctx.accounts.authority.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-        
-        Ok(())
+        instructions::pause::unpause_vault(ctx)
+    }
+
+    pub fn suspend_user(ctx: Context<ModerateUser>, user: Pubkey) -> Result<()> {
+        instructions::moderation::suspend_user(ctx, user)
+    }
+
+    pub fn unsuspend_user(ctx: Context<ModerateUser>, user: Pubkey) -> Result<()> {
+        instructions::moderation::unsuspend_user(ctx, user)
+    }
+
+    pub fn set_pause_flags(ctx: Context<PauseVault>, pause_flags: u8) -> Result<()> {
+        instructions::pause::set_pause_flags(ctx, pause_flags)
+    }
+
+    pub fn set_emergency_mode(ctx: Context<PauseVault>, enabled: bool) -> Result<()> {
+        instructions::pause::set_emergency_mode(ctx, enabled)
+    }
+
+    pub fn trip_circuit_breaker(ctx: Context<PauseVault>) -> Result<()> {
+        instructions::pause::trip_circuit_breaker(ctx)
+    }
+
+    pub fn reset_circuit_breaker(ctx: Context<PauseVault>) -> Result<()> {
+        instructions::pause::reset_circuit_breaker(ctx)
     }
 
     // RBAC Functions
     pub fn grant_role(
         ctx: Context<ManageRole>, 
         user: Pubkey,
-        role: Role
+        role: Role,
+        expires_at: Option<i64>,
     ) -> Result<()> {
-        let vault = &ctx.accounts.vault;
-        let granter_role_account = &ctx.accounts.granter_role;
-        
-        // Only SuperAdmin can grant roles
-        require!(
-            granter_role_account.role.can_manage_roles(),
-            ErrorCode::InsufficientPermissions
-        );
-
-        let role_account = &mut ctx.accounts.user_role;
-        role_account.user = user;
-        role_account.role = role;
-        role_account.granted_by = ctx.accounts.granter.key();
-        role_account.granted_at = Clock::get()?.unix_timestamp;
-
-        emit!(RoleGranted {
-            user,
-            role: role.clone(),
-            granted_by: ctx.accounts.granter.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+        instructions::roles::grant_role(ctx, user, role, expires_at)
+    }
 
-        Ok(())
+    pub fn renew_role(ctx: Context<ManageRole>, expires_at: Option<i64>) -> Result<()> {
+        instructions::roles::renew_role(ctx, expires_at)
     }
 
     pub fn revoke_role(ctx: Context<ManageRole>) -> Result<()> {
-        let granter_role_account = &ctx.accounts.granter_role;
-        
-        require!(
-            granter_role_account.role.can_manage_roles(),
-            ErrorCode::InsufficientPermissions
-        );
+        instructions::roles::revoke_role(ctx)
+    }
 
-        let role_account = &mut ctx.accounts.user_role;
-        let user = role_account.user;
+    /// Records that the role managers are still around; see
+    /// `set_dead_man_switch`.
+    pub fn admin_heartbeat(ctx: Context<AdminHeartbeat>) -> Result<()> {
+        instructions::roles::admin_heartbeat(ctx)
+    }
 
-        emit!(RoleRevoked {
-            user,
-            revoked_by: ctx.accounts.granter.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    /// Lets `recovery_key` claim SuperAdmin after `timeout_seconds` without
+    /// an `admin_heartbeat`. `None` disarms the switch.
+    pub fn set_dead_man_switch(
+        ctx: Context<AdminHeartbeat>,
+        recovery_key: Option<Pubkey>,
+        timeout_seconds: i64,
+    ) -> Result<()> {
+        instructions::roles::set_dead_man_switch(ctx, recovery_key, timeout_seconds)
+    }
+
+    pub fn claim_super_admin(ctx: Context<ClaimSuperAdmin>) -> Result<()> {
+        instructions::roles::claim_super_admin(ctx)
+    }
 
-        Ok(())
+    /// Creates the `RoleRegistry`, listing the caller and any existing
+    /// role accounts passed as remaining accounts.
+    pub fn initialize_role_registry<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitializeRoleRegistry<'info>>,
+    ) -> Result<()> {
+        instructions::roles::initialize_role_registry(ctx)
     }
 
     // Upgrade Functions
+    /// `buffer_hash` is the SHA-256 of the buffer's program bytes, i.e. the
+    /// data after the loader's buffer header.
     pub fn propose_upgrade(
         ctx: Context<ProposeUpgrade>,
         new_version: u32,
+        buffer: Pubkey,
+        buffer_hash: [u8; 32],
         timelock_seconds: i64,
     ) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let proposer_role = &ctx.accounts.proposer_role;
+        instructions::upgrade::propose_upgrade(ctx, new_version, buffer, buffer_hash, timelock_seconds)
+    }
 
-        require!(!vault.upgrade_locked, ErrorCode::UpgradesLocked);
-        require!(vault.pending_upgrade.is_none(), ErrorCode::UpgradePending);
-        require!(
-            proposer_role.role.can_manage_upgrades(),
-            ErrorCode::InsufficientPermissions
-        );
-        require!(new_version > vault.version, ErrorCode::InvalidVersion);
-        require!(
-            timelock_seconds >= 3600, // Minimum 1 hour
-            ErrorCode::InvalidTimelock
-        );
+    /// Objects to the pending upgrade with the NFTs the caller had staked
+    /// when it was proposed; the upgrade is cancelled once objections reach
+    /// its veto threshold.
+    pub fn object_to_upgrade(ctx: Context<ObjectToUpgrade>) -> Result<()> {
+        instructions::upgrade::object_to_upgrade(ctx)
+    }
 
-        let scheduled_timestamp = Clock::get()?.unix_timestamp + timelock_seconds;
+    /// Share of staked NFTs, in bps, whose objections cancel an upgrade.
+    pub fn set_upgrade_veto(ctx: Context<UpdateConfig>, veto_bps: u64) -> Result<()> {
+        instructions::upgrade::set_upgrade_veto(ctx, veto_bps)
+    }
 
-        vault.pending_upgrade = Some(PendingUpgrade {
-            new_version,
-            scheduled_timestamp,
-            proposer: ctx.accounts.proposer.key(),
-        });
+    pub fn execute_upgrade(ctx: Context<ExecuteUpgrade>) -> Result<()> {
+        instructions::upgrade::execute_upgrade(ctx)
+    }
 
-        emit!(UpgradeProposed {
-            new_version,
-            scheduled_timestamp,
-            proposer: ctx.accounts.proposer.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    pub fn cancel_upgrade(ctx: Context<CancelUpgrade>) -> Result<()> {
+        instructions::upgrade::cancel_upgrade(ctx)
+    }
 
-        Ok(())
+    pub fn lock_upgrades(ctx: Context<LockUpgrades>) -> Result<()> {
+        instructions::upgrade::lock_upgrades(ctx)
     }
 
-    pub fn execute_upgrade(ctx: Context<ExecuteUpgrade>) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let executor_role = &ctx.accounts.executor_role;
-        
-        require!(
-            executor_role.role.can_manage_upgrades(),
-            ErrorCode::InsufficientPermissions
-        );
+    pub fn propose_config_change(
+        ctx: Context<UpdateVaultConfig>,
+        new_reward_rate: Option<u64>,
+        new_collection_mint: Option<Pubkey>,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::config::propose_config_change(
+            ctx,
+            new_reward_rate,
+            new_collection_mint,
+            timelock_seconds,
+        )
+    }
 
-        let pending_upgrade = vault.pending_upgrade.as_ref()
-            .ok_or(ErrorCode::NoUpgradePending)?;
+    pub fn execute_config_change(ctx: Context<UpdateVaultConfig>) -> Result<()> {
+        instructions::config::execute_config_change(ctx)
+    }
 
-        let now = Clock::get()?.unix_timestamp;
-        require!(
-            now >= pending_upgrade.scheduled_timestamp,
-            ErrorCode::TimelockNotExpired
-        );
+    pub fn cancel_config_change(ctx: Context<UpdateConfig>) -> Result<()> {
+        instructions::config::cancel_config_change(ctx)
+    }
 
-        // Execute upgrade
-        vault.version = pending_upgrade.new_version;
-        vault.pending_upgrade = None;
+    /// `max_total_rewards_per_day` is in whole reward tokens.
+    pub fn update_limits(
+        ctx: Context<UpdateVaultConfig>,
+        max_stakes_per_day: Option<u32>,
+        max_claims_per_day: Option<u32>,
+        max_total_rewards_per_day: Option<u64>,
+    ) -> Result<()> {
+        instructions::config::update_limits(
+            ctx,
+            max_stakes_per_day,
+            max_claims_per_day,
+            max_total_rewards_per_day,
+        )
+    }
 
-        emit!(UpgradeExecuted {
-            new_version: vault.version,
-            executor: ctx.accounts.executor.key(),
-            timestamp: now,
-        });
+    /// Caps how many NFTs one user and the whole vault may hold staked;
+    /// 0 lifts a cap. Lowering a cap never forces anyone out.
+    pub fn set_stake_caps(
+        ctx: Context<UpdateConfig>,
+        max_nfts_per_user: Option<u32>,
+        max_total_staked: Option<u32>,
+    ) -> Result<()> {
+        instructions::config::set_stake_caps(ctx, max_nfts_per_user, max_total_staked)
+    }
 
-        Ok(())
+    pub fn update_circuit_breaker_config(
+        ctx: Context<UpdateVaultConfig>,
+        failure_threshold: Option<u32>,
+        reset_timeout: Option<i64>,
+    ) -> Result<()> {
+        instructions::config::update_circuit_breaker_config(ctx, failure_threshold, reset_timeout)
     }
 
-    pub fn cancel_upgrade(ctx: Context<CancelUpgrade>) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let canceller_role = &ctx.accounts.canceller_role;
-        
-        require!(
-            canceller_role.role.can_manage_upgrades(),
-            ErrorCode::InsufficientPermissions
-        );
-        require!(vault.pending_upgrade.is_some(), ErrorCode::NoUpgradePending);
+    /// Schedules a new campaign window for a staking season. Positions
+    /// staked before `start_timestamp` earn nothing until it, and accrual
+    /// stops at `end_timestamp` (0 = open-ended) whenever the position next
+    /// settles. The end can't fall before the change takes effect.
+    pub fn propose_emission_schedule(
+        ctx: Context<UpdateConfig>,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        halving_interval: i64,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::config::propose_emission_schedule(
+            ctx,
+            start_timestamp,
+            end_timestamp,
+            halving_interval,
+            timelock_seconds,
+        )
+    }
 
-        vault.pending_upgrade = None;
+    pub fn execute_emission_schedule(ctx: Context<UpdateConfig>) -> Result<()> {
+        instructions::config::execute_emission_schedule(ctx)
+    }
 
-        emit!(UpgradeCancelled {
-            cancelled_by: ctx.accounts.canceller.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    pub fn cancel_emission_schedule(ctx: Context<UpdateConfig>) -> Result<()> {
+        instructions::config::cancel_emission_schedule(ctx)
+    }
 
-        Ok(())
+    /// Starts season `id` (always the next one) with its own window, rate
+    /// and boost. Positions from earlier seasons must `roll_over_season`
+    /// before they accrue again. The first season also records everything
+    /// emitted before it as season 0.
+    pub fn start_season(
+        ctx: Context<StartSeason>,
+        id: u32,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        reward_rate_per_second: u64,
+        boost_bps: u16,
+    ) -> Result<()> {
+        instructions::season::start_season(
+            ctx,
+            id,
+            start_timestamp,
+            end_timestamp,
+            reward_rate_per_second,
+            boost_bps,
+        )
     }
 
-    pub fn lock_upgrades(ctx: Context<LockUpgrades>) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let locker_role = &ctx.accounts.locker_role;
-        
-        require!(
-            locker_role.role.can_manage_upgrades(),
-            ErrorCode::InsufficientPermissions
-        );
-        require!(!vault.upgrade_locked, ErrorCode::UpgradesAlreadyLocked);
+    /// Ends the active season now, or confirms its scheduled end if that
+    /// already passed. Accrual stops at the recorded end.
+    pub fn end_season(ctx: Context<EndSeason>) -> Result<()> {
+        instructions::season::end_season(ctx)
+    }
 
-        vault.upgrade_locked = true;
-        vault.pending_upgrade = None;
+    /// Permissionless crank that settles a position's last season into
+    /// `pending_rewards` at that season's terms and moves it to the next
+    /// one. Positions that skipped several seasons call it once per season.
+    pub fn roll_over_season(ctx: Context<RollOverSeason>) -> Result<()> {
+        instructions::season::roll_over_season(ctx)
+    }
 
-        emit!(UpgradesLocked {
-            locked_by: ctx.accounts.locker.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    /// Sets a fixed total emission shared pro-rata by all staked NFTs, so the
+    /// per-NFT rate falls as `total_staked` grows. Zero restores the linear
+    /// per-NFT `reward_rate_per_second`. Unsettled windows are paid under the
+    /// curve in force when they settle, as with rate changes.
+    pub fn set_curve_config(
+        ctx: Context<UpdateConfig>,
+        total_emission_per_second: u64,
+    ) -> Result<()> {
+        instructions::config::set_curve_config(ctx, total_emission_per_second)
+    }
 
-        Ok(())
+    pub fn set_loyalty_config(
+        ctx: Context<UpdateConfig>,
+        bonus_bps_per_period: u64,
+        period_seconds: i64,
+        max_bonus_bps: u64,
+    ) -> Result<()> {
+        instructions::config::set_loyalty_config(
+            ctx,
+            bonus_bps_per_period,
+            period_seconds,
+            max_bonus_bps,
+        )
     }
 
-    pub fn update_config(
+    pub fn set_streak_config(
         ctx: Context<UpdateConfig>,
-        new_reward_rate: Option<u64>,
-        new_collection_mint: Option<Pubkey>,
+        bonus_bps_per_day: u64,
+        max_bonus_bps: u64,
     ) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let updater_role = &ctx.accounts.updater_role;
-        
-        require!(
-            updater_role.role.can_update_config(),
-            ErrorCode::InsufficientPermissions
-        );
+        instructions::config::set_streak_config(ctx, bonus_bps_per_day, max_bonus_bps)
+    }
 
-        if let Some(rate) = new_reward_rate {
-            require!(rate > 0, ErrorCode::InvalidRewardRate);
-            vault.reward_rate_per_second = rate;
-        }
+    pub fn set_fee_config(
+        ctx: Context<UpdateConfig>,
+        fee_bps: u64,
+        fee_destination: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::config::set_fee_config(ctx, fee_bps, fee_destination)
+    }
 
-        if let Some(mint) = new_collection_mint {
-            vault.collection_mint = mint;
-        }
+    pub fn set_referral_config(
+        ctx: Context<UpdateConfig>,
+        referral_bonus_bps: u64,
+        referral_bonus_cap: u64,
+    ) -> Result<()> {
+        instructions::config::set_referral_config(ctx, referral_bonus_bps, referral_bonus_cap)
+    }
 
-        emit!(ConfigUpdated {
-            updated_by: ctx.accounts.updater.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    /// Switches the vault to oracle-driven emissions, or back to the fixed
+    /// rate with `None`. The rate itself only moves on `refresh_oracle_rate`.
+    pub fn set_oracle_config(
+        ctx: Context<UpdateConfig>,
+        oracle: Option<OracleConfig>,
+    ) -> Result<()> {
+        instructions::oracle::set_oracle_config(ctx, oracle)
+    }
 
-        Ok(())
+    /// Re-derives `reward_rate_per_second` from the configured Pyth feed so
+    /// emissions hold their USD value. Permissionless.
+    pub fn refresh_oracle_rate(ctx: Context<RefreshOracleRate>) -> Result<()> {
+        instructions::oracle::refresh_oracle_rate(ctx)
     }
-}
 
-fn calculate_rewards(
-    time_elapsed: i64,
-    reward_rate_per_second: u64,
-    staked_nfts: u64,
-) -> Result<u64> {
-    require!(
-        time_elapsed >= 0 && time_elapsed <= 172_800, // 48 hours max
-        ErrorCode::InvalidTimeElapsed
-    );
-
-    let time_elapsed = time_elapsed as u64;
-    let rewards = time_elapsed
-        .checked_mul(reward_rate_per_second)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_mul(staked_nfts)
-        .ok_or(ErrorCode::MathOverflow)?;
-
-    Ok(rewards)
-}
+    /// Caps lifetime claim emissions; 0 removes the cap. Cannot be set below
+    /// what has already been paid.
+    pub fn set_reward_supply_cap(
+        ctx: Context<UpdateConfig>,
+        max_reward_supply: u64,
+    ) -> Result<()> {
+        instructions::config::set_reward_supply_cap(ctx, max_reward_supply)
+    }
 
-#[derive(Accounts)]
-pub struct InitializeVault<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + VaultAccount::INIT_SPACE,
-        seeds = [b"vault"],
-        bump
-    )]
-    pub vault: Account<'info, VaultAccount>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-
-    #[account(mut)]
-    pub reward_token_mint: Account<'info, Mint>,
-
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+    /// Vests future claims linearly over `duration_seconds`, nothing before
+    /// `cliff_seconds`. A zero duration pays claims out directly.
+    pub fn set_vesting_config(
+        ctx: Context<UpdateConfig>,
+        duration_seconds: i64,
+        cliff_seconds: i64,
+    ) -> Result<()> {
+        instructions::config::set_vesting_config(ctx, duration_seconds, cliff_seconds)
+    }
 
-#[derive(Accounts)]
-pub struct StakeNft<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
-
-    #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + UserStakeAccount::INIT_SPACE,
-        seeds = [b"user_stake", user.key().as_ref()],
-        bump
-    )]
-    pub user_stake: Account<'info, UserStakeAccount>,
-
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    pub nft_mint: Account<'info, Mint>,
-
-    #[account(
-        seeds = [
-            b"metadata",
-            metadata_program.key().as_ref(),
-            nft_mint.key().as_ref()
-        ],
-        seeds::program = metadata_program.key(),
-        bump
-    )]
-    pub nft_metadata: Account<'info, MetadataAccount>,
-
-    #[account(
-        mut,
-        constraint = user_nft_token_account.mint == nft_mint.key(),
-        constraint = user_nft_token_account.owner == user.key(),
-        constraint = user_nft_token_account.amount == 1
-    )]
-    pub user_nft_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        init_if_needed,
-        payer = user,
-        associated_token::mint = nft_mint,
-        associated_token::authority = vault
-    )]
-    pub vault_nft_token_account: Account<'info, TokenAccount>,
-
-    pub metadata_program: Program<'info, Metadata>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-}
+    /// Configures the SPL token boost. `boost_bps == 0` switches it off; the
+    /// mint can only change while no boost tokens are staked.
+    pub fn set_token_boost_config(
+        ctx: Context<UpdateConfig>,
+        mint: Pubkey,
+        required_amount: u64,
+        boost_bps: u64,
+    ) -> Result<()> {
+        instructions::config::set_token_boost_config(ctx, mint, required_amount, boost_bps)
+    }
 
-#[derive(Accounts)]
-pub struct UnstakeNft<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
-
-    #[account(
-        mut,
-        seeds = [b"user_stake", user.key().as_ref()],
-        bump
-    )]
-    pub user_stake: Account<'info, UserStakeAccount>,
-
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    pub nft_mint: Account<'info, Mint>,
-
-    #[account(
-        mut,
-        constraint = user_nft_token_account.mint == nft_mint.key(),
-        constraint = user_nft_token_account.owner == user.key()
-    )]
-    pub user_nft_token_account: Account<'info, TokenAccount>,
-
-    #[account(
-        mut,
-        associated_token::mint = nft_mint,
-        associated_token::authority = vault
-    )]
-    pub vault_nft_token_account: Account<'info, TokenAccount>,
-
-    pub token_program: Program<'info, Token>,
-}
+    pub fn set_cooldowns(
+        ctx: Context<UpdateConfig>,
+        action_cooldown_seconds: i64,
+        claim_cooldown_seconds: i64,
+    ) -> Result<()> {
+        instructions::config::set_cooldowns(ctx, action_cooldown_seconds, claim_cooldown_seconds)
+    }
 
-#[derive(Accounts)]
-pub struct ClaimRewards<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
-
-    #[account(
-        mut,
-        seeds = [b"user_stake", user.key().as_ref()],
-        bump
-    )]
-    pub user_stake: Account<'info, UserStakeAccount>,
-
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        mut,
-        constraint = reward_token_mint.key() == vault.reward_token_mint
-    )]
-    pub reward_token_mint: Account<'info, Mint>,
-
-    #[account(
-        init_if_needed,
-        payer = user,
-        associated_token::mint = reward_token_mint,
-        associated_token::authority = user
-    )]
-    pub user_reward_token_account: Account<'info, TokenAccount>,
-
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-}
+    /// Sets the sub-unit part of the per-NFT rate so emissions below one
+    /// base unit per second are representable.
+    pub fn set_reward_rate_fraction(
+        ctx: Context<UpdateVaultConfig>,
+        reward_rate_fraction: u64,
+    ) -> Result<()> {
+        instructions::config::set_reward_rate_fraction(ctx, reward_rate_fraction)
+    }
 
-#[derive(Accounts)]
-pub struct PauseVault<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
+    pub fn set_min_stake_slots(ctx: Context<UpdateConfig>, min_stake_slots: u64) -> Result<()> {
+        instructions::config::set_min_stake_slots(ctx, min_stake_slots)
+    }
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    pub fn set_max_reward_adjustment(
+        ctx: Context<UpdateConfig>,
+        max_reward_adjustment: u64,
+    ) -> Result<()> {
+        instructions::reward_adjustment::set_max_reward_adjustment(ctx, max_reward_adjustment)
+    }
 
-    #[account(
-        seeds = [b"role", authority.key().as_ref()],
-        bump
-    )]
-    pub user_role: Account<'info, AccountRole>,
-}
+    /// Schedules crediting `amount` to a user's pending rewards, e.g. to make
+    /// up for a mis-set rate. Executed by `adjust_pending_rewards` once the
+    /// timelock has passed.
+    pub fn propose_reward_adjustment(
+        ctx: Context<ProposeRewardAdjustment>,
+        amount: u64,
+        reason: AdjustmentReason,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::reward_adjustment::propose_reward_adjustment(
+            ctx,
+            amount,
+            reason,
+            timelock_seconds,
+        )
+    }
 
-#[derive(Accounts)]
-pub struct ManageRole<'info> {
-    #[account(seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
-
-    #[account(mut)]
-    pub granter: Signer<'info>,
-
-    #[account(
-        seeds = [b"role", granter.key().as_ref()],
-        bump
-    )]
-    pub granter_role: Account<'info, AccountRole>,
-
-    #[account(
-        init_if_needed,
-        payer = granter,
-        space = 8 + AccountRole::INIT_SPACE,
-        seeds = [b"role", user_role.user.as_ref()],
-        bump
-    )]
-    pub user_role: Account<'info, AccountRole>,
-
-    pub system_program: Program<'info, System>,
-}
+    pub fn cancel_reward_adjustment(ctx: Context<ExecuteRewardAdjustment>) -> Result<()> {
+        instructions::reward_adjustment::cancel_reward_adjustment(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct ProposeUpgrade<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
+    /// Credits a proposed adjustment to the user's pending rewards. The
+    /// amount is still subject to the supply cap and daily limit at claim.
+    pub fn adjust_pending_rewards(ctx: Context<ExecuteRewardAdjustment>) -> Result<()> {
+        instructions::reward_adjustment::adjust_pending_rewards(ctx)
+    }
 
-    #[account(mut)]
-    pub proposer: Signer<'info>,
+    /// Opens a whitelist-only phase until `public_start_timestamp` (0 ends
+    /// it). With a `merkle_root`, wallets in the tree can add themselves
+    /// via `join_whitelist`.
+    pub fn set_whitelist_phase(
+        ctx: Context<UpdateConfig>,
+        public_start_timestamp: i64,
+        merkle_root: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::whitelist::set_whitelist_phase(ctx, public_start_timestamp, merkle_root)
+    }
 
-    #[account(
-        seeds = [b"role", proposer.key().as_ref()],
-        bump
-    )]
-    pub proposer_role: Account<'info, AccountRole>,
-}
+    /// Whitelists `users` in one go. `remaining_accounts` holds each user's
+    /// `WhitelistEntry` address, in the same order; existing entries are
+    /// left alone.
+    pub fn add_whitelist_entries<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddWhitelistEntries<'info>>,
+        users: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::whitelist::add_whitelist_entries(ctx, users)
+    }
 
-#[derive(Accounts)]
-pub struct ExecuteUpgrade<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
+    pub fn join_whitelist(ctx: Context<JoinWhitelist>, proof: Vec<[u8; 32]>) -> Result<()> {
+        instructions::whitelist::join_whitelist(ctx, proof)
+    }
 
-    #[account(mut)]
-    pub executor: Signer<'info>,
+    /// Restricts `stake_nft` to NFTs with the attribute hashed in
+    /// `trait_hash`, proven by a merkle proof against `merkle_root` or an
+    /// attestation from `attestor`. Passing neither lifts the gate.
+    pub fn set_trait_gate(
+        ctx: Context<UpdateConfig>,
+        trait_hash: [u8; 32],
+        merkle_root: Option<[u8; 32]>,
+        attestor: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::trait_gate::set_trait_gate(ctx, trait_hash, merkle_root, attestor)
+    }
 
-    #[account(
-        seeds = [b"role", executor.key().as_ref()],
-        bump
-    )]
-    pub executor_role: Account<'info, AccountRole>,
-}
+    /// Records that an NFT passes the current trait gate. Without a `proof`
+    /// the previous instruction must be the attestor's Ed25519 signature.
+    pub fn verify_nft_trait(
+        ctx: Context<VerifyNftTrait>,
+        proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        instructions::trait_gate::verify_nft_trait(ctx, proof)
+    }
 
-#[derive(Accounts)]
-pub struct CancelUpgrade<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
+    pub fn set_crank_bounty(ctx: Context<UpdateConfig>, crank_bounty: u64) -> Result<()> {
+        instructions::config::set_crank_bounty(ctx, crank_bounty)
+    }
 
-    #[account(mut)]
-    pub canceller: Signer<'info>,
+    pub fn configure_set_bonus(
+        ctx: Context<ConfigureSetBonus>,
+        group_count: u8,
+        completion_bonus_bps: u64,
+    ) -> Result<()> {
+        instructions::booster::configure_set_bonus(ctx, group_count, completion_bonus_bps)
+    }
 
-    #[account(
-        seeds = [b"role", canceller.key().as_ref()],
-        bump
-    )]
-    pub canceller_role: Account<'info, AccountRole>,
-}
+    /// Registers (or updates) a booster collection. Changes apply to boosters
+    /// attached afterwards; attached ones keep their boost until detached.
+    pub fn configure_booster_collection(
+        ctx: Context<ConfigureBoosterCollection>,
+        boost_bps: u64,
+        active: bool,
+    ) -> Result<()> {
+        instructions::booster::configure_booster_collection(ctx, boost_bps, active)
+    }
 
-#[derive(Accounts)]
-pub struct LockUpgrades<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
+    /// Registers or updates a partner program allowed to call stake/unstake
+    /// (`can_stake`) and/or `claim_rewards` (`can_claim`) via CPI.
+    pub fn configure_partner_program(
+        ctx: Context<ConfigurePartnerProgram>,
+        can_stake: bool,
+        can_claim: bool,
+        active: bool,
+    ) -> Result<()> {
+        instructions::booster::configure_partner_program(ctx, can_stake, can_claim, active)
+    }
 
-    #[account(mut)]
-    pub locker: Signer<'info>,
+    /// Slots a booster-collection NFT into the user's position. One booster
+    /// per user; it boosts all their staked NFTs while attached. The NFT is
+    /// held in an escrow keyed by its mint, apart from staked NFTs.
+    pub fn attach_booster(ctx: Context<AttachBooster>) -> Result<()> {
+        instructions::booster::attach_booster(ctx)
+    }
 
-    #[account(
-        seeds = [b"role", locker.key().as_ref()],
-        bump
-    )]
-    pub locker_role: Account<'info, AccountRole>,
-}
+    /// Returns the user's booster NFT and removes its boost.
+    pub fn detach_booster(ctx: Context<DetachBooster>) -> Result<()> {
+        instructions::booster::detach_booster(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct UpdateConfig<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
-    pub vault: Account<'info, VaultAccount>,
+    /// Assigns an NFT to a set-bonus group. Tags are permanent and can only be
+    /// set while the NFT is not staked, so per-user group counts stay exact.
+    pub fn tag_nft_group(ctx: Context<TagNftGroup>, group: u8) -> Result<()> {
+        instructions::booster::tag_nft_group(ctx, group)
+    }
 
-    #[account(mut)]
-    pub updater: Signer<'info>,
+    /// Sets a single 1/1 NFT's reward multiplier, in bps (10_000 = 1x),
+    /// while it is not staked. Averaged into the boost of the position it
+    /// joins; editions can't have one.
+    pub fn set_nft_multiplier(ctx: Context<SetNftMultiplier>, multiplier_bps: u64) -> Result<()> {
+        instructions::booster::set_nft_multiplier(ctx, multiplier_bps)
+    }
 
-    #[account(
-        seeds = [b"role", updater.key().as_ref()],
-        bump
-    )]
-    pub updater_role: Account<'info, AccountRole>,
-}
+    /// Lets users burn NFTs of `collection_mint` for `boost_bps_per_burn`
+    /// each, up to `max_boost_bps` per position. 0 per burn turns it off.
+    pub fn set_burn_boost(
+        ctx: Context<UpdateConfig>,
+        collection_mint: Pubkey,
+        boost_bps_per_burn: u64,
+        max_boost_bps: u64,
+    ) -> Result<()> {
+        instructions::burn_boost::set_burn_boost(
+            ctx,
+            collection_mint,
+            boost_bps_per_burn,
+            max_boost_bps,
+        )
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct VaultAccount {
-    pub authority: Pubkey,
-    pub total_staked: u32,
-    pub reward_token_mint: Pubkey,
-    pub reward_rate_per_second: u64,
-    pub collection_mint: Pubkey,
-    pub paused: bool,
-    pub last_update_timestamp: i64,
-    pub bump: u8,
-    // RBAC & Governance
-    pub upgrade_authority: Pubkey,
-    pub version: u32,
-    pub upgrade_locked: bool,
-    pub pending_upgrade: Option<PendingUpgrade>,
-    // Circuit Breaker & Security
-    pub circuit_breaker: CircuitBreakerState,
-    pub daily_limit: DailyLimits,
-}
+    pub fn burn_for_boost(ctx: Context<BurnForBoost>) -> Result<()> {
+        instructions::burn_boost::burn_for_boost(ctx)
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
-pub struct CircuitBreakerState {
-    pub failure_count: u32,
-    pub last_failure_timestamp: i64,
-    pub blocked: bool,
-    pub total_transactions: u64,
-    pub failed_transactions: u64,
-}
+    pub fn set_level_config(
+        ctx: Context<ConfigureLevels>,
+        xp_per_nft_day: u64,
+        xp_per_claim: u64,
+        levels: Vec<LevelTier>,
+    ) -> Result<()> {
+        instructions::config::set_level_config(ctx, xp_per_nft_day, xp_per_claim, levels)
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
-pub struct DailyLimits {
-    pub max_stakes_per_day: u32,
-    pub max_claims_per_day: u32,
-    pub max_total_rewards_per_day: u64,
-    pub stakes_today: u32,
-    pub claims_today: u32,
-    pub rewards_claimed_today: u64,
-    pub last_reset_timestamp: i64,
-}
+    pub fn set_cpi_caller_allowlist(
+        ctx: Context<UpdateConfig>,
+        programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::config::set_cpi_caller_allowlist(ctx, programs)
+    }
 
-impl CircuitBreakerState {
-    pub fn new() -> Self {
-        Self {
-            failure_count: 0,
-            last_failure_timestamp: 0,
-            blocked: false,
-            total_transactions: 0,
-            failed_transactions: 0,
-        }
+    pub fn set_lock_tiers(ctx: Context<UpdateConfig>, lock_tiers: Vec<LockTier>) -> Result<()> {
+        instructions::config::set_lock_tiers(ctx, lock_tiers)
     }
 
-    pub fn can_execute(&self, current_timestamp: i64) -> bool {
-        const FAILURE_THRESHOLD: u32 = 10;
-        const RESET_TIMEOUT: i64 = 600; // 10 minutes
+    pub fn set_early_unstake_penalty(
+        ctx: Context<UpdateConfig>,
+        penalty_bps: u64,
+        penalty_mode: PenaltyMode,
+    ) -> Result<()> {
+        instructions::config::set_early_unstake_penalty(ctx, penalty_bps, penalty_mode)
+    }
 
-        if !self.blocked {
-            return true;
-        }
+    pub fn set_unstake_cooldown(
+        ctx: Context<UpdateConfig>,
+        unstake_cooldown_seconds: i64,
+    ) -> Result<()> {
+        instructions::config::set_unstake_cooldown(ctx, unstake_cooldown_seconds)
+    }
 
-        // Reset if timeout has passed
-        if current_timestamp - self.last_failure_timestamp > RESET_TIMEOUT {
-            return true;
-        }
+    pub fn set_warmup_seconds(ctx: Context<UpdateConfig>, warmup_seconds: i64) -> Result<()> {
+        instructions::config::set_warmup_seconds(ctx, warmup_seconds)
+    }
 
-        self.failure_count < FAILURE_THRESHOLD
+    /// Pauses the vault automatically on a single claim above
+    /// `max_claim_bps` of the daily reward limit, or when `total_staked`
+    /// falls more than `max_daily_staked_drop` in a day. 0 disables a rule.
+    pub fn set_anomaly_rules(
+        ctx: Context<UpdateConfig>,
+        max_claim_bps: u64,
+        max_daily_staked_drop: u32,
+    ) -> Result<()> {
+        instructions::config::set_anomaly_rules(ctx, max_claim_bps, max_daily_staked_drop)
     }
 
-    pub fn on_success(&mut self) {
-        self.total_transactions += 1;
-        if self.blocked && self.failure_count > 0 {
-            self.failure_count = self.failure_count.saturating_sub(1);
-            if self.failure_count == 0 {
-                self.blocked = false;
-            }
-        }
+    /// Gives every wallet a bucket of `capacity` stakes and claims that
+    /// refills one per `refill_interval_seconds`. Capacity 0 disables it.
+    pub fn set_user_rate_limit(
+        ctx: Context<UpdateConfig>,
+        capacity: u32,
+        refill_interval_seconds: i64,
+    ) -> Result<()> {
+        instructions::config::set_user_rate_limit(ctx, capacity, refill_interval_seconds)
     }
 
-    pub fn on_failure(&mut self, current_timestamp: i64) {
-        const FAILURE_THRESHOLD: u32 = 10;
-        
-        self.total_transactions += 1;
-        self.failed_transactions += 1;
-        self.failure_count += 1;
-        self.last_failure_timestamp = current_timestamp;
+    // Governance Functions
+    pub fn set_approval_threshold(ctx: Context<ManageGovernance>, threshold: u8) -> Result<()> {
+        instructions::governance::set_approval_threshold(ctx, threshold)
+    }
 
-        if self.failure_count >= FAILURE_THRESHOLD {
-            self.blocked = true;
-        }
+    pub fn propose_action(ctx: Context<ProposeAction>, action: GovernanceAction) -> Result<()> {
+        instructions::governance::propose_action(ctx, action)
     }
-}
 
-impl DailyLimits {
-    pub fn new() -> Self {
-        Self {
-            max_stakes_per_day: 100,
-            max_claims_per_day: 50,  
-            max_total_rewards_per_day: 1_000_000_000, // 1000 tokens with 6 decimals
-            stakes_today: 0,
-            claims_today: 0,
-            rewards_claimed_today: 0,
-            last_reset_timestamp: 0,
-        }
+    pub fn approve_action(ctx: Context<ApproveAction>) -> Result<()> {
+        instructions::governance::approve_action(ctx)
     }
 
-    pub fn reset_if_new_day(&mut self, current_timestamp: i64) {
-        const SECONDS_PER_DAY: i64 = 86400;
-        
-        if current_timestamp - self.last_reset_timestamp > SECONDS_PER_DAY {
-            self.stakes_today = 0;
-            self.claims_today = 0;
-            self.rewards_claimed_today = 0;
-            self.last_reset_timestamp = current_timestamp;
-        }
+    /// Pass the approvers' role accounts, in approval order, as remaining
+    /// accounts; only approvers still holding a permitted role count.
+    pub fn execute_action<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteAction<'info>>,
+    ) -> Result<()> {
+        instructions::governance::execute_action(ctx)
     }
 
-    pub fn can_stake(&self) -> bool {
-        self.stakes_today < self.max_stakes_per_day
+    // Decommission Functions
+    pub fn propose_decommission(
+        ctx: Context<ManageGovernance>,
+        mint_authority_recipient: Option<Pubkey>,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::decommission::propose_decommission(
+            ctx,
+            mint_authority_recipient,
+            timelock_seconds,
+        )
     }
 
-    pub fn can_claim(&self, reward_amount: u64) -> bool {
-        self.claims_today < self.max_claims_per_day &&
-        self.rewards_claimed_today + reward_amount <= self.max_total_rewards_per_day
+    pub fn cancel_decommission(ctx: Context<ManageGovernance>) -> Result<()> {
+        instructions::decommission::cancel_decommission(ctx)
     }
 
-    pub fn record_stake(&mut self) {
-        self.stakes_today += 1;
+    pub fn decommission_vault(ctx: Context<DecommissionVault>) -> Result<()> {
+        instructions::decommission::decommission_vault(ctx)
     }
 
-    pub fn record_claim(&mut self, reward_amount: u64) {
-        self.claims_today += 1;
-        self.rewards_claimed_today += reward_amount;
+    /// Schedules handing the reward mint (and freeze) authority of a
+    /// decommissioned vault back to `recipient`, for vaults retired without
+    /// naming one in `propose_decommission`.
+    pub fn propose_mint_authority_reclaim(
+        ctx: Context<ManageGovernance>,
+        recipient: Pubkey,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::decommission::propose_mint_authority_reclaim(ctx, recipient, timelock_seconds)
     }
-}
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
-pub struct PendingUpgrade {
-    pub new_version: u32,
-    pub scheduled_timestamp: i64,
-    pub proposer: Pubkey,
-}
+    pub fn cancel_mint_authority_reclaim(ctx: Context<ManageGovernance>) -> Result<()> {
+        instructions::decommission::cancel_mint_authority_reclaim(ctx)
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct AccountRole {
-    pub user: Pubkey,
-    pub role: Role,
-    pub granted_by: Pubkey,
-    pub granted_at: i64,
-}
+    pub fn reclaim_mint_authority(ctx: Context<DecommissionVault>) -> Result<()> {
+        instructions::decommission::reclaim_mint_authority(ctx)
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, InitSpace)]
-pub enum Role {
-    SuperAdmin,
-    Admin, 
-    Moderator,
-    Operator,
-}
+    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+        instructions::decommission::close_vault(ctx)
+    }
 
-impl Role {
-    pub fn can_pause_vault(&self) -> bool {
-        matches!(self, Role::SuperAdmin | Role::Admin | Role::Moderator)
+    // Migration Functions
+    /// Sets the successor deployment stakes may be migrated to and the
+    /// predecessor deployment this vault accepts migrated stakes from.
+    pub fn configure_migration(
+        ctx: Context<ManageGovernance>,
+        migration_target: Option<Pubkey>,
+        migration_source: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::migration::configure_migration(ctx, migration_target, migration_source)
     }
 
-    pub fn can_update_config(&self) -> bool {
-        matches!(self, Role::SuperAdmin | Role::Admin)
+    /// Points voter-weight records at a Realms DAO. Set both to `None` to stop
+    /// new records from being created.
+    pub fn configure_voter_weight(
+        ctx: Context<UpdateConfig>,
+        governance_realm: Option<Pubkey>,
+        governing_token_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::voting::configure_voter_weight(ctx, governance_realm, governing_token_mint)
     }
 
-    pub fn can_manage_roles(&self) -> bool {
-        matches!(self, Role::SuperAdmin)
+    /// Creates the user's voter-weight record. Stake and unstake keep its
+    /// weight equal to the user's staked NFT count from then on.
+    pub fn create_voter_weight_record(ctx: Context<CreateVoterWeightRecord>) -> Result<()> {
+        instructions::voting::create_voter_weight_record(ctx)
     }
 
-    pub fn can_moderate_users(&self) -> bool {
-        matches!(self, Role::SuperAdmin | Role::Admin | Role::Moderator)
+    pub fn set_rate_vote_bounds(
+        ctx: Context<UpdateConfig>,
+        min_rate: u64,
+        max_rate: u64,
+        quorum: u64,
+    ) -> Result<()> {
+        instructions::voting::set_rate_vote_bounds(ctx, min_rate, max_rate, quorum)
     }
 
-    pub fn can_manage_treasury(&self) -> bool {
-        matches!(self, Role::SuperAdmin | Role::Admin)
+    /// Opens a staker vote on a new reward rate within the admin bounds.
+    pub fn create_proposal(ctx: Context<CreateRateProposal>, new_rate: u64) -> Result<()> {
+        instructions::voting::create_proposal(ctx, new_rate)
     }
 
-    pub fn can_manage_upgrades(&self) -> bool {
-        matches!(self, Role::SuperAdmin | Role::Admin)
+    /// Votes with the voter's staked NFT count. Only users who have been
+    /// staking without a break since the proposal opened may vote, so NFTs
+    /// staked after the fact carry no weight.
+    pub fn cast_vote(ctx: Context<CastRateVote>, approve: bool) -> Result<()> {
+        instructions::voting::cast_vote(ctx, approve)
     }
-}
 
-#[account]
-#[derive(InitSpace)]
-pub struct UserStakeAccount {
-    pub user: Pubkey,
-    pub staked_nfts: u32,
-    pub pending_rewards: u64,
-    pub last_update_timestamp: i64,
-}
+    /// Applies a passed proposal. Permissionless once voting has ended.
+    pub fn execute_proposal(ctx: Context<ExecuteRateProposal>) -> Result<()> {
+        instructions::voting::execute_proposal(ctx)
+    }
 
-// Events
-#[event]
-pub struct NftStaked {
-    pub user: Pubkey,
-    pub nft_mint: Pubkey,
-    pub timestamp: i64,
-}
+    /// Opens a raffle and escrows its prize in a vault-owned token account.
+    pub fn create_raffle(
+        ctx: Context<CreateRaffle>,
+        prize_amount: u64,
+        entries_close_at: i64,
+    ) -> Result<()> {
+        instructions::raffle::create_raffle(ctx, prize_amount, entries_close_at)
+    }
 
-#[event]
-pub struct NftUnstaked {
-    pub user: Pubkey,
-    pub nft_mint: Pubkey,
-    pub timestamp: i64,
-}
+    /// Enters all tickets earned since the user's last entry. Each user
+    /// enters a raffle once and holds one contiguous ticket range.
+    pub fn enter_raffle(ctx: Context<EnterRaffle>) -> Result<()> {
+        instructions::raffle::enter_raffle(ctx)
+    }
 
-#[event]
-pub struct RewardsClaimed {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub timestamp: i64,
-}
+    /// Requests VRF randomness for a closed raffle. Permissionless; the
+    /// caller pays the oracle fee.
+    pub fn request_raffle_randomness(ctx: Context<RequestRaffleRandomness>) -> Result<()> {
+        instructions::raffle::request_raffle_randomness(ctx)
+    }
 
-#[event]
-pub struct VaultPaused {
-    pub authority: Pubkey,
-    pub timestamp: i64,
-}
+    /// Picks the winning ticket from the fulfilled VRF output. Permissionless.
+    pub fn draw_raffle(ctx: Context<DrawRaffle>) -> Result<()> {
+        instructions::raffle::draw_raffle(ctx)
+    }
 
-#[event]
-pub struct VaultUnpaused {
-    pub authority: Pubkey,
-    pub timestamp: i64,
-}
+    pub fn claim_prize(ctx: Context<ClaimPrize>) -> Result<()> {
+        instructions::raffle::claim_prize(ctx)
+    }
 
-#[event]
-pub struct RoleGranted {
-    pub user: Pubkey,
-    pub role: Role,
-    pub granted_by: Pubkey,
-    pub timestamp: i64,
-}
+    /// Returns the prize of a raffle that closed without any entries.
+    pub fn cancel_raffle(ctx: Context<CancelRaffle>) -> Result<()> {
+        instructions::raffle::cancel_raffle(ctx)
+    }
 
-#[event]
-pub struct RoleRevoked {
-    pub user: Pubkey,
-    pub revoked_by: Pubkey,
-    pub timestamp: i64,
-}
+    pub fn create_mission(
+        ctx: Context<CreateMission>,
+        requirement: MissionRequirement,
+        reward: u64,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        instructions::mission::create_mission(ctx, requirement, reward, expires_at)
+    }
 
-#[event]
-pub struct UpgradeProposed {
-    pub new_version: u32,
-    pub scheduled_timestamp: i64,
-    pub proposer: Pubkey,
-    pub timestamp: i64,
-}
+    pub fn set_mission_active(ctx: Context<UpdateMission>, active: bool) -> Result<()> {
+        instructions::mission::set_mission_active(ctx, active)
+    }
 
-#[event]
-pub struct UpgradeExecuted {
-    pub new_version: u32,
-    pub executor: Pubkey,
-    pub timestamp: i64,
-}
+    /// Pays a mission's reward once per user, after checking its requirement
+    /// against the user's current stake. Held to the claim limits and the
+    /// reward supply cap.
+    pub fn complete_mission(ctx: Context<CompleteMission>) -> Result<()> {
+        instructions::mission::complete_mission(ctx)
+    }
 
-#[event]
-pub struct UpgradeCancelled {
-    pub cancelled_by: Pubkey,
-    pub timestamp: i64,
-}
+    pub fn set_coupon_signer(
+        ctx: Context<UpdateConfig>,
+        coupon_signer: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::coupon::set_coupon_signer(ctx, coupon_signer)
+    }
 
-#[event]
-pub struct UpgradesLocked {
-    pub locked_by: Pubkey,
-    pub timestamp: i64,
-}
+    /// Redeems a one-time bonus signed off-chain by `vault.coupon_signer`.
+    /// The transaction must carry an Ed25519 program instruction verifying
+    /// the signature over `coupon_message` immediately before this one.
+    /// Counts against the daily limit and supply cap like a claim.
+    pub fn redeem_coupon(
+        ctx: Context<RedeemCoupon>,
+        amount: u64,
+        nonce: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::coupon::redeem_coupon(ctx, amount, nonce, expires_at)
+    }
 
-#[event]
-pub struct ConfigUpdated {
-    pub updated_by: Pubkey,
-    pub timestamp: i64,
-}
+    /// Publishes a merkle root of `(index, wallet, amount)` leaves that
+    /// recipients redeem through `claim_distribution`, paid like rewards.
+    pub fn create_distribution(
+        ctx: Context<CreateDistribution>,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_recipients: u32,
+    ) -> Result<()> {
+        instructions::distribution::create_distribution(
+            ctx,
+            merkle_root,
+            total_amount,
+            num_recipients,
+        )
+    }
+
+    /// Rejected, and retryable, while the claimant's shard has no room
+    /// left under the daily limit or supply cap.
+    pub fn claim_distribution(
+        ctx: Context<ClaimDistribution>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::distribution::claim_distribution(ctx, index, amount, proof)
+    }
+
+    /// Moves one staked NFT, together with the user's pending rewards and
+    /// its original stake time, into the successor vault without an
+    /// unstake/restake round trip.
+    pub fn migrate_stake(ctx: Context<MigrateStake>) -> Result<()> {
+        instructions::migration::migrate_stake(ctx)
+    }
+
+    /// Receiving side of `migrate_stake`. Only callable by the vault PDA of
+    /// the configured predecessor deployment, which has already moved the NFT
+    /// into this vault's custody.
+    pub fn accept_migrated_stake(
+        ctx: Context<AcceptMigratedStake>,
+        pending_rewards: u64,
+        reward_token_mint: Pubkey,
+        staked_at: i64,
+    ) -> Result<()> {
+        instructions::migration::accept_migrated_stake(
+            ctx,
+            pending_rewards,
+            reward_token_mint,
+            staked_at,
+        )
+    }
+
+    // Treasury Functions
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        instructions::treasury::initialize_treasury(ctx)
+    }
+
+    /// Deposits SPL tokens when both token accounts are passed, SOL otherwise.
+    pub fn deposit_treasury(ctx: Context<DepositTreasury>, amount: u64) -> Result<()> {
+        instructions::treasury::deposit_treasury(ctx, amount)
+    }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Math overflow")]
-    MathOverflow,
-    #[msg("Math underflow")]
-    MathUnderflow,
-    #[msg("No NFTs staked")]
-    NoNftsStaked,
-    #[msg("No rewards to claim")]
-    NoRewardsToClaim,
-    #[msg("Invalid NFT - must have amount=1 and decimals=0")]
-    InvalidNft,
-    #[msg("No collection found in NFT metadata")]
-    NoCollectionFound,
-    #[msg("Collection not verified")]
-    CollectionNotVerified,
-    #[msg("Wrong collection - NFT not from authorized collection")]
-    WrongCollection,
-    #[msg("Vault is paused")]
-    VaultPaused,
-    #[msg("Operation too frequent - rate limited")]
-    TooFrequent,
-    #[msg("Claim too frequent - minimum 60 seconds between claims")]
-    TooFrequentClaim,
-    #[msg("Invalid time elapsed - must be between 0 and 30 days")]
-    InvalidTimeElapsed,
-    #[msg("Excessive reward claim - exceeds maximum allowed")]
-    ExcessiveRewardClaim,
-    #[msg("Invalid reward rate - must be greater than 0")]
-    InvalidRewardRate,
-    #[msg("Already paused")]
-    AlreadyPaused,
-    #[msg("Not paused")]
-    NotPaused,
-    #[msg("Unauthorized access")]
-    Unauthorized,
-    #[msg("Insufficient permissions for this action")]
-    InsufficientPermissions,
-    #[msg("Upgrades are permanently locked")]
-    UpgradesLocked,
-    #[msg("An upgrade is already pending")]
-    UpgradePending,
-    #[msg("No upgrade is currently pending")]
-    NoUpgradePending,
-    #[msg("Invalid version number")]
-    InvalidVersion,
-    #[msg("Invalid timelock duration")]
-    InvalidTimelock,
-    #[msg("Timelock period has not expired")]
-    TimelockNotExpired,
-    #[msg("Upgrades are already locked")]
-    UpgradesAlreadyLocked,
-    #[msg("Failed to transfer mint authority to vault")]
-    MintAuthorityTransferFailed,
-    #[msg("Invalid mint authority")]
-    InvalidMintAuthority,
-    #[msg("Circuit breaker is active - too many failures")]
-    CircuitBreakerActive,
-    #[msg("Daily operation limit exceeded")]
-    DailyLimitExceeded,
-}
\ No newline at end of file
+    /// Schedules a withdrawal of `amount` SOL (`mint == None`) or SPL tokens
+    /// to `destination`, executable once the timelock has passed.
+    pub fn propose_treasury_withdrawal(
+        ctx: Context<ManageTreasury>,
+        mint: Option<Pubkey>,
+        amount: u64,
+        destination: Pubkey,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        instructions::treasury::propose_treasury_withdrawal(
+            ctx,
+            mint,
+            amount,
+            destination,
+            timelock_seconds,
+        )
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>) -> Result<()> {
+        instructions::treasury::withdraw_treasury(ctx)
+    }
+
+    pub fn cancel_treasury_withdrawal(ctx: Context<ManageTreasury>) -> Result<()> {
+        instructions::treasury::cancel_treasury_withdrawal(ctx)
+    }
+}
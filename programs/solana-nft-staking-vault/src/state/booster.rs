@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+
+use crate::state::UserStakeAccount;
+use crate::utils::BPS_DENOMINATOR;
+
+#[account]
+#[derive(InitSpace)]
+pub struct SetBonusConfig {
+    /// Groups `0..group_count` must each have a staked NFT to complete the set
+    pub group_count: u8,
+    pub completion_bonus_bps: u64,
+    pub bump: u8,
+}
+
+impl SetBonusConfig {
+    pub const MAX_BONUS_BPS: u64 = 5_000; // +50%
+
+    pub fn bonus_bps(&self, user_stake: &UserStakeAccount) -> u64 {
+        if user_stake.has_complete_set(self.group_count) {
+            self.completion_bonus_bps
+        } else {
+            0
+        }
+    }
+}
+
+/// A collection whose NFTs can be attached as boosters.
+#[account]
+#[derive(InitSpace)]
+pub struct BoosterCollection {
+    pub collection_mint: Pubkey,
+    pub boost_bps: u64,
+    /// Inactive collections accept no new attachments
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl BoosterCollection {
+    pub const MAX_BOOST_BPS: u64 = 5_000; // +50%
+}
+
+/// An integrator program allowed to call the vault via CPI, in addition to
+/// `vault.cpi_caller_allowlist`.
+#[account]
+#[derive(InitSpace)]
+pub struct PartnerProgram {
+    pub program_id: Pubkey,
+    /// May call `stake_nft`, `unstake_nft` and `emergency_unstake`
+    pub can_stake: bool,
+    /// May call `claim_rewards`
+    pub can_claim: bool,
+    pub active: bool,
+    pub bump: u8,
+}
+
+/// The booster NFT attached to a user's position.
+#[account]
+#[derive(InitSpace)]
+pub struct BoosterRecord {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub collection_mint: Pubkey,
+    /// Collection boost at attach time
+    pub boost_bps: u64,
+    pub attached_at: i64,
+    pub bump: u8,
+}
+
+/// Admin-assigned set-bonus group (trait group or sub-collection) of an NFT.
+#[account]
+#[derive(InitSpace)]
+pub struct NftGroupTag {
+    pub nft_mint: Pubkey,
+    pub group: u8,
+    pub bump: u8,
+}
+
+impl NftGroupTag {
+    pub const MAX_GROUPS: u8 = 8;
+}
+
+/// Admin-assigned reward multiplier of a single NFT, e.g. a 1-of-1.
+#[account]
+#[derive(InitSpace)]
+pub struct NftMultiplier {
+    pub nft_mint: Pubkey,
+    /// 10_000 = 1x
+    pub multiplier_bps: u64,
+    pub bump: u8,
+}
+
+impl NftMultiplier {
+    pub const MAX_MULTIPLIER_BPS: u64 = 50_000; // 5x
+
+    /// Extra weight the NFT adds to its position, in bps of one NFT.
+    pub fn bonus_bps(&self) -> u64 {
+        self.multiplier_bps.saturating_sub(BPS_DENOMINATOR)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LevelConfig {
+    pub xp_per_nft_day: u64,
+    pub xp_per_claim: u64,
+    /// Ascending thresholds; reaching `levels[i]` puts a user at level `i + 1`
+    #[max_len(10)]
+    pub levels: Vec<LevelTier>,
+    pub bump: u8,
+}
+
+impl LevelConfig {
+    pub const MAX_LEVELS: usize = 10;
+    pub const MAX_BOOST_BPS: u64 = 5_000; // +50%
+
+    pub fn level_for(&self, xp: u64) -> u8 {
+        self.levels
+            .iter()
+            .take_while(|tier| xp >= tier.xp_threshold)
+            .count() as u8
+    }
+
+    pub fn boost_bps(&self, level: u8) -> u64 {
+        (level as usize)
+            .checked_sub(1)
+            .and_then(|index| self.levels.get(index))
+            .map_or(0, |tier| tier.boost_bps)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct LevelTier {
+    pub xp_threshold: u64,
+    pub boost_bps: u64,
+}
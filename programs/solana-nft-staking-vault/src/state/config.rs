@@ -0,0 +1,751 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::{VaultInitParams, VaultAccount};
+use crate::utils::BPS_DENOMINATOR;
+
+/// Rate, pauses and safety limits, kept apart from `VaultAccount` so
+/// custody and accounting don't share an account with settings admins
+/// change at any time. Zero-copy, since nearly every instruction reads it.
+#[account(zero_copy)]
+pub struct VaultConfig {
+    pub reward_rate_per_second: u64,
+    /// Sub-unit part of the per-NFT rate, in `1 / RATE_PRECISION` reward
+    /// units per second
+    pub reward_rate_fraction: u64,
+    pub circuit_breaker: CircuitBreakerState,
+    pub daily_limit: DailyLimits,
+    /// Bumped by `reset_circuit_breaker` so vault shards reset theirs too
+    pub circuit_breaker_resets: u32,
+    /// Bitmask of `PAUSE_*` flags
+    pub pause_flags: u8,
+    pub bump: u8,
+    pub _padding: [u8; 2],
+}
+
+impl VaultConfig {
+    pub const PAUSE_STAKE: u8 = 1 << 0;
+    pub const PAUSE_CLAIM: u8 = 1 << 1;
+    pub const PAUSE_UNSTAKE: u8 = 1 << 2;
+    pub const PAUSE_ALL: u8 = Self::PAUSE_STAKE | Self::PAUSE_CLAIM | Self::PAUSE_UNSTAKE;
+
+    /// Converts the whole-token rate and limit with the vault's reward
+    /// decimals, so `vault` must be initialized first.
+    pub fn initialize(
+        &mut self,
+        params: &VaultInitParams,
+        vault: &VaultAccount,
+        bump: u8,
+    ) -> Result<()> {
+        require!(params.reward_tokens_per_day > 0, ErrorCode::InvalidRewardRate);
+        require!(
+            params.max_stakes_per_day > 0
+                && params.max_claims_per_day > 0
+                && params.max_total_rewards_per_day > 0,
+            ErrorCode::InvalidLimits
+        );
+        require!(
+            params.failure_threshold > 0 && params.reset_timeout > 0,
+            ErrorCode::InvalidCircuitBreakerConfig
+        );
+
+        let (reward_rate_per_second, reward_rate_fraction) =
+            vault.rate_from_tokens_per_day(params.reward_tokens_per_day)?;
+        self.reward_rate_per_second = reward_rate_per_second;
+        self.reward_rate_fraction = reward_rate_fraction;
+        self.pause_flags = 0;
+        self.circuit_breaker = CircuitBreakerState::new(
+            params.failure_threshold,
+            params.reset_timeout,
+        );
+        self.daily_limit = DailyLimits::new(
+            params.max_stakes_per_day,
+            params.max_claims_per_day,
+            vault.to_base_units(params.max_total_rewards_per_day)?,
+        );
+        self.circuit_breaker_resets = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.pause_flags & flag != 0
+    }
+
+    /// Per-NFT rate scaled by `VaultAccount::RATE_PRECISION`.
+    pub fn precise_reward_rate(&self) -> u128 {
+        (self.reward_rate_per_second as u128) * VaultAccount::RATE_PRECISION
+            + self.reward_rate_fraction as u128
+    }
+
+    /// Per-NFT rate rounded up to whole reward units, for upper bounds.
+    pub fn reward_rate_ceil(&self) -> u64 {
+        self.reward_rate_per_second
+            .saturating_add((self.reward_rate_fraction > 0) as u64)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct EmissionSchedule {
+    /// Nothing accrues before this
+    pub start_timestamp: i64,
+    /// 0 = emissions never end
+    pub end_timestamp: i64,
+    /// 0 = no halving
+    pub halving_interval: i64,
+}
+
+impl EmissionSchedule {
+    pub const MIN_HALVING_INTERVAL: i64 = 86400; // 1 day
+
+    pub fn new(start_timestamp: i64) -> Self {
+        Self {
+            start_timestamp,
+            end_timestamp: 0,
+            halving_interval: 0,
+        }
+    }
+
+    /// Per-NFT rate integrated over `[from, to)`, in the units of `base_rate`.
+    pub fn integrate(&self, base_rate: u128, from: i64, to: i64) -> Result<u128> {
+        let from = from.max(self.start_timestamp);
+        let to = if self.end_timestamp > 0 {
+            to.min(self.end_timestamp)
+        } else {
+            to
+        };
+        if to <= from {
+            return Ok(0);
+        }
+
+        if self.halving_interval == 0 {
+            return ((to - from) as u128)
+                .checked_mul(base_rate)
+                .ok_or(ErrorCode::MathOverflow.into());
+        }
+
+        let mut total: u128 = 0;
+        let mut cursor = from;
+        while cursor < to {
+            let epoch = (cursor - self.start_timestamp) / self.halving_interval;
+            if epoch >= 128 {
+                break;
+            }
+
+            let epoch_end = self.start_timestamp
+                .checked_add((epoch + 1) * self.halving_interval)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let segment_end = epoch_end.min(to);
+            let segment = ((segment_end - cursor) as u128)
+                .checked_mul(base_rate >> epoch)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            total = total.checked_add(segment).ok_or(ErrorCode::MathOverflow)?;
+            cursor = segment_end;
+        }
+
+        Ok(total)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, InitSpace)]
+pub enum RewardMode {
+    /// Vault PDA holds the mint authority and mints on claim.
+    Mint,
+    /// Rewards are transferred out of a pre-funded vault-owned token account.
+    Pool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct LockTier {
+    pub duration_seconds: i64,
+    pub boost_bps: u64,
+}
+
+impl LockTier {
+    pub const MAX_TIERS: usize = 4;
+    pub const MAX_DURATION_SECONDS: i64 = 365 * 86400; // 1 year
+    pub const MAX_BOOST_BPS: u64 = 10_000; // +100%
+
+    pub fn default_tiers() -> Vec<LockTier> {
+        vec![
+            LockTier { duration_seconds: 30 * 86400, boost_bps: 1_000 },  // +10%
+            LockTier { duration_seconds: 90 * 86400, boost_bps: 2_500 },  // +25%
+            LockTier { duration_seconds: 180 * 86400, boost_bps: 5_000 }, // +50%
+        ]
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, InitSpace)]
+pub enum PenaltyMode {
+    /// Forfeited rewards are never paid out.
+    Burn,
+    /// Forfeited rewards are shared pro rata among the remaining staked NFTs.
+    Redistribute,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct StreakConfig {
+    pub bonus_bps_per_day: u64,
+    pub max_bonus_bps: u64,
+}
+
+impl StreakConfig {
+    pub const DEFAULT_BONUS_BPS_PER_DAY: u64 = 10; // +0.1%
+    pub const DEFAULT_MAX_BONUS_BPS: u64 = 1_000; // +10%
+    pub const MAX_BONUS_BPS: u64 = 5_000; // +50%
+
+    pub fn new() -> Self {
+        Self {
+            bonus_bps_per_day: Self::DEFAULT_BONUS_BPS_PER_DAY,
+            max_bonus_bps: Self::DEFAULT_MAX_BONUS_BPS,
+        }
+    }
+
+    pub fn bonus_bps(&self, streak_days: u32) -> u64 {
+        (streak_days as u64)
+            .saturating_mul(self.bonus_bps_per_day)
+            .min(self.max_bonus_bps)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct TokenBoostConfig {
+    pub mint: Pubkey,
+    /// Staked tokens, in base units, needed for the boost
+    pub required_amount: u64,
+    /// 0 disables the boost and new token stakes
+    pub boost_bps: u64,
+}
+
+impl TokenBoostConfig {
+    pub const MAX_BOOST_BPS: u64 = 10_000; // +100%
+
+    pub fn new() -> Self {
+        Self { mint: Pubkey::default(), required_amount: 0, boost_bps: 0 }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.boost_bps > 0
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.boost_bps <= Self::MAX_BOOST_BPS
+            && (self.boost_bps == 0 || (self.mint != Pubkey::default() && self.required_amount > 0))
+    }
+
+    pub fn is_met(&self, staked_tokens: u64) -> bool {
+        self.is_enabled() && staked_tokens >= self.required_amount
+    }
+
+    pub fn bonus_bps(&self, staked_tokens: u64) -> u64 {
+        if self.is_met(staked_tokens) {
+            self.boost_bps
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct VestingConfig {
+    /// 0 pays claims out directly
+    pub duration_seconds: i64,
+    pub cliff_seconds: i64,
+}
+
+impl VestingConfig {
+    pub const MAX_DURATION_SECONDS: i64 = 2 * 365 * 86400; // 2 years
+
+    pub fn new() -> Self {
+        Self { duration_seconds: 0, cliff_seconds: 0 }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.duration_seconds > 0
+    }
+
+    pub fn is_valid(&self) -> bool {
+        (0..=Self::MAX_DURATION_SECONDS).contains(&self.duration_seconds)
+            && self.cliff_seconds >= 0
+            && self.cliff_seconds <= self.duration_seconds
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct CurveConfig {
+    /// Emission split across all staked NFTs; 0 means linear per-NFT emissions
+    pub total_emission_per_second: u64,
+}
+
+impl CurveConfig {
+    pub fn new() -> Self {
+        Self { total_emission_per_second: 0 }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.total_emission_per_second > 0
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct OracleConfig {
+    /// Pyth price feed id for the reward token in USD
+    pub feed_id: [u8; 32],
+    /// Target emission per NFT, in micro-USD per second
+    pub usd_micros_per_second: u64,
+    pub max_staleness_seconds: u64,
+    /// Largest accepted confidence interval relative to the price
+    pub max_confidence_bps: u64,
+    pub min_rate: u64,
+    pub max_rate: u64,
+}
+
+impl OracleConfig {
+    pub const USD_MICROS: u128 = 1_000_000;
+    pub const MAX_STALENESS_SECONDS: u64 = 3_600; // 1 hour
+
+    pub fn is_valid(&self) -> bool {
+        self.usd_micros_per_second > 0
+            && self.max_staleness_seconds > 0
+            && self.max_staleness_seconds <= Self::MAX_STALENESS_SECONDS
+            && self.max_confidence_bps > 0
+            && self.max_confidence_bps <= BPS_DENOMINATOR
+            && self.min_rate > 0
+            && self.min_rate <= self.max_rate
+    }
+
+    /// Reward base units per second worth `usd_micros_per_second` at
+    /// `price * 10^exponent` USD per whole token, clamped to the bounds.
+    pub fn rate_for_price(&self, price: u64, exponent: i32, decimals: u8) -> Result<u64> {
+        // rate = usd_micros * 10^decimals / (USD_MICROS * price * 10^exponent)
+        let scale = exponent
+            .checked_neg()
+            .and_then(|e| e.checked_add(decimals as i32))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let mut numerator = self.usd_micros_per_second as u128;
+        let mut denominator = Self::USD_MICROS
+            .checked_mul(price as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let factor = 10u128
+            .checked_pow(scale.unsigned_abs())
+            .ok_or(ErrorCode::MathOverflow)?;
+        if scale >= 0 {
+            numerator = numerator.checked_mul(factor).ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            denominator = denominator.checked_mul(factor).ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        let rate = (numerator / denominator).min(u64::MAX as u128) as u64;
+        Ok(rate.clamp(self.min_rate, self.max_rate))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct LoyaltyConfig {
+    pub bonus_bps_per_period: u64,
+    pub period_seconds: i64,
+    pub max_bonus_bps: u64,
+}
+
+impl LoyaltyConfig {
+    pub const DEFAULT_BONUS_BPS_PER_PERIOD: u64 = 500; // +5%
+    pub const DEFAULT_PERIOD_SECONDS: i64 = 30 * 86400; // 30 days
+    pub const DEFAULT_MAX_BONUS_BPS: u64 = 2_500; // +25%
+    pub const MAX_BONUS_BPS: u64 = 10_000; // +100%
+
+    pub fn new() -> Self {
+        Self {
+            bonus_bps_per_period: Self::DEFAULT_BONUS_BPS_PER_PERIOD,
+            period_seconds: Self::DEFAULT_PERIOD_SECONDS,
+            max_bonus_bps: Self::DEFAULT_MAX_BONUS_BPS,
+        }
+    }
+
+    /// Bonus for every full period of continuous staking.
+    pub fn bonus_bps(&self, staked_duration: i64) -> u64 {
+        if self.period_seconds <= 0 || staked_duration <= 0 {
+            return 0;
+        }
+
+        ((staked_duration / self.period_seconds) as u64)
+            .saturating_mul(self.bonus_bps_per_period)
+            .min(self.max_bonus_bps)
+    }
+}
+
+/// Per-wallet token bucket for stakes and claims, so a single wallet can't
+/// use up the shared daily limits. Capacity 0 turns it off.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct RateLimitConfig {
+    /// Most actions a wallet can make back to back
+    pub capacity: u32,
+    /// Seconds to earn back one action
+    pub refill_interval_seconds: i64,
+}
+
+impl RateLimitConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Bucket level and refill time after refilling up to `now`. A full
+    /// bucket restarts its clock so it doesn't bank time.
+    pub fn refill(&self, tokens: u32, refilled_at: i64, now: i64) -> (u32, i64) {
+        if tokens >= self.capacity || self.refill_interval_seconds <= 0 {
+            return (self.capacity, now);
+        }
+        let intervals = now.saturating_sub(refilled_at).max(0) / self.refill_interval_seconds;
+        let refilled = tokens as i64 + intervals;
+        if refilled >= self.capacity as i64 {
+            (self.capacity, now)
+        } else {
+            (refilled as u32, refilled_at + intervals * self.refill_interval_seconds)
+        }
+    }
+}
+
+/// Conditions that pause the vault without an admin. 0 turns a rule off.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct AnomalyRules {
+    /// Single claim above this share of `max_total_rewards_per_day`
+    pub max_claim_bps: u64,
+    /// Most `total_staked` may fall below the day's high
+    pub max_daily_staked_drop: u32,
+}
+
+impl AnomalyRules {
+    /// Largest claim allowed under `daily_reward_limit`, if the rule is on.
+    pub fn claim_ceiling(&self, daily_reward_limit: u64) -> Option<u64> {
+        if self.max_claim_bps == 0 {
+            return None;
+        }
+        Some((daily_reward_limit as u128 * self.max_claim_bps as u128 / BPS_DENOMINATOR as u128) as u64)
+    }
+
+    pub fn staked_drop_exceeded(&self, drop: u32) -> bool {
+        self.max_daily_staked_drop > 0 && drop > self.max_daily_staked_drop
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub enum AnomalyKind {
+    /// A single claim crossed `AnomalyRules::max_claim_bps`
+    LargeClaim,
+    /// `total_staked` fell by more than `AnomalyRules::max_daily_staked_drop`
+    StakedDrop,
+}
+
+/// Limits `stake_nft` to NFTs with one attribute, e.g. "Generation: 1",
+/// proven once per NFT by `verify_nft_trait`. Off while neither a merkle
+/// root nor an attestor is set.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct TraitGate {
+    /// keccak of the attribute as `trait_type:value`
+    pub trait_hash: [u8; 32],
+    /// Root of a tree of eligible mints
+    pub merkle_root: Option<[u8; 32]>,
+    /// Signs attestations for eligible mints
+    pub attestor: Option<Pubkey>,
+    /// Bumped by every `set_trait_gate`, so passes for an earlier gate stop
+    /// counting
+    pub version: u32,
+}
+
+impl TraitGate {
+    pub fn is_enabled(&self) -> bool {
+        self.merkle_root.is_some() || self.attestor.is_some()
+    }
+}
+
+/// Permanent position boost earned by burning NFTs of `collection_mint`
+/// through `burn_for_boost`. 0 per burn turns it off.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct BurnBoostConfig {
+    pub collection_mint: Pubkey,
+    pub boost_bps_per_burn: u64,
+    /// Most a single position can earn from burns
+    pub max_boost_bps: u64,
+}
+
+impl BurnBoostConfig {
+    pub const MAX_BOOST_BPS: u64 = 10_000; // +100%
+
+    pub fn is_enabled(&self) -> bool {
+        self.boost_bps_per_burn > 0
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.max_boost_bps <= Self::MAX_BOOST_BPS
+            && (!self.is_enabled()
+                || (self.collection_mint != Pubkey::default()
+                    && self.boost_bps_per_burn <= self.max_boost_bps))
+    }
+
+    /// A position's burn boost after one more burn.
+    pub fn boost_after_burn(&self, current_bps: u64) -> u64 {
+        current_bps
+            .saturating_add(self.boost_bps_per_burn)
+            .min(self.max_boost_bps)
+    }
+}
+
+/// Lives in zero-copy accounts, so flags are `u8` (0 or 1) rather than
+/// `bool` and fields are ordered to leave no padding.
+#[zero_copy]
+pub struct CircuitBreakerState {
+    pub last_failure_timestamp: i64,
+    pub total_transactions: u64,
+    pub failed_transactions: u64,
+    pub reset_timeout: i64,
+    pub failure_count: u32,
+    pub failure_threshold: u32,
+    pub blocked: u8,
+    /// Set by an admin; only cleared by `reset_circuit_breaker`.
+    pub manually_tripped: u8,
+    pub _padding: [u8; 6],
+}
+
+#[zero_copy]
+pub struct DailyLimits {
+    pub max_stakes_per_day: u32,
+    pub max_claims_per_day: u32,
+    pub max_total_rewards_per_day: u64,
+    pub stakes_today: u32,
+    pub claims_today: u32,
+    pub rewards_claimed_today: u64,
+    pub last_reset_timestamp: i64,
+}
+
+impl CircuitBreakerState {
+    pub const DEFAULT_FAILURE_THRESHOLD: u32 = 10;
+    pub const DEFAULT_RESET_TIMEOUT: i64 = 600; // 10 minutes
+
+    pub fn new(failure_threshold: u32, reset_timeout: i64) -> Self {
+        Self {
+            last_failure_timestamp: 0,
+            total_transactions: 0,
+            failed_transactions: 0,
+            reset_timeout,
+            failure_count: 0,
+            failure_threshold,
+            blocked: 0,
+            manually_tripped: 0,
+            _padding: [0; 6],
+        }
+    }
+
+    pub fn can_execute(&self, current_timestamp: i64) -> bool {
+        if self.manually_tripped != 0 {
+            return false;
+        }
+
+        if self.blocked == 0 {
+            return true;
+        }
+
+        // Reset if timeout has passed
+        if current_timestamp - self.last_failure_timestamp > self.reset_timeout {
+            return true;
+        }
+
+        self.failure_count < self.failure_threshold
+    }
+
+    pub fn on_success(&mut self) {
+        self.total_transactions += 1;
+        if self.blocked != 0 && self.failure_count > 0 {
+            self.failure_count = self.failure_count.saturating_sub(1);
+            if self.failure_count == 0 {
+                self.blocked = 0;
+            }
+        }
+    }
+
+    pub fn on_failure(&mut self, current_timestamp: i64) {
+        self.total_transactions += 1;
+        self.failed_transactions += 1;
+        self.failure_count += 1;
+        self.last_failure_timestamp = current_timestamp;
+
+        if self.failure_count >= self.failure_threshold {
+            self.blocked = 1;
+        }
+    }
+
+    pub fn trip(&mut self, current_timestamp: i64) {
+        self.manually_tripped = 1;
+        self.blocked = 1;
+        self.last_failure_timestamp = current_timestamp;
+    }
+
+    pub fn reset(&mut self) {
+        self.manually_tripped = 0;
+        self.blocked = 0;
+        self.failure_count = 0;
+    }
+}
+
+impl DailyLimits {
+    pub const DEFAULT_MAX_STAKES_PER_DAY: u32 = 100;
+    pub const DEFAULT_MAX_CLAIMS_PER_DAY: u32 = 50;
+    /// In whole reward tokens, as `initialize_vault` takes it
+    pub const DEFAULT_MAX_TOTAL_REWARDS_PER_DAY: u64 = 1_000;
+
+    pub fn new(
+        max_stakes_per_day: u32,
+        max_claims_per_day: u32,
+        max_total_rewards_per_day: u64,
+    ) -> Self {
+        Self {
+            max_stakes_per_day,
+            max_claims_per_day,
+            max_total_rewards_per_day,
+            stakes_today: 0,
+            claims_today: 0,
+            rewards_claimed_today: 0,
+            last_reset_timestamp: 0,
+        }
+    }
+
+    pub fn reset_if_new_day(&mut self, current_timestamp: i64) {
+        const SECONDS_PER_DAY: i64 = 86400;
+        
+        if current_timestamp - self.last_reset_timestamp > SECONDS_PER_DAY {
+            self.stakes_today = 0;
+            self.claims_today = 0;
+            self.rewards_claimed_today = 0;
+            self.last_reset_timestamp = current_timestamp;
+        }
+    }
+
+    /// Fails once today's stakes reach the limit, logging the count.
+    pub fn check_stake(&self) -> Result<()> {
+        if self.stakes_today >= self.max_stakes_per_day {
+            msg!(
+                "Daily stake limit reached: {} of {} stakes today",
+                self.stakes_today,
+                self.max_stakes_per_day
+            );
+            return err!(ErrorCode::DailyStakeLimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// Fails with the limit `reward_amount` would break, logging the
+    /// request against what is left today.
+    pub fn check_claim(&self, reward_amount: u64) -> Result<()> {
+        if self.claims_today >= self.max_claims_per_day {
+            msg!(
+                "Daily claim limit reached: {} of {} claims today",
+                self.claims_today,
+                self.max_claims_per_day
+            );
+            return err!(ErrorCode::DailyClaimLimitExceeded);
+        }
+
+        let remaining = self.remaining_rewards();
+        if reward_amount > remaining {
+            msg!(
+                "Daily reward limit: requested {}, {} of {} left today",
+                reward_amount,
+                remaining,
+                self.max_total_rewards_per_day
+            );
+            return err!(ErrorCode::DailyRewardLimitExceeded);
+        }
+        Ok(())
+    }
+
+    pub fn record_stake(&mut self) {
+        self.stakes_today += 1;
+    }
+
+    pub fn record_claim(&mut self, reward_amount: u64) {
+        self.claims_today += 1;
+        self.rewards_claimed_today += reward_amount;
+    }
+
+    pub fn remaining_rewards(&self) -> u64 {
+        self.max_total_rewards_per_day.saturating_sub(self.rewards_claimed_today)
+    }
+
+    /// Counts rewards paid outside a claim, such as crank bounties, toward
+    /// today's total without using up a claim.
+    pub fn record_payout(&mut self, reward_amount: u64) {
+        self.rewards_claimed_today += reward_amount;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oracle_rate_tracks_price_within_bounds() {
+        let config = OracleConfig {
+            feed_id: [0; 32],
+            usd_micros_per_second: 1_000,
+            max_staleness_seconds: 60,
+            max_confidence_bps: 100,
+            min_rate: 100,
+            max_rate: 1_000,
+        };
+
+        // $2.00 with expo -8, 6-decimal token: 0.001 USD/s = 500 base units/s
+        assert_eq!(config.rate_for_price(200_000_000, -8, 6).unwrap(), 500);
+        // Price halves, rate doubles
+        assert_eq!(config.rate_for_price(100_000_000, -8, 6).unwrap(), 1_000);
+        // Clamped at both ends
+        assert_eq!(config.rate_for_price(10_000_000, -8, 6).unwrap(), 1_000);
+        assert_eq!(config.rate_for_price(100_000_000_000, -8, 6).unwrap(), 100);
+    }
+
+    #[test]
+    fn anomaly_rules_are_off_at_zero() {
+        let rules = AnomalyRules::default();
+        assert_eq!(rules.claim_ceiling(1_000_000), None);
+        assert!(!rules.staked_drop_exceeded(u32::MAX));
+
+        let rules = AnomalyRules {
+            max_claim_bps: 2_500,
+            max_daily_staked_drop: 50,
+        };
+        assert_eq!(rules.claim_ceiling(1_000_000), Some(250_000));
+        assert!(!rules.staked_drop_exceeded(50));
+        assert!(rules.staked_drop_exceeded(51));
+    }
+
+    #[test]
+    fn burn_boost_stops_at_the_cap() {
+        let burn_boost = BurnBoostConfig {
+            collection_mint: Pubkey::new_unique(),
+            boost_bps_per_burn: 400,
+            max_boost_bps: 1_000,
+        };
+        assert!(burn_boost.is_valid());
+        assert_eq!(burn_boost.boost_after_burn(0), 400);
+        assert_eq!(burn_boost.boost_after_burn(800), 1_000);
+        assert_eq!(burn_boost.boost_after_burn(1_000), 1_000);
+
+        let uncapped = BurnBoostConfig { max_boost_bps: 20_000, ..burn_boost };
+        assert!(!uncapped.is_valid());
+        assert!(BurnBoostConfig::default().is_valid());
+    }
+
+    #[test]
+    fn rate_limit_bucket_refills_one_token_per_interval() {
+        let limit = RateLimitConfig {
+            capacity: 3,
+            refill_interval_seconds: 60,
+        };
+
+        // A fresh bucket starts full
+        assert_eq!(limit.refill(0, 0, 1_000), (3, 1_000));
+        // Partial intervals carry over instead of being lost
+        assert_eq!(limit.refill(0, 1_000, 1_090), (1, 1_060));
+        assert_eq!(limit.refill(1, 1_060, 1_120), (2, 1_120));
+        // Never above capacity
+        assert_eq!(limit.refill(2, 1_120, 5_000), (3, 5_000));
+    }
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Distribution {
+    pub id: u64,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub num_recipients: u32,
+    pub created_by: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Distribution {
+    /// Keeps the claimed bitmap within the 10KiB CPI allocation limit
+    pub const MAX_RECIPIENTS: u32 = 64_000;
+
+    pub fn bitmap_len(num_recipients: u32) -> usize {
+        (num_recipients as usize).div_ceil(8)
+    }
+}
+
+/// One bit per leaf index of a `Distribution`.
+#[account]
+pub struct ClaimedBitmap {
+    pub distribution: Pubkey,
+    pub bits: Vec<u8>,
+    pub bump: u8,
+}
+
+impl ClaimedBitmap {
+    pub fn space(num_recipients: u32) -> usize {
+        8 + 32 + 4 + Distribution::bitmap_len(num_recipients) + 1
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.bits[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        self.bits[index as usize / 8] |= 1 << (index % 8);
+    }
+}
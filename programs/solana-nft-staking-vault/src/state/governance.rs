@@ -0,0 +1,272 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::{EmissionSchedule, Role};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingUpgrade {
+    pub new_version: u32,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    /// Loader buffer holding the new program, with the vault PDA as its authority
+    pub buffer: Pubkey,
+    /// SHA-256 of the buffer's program bytes, checked before upgrading
+    pub buffer_hash: [u8; 32],
+    /// Set by an executed `GovernanceAction::ExecuteUpgrade` proposal
+    pub approved: bool,
+    /// Seeds the proposal's `UpgradeObjection` records
+    pub id: u64,
+    pub proposed_at: i64,
+    /// Staked NFTs objecting so far
+    pub objections: u64,
+    /// Objections that cancel the upgrade, fixed when it was proposed;
+    /// 0 means stakers can't veto it
+    pub veto_threshold: u64,
+}
+
+/// One per staker and upgrade proposal, so each wallet objects once.
+#[account]
+#[derive(InitSpace)]
+pub struct UpgradeObjection {
+    pub upgrade_id: u64,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ActionProposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: GovernanceAction,
+    #[max_len(10)]
+    pub approvals: Vec<Pubkey>,
+    pub created_at: i64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl ActionProposal {
+    pub const MAX_APPROVALS: usize = 10;
+    pub const EXPIRY_SECONDS: i64 = 7 * 86400; // 7 days
+
+    pub fn is_expired(&self, current_timestamp: i64) -> bool {
+        current_timestamp - self.created_at > Self::EXPIRY_SECONDS
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, InitSpace)]
+pub enum GovernanceAction {
+    ExecuteUpgrade,
+    UpdateConfig {
+        new_reward_rate: Option<u64>,
+        new_collection_mint: Option<Pubkey>,
+    },
+    UnpauseVault,
+    SetApprovalThreshold { threshold: u8 },
+}
+
+impl GovernanceAction {
+    pub fn is_permitted(&self, role: &Role) -> bool {
+        match self {
+            GovernanceAction::ExecuteUpgrade => role.can_manage_upgrades(),
+            GovernanceAction::UpdateConfig { .. } => role.can_update_config(),
+            GovernanceAction::UnpauseVault => role.can_pause_vault(),
+            GovernanceAction::SetApprovalThreshold { .. } => role.can_manage_roles(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingConfigChange {
+    pub new_reward_rate: Option<u64>,
+    pub new_collection_mint: Option<Pubkey>,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+}
+
+impl PendingConfigChange {
+    pub const MIN_TIMELOCK_SECONDS: i64 = 3600; // 1 hour
+}
+
+/// Accrual is settled lazily, so a new schedule also applies to time
+/// positions haven't settled yet; the timelock gives stakers the chance to
+/// settle or claim under the old one first.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingEmissionSchedule {
+    pub schedule: EmissionSchedule,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+}
+
+impl PendingEmissionSchedule {
+    pub const MIN_TIMELOCK_SECONDS: i64 = 86400; // 1 day
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingDecommission {
+    pub mint_authority_recipient: Option<Pubkey>,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+}
+
+impl PendingDecommission {
+    pub const MIN_TIMELOCK_SECONDS: i64 = 86400; // 1 day
+}
+
+/// A proposed credit to one user's pending rewards.
+#[account]
+#[derive(InitSpace)]
+pub struct RewardAdjustment {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub reason: AdjustmentReason,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+    pub bump: u8,
+}
+
+impl RewardAdjustment {
+    pub const MIN_TIMELOCK_SECONDS: i64 = 86400; // 1 day
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, InitSpace)]
+pub enum AdjustmentReason {
+    /// The reward rate or schedule was set wrong
+    MisconfiguredRate,
+    /// Accrual was cut off by the per-update accrual cap (since removed)
+    AccrualCap,
+    /// The vault was paused or broken through no fault of the user
+    Outage,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingMintAuthorityReclaim {
+    pub recipient: Pubkey,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+}
+
+/// Tokens or lamports stuck at the vault PDA, swept out by `execute_sweep`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingSweep {
+    /// `None` for SOL above the vault's rent-exempt minimum
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+}
+
+impl PendingSweep {
+    pub const MIN_TIMELOCK_SECONDS: i64 = 86400; // 1 day
+}
+
+/// Reward tokens taken back out of a funded pool by
+/// `execute_pool_withdrawal`. Timelocked so stakers can claim what they are
+/// owed before the pool shrinks.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingPoolWithdrawal {
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+    /// Token account of `reward_mint`
+    pub destination: Pubkey,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+}
+
+impl PendingPoolWithdrawal {
+    pub const MIN_TIMELOCK_SECONDS: i64 = 86400; // 1 day
+}
+
+/// A payout from the insurance pool to a staker hurt by an incident,
+/// executed by `pay_insurance_claim`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingInsuranceClaim {
+    /// Reward-mint token account paid
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub reason: AdjustmentReason,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+}
+
+impl PendingInsuranceClaim {
+    pub const MIN_TIMELOCK_SECONDS: i64 = 86400; // 1 day
+
+    /// A claim payable `timelock_seconds` after `now`.
+    pub fn schedule(
+        recipient: Pubkey,
+        amount: u64,
+        reason: AdjustmentReason,
+        proposer: Pubkey,
+        now: i64,
+        timelock_seconds: i64,
+    ) -> Result<Self> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            timelock_seconds >= Self::MIN_TIMELOCK_SECONDS,
+            ErrorCode::InvalidTimelock
+        );
+
+        let scheduled_timestamp = now
+            .checked_add(timelock_seconds)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(Self {
+            recipient,
+            amount,
+            reason,
+            scheduled_timestamp,
+            proposer,
+        })
+    }
+
+    pub fn is_payable(&self, now: i64) -> bool {
+        now >= self.scheduled_timestamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insurance_claim_waits_for_timelock() {
+        let recipient = Pubkey::new_unique();
+        let proposer = Pubkey::new_unique();
+        let timelock = PendingInsuranceClaim::MIN_TIMELOCK_SECONDS;
+
+        assert!(PendingInsuranceClaim::schedule(
+            recipient, 100, AdjustmentReason::Outage, proposer, 1_000, timelock - 1
+        ).is_err());
+        assert!(PendingInsuranceClaim::schedule(
+            recipient, 0, AdjustmentReason::Outage, proposer, 1_000, timelock
+        ).is_err());
+
+        let claim = PendingInsuranceClaim::schedule(
+            recipient, 100, AdjustmentReason::Outage, proposer, 1_000, timelock
+        ).unwrap();
+        assert_eq!(claim.scheduled_timestamp, 1_000 + timelock);
+        assert!(!claim.is_payable(1_000));
+        assert!(!claim.is_payable(1_000 + timelock - 1));
+        assert!(claim.is_payable(1_000 + timelock));
+    }
+
+    #[test]
+    fn action_proposals_expire() {
+        let proposal = ActionProposal {
+            id: 0,
+            proposer: Pubkey::new_unique(),
+            action: GovernanceAction::UnpauseVault,
+            approvals: vec![],
+            created_at: 1_000,
+            executed: false,
+            bump: 0,
+        };
+        assert!(!proposal.is_expired(1_000 + ActionProposal::EXPIRY_SECONDS));
+        assert!(proposal.is_expired(1_000 + ActionProposal::EXPIRY_SECONDS + 1));
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::state::UserStakeAccount;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Mission {
+    pub id: u64,
+    pub requirement: MissionRequirement,
+    pub reward: u64,
+    pub expires_at: Option<i64>,
+    pub active: bool,
+    pub completions: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, InitSpace)]
+pub enum MissionRequirement {
+    /// Keep at least one NFT staked without a break for this long
+    StakedFor { seconds: i64 },
+    /// Have at least this many NFTs staked at once
+    StakedNfts { count: u32 },
+}
+
+impl MissionRequirement {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::StakedFor { seconds } => *seconds > 0,
+            Self::StakedNfts { count } => *count > 0,
+        }
+    }
+
+    pub fn is_met(&self, user_stake: &UserStakeAccount, current_timestamp: i64) -> bool {
+        match self {
+            Self::StakedFor { seconds } => {
+                user_stake.staked_nfts > 0
+                    && user_stake.streak_started_at > 0
+                    && current_timestamp - user_stake.streak_started_at >= *seconds
+            }
+            Self::StakedNfts { count } => user_stake.staked_nfts >= *count,
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MissionCompletion {
+    pub mission_id: u64,
+    pub user: Pubkey,
+    pub completed_at: i64,
+    pub bump: u8,
+}
+
+/// Marks a coupon nonce as spent for a user.
+#[account]
+#[derive(InitSpace)]
+pub struct CouponRedemption {
+    pub user: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub redeemed_at: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,39 @@
+pub mod booster;
+pub mod config;
+pub mod distribution;
+pub mod governance;
+pub mod mission;
+pub mod raffle;
+pub mod roles;
+pub mod season;
+pub mod secondary_rewards;
+pub mod session;
+pub mod shard;
+pub mod snapshot;
+pub mod squad;
+pub mod stats;
+pub mod treasury;
+pub mod user_stake;
+pub mod vault;
+pub mod vesting;
+pub mod voting;
+
+pub use booster::*;
+pub use config::*;
+pub use distribution::*;
+pub use governance::*;
+pub use mission::*;
+pub use raffle::*;
+pub use roles::*;
+pub use season::*;
+pub use secondary_rewards::*;
+pub use session::*;
+pub use shard::*;
+pub use snapshot::*;
+pub use squad::*;
+pub use stats::*;
+pub use treasury::*;
+pub use user_stake::*;
+pub use vault::*;
+pub use vesting::*;
+pub use voting::*;
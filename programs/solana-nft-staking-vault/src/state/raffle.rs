@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Raffle {
+    pub id: u64,
+    pub creator: Pubkey,
+    pub prize_mint: Pubkey,
+    pub prize_amount: u64,
+    pub entries_close_at: i64,
+    pub total_tickets: u64,
+    pub winning_ticket: Option<u64>,
+    pub randomness_requested: bool,
+    /// Prize paid out or returned
+    pub settled: bool,
+    pub bump: u8,
+}
+
+impl Raffle {
+    pub const NFT_SECONDS_PER_TICKET: u64 = 86_400; // 1 ticket per NFT-day
+
+    /// VRF request seed, unique per raffle.
+    pub fn randomness_seed(&self) -> [u8; 32] {
+        keccak::hashv(&[b"raffle", &self.id.to_le_bytes()]).to_bytes()
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RaffleEntry {
+    pub raffle_id: u64,
+    pub user: Pubkey,
+    /// Holds tickets `first_ticket..first_ticket + tickets`
+    pub first_ticket: u64,
+    pub tickets: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,263 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+
+#[account]
+#[derive(InitSpace)]
+pub struct AccountRole {
+    pub user: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+    pub granted_at: i64,
+    /// None = the role never expires
+    pub expires_at: Option<i64>,
+}
+
+impl AccountRole {
+    pub fn is_active(&self, current_timestamp: i64) -> bool {
+        self.expires_at.map_or(true, |expires_at| current_timestamp < expires_at)
+    }
+
+    /// Every role-gated instruction goes through here so expired grants are
+    /// rejected consistently.
+    pub fn require_permission(&self, permission: impl Fn(&Role) -> bool) -> Result<()> {
+        require!(
+            self.is_active(Clock::get()?.unix_timestamp),
+            ErrorCode::RoleExpired
+        );
+        require!(permission(&self.role), ErrorCode::InsufficientPermissions);
+        Ok(())
+    }
+}
+
+/// Every wallet holding a role, so audits and UIs can list them from one
+/// account. `grant_role`, `renew_role` and `revoke_role` keep it in step.
+#[account]
+#[derive(InitSpace)]
+pub struct RoleRegistry {
+    #[max_len(32)]
+    pub entries: Vec<RoleEntry>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, InitSpace)]
+pub struct RoleEntry {
+    pub user: Pubkey,
+    pub role: Role,
+    pub expires_at: Option<i64>,
+}
+
+impl RoleRegistry {
+    pub const MAX_ENTRIES: usize = 32;
+
+    /// Adds `role_account`'s holder, or updates their entry.
+    pub fn upsert(&mut self, role_account: &AccountRole) -> Result<()> {
+        let entry = RoleEntry {
+            user: role_account.user,
+            role: role_account.role.clone(),
+            expires_at: role_account.expires_at,
+        };
+        match self.entries.iter_mut().find(|existing| existing.user == entry.user) {
+            Some(existing) => *existing = entry,
+            None => {
+                require!(
+                    self.entries.len() < Self::MAX_ENTRIES,
+                    ErrorCode::RoleRegistryFull
+                );
+                self.entries.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, user: &Pubkey) {
+        self.entries.retain(|entry| entry.user != *user);
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, InitSpace)]
+pub enum Role {
+    SuperAdmin,
+    Admin, 
+    Moderator,
+    Operator,
+    /// Set by `revoke_role`; every `can_*` check rejects it.
+    Revoked,
+    /// Exactly the given `Role::*` permission bits, for least-privilege keys
+    /// the fixed roles can't express.
+    Custom(u32),
+}
+
+impl Role {
+    pub const PAUSE_VAULT: u32 = 1 << 0;
+    pub const UPDATE_CONFIG: u32 = 1 << 1;
+    pub const MANAGE_ROLES: u32 = 1 << 2;
+    pub const MODERATE_USERS: u32 = 1 << 3;
+    pub const MANAGE_TREASURY: u32 = 1 << 4;
+    pub const MANAGE_UPGRADES: u32 = 1 << 5;
+    pub const ALL_PERMISSIONS: u32 = Self::PAUSE_VAULT
+        | Self::UPDATE_CONFIG
+        | Self::MANAGE_ROLES
+        | Self::MODERATE_USERS
+        | Self::MANAGE_TREASURY
+        | Self::MANAGE_UPGRADES;
+
+    /// Permission bits the role grants. The fixed roles map to presets, so
+    /// accounts granted before `Custom` existed keep exactly what they had.
+    pub fn permissions(&self) -> u32 {
+        match self {
+            Role::SuperAdmin => Self::ALL_PERMISSIONS,
+            Role::Admin => Self::ALL_PERMISSIONS & !Self::MANAGE_ROLES,
+            Role::Moderator => Self::PAUSE_VAULT | Self::MODERATE_USERS,
+            Role::Operator | Role::Revoked => 0,
+            Role::Custom(permissions) => *permissions,
+        }
+    }
+
+    pub fn has_permission(&self, permission: u32) -> bool {
+        self.permissions() & permission == permission
+    }
+
+    /// Custom roles must grant something and only known bits.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Role::Custom(permissions) => {
+                *permissions != 0 && permissions & !Self::ALL_PERMISSIONS == 0
+            }
+            _ => true,
+        }
+    }
+
+    pub fn can_pause_vault(&self) -> bool {
+        self.has_permission(Self::PAUSE_VAULT)
+    }
+
+    pub fn can_update_config(&self) -> bool {
+        self.has_permission(Self::UPDATE_CONFIG)
+    }
+
+    pub fn can_manage_roles(&self) -> bool {
+        self.has_permission(Self::MANAGE_ROLES)
+    }
+
+    pub fn can_moderate_users(&self) -> bool {
+        self.has_permission(Self::MODERATE_USERS)
+    }
+
+    pub fn can_manage_treasury(&self) -> bool {
+        self.has_permission(Self::MANAGE_TREASURY)
+    }
+
+    pub fn can_manage_upgrades(&self) -> bool {
+        self.has_permission(Self::MANAGE_UPGRADES)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserStatus {
+    pub user: Pubkey,
+    pub suspended: bool,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::GovernanceAction;
+
+    fn role_account(role: Role) -> AccountRole {
+        AccountRole {
+            user: Pubkey::new_unique(),
+            role,
+            granted_by: Pubkey::new_unique(),
+            granted_at: 0,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn revoked_admin_cannot_pause_or_update_config() {
+        let mut admin = role_account(Role::Admin);
+        assert!(admin.role.can_pause_vault());
+        assert!(admin.role.can_update_config());
+
+        admin.role = Role::Revoked;
+        assert!(!admin.role.can_pause_vault());
+        assert!(!admin.role.can_update_config());
+    }
+
+    #[test]
+    fn revoked_role_has_no_permissions() {
+        let revoked = Role::Revoked;
+        assert!(!revoked.can_pause_vault());
+        assert!(!revoked.can_update_config());
+        assert!(!revoked.can_manage_roles());
+        assert!(!revoked.can_moderate_users());
+        assert!(!revoked.can_manage_treasury());
+        assert!(!revoked.can_manage_upgrades());
+        assert!(!GovernanceAction::UnpauseVault.is_permitted(&revoked));
+    }
+
+    #[test]
+    fn expired_role_is_inactive() {
+        let mut operator = role_account(Role::Operator);
+        assert!(operator.is_active(1_000));
+
+        operator.expires_at = Some(1_000);
+        assert!(operator.is_active(999));
+        assert!(!operator.is_active(1_000));
+    }
+
+    #[test]
+    fn registry_tracks_grants_and_revocations() {
+        let mut registry = RoleRegistry { entries: Vec::new(), bump: 0 };
+        let mut admin = role_account(Role::Admin);
+        let operator = role_account(Role::Operator);
+
+        registry.upsert(&admin).unwrap();
+        registry.upsert(&operator).unwrap();
+        // Re-granting updates in place rather than listing the wallet twice
+        admin.role = Role::SuperAdmin;
+        registry.upsert(&admin).unwrap();
+        assert_eq!(registry.entries.len(), 2);
+        assert!(registry.entries[0].role == Role::SuperAdmin);
+
+        registry.remove(&admin.user);
+        assert_eq!(registry.entries.len(), 1);
+        assert_eq!(registry.entries[0].user, operator.user);
+    }
+
+    #[test]
+    fn registry_rejects_entries_past_capacity() {
+        let mut registry = RoleRegistry { entries: Vec::new(), bump: 0 };
+        for _ in 0..RoleRegistry::MAX_ENTRIES {
+            registry.upsert(&role_account(Role::Operator)).unwrap();
+        }
+        assert!(registry.upsert(&role_account(Role::Operator)).is_err());
+    }
+
+    #[test]
+    fn custom_role_grants_only_its_bits() {
+        let pauser = Role::Custom(Role::PAUSE_VAULT);
+        assert!(pauser.can_pause_vault());
+        assert!(!pauser.can_update_config());
+        assert!(!pauser.can_manage_roles());
+        assert!(pauser.is_valid());
+
+        assert!(!Role::Custom(0).is_valid());
+        assert!(!Role::Custom(1 << 31).is_valid());
+    }
+
+    #[test]
+    fn fixed_roles_keep_their_permissions() {
+        assert!(Role::SuperAdmin.can_manage_roles());
+        assert!(!Role::Admin.can_manage_roles());
+        assert!(Role::Admin.can_manage_treasury() && Role::Admin.can_manage_upgrades());
+        assert!(Role::Moderator.can_pause_vault() && Role::Moderator.can_moderate_users());
+        assert!(!Role::Moderator.can_update_config());
+        assert_eq!(Role::Operator.permissions(), 0);
+    }
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{VaultAccount, VaultConfig, EmissionSchedule};
+
+/// One staking campaign. Kept after it ends so positions can settle it.
+#[account]
+#[derive(InitSpace)]
+pub struct Season {
+    pub id: u32,
+    pub schedule: EmissionSchedule,
+    pub reward_rate_per_second: u64,
+    pub reward_rate_fraction: u64,
+    pub boost_bps: u16,
+    /// 0 while the season is active
+    pub ended_at: i64,
+    pub bump: u8,
+}
+
+impl Season {
+    /// Makes the vault accrue at this season's terms.
+    pub fn apply_to(&self, vault: &mut VaultAccount, config: &mut VaultConfig) {
+        config.reward_rate_per_second = self.reward_rate_per_second;
+        config.reward_rate_fraction = self.reward_rate_fraction;
+        vault.emission_schedule = self.schedule.clone();
+        vault.season_boost_bps = self.boost_bps;
+    }
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::state::RewardMode;
+
+#[account]
+#[derive(InitSpace)]
+pub struct RewardConfig {
+    pub reward_mint: Pubkey,
+    pub reward_rate_per_second: u64,
+    pub reward_mode: RewardMode,
+    pub start_timestamp: i64,
+    pub active: bool,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserRewardState {
+    pub user: Pubkey,
+    pub reward_mint: Pubkey,
+    pub nft_seconds_checkpoint: u64,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
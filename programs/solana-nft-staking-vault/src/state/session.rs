@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+/// A short-lived key allowed to act for `user` within `scope`.
+#[account]
+#[derive(InitSpace)]
+pub struct SessionAuthority {
+    pub user: Pubkey,
+    pub session_key: Pubkey,
+    /// Bitmask of `SCOPE_*` flags
+    pub scope: u8,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl SessionAuthority {
+    /// May call `claim_rewards`; rewards still go to the user's accounts
+    pub const SCOPE_CLAIM: u8 = 1 << 0;
+    pub const SUPPORTED_SCOPES: u8 = Self::SCOPE_CLAIM;
+    pub const MAX_DURATION_SECONDS: i64 = 30 * 86400; // 30 days
+
+    pub fn allows(&self, scope: u8, current_timestamp: i64) -> bool {
+        self.scope & scope == scope && current_timestamp < self.expires_at
+    }
+}
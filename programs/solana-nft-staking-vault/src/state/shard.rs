@@ -0,0 +1,224 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::{VaultAccount, VaultConfig, CircuitBreakerState, DailyLimits};
+
+/// Counters that `stake_nft`, `unstake_nft` and `claim_rewards` write
+/// instead of the vault, config, stats and metrics accounts, so users in
+/// different shards don't lock the same accounts and their transactions can
+/// run in parallel. `fold_vault_shard` moves them into the global accounts.
+#[account(zero_copy)]
+pub struct VaultShard {
+    /// NFTs staked minus NFTs unstaked since the last fold
+    pub staked_delta: i64,
+    /// Counted against `max_reward_supply` once folded
+    pub rewards_minted: u64,
+    /// Part of claims that did not fit under the supply cap
+    pub rewards_forfeited: u64,
+    /// Paid out by claims, as in `VaultStats::total_rewards_distributed`
+    pub rewards_distributed: u64,
+    /// Early-unstake penalties, burned or redistributed once folded
+    pub pending_penalties: u64,
+    pub stake_events: u64,
+    pub unstake_events: u64,
+    pub last_stake_timestamp: i64,
+    pub last_claim_timestamp: i64,
+    pub folded_at: i64,
+    /// `VaultConfig::daily_limit` split evenly across shards
+    pub daily_limit: DailyLimits,
+    /// Failures seen by this shard; the config's breaker still applies
+    pub circuit_breaker: CircuitBreakerState,
+    pub new_stakers: u32,
+    /// `VaultConfig::circuit_breaker_resets` as of the last sync
+    pub circuit_breaker_resets: u32,
+    pub index: u8,
+    pub bump: u8,
+    /// Set when an anomaly rule tripped this shard; the next fold pauses
+    /// the whole vault
+    pub auto_paused: u8,
+    pub _padding: [u8; 5],
+}
+
+impl VaultShard {
+    pub const COUNT: u8 = 8;
+
+    /// Shard a user's transactions go through. Spread by key, so a user
+    /// always lands in the same shard.
+    pub fn index_for(user: &Pubkey) -> u8 {
+        user.to_bytes()[0] % Self::COUNT
+    }
+
+    /// Picks up limit and breaker changes made on the config since the
+    /// last transaction through this shard.
+    pub fn sync_config(&mut self, config: &VaultConfig) {
+        let count = Self::COUNT as u32;
+        self.daily_limit.max_stakes_per_day = config.daily_limit.max_stakes_per_day.div_ceil(count);
+        self.daily_limit.max_claims_per_day = config.daily_limit.max_claims_per_day.div_ceil(count);
+        self.daily_limit.max_total_rewards_per_day = config.daily_limit
+            .max_total_rewards_per_day
+            .div_ceil(count as u64);
+        self.circuit_breaker.failure_threshold = config.circuit_breaker.failure_threshold;
+        self.circuit_breaker.reset_timeout = config.circuit_breaker.reset_timeout;
+        if self.circuit_breaker_resets != config.circuit_breaker_resets {
+            self.circuit_breaker.reset();
+            self.circuit_breaker_resets = config.circuit_breaker_resets;
+        }
+    }
+
+    pub fn can_execute(&self, config: &VaultConfig, current_timestamp: i64) -> bool {
+        config.circuit_breaker.can_execute(current_timestamp)
+            && self.circuit_breaker.can_execute(current_timestamp)
+    }
+
+    /// This shard's part of `total`. Shard 0 also gets the remainder, so the
+    /// parts add up to exactly `total`.
+    fn share_of(&self, total: u64) -> u64 {
+        let count = Self::COUNT as u64;
+        total / count + if self.index == 0 { total % count } else { 0 }
+    }
+
+    /// `total_staked` as of the last fold plus this shard's changes since.
+    pub fn projected_total_staked(&self, vault: &VaultAccount) -> u32 {
+        (vault.total_staked as i64 + self.staked_delta).clamp(0, u32::MAX as i64) as u32
+    }
+
+    /// Supply left at the last fold, less what this shard minted since.
+    /// Other shards may have minted too; `reward_allowance` is the hard bound.
+    pub fn remaining_reward_supply(&self, vault: &VaultAccount) -> u64 {
+        vault.remaining_reward_supply().saturating_sub(self.rewards_minted)
+    }
+
+    /// What this shard may still mint before the next fold. Each shard only
+    /// gets its share of the remaining supply, so they can't overshoot the
+    /// cap together.
+    pub fn reward_allowance(&self, vault: &VaultAccount) -> u64 {
+        if vault.max_reward_supply == 0 {
+            return u64::MAX;
+        }
+        self.share_of(vault.remaining_reward_supply())
+            .saturating_sub(self.rewards_minted)
+    }
+
+    /// `VaultAccount::check_stake_caps`, with the vault cap's headroom split
+    /// across shards the same way as the reward supply.
+    pub fn check_stake_caps(&self, vault: &VaultAccount, user_staked: u32, amount: u32) -> Result<()> {
+        vault.check_user_stake_cap(user_staked, amount)?;
+        if vault.max_total_staked > 0 {
+            let headroom = vault.max_total_staked.saturating_sub(vault.total_staked) as i64;
+            let shard_total = self.staked_delta
+                .checked_add(amount as i64)
+                .ok_or(ErrorCode::MathOverflow)?;
+            require!(
+                shard_total <= self.share_of(headroom as u64) as i64,
+                ErrorCode::VaultStakeCapReached
+            );
+        }
+        Ok(())
+    }
+
+    pub fn record_stake(&mut self, is_new_staker: bool, amount: u32, current_timestamp: i64) -> Result<()> {
+        if is_new_staker {
+            self.new_stakers = self.new_stakers
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        self.staked_delta = self.staked_delta
+            .checked_add(amount as i64)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.stake_events = self.stake_events
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.last_stake_timestamp = current_timestamp;
+        Ok(())
+    }
+
+    pub fn record_unstake(&mut self, amount: u32, penalty: u64) -> Result<()> {
+        self.staked_delta = self.staked_delta
+            .checked_sub(amount as i64)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        self.unstake_events = self.unstake_events
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.pending_penalties = self.pending_penalties
+            .checked_add(penalty)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_rewards_minted(&mut self, amount: u64, forfeited: u64) -> Result<()> {
+        self.rewards_minted = self.rewards_minted
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.rewards_forfeited = self.rewards_forfeited
+            .checked_add(forfeited)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_claim(&mut self, amount: u64, current_timestamp: i64) -> Result<()> {
+        self.rewards_distributed = self.rewards_distributed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.last_claim_timestamp = current_timestamp;
+        Ok(())
+    }
+
+    /// Zeroes the folded counters. Daily limits and the breaker carry over.
+    pub fn clear(&mut self, current_timestamp: i64) {
+        self.staked_delta = 0;
+        self.rewards_minted = 0;
+        self.rewards_forfeited = 0;
+        self.rewards_distributed = 0;
+        self.pending_penalties = 0;
+        self.new_stakers = 0;
+        self.stake_events = 0;
+        self.unstake_events = 0;
+        self.folded_at = current_timestamp;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shard_shares_add_up_to_total() {
+        let shard = |index| VaultShard {
+            index,
+            staked_delta: 0,
+            rewards_minted: 0,
+            rewards_forfeited: 0,
+            rewards_distributed: 0,
+            pending_penalties: 0,
+            new_stakers: 0,
+            stake_events: 0,
+            unstake_events: 0,
+            last_stake_timestamp: 0,
+            last_claim_timestamp: 0,
+            daily_limit: DailyLimits::new(0, 0, 0),
+            circuit_breaker: CircuitBreakerState::new(0, 0),
+            circuit_breaker_resets: 0,
+            folded_at: 0,
+            bump: 0,
+            auto_paused: 0,
+            _padding: [0; 5],
+        };
+
+        for total in [0, 7, 1_003, u64::MAX] {
+            let shares = (0..VaultShard::COUNT).map(|index| shard(index).share_of(total) as u128);
+            assert_eq!(shares.sum::<u128>(), total as u128);
+        }
+        // Dust below the shard count all goes to shard 0
+        assert_eq!(shard(0).share_of(7), 7);
+        assert_eq!(shard(1).share_of(7), 0);
+    }
+
+    #[test]
+    fn zero_copy_layouts_are_stable() {
+        // Changing these moves fields under existing accounts
+        assert_eq!(std::mem::size_of::<CircuitBreakerState>(), 48);
+        assert_eq!(std::mem::size_of::<DailyLimits>(), 40);
+        assert_eq!(std::mem::size_of::<VaultConfig>(), 112);
+        assert_eq!(std::mem::size_of::<VaultShard>(), 184);
+    }
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+/// Shapes the primary reward rate over time. Secondary rewards configured
+/// through `RewardConfig` emit at their own constant rate.
+/// The vault as of one UTC day, for airdrops and trend data. Positions are
+/// recorded as `SnapshotEntry` accounts within `RECORD_WINDOW_SECONDS` of
+/// the snapshot being taken.
+#[account]
+#[derive(InitSpace)]
+pub struct Snapshot {
+    /// Days since the Unix epoch
+    pub day: u32,
+    pub taken_at: i64,
+    pub total_staked: u32,
+    pub positions_recorded: u32,
+    /// Sum of `staked_nfts` over the recorded entries
+    pub nfts_recorded: u64,
+    pub bump: u8,
+}
+
+impl Snapshot {
+    pub const SECONDS_PER_DAY: i64 = 86400;
+    pub const RECORD_WINDOW_SECONDS: i64 = 3600; // 1 hour
+}
+
+/// Lets `user` stake during the whitelist phase.
+#[account]
+#[derive(InitSpace)]
+pub struct WhitelistEntry {
+    pub user: Pubkey,
+    pub bump: u8,
+}
+
+/// Lets `nft_mint` be staked while the vault's trait gate is at
+/// `gate_version`.
+#[account]
+#[derive(InitSpace)]
+pub struct TraitPass {
+    pub nft_mint: Pubkey,
+    pub gate_version: u32,
+    pub bump: u8,
+}
+
+/// One position in a `Snapshot`, at the time it was recorded.
+#[account]
+#[derive(InitSpace)]
+pub struct SnapshotEntry {
+    pub snapshot: Pubkey,
+    pub user: Pubkey,
+    pub staked_nfts: u32,
+    pub accrued_nft_seconds: u64,
+    pub recorded_at: i64,
+    pub bump: u8,
+}
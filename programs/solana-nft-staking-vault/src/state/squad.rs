@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::VaultAccount;
+
+/// A group of stakers pooling their rewards. Members' rewards go to the
+/// pot and are shared pro-rata by staked NFTs (their contribution).
+#[account]
+#[derive(InitSpace)]
+pub struct Squad {
+    pub creator: Pubkey,
+    #[max_len(32)]
+    pub name: String,
+    pub member_count: u32,
+    /// Sum of members' contributions
+    pub total_contribution: u64,
+    /// Pot rewards per unit of contribution, scaled by `REWARD_INDEX_SCALE`
+    pub reward_index: u128,
+    /// Contributed rewards not yet claimed by members
+    pub pot: u64,
+    pub bump: u8,
+}
+
+impl Squad {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_MEMBERS: u32 = 50;
+
+    /// Adds `amount` to the pot, shared by the current contributions.
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        let per_unit = (amount as u128)
+            .checked_mul(VaultAccount::REWARD_INDEX_SCALE)
+            .ok_or(ErrorCode::MathOverflow)?
+            / self.total_contribution as u128;
+        self.reward_index = self.reward_index
+            .checked_add(per_unit)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.pot = self.pot.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SquadMember {
+    pub squad: Pubkey,
+    pub user: Pubkey,
+    /// Staked NFTs as of the member's last contribution; 0 after leaving
+    pub contribution: u64,
+    pub reward_index_checkpoint: u128,
+    /// Settled pot share, paid by `claim_squad_rewards`
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+impl SquadMember {
+    /// Credits the pot share earned since the last checkpoint.
+    pub fn settle(&mut self, squad: &Squad) -> Result<()> {
+        let share = (self.contribution as u128)
+            .checked_mul(squad.reward_index - self.reward_index_checkpoint)
+            .ok_or(ErrorCode::MathOverflow)?
+            / VaultAccount::REWARD_INDEX_SCALE;
+        self.pending_rewards = self.pending_rewards
+            .checked_add(u64::try_from(share).map_err(|_| ErrorCode::MathOverflow)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.reward_index_checkpoint = squad.reward_index;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squad_pot_splits_by_contribution() {
+        let mut squad = Squad {
+            creator: Pubkey::new_unique(),
+            name: "guild".to_string(),
+            member_count: 2,
+            total_contribution: 4,
+            reward_index: 0,
+            pot: 0,
+            bump: 0,
+        };
+        let member = |contribution| SquadMember {
+            squad: Pubkey::default(),
+            user: Pubkey::new_unique(),
+            contribution,
+            reward_index_checkpoint: 0,
+            pending_rewards: 0,
+            bump: 0,
+        };
+        let (mut small, mut large) = (member(1), member(3));
+
+        squad.deposit(1_000).unwrap();
+        small.settle(&squad).unwrap();
+        large.settle(&squad).unwrap();
+        assert_eq!(small.pending_rewards, 250);
+        assert_eq!(large.pending_rewards, 750);
+        assert_eq!(squad.pot, 1_000);
+    }
+}
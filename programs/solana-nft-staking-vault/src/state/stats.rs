@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::VaultShard;
+
+/// Running totals for analytics, so dashboards don't need to replay events.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultStats {
+    /// Primary rewards minted or paid out of the pool
+    pub total_rewards_distributed: u64,
+    /// Stake accounts created; a user who closes and recreates theirs is
+    /// counted again.
+    pub unique_stakers: u32,
+    pub total_stake_events: u64,
+    pub total_unstake_events: u64,
+    pub peak_total_staked: u32,
+    pub last_stake_timestamp: i64,
+    pub last_claim_timestamp: i64,
+    pub bump: u8,
+}
+
+impl VaultStats {
+    pub fn record_stake(
+        &mut self,
+        is_new_staker: bool,
+        total_staked: u32,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        if is_new_staker {
+            self.unique_stakers = self.unique_stakers
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        self.total_stake_events = self.total_stake_events
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.peak_total_staked = self.peak_total_staked.max(total_staked);
+        self.last_stake_timestamp = current_timestamp;
+        Ok(())
+    }
+
+    pub fn record_unstake(&mut self) -> Result<()> {
+        self.total_unstake_events = self.total_unstake_events
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_claim(&mut self, amount: u64, current_timestamp: i64) -> Result<()> {
+        self.total_rewards_distributed = self.total_rewards_distributed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.last_claim_timestamp = current_timestamp;
+        Ok(())
+    }
+
+    /// Adds the activity a shard collected since its last fold.
+    pub fn record_shard(&mut self, shard: &VaultShard, total_staked: u32) -> Result<()> {
+        self.total_rewards_distributed = self.total_rewards_distributed
+            .checked_add(shard.rewards_distributed)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.unique_stakers = self.unique_stakers
+            .checked_add(shard.new_stakers)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_stake_events = self.total_stake_events
+            .checked_add(shard.stake_events)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_unstake_events = self.total_unstake_events
+            .checked_add(shard.unstake_events)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.peak_total_staked = self.peak_total_staked.max(total_staked);
+        self.last_stake_timestamp = self.last_stake_timestamp.max(shard.last_stake_timestamp);
+        self.last_claim_timestamp = self.last_claim_timestamp.max(shard.last_claim_timestamp);
+        Ok(())
+    }
+}
+
+/// Averages for APR displays, updated wherever `VaultStats` is so they can
+/// be trusted without an indexer.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultMetrics {
+    /// Time-weighted moving average of `total_staked` over roughly
+    /// `EMA_WINDOW_SECONDS`, scaled by `EMA_SCALE`
+    pub staked_ema: u128,
+    /// `total_staked` as of the last update, held since `updated_at`
+    pub last_total_staked: u32,
+    pub updated_at: i64,
+    /// Index of the current `EPOCH_SECONDS`-long epoch since the Unix epoch
+    pub current_epoch: u64,
+    /// Rewards paid out by claims so far this epoch
+    pub epoch_rewards: u64,
+    /// Rewards paid out in the epoch before; 0 if it saw no updates
+    pub previous_epoch_rewards: u64,
+    pub bump: u8,
+}
+
+impl VaultMetrics {
+    pub const EMA_WINDOW_SECONDS: i64 = 7 * 86400; // 7 days
+    pub const EMA_SCALE: u128 = 1_000_000;
+    pub const EPOCH_SECONDS: i64 = 86400; // 1 day
+
+    /// Folds the time since the last update into the average, then records
+    /// the new `total_staked` and any rewards paid out.
+    pub fn update(&mut self, total_staked: u32, rewards_paid: u64, current_timestamp: i64) -> Result<()> {
+        // Move toward the value held since the last update, in proportion
+        // to how long it was held
+        let elapsed = (current_timestamp - self.updated_at).clamp(0, Self::EMA_WINDOW_SECONDS) as u128;
+        let window = Self::EMA_WINDOW_SECONDS as u128;
+        let held = self.last_total_staked as u128 * Self::EMA_SCALE;
+        self.staked_ema = if held >= self.staked_ema {
+            self.staked_ema + (held - self.staked_ema) * elapsed / window
+        } else {
+            self.staked_ema - (self.staked_ema - held) * elapsed / window
+        };
+
+        let epoch = (current_timestamp / Self::EPOCH_SECONDS) as u64;
+        if epoch != self.current_epoch {
+            self.previous_epoch_rewards = if epoch == self.current_epoch + 1 {
+                self.epoch_rewards
+            } else {
+                0
+            };
+            self.current_epoch = epoch;
+            self.epoch_rewards = 0;
+        }
+        self.epoch_rewards = self.epoch_rewards
+            .checked_add(rewards_paid)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.last_total_staked = total_staked;
+        self.updated_at = current_timestamp;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staked_ema_tracks_held_value() {
+        let mut metrics = VaultMetrics {
+            staked_ema: 0,
+            last_total_staked: 10,
+            updated_at: 0,
+            current_epoch: 0,
+            epoch_rewards: 0,
+            previous_epoch_rewards: 0,
+            bump: 0,
+        };
+        let half_window = VaultMetrics::EMA_WINDOW_SECONDS / 2;
+
+        metrics.update(0, 500, half_window).unwrap();
+        assert_eq!(metrics.staked_ema, 5 * VaultMetrics::EMA_SCALE);
+        metrics.update(0, 0, half_window + VaultMetrics::EMA_WINDOW_SECONDS).unwrap();
+        assert_eq!(metrics.staked_ema, 0);
+        // The last epoch saw no updates, so it reports nothing
+        assert_eq!(metrics.epoch_rewards, 0);
+        assert_eq!(metrics.previous_epoch_rewards, 0);
+    }
+}
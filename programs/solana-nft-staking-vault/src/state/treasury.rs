@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+/// Holds SOL directly and owns the treasury's SPL token accounts.
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub pending_withdrawal: Option<PendingTreasuryWithdrawal>,
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub const MIN_TIMELOCK_SECONDS: i64 = 86400; // 1 day
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingTreasuryWithdrawal {
+    /// `None` for SOL
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub scheduled_timestamp: i64,
+    pub proposer: Pubkey,
+}
@@ -0,0 +1,708 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::{VaultAccount, Raffle, LevelConfig, RateLimitConfig};
+use crate::utils::BPS_DENOMINATOR;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralAccount {
+    pub referrer: Pubkey,
+    pub referred_users: u32,
+    /// Lifetime bonus paid, bounded by `vault.referral_bonus_cap`
+    pub total_bonus_paid: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+/// A user's staked copies of one semi-fungible edition mint.
+#[account]
+#[derive(InitSpace)]
+pub struct EditionStake {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    /// Amount-weighted average stake time across top-ups
+    pub staked_at: i64,
+    /// Latest top-up; the unstake cooldown runs from here
+    pub last_staked_at: i64,
+    pub last_staked_slot: u64,
+    pub bump: u8,
+}
+
+impl EditionStake {
+    pub fn average_staked_at(&self, added: u64, current_timestamp: i64) -> Result<i64> {
+        let total = (self.amount as i128)
+            .checked_mul(self.staked_at as i128)
+            .and_then(|sum| sum.checked_add((added as i128).checked_mul(current_timestamp as i128)?))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let count = self.amount
+            .checked_add(added)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok((total / count as i128) as i64)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakeRecord {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub staked_at: i64,
+    /// Set by `request_unstake`; 0 while the NFT is still staked
+    pub unlocks_at: i64,
+    pub bump: u8,
+    pub staked_slot: u64,
+    /// Funded the NFT's accounts at stake time and gets their rent back
+    pub rent_payer: Pubkey,
+}
+
+impl StakeRecord {
+    /// Records from before sponsored staking refund the staker.
+    pub fn rent_receiver(&self) -> Pubkey {
+        if self.rent_payer == Pubkey::default() {
+            self.user
+        } else {
+            self.rent_payer
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserStakeAccount {
+    pub user: Pubkey,
+    pub staked_nfts: u32,
+    pub pending_rewards: u64,
+    pub last_update_timestamp: i64,
+    pub compound_position: CompoundPosition,
+    /// Running sum of `staked_nfts * seconds`, used by secondary rewards.
+    pub accrued_nft_seconds: u64,
+    /// Layout version. Fields added later go below it so it never moves
+    /// and `migrate_account` can grow older accounts in place.
+    pub version: u8,
+    // v2
+    /// Sum of `staked_at` over all staked NFTs, for the average stake age
+    pub staked_at_sum: i64,
+    /// NFTs staked before per-NFT records existed
+    pub unrecorded_nfts: u32,
+    pub unrecorded_staked_at: i64,
+    // v3
+    /// NFTs that earn nothing until `warmup_ends_at`
+    pub warming_nfts: u32,
+    pub warmup_ends_at: i64,
+    // v4
+    /// Whole-position lock set by `lock_stake`
+    pub locked_until: i64,
+    pub lock_boost_bps: u64,
+    pub penalty_index_checkpoint: u128,
+    /// Settled share of early-unstake penalties, paid with the next claim
+    pub redistributed_rewards: u64,
+    // v5
+    /// May call `claim_rewards` on the user's behalf
+    pub claim_delegate: Option<Pubkey>,
+    // v6
+    /// Set on the first stake; earns a bonus on this user's claims
+    pub referrer: Option<Pubkey>,
+    // v7
+    /// Start of the current unbroken run with at least one NFT staked
+    pub streak_started_at: i64,
+    pub current_streak_days: u32,
+    // v8
+    pub xp: u64,
+    pub level: u8,
+    /// `nft_seconds_at` value up to which staking XP has been credited
+    pub xp_nft_seconds_checkpoint: u64,
+    // v9
+    /// Staked NFTs per set-bonus group, from their `NftGroupTag`
+    pub group_counts: [u16; 8],
+    // v10
+    /// `nft_seconds_at` value up to which raffle tickets have been spent
+    pub raffle_nft_seconds_checkpoint: u64,
+    // v11
+    /// `vault.reward_index` at the last update, for curve emissions
+    pub reward_index_checkpoint: u128,
+    // v12
+    /// Seeds the next `VestingPosition`
+    pub vesting_position_count: u64,
+    // v13
+    /// Boost tokens deposited via `stake_tokens`
+    pub staked_tokens: u64,
+    // v14
+    /// Boost from the attached `BoosterRecord`, 0 without one
+    pub booster_boost_bps: u64,
+    // v15
+    /// Slot of the latest stake into the position, for the claim slot guard
+    pub last_stake_slot: u64,
+    // v16
+    /// Sponsor that paid this account's rent; `None` means the user did
+    pub rent_payer: Option<Pubkey>,
+    // v17
+    /// Token account every claim must pay, set by `set_payout_address`
+    pub payout_address: Option<Pubkey>,
+    // v18
+    /// Shares of each claim paid to other token accounts
+    #[max_len(4)]
+    pub reward_split: Vec<RewardShare>,
+    // v19
+    /// Squad whose pot receives this position's rewards
+    pub squad: Option<Pubkey>,
+    // v20
+    /// May move the position to a new wallet via `transfer_stake_ownership`
+    pub recovery_delegate: Option<Pubkey>,
+    /// `accrued_nft_seconds` at the last ownership transfer; secondary
+    /// rewards before it belong to the previous wallet
+    pub secondary_nft_seconds_floor: u64,
+    // v21
+    /// Lifetime rewards paid out (or vested) by claims, net of fees
+    pub total_rewards_claimed: u64,
+    /// 0 for positions first staked before v21
+    pub first_stake_timestamp: i64,
+    /// Wall-clock seconds with at least one NFT staked
+    pub total_staking_seconds: u64,
+    // v22
+    /// Season the position last accrued in
+    pub season_id: u32,
+    // v23
+    /// Stakes and claims left in the wallet's `user_rate_limit` bucket
+    pub rate_tokens: u32,
+    pub rate_refilled_at: i64,
+    // v24
+    /// Sum of the `NftMultiplier` bonuses of the staked NFTs
+    pub nft_bonus_bps: u64,
+    // v25
+    /// Permanent boost from NFTs burned via `burn_for_boost`
+    pub burn_boost_bps: u64,
+    pub burned_nfts: u32,
+    // v26
+    /// Reward adjustments and rewards carried over by a migration, paid
+    /// with the next claim outside the emission bound
+    pub credited_rewards: u64,
+    // v27
+    /// Last rejected claim counted toward the shard's circuit breaker
+    pub last_breaker_failure_at: i64,
+    // v28
+    /// NFT-seconds at each recent secondary reward launch this position was
+    /// first touched after
+    #[max_len(4)]
+    pub launch_checkpoints: Vec<LaunchCheckpoint>,
+    // v29
+    /// `proposed_at` of the pending upgrade when the position was first
+    /// touched after it, and the NFTs staked at the time
+    pub upgrade_snapshot_at: i64,
+    pub upgrade_snapshot_nfts: u32,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct RewardShare {
+    /// Reward-mint token account
+    pub destination: Pubkey,
+    pub bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct LaunchCheckpoint {
+    pub launched_at: i64,
+    pub nft_seconds: u64,
+}
+
+impl RewardShare {
+    pub const MAX_SHARES: usize = 4;
+
+    pub fn amount_of(&self, payout: u64) -> Result<u64> {
+        let amount = (payout as u128)
+            .checked_mul(self.bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        u64::try_from(amount).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+}
+
+impl UserStakeAccount {
+    pub const CURRENT_VERSION: u8 = 29;
+
+    /// Fills in fields introduced since `self.version`.
+    pub fn upgrade(&mut self, vault: &VaultAccount) {
+        if self.version < 2 {
+            // Treat NFTs staked before records existed as staked at the last update
+            self.unrecorded_nfts = self.staked_nfts;
+            self.unrecorded_staked_at = self.last_update_timestamp;
+            self.staked_at_sum = (self.staked_nfts as i64)
+                .saturating_mul(self.last_update_timestamp);
+        }
+        // v3 warm-up fields start zeroed, i.e. nothing warming
+        if self.version < 4 {
+            // No lock, and no share of penalties from before the upgrade
+            self.penalty_index_checkpoint = vault.penalty_index;
+        }
+        // v5 claim delegate and v6 referrer start unset
+        if self.version < 7 && self.staked_nfts > 0 {
+            self.streak_started_at = self.last_update_timestamp;
+        }
+        if self.version < 8 {
+            // No retroactive XP
+            self.xp_nft_seconds_checkpoint = self.accrued_nft_seconds;
+        }
+        // v9 group counts start empty; NFTs already staked count toward no group
+        if self.version < 10 {
+            self.raffle_nft_seconds_checkpoint = self.accrued_nft_seconds;
+        }
+        if self.version < 11 {
+            self.reward_index_checkpoint = vault.reward_index;
+        }
+        // v12 vesting positions, v13 staked tokens, v14 booster and v15 stake
+        // slot start at zero; v16 rent payer and v17 payout address start
+        // unset; v18 reward split starts empty; v19 squad and v20 recovery
+        // delegate start unset, with no secondary-rewards floor; v21 lifetime
+        // stats only count from the upgrade on; v22 positions start in
+        // season 0, i.e. before any season; v23 rate-limit buckets start
+        // empty with no refill time, so they read as full; v24 NFTs already
+        // staked carry no multiplier until restaked; v25 positions have
+        // burned nothing yet; v26 positions have nothing credited; v27
+        // positions have no breaker failure on record; v28 positions have no
+        // launch checkpoints, so rewards launched before the upgrade count
+        // from the position's last update; v29 positions have no upgrade
+        // snapshot
+        self.version = Self::CURRENT_VERSION;
+    }
+
+    pub fn add_staked_at(&mut self, staked_at: i64) -> Result<()> {
+        self.staked_at_sum = self.staked_at_sum
+            .checked_add(staked_at)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn remove_staked_at(&mut self, staked_at: i64) -> Result<()> {
+        self.staked_at_sum = self.staked_at_sum
+            .checked_sub(staked_at)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        Ok(())
+    }
+
+    pub fn average_staked_at(&self) -> Option<i64> {
+        if self.staked_nfts == 0 {
+            return None;
+        }
+        Some(self.staked_at_sum / self.staked_nfts as i64)
+    }
+
+    pub fn require_current_version(&self) -> Result<()> {
+        require!(self.version == Self::CURRENT_VERSION, ErrorCode::AccountNotMigrated);
+        Ok(())
+    }
+
+    /// Spends one action from the wallet's rate-limit bucket, if the vault
+    /// has one.
+    pub fn take_rate_token(&mut self, limit: &RateLimitConfig, current_timestamp: i64) -> Result<()> {
+        if !limit.is_enabled() {
+            return Ok(());
+        }
+
+        let (tokens, refilled_at) = limit.refill(self.rate_tokens, self.rate_refilled_at, current_timestamp);
+        if tokens == 0 {
+            msg!(
+                "Wallet rate limit: next action in {} seconds",
+                refilled_at + limit.refill_interval_seconds - current_timestamp
+            );
+            return err!(ErrorCode::RateLimited);
+        }
+        self.rate_tokens = tokens - 1;
+        self.rate_refilled_at = refilled_at;
+        Ok(())
+    }
+
+    /// Sets `first_stake_timestamp` on the position's first stake ever.
+    pub fn record_first_stake(&mut self, current_timestamp: i64) {
+        if self.first_stake_timestamp == 0 {
+            self.first_stake_timestamp = current_timestamp;
+        }
+    }
+
+    pub fn record_claimed(&mut self, amount: u64) -> Result<()> {
+        self.total_rewards_claimed = self.total_rewards_claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Rolls the elapsed NFT-seconds into the running total and moves the
+    /// update timestamp forward. Call before `staked_nfts` changes.
+    pub fn touch(&mut self, vault: &VaultAccount, current_timestamp: i64) -> Result<()> {
+        // Nothing is owed from earlier seasons while nothing is staked
+        if self.staked_nfts == 0 {
+            self.season_id = vault.current_season_id;
+        }
+        if self.staked_nfts > 0 {
+            self.total_staking_seconds = self.total_staking_seconds
+                .checked_add(current_timestamp.saturating_sub(self.last_update_timestamp).max(0) as u64)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        self.checkpoint_launches(vault, current_timestamp)?;
+        if let Some(pending) = vault.pending_upgrade.as_ref() {
+            if self.last_update_timestamp < pending.proposed_at
+                && pending.proposed_at <= current_timestamp
+            {
+                self.upgrade_snapshot_at = pending.proposed_at;
+                self.upgrade_snapshot_nfts = self.staked_nfts;
+            }
+        }
+        self.accrued_nft_seconds = self.nft_seconds_at(current_timestamp)?;
+        self.redistributed_rewards = self.redistributed_rewards
+            .checked_add(self.penalty_share(vault.penalty_index)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.penalty_index_checkpoint = vault.penalty_index;
+        self.reward_index_checkpoint = vault.reward_index_at(current_timestamp)?;
+        self.last_update_timestamp = current_timestamp;
+        if current_timestamp >= self.warmup_ends_at {
+            self.warming_nfts = 0;
+        }
+        if current_timestamp >= self.locked_until {
+            self.clear_lock();
+        }
+        Ok(())
+    }
+
+    /// Records the NFT-seconds at secondary reward launches since the last
+    /// update, while they can still be worked out exactly. Call before the
+    /// update is rolled in.
+    fn checkpoint_launches(&mut self, vault: &VaultAccount, current_timestamp: i64) -> Result<()> {
+        for &launched_at in &vault.secondary_launches {
+            if launched_at <= self.last_update_timestamp || launched_at > current_timestamp {
+                continue;
+            }
+            if self.launch_checkpoints.len() == VaultAccount::MAX_SECONDARY_LAUNCHES {
+                self.launch_checkpoints.remove(0);
+            }
+            let nft_seconds = self.nft_seconds_at(launched_at)?;
+            self.launch_checkpoints.push(LaunchCheckpoint { launched_at, nft_seconds });
+        }
+        Ok(())
+    }
+
+    /// Where a new secondary reward position starts counting from. Exact
+    /// unless the position was last touched before the upgrade to v28, or
+    /// more than `MAX_SECONDARY_LAUNCHES` launches ago.
+    pub fn nft_seconds_at_launch(&self, launched_at: i64) -> Result<u64> {
+        if launched_at > self.last_update_timestamp {
+            return self.nft_seconds_at(launched_at);
+        }
+        Ok(self.launch_checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.launched_at == launched_at)
+            .map_or(self.accrued_nft_seconds, |checkpoint| checkpoint.nft_seconds))
+    }
+
+    /// NFTs staked when an upgrade was proposed, so objections can't count
+    /// NFTs moved between wallets after it.
+    pub fn nfts_staked_at_proposal(&self, proposed_at: i64) -> u32 {
+        if self.upgrade_snapshot_at == proposed_at {
+            self.upgrade_snapshot_nfts
+        } else if self.last_update_timestamp <= proposed_at {
+            self.staked_nfts
+        } else {
+            0
+        }
+    }
+
+    /// Redistributed penalties owed since the last checkpoint.
+    pub fn penalty_share(&self, penalty_index: u128) -> Result<u64> {
+        let share = penalty_index
+            .checked_sub(self.penalty_index_checkpoint)
+            .and_then(|delta| delta.checked_mul(self.staked_nfts as u128))
+            .ok_or(ErrorCode::MathOverflow)?
+            / VaultAccount::PENALTY_INDEX_SCALE;
+        u64::try_from(share).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+
+    /// Starts a streak when the first NFT goes in. Call before `staked_nfts`
+    /// is incremented.
+    pub fn start_streak(&mut self, current_timestamp: i64) {
+        if self.staked_nfts == 0 {
+            self.streak_started_at = current_timestamp;
+            self.current_streak_days = 0;
+        }
+    }
+
+    pub fn end_streak(&mut self) {
+        self.streak_started_at = 0;
+        self.current_streak_days = 0;
+    }
+
+    /// Recomputes `current_streak_days`, returning whether it changed.
+    pub fn update_streak(&mut self, current_timestamp: i64) -> bool {
+        let days = self.streak_days_at(current_timestamp);
+        let changed = days != self.current_streak_days;
+        self.current_streak_days = days;
+        changed
+    }
+
+    /// Streak `update_streak` would record at `current_timestamp`.
+    pub fn streak_days_at(&self, current_timestamp: i64) -> u32 {
+        if self.staked_nfts == 0 || self.streak_started_at == 0 {
+            0
+        } else {
+            ((current_timestamp - self.streak_started_at).max(0) / 86400) as u32
+        }
+    }
+
+    /// Converts NFT-seconds staked since the last raffle entry into tickets
+    /// and spends them.
+    pub fn take_raffle_tickets(&mut self, current_timestamp: i64) -> Result<u64> {
+        let nft_seconds_now = self.nft_seconds_at(current_timestamp)?;
+        if nft_seconds_now < self.raffle_nft_seconds_checkpoint {
+            self.raffle_nft_seconds_checkpoint = 0;
+        }
+
+        let tickets = (nft_seconds_now - self.raffle_nft_seconds_checkpoint)
+            / Raffle::NFT_SECONDS_PER_TICKET;
+        self.raffle_nft_seconds_checkpoint += tickets * Raffle::NFT_SECONDS_PER_TICKET;
+        Ok(tickets)
+    }
+
+    /// Credits XP for every whole NFT-day staked since the checkpoint.
+    pub fn credit_staking_xp(&mut self, config: &LevelConfig, current_timestamp: i64) -> Result<()> {
+        let nft_seconds_now = self.nft_seconds_at(current_timestamp)?;
+        if nft_seconds_now < self.xp_nft_seconds_checkpoint {
+            self.xp_nft_seconds_checkpoint = 0;
+        }
+
+        let nft_days = (nft_seconds_now - self.xp_nft_seconds_checkpoint) / 86400;
+        self.xp_nft_seconds_checkpoint += nft_days * 86400;
+        self.add_xp(nft_days.saturating_mul(config.xp_per_nft_day), config);
+        Ok(())
+    }
+
+    /// Level `credit_staking_xp` would reach at `current_timestamp`.
+    pub fn level_with_staking_xp(&self, config: &LevelConfig, current_timestamp: i64) -> Result<u8> {
+        let nft_seconds_now = self.nft_seconds_at(current_timestamp)?;
+        let checkpoint = if nft_seconds_now < self.xp_nft_seconds_checkpoint {
+            0
+        } else {
+            self.xp_nft_seconds_checkpoint
+        };
+
+        let nft_days = (nft_seconds_now - checkpoint) / 86400;
+        Ok(config.level_for(self.xp.saturating_add(nft_days.saturating_mul(config.xp_per_nft_day))))
+    }
+
+    /// Whether a rejected claim may count toward the shard's circuit
+    /// breaker. Each position counts at most once per `reset_timeout`, so
+    /// one wallet can't trip the breaker for everyone.
+    pub fn take_breaker_failure(&mut self, reset_timeout: i64, current_timestamp: i64) -> bool {
+        if self.last_breaker_failure_at != 0
+            && current_timestamp - self.last_breaker_failure_at < reset_timeout
+        {
+            return false;
+        }
+        self.last_breaker_failure_at = current_timestamp;
+        true
+    }
+
+    pub fn add_xp(&mut self, amount: u64, config: &LevelConfig) {
+        self.xp = self.xp.saturating_add(amount);
+        self.level = config.level_for(self.xp);
+    }
+
+    pub fn add_nft_bonus(&mut self, bonus_bps: u64) -> Result<()> {
+        self.nft_bonus_bps = self.nft_bonus_bps
+            .checked_add(bonus_bps)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Saturates so NFTs staked before v24 can leave.
+    pub fn remove_nft_bonus(&mut self, bonus_bps: u64) {
+        self.nft_bonus_bps = self.nft_bonus_bps.saturating_sub(bonus_bps);
+    }
+
+    /// Per-NFT multipliers averaged into a boost on the whole position,
+    /// since rewards accrue per position rather than per NFT.
+    pub fn nft_multiplier_boost_bps(&self) -> u64 {
+        if self.staked_nfts == 0 {
+            return 0;
+        }
+        self.nft_bonus_bps / self.staked_nfts as u64
+    }
+
+    pub fn join_group(&mut self, group: u8) -> Result<()> {
+        let count = self.group_counts
+            .get_mut(group as usize)
+            .ok_or(ErrorCode::InvalidNftGroup)?;
+        *count = count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Saturates so NFTs that were never counted (e.g. migrated in) can leave.
+    pub fn leave_group(&mut self, group: u8) {
+        if let Some(count) = self.group_counts.get_mut(group as usize) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    pub fn has_complete_set(&self, group_count: u8) -> bool {
+        group_count > 0
+            && self.group_counts
+                .iter()
+                .take(group_count as usize)
+                .all(|&count| count > 0)
+    }
+
+    pub fn clear_lock(&mut self) {
+        self.locked_until = 0;
+        self.lock_boost_bps = 0;
+    }
+
+    /// NFT-seconds earned up to `timestamp`, excluding NFTs still warming up.
+    pub fn nft_seconds_at(&self, timestamp: i64) -> Result<u64> {
+        if self.staked_nfts == 0 || timestamp <= self.last_update_timestamp {
+            return Ok(self.accrued_nft_seconds);
+        }
+
+        let elapsed = (timestamp - self.last_update_timestamp) as u64;
+        let warming_elapsed =
+            (timestamp.min(self.warmup_ends_at) - self.last_update_timestamp).max(0) as u64;
+        elapsed
+            .checked_mul(self.staked_nfts as u64)
+            .and_then(|nft_seconds| {
+                nft_seconds.checked_sub(warming_elapsed.checked_mul(self.warming_nfts as u64)?)
+            })
+            .and_then(|nft_seconds| nft_seconds.checked_add(self.accrued_nft_seconds))
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    /// Puts a newly staked NFT into warm-up. All warming NFTs share one end
+    /// time, so staking again restarts the window for those still warming.
+    /// Call after `touch`.
+    pub fn start_warmup(
+        &mut self,
+        warmup_seconds: i64,
+        nfts: u32,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        if warmup_seconds <= 0 {
+            return Ok(());
+        }
+
+        self.warming_nfts = self.warming_nfts
+            .checked_add(nfts)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.warmup_ends_at = current_timestamp
+            .checked_add(warmup_seconds)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Drops a removed NFT from the warming count if it was still warming up.
+    /// Call after `staked_nfts` has been decremented.
+    pub fn end_warmup_for(&mut self, staked_at: i64, warmup_seconds: i64, current_timestamp: i64) {
+        if self.warming_nfts > 0
+            && current_timestamp < self.warmup_ends_at
+            && staked_at.saturating_add(warmup_seconds) > current_timestamp
+        {
+            self.warming_nfts -= 1;
+        }
+        self.warming_nfts = self.warming_nfts.min(self.staked_nfts);
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct CompoundPosition {
+    pub amount: u64,
+    pub locked_until: i64,
+}
+
+impl CompoundPosition {
+    pub const LOCK_SECONDS: i64 = 30 * 86400; // 30 days
+    pub const BOOST_BPS_PER_DAY_OF_EMISSIONS: u64 = 100; // +1%
+    pub const MAX_BOOST_BPS: u64 = 2_500; // +25%
+
+    pub fn new() -> Self {
+        Self {
+            amount: 0,
+            locked_until: 0,
+        }
+    }
+
+    /// Boost granted for every full day of single-NFT emissions compounded.
+    pub fn boost_bps(&self, reward_rate_per_second: u64) -> u64 {
+        let daily_emission = reward_rate_per_second.saturating_mul(86400);
+        if daily_emission == 0 {
+            return 0;
+        }
+
+        (self.amount / daily_emission)
+            .saturating_mul(Self::BOOST_BPS_PER_DAY_OF_EMISSIONS)
+            .min(Self::MAX_BOOST_BPS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::PendingUpgrade;
+
+    #[test]
+    fn reward_share_rounds_down() {
+        let guild = RewardShare { destination: Pubkey::new_unique(), bps: 1_000 };
+        assert_eq!(guild.amount_of(1_000).unwrap(), 100);
+        assert_eq!(guild.amount_of(9).unwrap(), 0);
+        assert_eq!(guild.amount_of(u64::MAX).unwrap(), u64::MAX / 10);
+    }
+
+    fn zeroed_stake() -> UserStakeAccount {
+        UserStakeAccount::deserialize(&mut vec![0u8; UserStakeAccount::INIT_SPACE].as_slice())
+            .unwrap()
+    }
+
+    #[test]
+    fn breaker_failures_count_once_per_window() {
+        let mut stake = zeroed_stake();
+        assert!(stake.take_breaker_failure(600, 1_000));
+        assert!(!stake.take_breaker_failure(600, 1_599));
+        assert!(stake.take_breaker_failure(600, 1_600));
+    }
+
+    #[test]
+    fn touch_checkpoints_secondary_launches() {
+        let mut vault = VaultAccount::deserialize(&mut vec![0u8; VaultAccount::INIT_SPACE].as_slice())
+            .unwrap();
+        vault.record_secondary_launch(150);
+        let mut stake = zeroed_stake();
+        stake.staked_nfts = 2;
+        stake.last_update_timestamp = 100;
+
+        stake.touch(&vault, 200).unwrap();
+        assert_eq!(stake.accrued_nft_seconds, 200);
+        assert_eq!(stake.nft_seconds_at_launch(150).unwrap(), 100);
+        // Launches after the last touch are still worked out from it
+        assert_eq!(stake.nft_seconds_at_launch(250).unwrap(), 300);
+    }
+
+    #[test]
+    fn upgrade_objections_use_nfts_staked_at_proposal() {
+        let mut vault = VaultAccount::deserialize(&mut vec![0u8; VaultAccount::INIT_SPACE].as_slice())
+            .unwrap();
+        vault.pending_upgrade = Some(PendingUpgrade {
+            new_version: 2,
+            scheduled_timestamp: 1_000,
+            proposer: Pubkey::new_unique(),
+            buffer: Pubkey::new_unique(),
+            buffer_hash: [0; 32],
+            approved: false,
+            id: 0,
+            proposed_at: 150,
+            objections: 0,
+            veto_threshold: 10,
+        });
+        let mut stake = zeroed_stake();
+        stake.staked_nfts = 3;
+        stake.last_update_timestamp = 100;
+        assert_eq!(stake.nfts_staked_at_proposal(150), 3);
+
+        // NFTs moved in after the proposal don't add weight
+        stake.touch(&vault, 200).unwrap();
+        stake.staked_nfts = 10;
+        assert_eq!(stake.nfts_staked_at_proposal(150), 3);
+    }
+}
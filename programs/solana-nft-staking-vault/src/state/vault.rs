@@ -0,0 +1,616 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::{
+    VaultConfig, RewardMode, LockTier, PenaltyMode, StreakConfig, TokenBoostConfig, VestingConfig,
+    CurveConfig, OracleConfig, LoyaltyConfig, EmissionSchedule, PendingUpgrade, ActionProposal,
+    PendingConfigChange, PendingDecommission, PendingMintAuthorityReclaim, PendingEmissionSchedule, PendingPoolWithdrawal, PendingSweep,
+    AnomalyRules, RateLimitConfig, PendingInsuranceClaim, TraitGate, BurnBoostConfig,
+};
+use crate::utils::{BPS_DENOMINATOR, whole_tokens_to_base_units, rate_from_tokens_per_day};
+
+/// Arguments shared by both vault initializers.
+pub struct VaultInitParams {
+    /// Whole reward tokens emitted per staked NFT per day
+    pub reward_tokens_per_day: u64,
+    pub collection_mint: Pubkey,
+    pub max_stakes_per_day: u32,
+    pub max_claims_per_day: u32,
+    /// In whole reward tokens
+    pub max_total_rewards_per_day: u64,
+    pub failure_threshold: u32,
+    pub reset_timeout: i64,
+    pub action_cooldown_seconds: i64,
+    pub claim_cooldown_seconds: i64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VaultAccount {
+    pub authority: Pubkey,
+    pub total_staked: u32,
+    pub reward_token_mint: Pubkey,
+    pub collection_mint: Pubkey,
+    /// When set, `unstake_nft` ignores pause flags, breaker and cooldown
+    pub emergency_mode: bool,
+    /// Minimum time an NFT (or edition top-up) stays staked before unstaking
+    pub action_cooldown_seconds: i64,
+    /// Minimum gap between a position's updates and its next claim
+    pub claim_cooldown_seconds: i64,
+    /// Slots that must pass after a stake before that NFT can be unstaked or
+    /// the position can claim; exits in the staking slot are always rejected
+    pub min_stake_slots: u64,
+    pub last_update_timestamp: i64,
+    pub bump: u8,
+    // RBAC & Governance
+    pub upgrade_authority: Pubkey,
+    pub version: u32,
+    pub upgrade_locked: bool,
+    pub pending_upgrade: Option<PendingUpgrade>,
+    // Reward Funding
+    pub reward_mode: RewardMode,
+    pub emission_schedule: EmissionSchedule,
+    pub loyalty: LoyaltyConfig,
+    pub streak: StreakConfig,
+    /// Seconds a newly staked NFT waits before it starts earning
+    pub warmup_seconds: i64,
+    /// Exit queue length; 0 lets `unstake_nft` withdraw directly
+    pub unstake_cooldown_seconds: i64,
+    /// NFTs requested for unstake but not yet withdrawn
+    pub pending_unstakes: u32,
+    /// Programs allowed to invoke stake, unstake and claim via CPI
+    #[max_len(8)]
+    pub cpi_caller_allowlist: Vec<Pubkey>,
+    // Lock-ups & Penalties
+    #[max_len(4)]
+    pub lock_tiers: Vec<LockTier>,
+    pub early_unstake_penalty_bps: u64,
+    pub penalty_mode: PenaltyMode,
+    /// Redistributed penalty per staked NFT, scaled by `PENALTY_INDEX_SCALE`
+    pub penalty_index: u128,
+    pub total_penalties: u64,
+    // Protocol Fee
+    pub fee_bps: u64,
+    /// Reward-mint token account receiving claim fees
+    pub fee_destination: Option<Pubkey>,
+    // Accrual Crank
+    /// Paid in the reward token to whoever settles a position via `accrue_rewards`
+    pub crank_bounty: u64,
+    // Referrals
+    /// Share of each referred claim minted to the referrer on top
+    pub referral_bonus_bps: u64,
+    /// Lifetime bonus a single referrer can earn
+    pub referral_bonus_cap: u64,
+    // Multi-approval Governance
+    pub approval_threshold: u8,
+    pub proposal_count: u64,
+    pub pending_config_change: Option<PendingConfigChange>,
+    // Decommission
+    pub decommissioned: bool,
+    pub pending_decommission: Option<PendingDecommission>,
+    pub pending_mint_authority_reclaim: Option<PendingMintAuthorityReclaim>,
+    // Migration
+    pub migration_target: Option<Pubkey>,
+    pub migration_source: Option<Pubkey>,
+    // SPL Governance voter weight
+    pub governance_realm: Option<Pubkey>,
+    pub governing_token_mint: Option<Pubkey>,
+    // Staker Rate Voting
+    /// Bounds a passed `RateProposal` may set; `rate_vote_max == 0` disables voting
+    pub rate_vote_min: u64,
+    pub rate_vote_max: u64,
+    /// Yes-votes, in staked NFTs, needed for a proposal to pass
+    pub rate_vote_quorum: u64,
+    pub rate_proposal_count: u64,
+    // Merkle Distributions
+    pub distribution_count: u64,
+    // Coupons
+    /// Backend key whose ed25519 signatures `redeem_coupon` accepts
+    pub coupon_signer: Option<Pubkey>,
+    // Missions
+    pub mission_count: u64,
+    // Raffles
+    pub raffle_count: u64,
+    // Oracle-driven Rate
+    /// When set, `refresh_oracle_rate` keeps emissions at a fixed USD value
+    pub oracle: Option<OracleConfig>,
+    // Emission Curve
+    pub curve: CurveConfig,
+    /// Cumulative curve emissions per staked NFT, scaled by `REWARD_INDEX_SCALE`
+    pub reward_index: u128,
+    pub reward_index_updated_at: i64,
+    // Reward Supply Cap
+    /// Ceiling on lifetime claim emissions; 0 means uncapped
+    pub max_reward_supply: u64,
+    /// Paid out by `claim_rewards`, referral bonuses and fees included
+    pub total_rewards_minted: u64,
+    // Vesting
+    pub vesting: VestingConfig,
+    // Token Boost
+    pub token_boost: TokenBoostConfig,
+    /// Boost tokens held in the `token_boost_escrow`
+    pub total_boost_tokens_staked: u64,
+    // Compensation
+    /// Most a single `adjust_pending_rewards` may credit; 0 disables it
+    pub max_reward_adjustment: u64,
+    // Whitelist Phase
+    /// Before this only wallets with a `WhitelistEntry` may stake; 0 = open
+    pub public_start_timestamp: i64,
+    /// Lets listed wallets create their own entry via `join_whitelist`
+    pub whitelist_merkle_root: Option<[u8; 32]>,
+    // Stake Caps
+    /// Most NFTs a single user may have staked; 0 = unlimited
+    pub max_nfts_per_user: u32,
+    /// Most NFTs the vault may hold staked; 0 = unlimited
+    pub max_total_staked: u32,
+    // Seasons
+    /// Latest season started; 0 = none yet
+    pub current_season_id: u32,
+    pub season_active: bool,
+    /// Current season's boost, added to every position's
+    pub season_boost_bps: u16,
+    // Sweeps
+    pub pending_sweep: Option<PendingSweep>,
+    // Reward Mint Decimals
+    /// Copied from the reward mint so whole-token settings convert on-chain
+    pub reward_decimals: u8,
+    // Anomaly Auto-pause
+    pub anomaly_rules: AnomalyRules,
+    /// Highest `total_staked` seen since `staked_window_opened_at`
+    pub staked_window_high: u32,
+    pub staked_window_opened_at: i64,
+    // Wallet Rate Limit
+    pub user_rate_limit: RateLimitConfig,
+    // Upgrade Veto
+    /// Share of `total_staked` whose objections cancel a pending upgrade;
+    /// 0 = no veto
+    pub upgrade_veto_bps: u64,
+    pub upgrade_proposal_count: u64,
+    // Dead-man Switch
+    /// May take SuperAdmin once no heartbeat arrived for `heartbeat_timeout_seconds`
+    pub recovery_key: Option<Pubkey>,
+    pub heartbeat_timeout_seconds: i64,
+    pub last_admin_heartbeat: i64,
+    // Insurance Fund
+    /// Share of each claim fee paid into the `insurance_pool` instead of
+    /// `fee_destination`, and of each early-unstake penalty
+    pub insurance_fee_bps: u64,
+    pub pending_insurance_claim: Option<PendingInsuranceClaim>,
+    /// Penalty share not yet moved into the pool by `fund_insurance_pool`
+    pub insurance_penalties_owed: u64,
+    // Trait Gate
+    pub trait_gate: TraitGate,
+    // Burn Boost
+    pub burn_boost: BurnBoostConfig,
+    // Pool Withdrawals
+    pub pending_pool_withdrawal: Option<PendingPoolWithdrawal>,
+    // Emission Schedule Changes
+    pub pending_emission_schedule: Option<PendingEmissionSchedule>,
+    // Secondary Reward Launches
+    /// Start times of the latest secondary rewards, oldest first
+    #[max_len(4)]
+    pub secondary_launches: Vec<i64>,
+    // Legacy Stake Records
+    /// Root over `(user, nft_mint)` of NFTs staked before per-NFT records
+    /// existed; set once by `set_legacy_stake_root`
+    pub legacy_stake_root: Option<[u8; 32]>,
+    /// Legacy NFTs in the root that have no stake record yet
+    pub unrecorded_legacy_nfts: u32,
+}
+
+impl VaultAccount {
+    pub const MAX_WARMUP_SECONDS: i64 = 7 * 86400; // 7 days
+    pub const MAX_UNSTAKE_COOLDOWN_SECONDS: i64 = 30 * 86400; // 30 days
+    pub const DEFAULT_EARLY_UNSTAKE_PENALTY_BPS: u64 = 2_500; // 25%
+    pub const PENALTY_INDEX_SCALE: u128 = 1_000_000_000_000;
+    pub const MAX_FEE_BPS: u64 = 1_000; // 10%
+    pub const CRANK_INTERVAL_SECONDS: i64 = 3_600; // 1 hour
+    pub const MAX_SECONDARY_LAUNCHES: usize = 4;
+    pub const MAX_REFERRAL_BONUS_BPS: u64 = 1_000; // 10%
+    pub const REWARD_INDEX_SCALE: u128 = 1_000_000_000_000;
+    /// Scale of `VaultConfig::precise_reward_rate`
+    pub const RATE_PRECISION: u128 = 1_000_000_000_000;
+    pub const MAX_REWARD_DECIMALS: u8 = 9;
+    pub const MIN_ACTION_COOLDOWN_SECONDS: i64 = 30;
+    pub const MIN_CLAIM_COOLDOWN_SECONDS: i64 = 10;
+    pub const MAX_COOLDOWN_SECONDS: i64 = 86400; // 1 day
+    pub const MAX_MIN_STAKE_SLOTS: u64 = 9_000; // ~1 hour
+    pub const MAX_CPI_CALLERS: usize = 8;
+    /// A higher veto threshold would make the veto meaningless
+    pub const MAX_UPGRADE_VETO_BPS: u64 = 5_000; // 50%
+    pub const MIN_HEARTBEAT_TIMEOUT_SECONDS: i64 = 7 * 86400; // 7 days
+
+    /// Sets every field of a freshly created vault except the reward mode.
+    /// Rate, pauses and limits live in `VaultConfig`.
+    pub fn initialize(
+        &mut self,
+        params: &VaultInitParams,
+        authority: Pubkey,
+        reward_token_mint: Pubkey,
+        reward_decimals: u8,
+        bump: u8,
+    ) -> Result<()> {
+        require!(
+            Self::cooldowns_valid(params.action_cooldown_seconds, params.claim_cooldown_seconds),
+            ErrorCode::InvalidCooldown
+        );
+        require!(
+            reward_decimals <= Self::MAX_REWARD_DECIMALS,
+            ErrorCode::InvalidRewardDecimals
+        );
+        
+        self.authority = authority;
+        self.total_staked = 0;
+        self.reward_token_mint = reward_token_mint;
+        self.reward_decimals = reward_decimals;
+        self.collection_mint = params.collection_mint;
+        self.bump = bump;
+        self.emergency_mode = false;
+        self.action_cooldown_seconds = params.action_cooldown_seconds;
+        self.claim_cooldown_seconds = params.claim_cooldown_seconds;
+        self.min_stake_slots = 0;
+        self.last_update_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Initialize RBAC & Governance
+        self.upgrade_authority = authority;
+        self.version = 1;
+        self.upgrade_locked = false;
+        self.pending_upgrade = None;
+        self.approval_threshold = 1;
+        self.proposal_count = 0;
+        self.pending_config_change = None;
+        self.decommissioned = false;
+        self.pending_decommission = None;
+        self.pending_mint_authority_reclaim = None;
+        self.migration_target = None;
+        self.migration_source = None;
+        self.governance_realm = None;
+        self.governing_token_mint = None;
+        self.rate_vote_min = 0;
+        self.rate_vote_max = 0;
+        self.rate_vote_quorum = 0;
+        self.rate_proposal_count = 0;
+        self.distribution_count = 0;
+        self.coupon_signer = None;
+        self.mission_count = 0;
+        self.raffle_count = 0;
+        self.oracle = None;
+        self.curve = CurveConfig::new();
+        self.reward_index = 0;
+        self.reward_index_updated_at = self.last_update_timestamp;
+        self.max_reward_supply = 0;
+        self.total_rewards_minted = 0;
+        self.vesting = VestingConfig::new();
+        self.token_boost = TokenBoostConfig::new();
+        self.total_boost_tokens_staked = 0;
+
+        // Constant emissions from now until an admin sets a schedule
+        self.emission_schedule = EmissionSchedule::new(self.last_update_timestamp);
+        self.loyalty = LoyaltyConfig::new();
+        self.streak = StreakConfig::new();
+        self.warmup_seconds = 0;
+        self.unstake_cooldown_seconds = 0;
+        self.pending_unstakes = 0;
+        self.lock_tiers = LockTier::default_tiers();
+        self.cpi_caller_allowlist = Vec::new();
+        self.early_unstake_penalty_bps = Self::DEFAULT_EARLY_UNSTAKE_PENALTY_BPS;
+        self.penalty_mode = PenaltyMode::Redistribute;
+        self.penalty_index = 0;
+        self.total_penalties = 0;
+        self.fee_bps = 0;
+        self.fee_destination = None;
+        self.crank_bounty = 0;
+        self.referral_bonus_bps = 0;
+        self.referral_bonus_cap = 0;
+
+        Ok(())
+    }
+
+    pub fn cooldowns_valid(action_cooldown_seconds: i64, claim_cooldown_seconds: i64) -> bool {
+        (Self::MIN_ACTION_COOLDOWN_SECONDS..=Self::MAX_COOLDOWN_SECONDS)
+            .contains(&action_cooldown_seconds)
+            && (Self::MIN_CLAIM_COOLDOWN_SECONDS..=Self::MAX_COOLDOWN_SECONDS)
+                .contains(&claim_cooldown_seconds)
+    }
+
+    /// Whether more than `min_stake_slots` slots have passed since
+    /// `staked_slot`, so the exit cannot share a transaction with the stake.
+    pub fn min_stake_slots_elapsed(&self, staked_slot: u64, current_slot: u64) -> bool {
+        current_slot > staked_slot.saturating_add(self.min_stake_slots)
+    }
+
+    /// Rejects a stake of `amount` that would push the user or the vault
+    /// over its cap.
+    pub fn check_stake_caps(&self, user_staked: u32, amount: u32) -> Result<()> {
+        self.check_user_stake_cap(user_staked, amount)?;
+        if self.max_total_staked > 0 {
+            let vault_total = self.total_staked.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+            require!(vault_total <= self.max_total_staked, ErrorCode::VaultStakeCapReached);
+        }
+        Ok(())
+    }
+
+    pub fn check_user_stake_cap(&self, user_staked: u32, amount: u32) -> Result<()> {
+        if self.max_nfts_per_user > 0 {
+            let user_total = user_staked.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+            require!(user_total <= self.max_nfts_per_user, ErrorCode::UserStakeCapReached);
+        }
+        Ok(())
+    }
+
+    /// `whole_tokens` in reward base units.
+    pub fn to_base_units(&self, whole_tokens: u64) -> Result<u64> {
+        whole_tokens_to_base_units(whole_tokens, self.reward_decimals)
+    }
+
+    /// Per-second rate and fraction emitting `tokens_per_day` whole tokens
+    /// per NFT per day.
+    pub fn rate_from_tokens_per_day(&self, tokens_per_day: u64) -> Result<(u64, u64)> {
+        rate_from_tokens_per_day(tokens_per_day, self.reward_decimals)
+    }
+
+    /// How far `total_staked` sits below the day's high, counting
+    /// `previous_total` as seen. A new window opens once a day has passed.
+    pub fn staked_drop(&mut self, previous_total: u32, current_timestamp: i64) -> u32 {
+        if current_timestamp - self.staked_window_opened_at >= 86400 {
+            self.staked_window_high = previous_total;
+            self.staked_window_opened_at = current_timestamp;
+        }
+        self.staked_window_high = self.staked_window_high.max(previous_total).max(self.total_staked);
+        self.staked_window_high - self.total_staked
+    }
+
+    /// Starts a fresh window so a drop that already paused the vault does
+    /// not pause it again on the next fold.
+    pub fn reset_staked_window(&mut self, current_timestamp: i64) {
+        self.staked_window_high = self.total_staked;
+        self.staked_window_opened_at = current_timestamp;
+    }
+
+    pub fn remaining_reward_supply(&self) -> u64 {
+        if self.max_reward_supply == 0 {
+            return u64::MAX;
+        }
+        self.max_reward_supply.saturating_sub(self.total_rewards_minted)
+    }
+
+    pub fn record_secondary_launch(&mut self, launched_at: i64) {
+        if self.secondary_launches.len() == Self::MAX_SECONDARY_LAUNCHES {
+            self.secondary_launches.remove(0);
+        }
+        self.secondary_launches.push(launched_at);
+    }
+
+    pub fn record_rewards_minted(&mut self, amount: u64) -> Result<()> {
+        self.total_rewards_minted = self.total_rewards_minted
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn protocol_fee(&self, amount: u64) -> Result<u64> {
+        let fee = (amount as u128)
+            .checked_mul(self.fee_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        Ok(fee as u64)
+    }
+
+    /// The part of a claim fee or penalty that goes to the insurance pool.
+    pub fn insurance_share(&self, fee: u64) -> Result<u64> {
+        let share = (fee as u128)
+            .checked_mul(self.insurance_fee_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        Ok(share as u64)
+    }
+
+    /// Books a forfeited amount. The insurance share is owed to the
+    /// insurance pool; returns whether the rest was shared out to the
+    /// remaining stakers, otherwise it is burned, i.e. never paid.
+    pub fn apply_penalty(&mut self, penalty: u64) -> Result<bool> {
+        self.total_penalties = self.total_penalties
+            .checked_add(penalty)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let insurance_cut = self.insurance_share(penalty)?;
+        self.insurance_penalties_owed = self.insurance_penalties_owed
+            .checked_add(insurance_cut)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let penalty = penalty - insurance_cut;
+
+        if self.penalty_mode != PenaltyMode::Redistribute || self.total_staked == 0 || penalty == 0 {
+            return Ok(false);
+        }
+
+        let per_nft = (penalty as u128)
+            .checked_mul(Self::PENALTY_INDEX_SCALE)
+            .ok_or(ErrorCode::MathOverflow)?
+            / self.total_staked as u128;
+        self.penalty_index = self.penalty_index
+            .checked_add(per_nft)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(true)
+    }
+
+    /// `reward_index` as of `timestamp`, assuming `total_staked` is unchanged
+    /// since the last update. Only grows while the curve is enabled.
+    pub fn reward_index_at(&self, timestamp: i64) -> Result<u128> {
+        if !self.curve.is_enabled()
+            || self.total_staked == 0
+            || timestamp <= self.reward_index_updated_at
+        {
+            return Ok(self.reward_index);
+        }
+
+        let emitted = self.emission_schedule.integrate(
+            self.curve.total_emission_per_second as u128,
+            self.reward_index_updated_at,
+            timestamp,
+        )?;
+        let per_nft = emitted
+            .checked_mul(Self::REWARD_INDEX_SCALE)
+            .ok_or(ErrorCode::MathOverflow)?
+            / self.total_staked as u128;
+        self.reward_index
+            .checked_add(per_nft)
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    /// Brings `reward_index` up to `timestamp`. Call before `total_staked`
+    /// or the curve changes.
+    pub fn update_reward_index(&mut self, timestamp: i64) -> Result<()> {
+        self.reward_index = self.reward_index_at(timestamp)?;
+        self.reward_index_updated_at = self.reward_index_updated_at.max(timestamp);
+        Ok(())
+    }
+
+    pub fn apply_upgrade(&mut self, current_timestamp: i64) -> Result<()> {
+        let pending_upgrade = self.pending_upgrade.as_ref()
+            .ok_or(ErrorCode::NoUpgradePending)?;
+
+        require!(
+            current_timestamp >= pending_upgrade.scheduled_timestamp,
+            ErrorCode::TimelockNotExpired
+        );
+
+        self.version = pending_upgrade.new_version;
+        self.pending_upgrade = None;
+
+        Ok(())
+    }
+
+    /// Whether the recovery key may take over: the switch is armed and the
+    /// admins have been silent for the whole timeout.
+    pub fn admins_unresponsive(&self, current_timestamp: i64) -> bool {
+        self.recovery_key.is_some()
+            && current_timestamp.saturating_sub(self.last_admin_heartbeat)
+                >= self.heartbeat_timeout_seconds
+    }
+
+    /// Objections needed to veto an upgrade proposed now; at least one
+    /// NFT while the veto is on.
+    pub fn upgrade_veto_threshold(&self) -> u64 {
+        if self.upgrade_veto_bps == 0 {
+            return 0;
+        }
+        (self.total_staked as u64 * self.upgrade_veto_bps / BPS_DENOMINATOR).max(1)
+    }
+
+    /// Lets `execute_upgrade` run when an approval threshold is set.
+    /// Returns the approved version.
+    pub fn approve_upgrade(&mut self) -> Result<u32> {
+        let pending_upgrade = self.pending_upgrade.as_mut()
+            .ok_or(ErrorCode::NoUpgradePending)?;
+        pending_upgrade.approved = true;
+        Ok(pending_upgrade.new_version)
+    }
+
+    pub fn apply_config_update(
+        &mut self,
+        config: &mut VaultConfig,
+        new_reward_rate: Option<u64>,
+        new_collection_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        if let Some(rate) = new_reward_rate {
+            // The active season owns the rate until it ends
+            require!(!self.season_active, ErrorCode::SeasonStillActive);
+            // A zero whole-unit rate is fine once a fractional rate is set
+            require!(rate > 0 || config.reward_rate_fraction > 0, ErrorCode::InvalidRewardRate);
+            config.reward_rate_per_second = rate;
+        }
+
+        if let Some(mint) = new_collection_mint {
+            self.collection_mint = mint;
+        }
+
+        Ok(())
+    }
+
+    pub fn schedule_config_change(
+        &mut self,
+        config: &VaultConfig,
+        new_reward_rate: Option<u64>,
+        new_collection_mint: Option<Pubkey>,
+        current_timestamp: i64,
+        timelock_seconds: i64,
+        proposer: Pubkey,
+    ) -> Result<i64> {
+        require!(self.pending_config_change.is_none(), ErrorCode::ConfigChangePending);
+        require!(
+            new_reward_rate.is_some() || new_collection_mint.is_some(),
+            ErrorCode::InvalidConfigChange
+        );
+        if let Some(rate) = new_reward_rate {
+            require!(rate > 0 || config.reward_rate_fraction > 0, ErrorCode::InvalidRewardRate);
+        }
+
+        let scheduled_timestamp = current_timestamp
+            .checked_add(timelock_seconds)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.pending_config_change = Some(PendingConfigChange {
+            new_reward_rate,
+            new_collection_mint,
+            scheduled_timestamp,
+            proposer,
+        });
+
+        Ok(scheduled_timestamp)
+    }
+
+    pub fn apply_approval_threshold(&mut self, threshold: u8) -> Result<()> {
+        require!(
+            threshold >= 1 && threshold as usize <= ActionProposal::MAX_APPROVALS,
+            ErrorCode::InvalidApprovalThreshold
+        );
+
+        self.approval_threshold = threshold;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every field zeroed: options empty, enums at their first variant.
+    fn zeroed_vault() -> VaultAccount {
+        let data = vec![0u8; VaultAccount::INIT_SPACE];
+        VaultAccount::deserialize(&mut data.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn insurance_share_takes_fee_bps() {
+        let mut vault = zeroed_vault();
+        assert_eq!(vault.insurance_share(1_000).unwrap(), 0);
+
+        vault.insurance_fee_bps = 2_500;
+        assert_eq!(vault.insurance_share(1_000).unwrap(), 250);
+        // Rounds down
+        assert_eq!(vault.insurance_share(3).unwrap(), 0);
+
+        vault.insurance_fee_bps = BPS_DENOMINATOR;
+        assert_eq!(vault.insurance_share(u64::MAX).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn penalties_owe_the_insurance_share() {
+        let mut vault = zeroed_vault();
+        vault.insurance_fee_bps = 1_000;
+        vault.penalty_mode = PenaltyMode::Redistribute;
+        vault.total_staked = 10;
+
+        assert!(vault.apply_penalty(1_000).unwrap());
+        assert_eq!(vault.total_penalties, 1_000);
+        assert_eq!(vault.insurance_penalties_owed, 100);
+        // Only the other 900 is shared out
+        assert_eq!(vault.penalty_index, 90 * VaultAccount::PENALTY_INDEX_SCALE);
+
+        vault.insurance_fee_bps = BPS_DENOMINATOR;
+        assert!(!vault.apply_penalty(500).unwrap());
+        assert_eq!(vault.insurance_penalties_owed, 600);
+        assert_eq!(vault.penalty_index, 90 * VaultAccount::PENALTY_INDEX_SCALE);
+    }
+}
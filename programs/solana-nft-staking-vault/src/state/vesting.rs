@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+
+/// One vesting claim; unlocks linearly from `start_at` to `end_at`, with
+/// nothing claimable before `cliff_at`. Releases go to `destination`, the
+/// claim's payout account when the position was created.
+#[account]
+#[derive(InitSpace)]
+pub struct VestingPosition {
+    pub user: Pubkey,
+    pub id: u64,
+    pub destination: Pubkey,
+    pub total_amount: u64,
+    pub released_amount: u64,
+    pub start_at: i64,
+    pub cliff_at: i64,
+    pub end_at: i64,
+    pub bump: u8,
+}
+
+impl VestingPosition {
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        if now < self.cliff_at {
+            return Ok(0);
+        }
+        if now >= self.end_at {
+            return Ok(self.total_amount);
+        }
+
+        let vested = (self.total_amount as u128)
+            .checked_mul((now - self.start_at) as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / (self.end_at - self.start_at) as u128;
+        Ok(vested as u64)
+    }
+
+    pub fn releasable(&self, now: i64) -> Result<u64> {
+        self.vested_amount(now)?
+            .checked_sub(self.released_amount)
+            .ok_or(ErrorCode::MathUnderflow.into())
+    }
+}
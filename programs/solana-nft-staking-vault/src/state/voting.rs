@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+/// Staker vote on the reward rate. Separate from `ActionProposal`, which is
+/// the role-holder multi-approval flow.
+#[account]
+#[derive(InitSpace)]
+pub struct RateProposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub new_rate: u64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl RateProposal {
+    pub const VOTING_PERIOD_SECONDS: i64 = 3 * 86400; // 3 days
+    pub const EXECUTION_WINDOW_SECONDS: i64 = 3 * 86400; // 3 days
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RateVoteRecord {
+    pub proposal_id: u64,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub approve: bool,
+    pub bump: u8,
+}
+
+/// SPL Governance voter-weight addin record. Layout and discriminator follow
+/// `spl-governance-addin-api` so Realms reads it without unstaking.
+#[account(discriminator = b"2ef99b4b")]
+#[derive(InitSpace)]
+pub struct VoterWeightRecord {
+    pub realm: Pubkey,
+    pub governing_token_mint: Pubkey,
+    pub governing_token_owner: Pubkey,
+    pub voter_weight: u64,
+    pub voter_weight_expiry: Option<u64>,
+    pub weight_action: Option<VoterWeightAction>,
+    pub weight_action_target: Option<Pubkey>,
+    pub reserved: [u8; 8],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, InitSpace)]
+pub enum VoterWeightAction {
+    CastVote,
+    CommentProposal,
+    CreateGovernance,
+    CreateProposal,
+    SignOffProposal,
+}
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, TokenAccount, CloseAccount, Burn, ThawAccount};
+use anchor_lang::system_program;
+
+use crate::errors::ErrorCode;
+use crate::state::*;
+
+/// Allocates a program-owned PDA passed through `remaining_accounts`, where
+/// Anchor's `init` can't be used. `signer_seeds` must include the bump.
+pub(crate) fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )
+}
+
+/// Closes the NFT's stake record and returns the time it was staked. NFTs
+/// staked before per-NFT records existed need `backfill_stake_record`
+/// first, so nothing without a record ever leaves custody here.
+pub(crate) fn release_stake_record<'info>(
+    stake_record: &UncheckedAccount<'info>,
+    user_stake: &mut UserStakeAccount,
+    receiver: &AccountInfo<'info>,
+) -> Result<i64> {
+    let record = load_stake_record(stake_record)?.ok_or(ErrorCode::StakeRecordRequired)?;
+    require!(record.user == user_stake.user, ErrorCode::StakeRecordMismatch);
+    require!(record.unlocks_at == 0, ErrorCode::UnstakeAlreadyRequested);
+
+    let lamports = stake_record.lamports();
+    **receiver.try_borrow_mut_lamports()? = receiver
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ErrorCode::MathOverflow)?;
+    **stake_record.try_borrow_mut_lamports()? = 0;
+    stake_record.assign(&system_program::ID);
+    stake_record.resize(0)?;
+
+    user_stake.remove_staked_at(record.staked_at)?;
+    Ok(record.staked_at)
+}
+
+/// The `StakeRecord` behind `stake_record`, or `None` if there is none,
+/// e.g. for legacy NFTs that haven't been backfilled.
+pub(crate) fn load_stake_record(stake_record: &UncheckedAccount) -> Result<Option<StakeRecord>> {
+    if stake_record.owner != &crate::ID || stake_record.data_is_empty() {
+        return Ok(None);
+    }
+    let record = StakeRecord::try_deserialize(&mut &stake_record.data.borrow()[..])?;
+    Ok(Some(record))
+}
+
+/// Whether `booster_escrow`, the mint's `booster_escrow` PDA, holds an
+/// attached booster, i.e. a `BoosterRecord` points at the mint.
+pub(crate) fn is_attached_booster(booster_escrow: &UncheckedAccount) -> bool {
+    booster_escrow.owner == &token::ID && !booster_escrow.data_is_empty()
+}
+
+/// Mirrors the user's staked NFT count into their voter-weight record, if
+/// they have created one.
+pub(crate) fn sync_voter_weight(record: &UncheckedAccount, staked_nfts: u32) -> Result<()> {
+    if record.owner != &crate::ID || record.data_is_empty() {
+        return Ok(());
+    }
+
+    let mut voter_weight = VoterWeightRecord::try_deserialize(&mut &record.data.borrow()[..])?;
+    voter_weight.voter_weight = staked_nfts as u64;
+
+    let mut data = record.try_borrow_mut_data()?;
+    voter_weight.try_serialize(&mut &mut data[..])
+}
+
+/// Set-bonus group of an NFT, if an admin has tagged it.
+pub(crate) fn nft_group(nft_group_tag: &UncheckedAccount) -> Result<Option<u8>> {
+    if nft_group_tag.owner != &crate::ID || nft_group_tag.data_is_empty() {
+        return Ok(None);
+    }
+    let tag = NftGroupTag::try_deserialize(&mut &nft_group_tag.data.borrow()[..])?;
+    Ok(Some(tag.group))
+}
+
+/// Multiplier bonus of an NFT, 0 unless an admin has set one.
+pub(crate) fn nft_bonus_bps(nft_multiplier: &UncheckedAccount) -> Result<u64> {
+    if nft_multiplier.owner != &crate::ID || nft_multiplier.data_is_empty() {
+        return Ok(0);
+    }
+    let multiplier = NftMultiplier::try_deserialize(&mut &nft_multiplier.data.borrow()[..])?;
+    Ok(multiplier.bonus_bps())
+}
+
+/// Thaws, burns and closes the position's receipt. NFTs staked before receipts
+/// were issued have no initialized receipt mint and are skipped.
+pub(crate) fn burn_receipt<'info>(
+    vault: &AccountInfo<'info>,
+    vault_signer: &[&[&[u8]]],
+    receipt_mint: &UncheckedAccount<'info>,
+    user_receipt_token_account: Option<&Account<'info, TokenAccount>>,
+    user: &AccountInfo<'info>,
+    rent_receiver: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if receipt_mint.owner != &token::ID || receipt_mint.data_is_empty() {
+        return Ok(());
+    }
+    let mint = Mint::try_deserialize(&mut &receipt_mint.data.borrow()[..])?;
+    if mint.supply == 0 {
+        return Ok(());
+    }
+
+    let receipt_account = user_receipt_token_account.ok_or(ErrorCode::ReceiptAccountRequired)?;
+    require!(
+        receipt_account.mint == receipt_mint.key()
+            && receipt_account.owner == user.key()
+            && receipt_account.amount == 1,
+        ErrorCode::ReceiptAccountRequired
+    );
+
+    let thaw_ctx = CpiContext::new_with_signer(
+        token_program.clone(),
+        ThawAccount {
+            account: receipt_account.to_account_info(),
+            mint: receipt_mint.to_account_info(),
+            authority: vault.clone(),
+        },
+        vault_signer,
+    );
+    token::thaw_account(thaw_ctx)?;
+
+    let burn_ctx = CpiContext::new(
+        token_program.clone(),
+        Burn {
+            mint: receipt_mint.to_account_info(),
+            from: receipt_account.to_account_info(),
+            authority: user.clone(),
+        },
+    );
+    token::burn(burn_ctx, 1)?;
+
+    let close_ctx = CpiContext::new(
+        token_program.clone(),
+        CloseAccount {
+            account: receipt_account.to_account_info(),
+            destination: rent_receiver.clone(),
+            authority: user.clone(),
+        },
+    );
+    token::close_account(close_ctx)
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::utils::cooldown_remaining;
+
+/// Rejects invocation via CPI unless the transaction's top-level program is
+/// on `vault.cpi_caller_allowlist`, or is the registered `partner` and
+/// `permitted` by its scope. Only the outermost program is visible through
+/// the instructions sysvar, so nested callers are judged by it.
+pub(crate) fn ensure_allowed_caller(
+    vault: &VaultAccount,
+    instructions: &UncheckedAccount,
+    partner: Option<&PartnerProgram>,
+    permitted: fn(&PartnerProgram) -> bool,
+) -> Result<()> {
+    if get_stack_height() <= TRANSACTION_LEVEL_STACK_HEIGHT {
+        return Ok(());
+    }
+
+    let instructions = instructions.to_account_info();
+    let current_index = load_current_index_checked(&instructions)?;
+    let caller = load_instruction_at_checked(current_index as usize, &instructions)?.program_id;
+    if vault.cpi_caller_allowlist.contains(&caller) {
+        return Ok(());
+    }
+
+    let partner = partner.ok_or(ErrorCode::CpiCallerNotAllowed)?;
+    require!(
+        partner.program_id == caller && partner.active && permitted(partner),
+        ErrorCode::CpiCallerNotAllowed
+    );
+    Ok(())
+}
+
+/// Fails with `error` until `cooldown_seconds` have passed since `since`,
+/// logging how long is left.
+pub(crate) fn ensure_cooldown_elapsed(
+    since: i64,
+    cooldown_seconds: i64,
+    current_timestamp: i64,
+    error: ErrorCode,
+) -> Result<()> {
+    let remaining = cooldown_remaining(since, cooldown_seconds, current_timestamp);
+    if remaining > 0 {
+        msg!(
+            "Cooldown active: {} of {} seconds remaining",
+            remaining,
+            cooldown_seconds
+        );
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Before `public_start_timestamp` only whitelisted wallets may stake.
+pub(crate) fn ensure_whitelisted(
+    vault: &VaultAccount,
+    whitelist_entry: Option<&WhitelistEntry>,
+    current_timestamp: i64,
+) -> Result<()> {
+    if current_timestamp < vault.public_start_timestamp {
+        require!(whitelist_entry.is_some(), ErrorCode::NotWhitelisted);
+    }
+    Ok(())
+}
+
+/// With a trait gate on, only NFTs verified against the current gate may be
+/// staked.
+pub(crate) fn ensure_trait_verified(
+    vault: &VaultAccount,
+    trait_pass: Option<&TraitPass>,
+) -> Result<()> {
+    if vault.trait_gate.is_enabled() {
+        let pass = trait_pass.ok_or(ErrorCode::TraitNotVerified)?;
+        require!(
+            pass.gate_version == vault.trait_gate.version,
+            ErrorCode::TraitNotVerified
+        );
+    }
+    Ok(())
+}
+
+/// Suspended users may still unstake, so only stake and claim paths call this.
+pub(crate) fn ensure_not_suspended(user_status: &UncheckedAccount) -> Result<()> {
+    if user_status.owner != &crate::ID || user_status.data_is_empty() {
+        return Ok(());
+    }
+
+    let status = UserStatus::try_deserialize(&mut &user_status.data.borrow()[..])?;
+    require!(!status.suspended, ErrorCode::UserSuspended);
+
+    Ok(())
+}
@@ -0,0 +1,263 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::state::*;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+const SECONDS_PER_DAY: u128 = 86_400;
+
+pub fn whole_tokens_to_base_units(whole_tokens: u64, decimals: u8) -> Result<u64> {
+    10u64
+        .checked_pow(decimals as u32)
+        .and_then(|unit| whole_tokens.checked_mul(unit))
+        .ok_or(ErrorCode::MathOverflow.into())
+}
+
+/// Splits `tokens_per_day` whole tokens into the whole per-second rate and
+/// its `RATE_PRECISION` fraction, as stored in `VaultConfig`.
+pub fn rate_from_tokens_per_day(tokens_per_day: u64, decimals: u8) -> Result<(u64, u64)> {
+    let precise = (whole_tokens_to_base_units(tokens_per_day, decimals)? as u128)
+        .checked_mul(VaultAccount::RATE_PRECISION)
+        .ok_or(ErrorCode::MathOverflow)?
+        / SECONDS_PER_DAY;
+    let per_second = u64::try_from(precise / VaultAccount::RATE_PRECISION)
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    Ok((per_second, (precise % VaultAccount::RATE_PRECISION) as u64))
+}
+
+/// Seconds until `cooldown_seconds` have passed since `since`; 0 once they
+/// have.
+pub fn cooldown_remaining(since: i64, cooldown_seconds: i64, current_timestamp: i64) -> i64 {
+    since
+        .saturating_add(cooldown_seconds)
+        .saturating_sub(current_timestamp)
+        .max(0)
+}
+
+/// Emissions for `staked_nfts` over `[from, to]`, still scaled by
+/// `RATE_PRECISION`; callers divide once after combining terms so rounding
+/// only happens at the end. The window is uncapped so positions left alone
+/// for weeks still earn for all of it; the u128 math can't overflow on any
+/// realistic span.
+pub(crate) fn calculate_rewards(
+    schedule: &EmissionSchedule,
+    precise_rate: u128,
+    from_timestamp: i64,
+    to_timestamp: i64,
+    staked_nfts: u64,
+) -> Result<u128> {
+    require!(to_timestamp >= from_timestamp, ErrorCode::InvalidTimeElapsed);
+
+    let rewards = schedule
+        .integrate(precise_rate, from_timestamp, to_timestamp)?
+        .checked_mul(staked_nfts as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(rewards)
+}
+
+/// Unboosted emissions over `[from, to]`. NFTs still warming up earn
+/// nothing until `warmup_ends_at`.
+pub(crate) fn earned_base_rewards(
+    vault: &VaultAccount,
+    config: &VaultConfig,
+    user_stake: &UserStakeAccount,
+    from: i64,
+    to: i64,
+) -> Result<u64> {
+    if vault.curve.is_enabled() {
+        return curve_base_rewards(vault, user_stake, from, to);
+    }
+
+    let precise_rate = config.precise_reward_rate();
+    let base_rewards = calculate_rewards(
+        &vault.emission_schedule,
+        precise_rate,
+        from,
+        to,
+        user_stake.staked_nfts as u64,
+    )?;
+
+    let warmup_end = user_stake.warmup_ends_at.min(to);
+    let forgone = if user_stake.warming_nfts == 0 || warmup_end <= from {
+        0
+    } else {
+        calculate_rewards(
+            &vault.emission_schedule,
+            precise_rate,
+            from,
+            warmup_end,
+            user_stake.warming_nfts as u64,
+        )?
+    };
+
+    let rewards = base_rewards
+        .checked_sub(forgone)
+        .ok_or(ErrorCode::MathUnderflow)?
+        / VaultAccount::RATE_PRECISION;
+    u64::try_from(rewards).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// `earned_base_rewards` under the fixed-total curve, read off the vault's
+/// reward index. `from` is always the position's last checkpoint.
+pub(crate) fn curve_base_rewards(
+    vault: &VaultAccount,
+    user_stake: &UserStakeAccount,
+    from: i64,
+    to: i64,
+) -> Result<u64> {
+    require!(to >= from, ErrorCode::InvalidTimeElapsed);
+
+    let base_rewards = curve_index_delta(vault, user_stake, to)?
+        .checked_mul(user_stake.staked_nfts as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let warmup_end = user_stake.warmup_ends_at.min(to);
+    let forgone = if user_stake.warming_nfts == 0 || warmup_end <= from {
+        0
+    } else {
+        curve_index_delta(vault, user_stake, warmup_end)?
+            .checked_mul(user_stake.warming_nfts as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+    };
+
+    let rewards = base_rewards
+        .checked_sub(forgone)
+        .ok_or(ErrorCode::MathUnderflow)?
+        / VaultAccount::REWARD_INDEX_SCALE;
+    u64::try_from(rewards).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// Per-NFT index growth from the position's checkpoint up to `to`. The
+/// index history is not stored, so points before the vault's last index
+/// update are interpolated linearly from the checkpoint.
+pub(crate) fn curve_index_delta(
+    vault: &VaultAccount,
+    user_stake: &UserStakeAccount,
+    to: i64,
+) -> Result<u128> {
+    let from = user_stake.last_update_timestamp;
+    let delta = if to >= vault.reward_index_updated_at {
+        vault.reward_index_at(to)?
+            .checked_sub(user_stake.reward_index_checkpoint)
+            .ok_or(ErrorCode::MathUnderflow)?
+    } else {
+        let settled = vault.reward_index
+            .checked_sub(user_stake.reward_index_checkpoint)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        let span = (vault.reward_index_updated_at - from).max(1) as u128;
+        settled
+            .checked_mul((to - from).max(0) as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / span
+    };
+    Ok(delta)
+}
+
+/// Compound boost plus the loyalty bonus and position-wide boosts. Loyalty is evaluated at the start
+/// of the accrual window from the average stake age of the user's NFTs.
+pub(crate) fn total_boost_bps(vault: &VaultAccount, config: &VaultConfig, user_stake: &UserStakeAccount) -> u64 {
+    let compound_bps = user_stake.compound_position.boost_bps(config.reward_rate_per_second);
+    let loyalty_bps = user_stake
+        .average_staked_at()
+        .map(|staked_at| vault.loyalty.bonus_bps(user_stake.last_update_timestamp - staked_at))
+        .unwrap_or(0);
+
+    compound_bps
+        .saturating_add(loyalty_bps)
+        .saturating_add(vault.token_boost.bonus_bps(user_stake.staked_tokens))
+        .saturating_add(user_stake.booster_boost_bps)
+        .saturating_add(user_stake.nft_multiplier_boost_bps())
+        .saturating_add(user_stake.burn_boost_bps)
+        .saturating_add(vault.season_boost_bps as u64)
+}
+
+pub(crate) fn apply_boost_bps(amount: u64, boost_bps: u64) -> Result<u64> {
+    let boosted = (amount as u128)
+        .checked_mul(BPS_DENOMINATOR as u128 + boost_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    u64::try_from(boosted).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractional_rate_accrues_without_overflow() {
+        let schedule = EmissionSchedule::new(0);
+        // 0.25 base units per second per NFT
+        let precise_rate = VaultAccount::RATE_PRECISION / 4;
+
+        let rewards = calculate_rewards(&schedule, precise_rate, 0, 100, 10).unwrap();
+        assert_eq!(rewards / VaultAccount::RATE_PRECISION, 250);
+
+        // time * rate * count beyond u64 stays representable
+        let large_rate = 1_000_000 * VaultAccount::RATE_PRECISION;
+        let rewards = calculate_rewards(&schedule, large_rate, 0, 172_800, 10_000).unwrap();
+        assert!(rewards > u64::MAX as u128);
+    }
+
+    #[test]
+    fn long_idle_positions_accrue_in_full() {
+        let schedule = EmissionSchedule::new(0);
+        let rate = VaultAccount::RATE_PRECISION;
+        let year = 365 * 86_400;
+
+        let rewards = calculate_rewards(&schedule, rate, 0, year, 3).unwrap();
+        assert_eq!(rewards / rate, 3 * year as u128);
+        assert!(calculate_rewards(&schedule, rate, 100, 99, 1).is_err());
+    }
+
+    #[test]
+    fn emission_window_clamps_accrual() {
+        let schedule = EmissionSchedule {
+            start_timestamp: 1_000,
+            end_timestamp: 2_000,
+            halving_interval: 0,
+        };
+        let rate = VaultAccount::RATE_PRECISION;
+
+        // Staked before the start: only time after it counts
+        assert_eq!(calculate_rewards(&schedule, rate, 500, 1_500, 1).unwrap() / rate, 500);
+        // Settled after the end: accrual stops at it
+        assert_eq!(calculate_rewards(&schedule, rate, 1_800, 2_600, 1).unwrap() / rate, 200);
+        // Entirely outside the window
+        assert_eq!(calculate_rewards(&schedule, rate, 0, 1_000, 1).unwrap(), 0);
+        assert_eq!(calculate_rewards(&schedule, rate, 2_000, 3_000, 1).unwrap(), 0);
+    }
+
+    #[test]
+    fn whole_token_rates_respect_decimals() {
+        assert_eq!(whole_tokens_to_base_units(1_000, 6).unwrap(), 1_000_000_000);
+        assert_eq!(whole_tokens_to_base_units(1_000, 9).unwrap(), 1_000_000_000_000);
+        assert!(whole_tokens_to_base_units(u64::MAX, 9).is_err());
+
+        // 1 token/s at 6 and 9 decimals
+        assert_eq!(rate_from_tokens_per_day(86_400, 6).unwrap(), (1_000_000, 0));
+        assert_eq!(rate_from_tokens_per_day(86_400, 9).unwrap(), (1_000_000_000, 0));
+        // 1 token/day at 0 decimals only fits the fractional part
+        let (per_second, fraction) = rate_from_tokens_per_day(1, 0).unwrap();
+        assert_eq!(per_second, 0);
+        assert_eq!(fraction as u128, VaultAccount::RATE_PRECISION / 86_400);
+    }
+
+    #[test]
+    fn cooldown_remaining_counts_down_to_zero() {
+        assert_eq!(cooldown_remaining(1_000, 60, 1_000), 60);
+        assert_eq!(cooldown_remaining(1_000, 60, 1_045), 15);
+        assert_eq!(cooldown_remaining(1_000, 60, 1_060), 0);
+        assert_eq!(cooldown_remaining(1_000, 60, 5_000), 0);
+    }
+
+    #[test]
+    fn boost_applies_in_basis_points() {
+        assert_eq!(apply_boost_bps(1_000, 0).unwrap(), 1_000);
+        assert_eq!(apply_boost_bps(1_000, 2_500).unwrap(), 1_250);
+        // Rounds down
+        assert_eq!(apply_boost_bps(3, 5_000).unwrap(), 4);
+        assert!(apply_boost_bps(u64::MAX, 10_000).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+use anchor_lang::solana_program::keccak;
+
+/// Verifies a proof built with sorted sibling pairs.
+pub(crate) fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        keccak::hashv(&[&[1u8], &left, &right]).to_bytes()
+    });
+    computed == root
+}
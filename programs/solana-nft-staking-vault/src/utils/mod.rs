@@ -0,0 +1,12 @@
+pub mod math;
+
+mod accounts;
+mod guards;
+mod merkle;
+mod rewards;
+
+pub use math::*;
+pub(crate) use accounts::*;
+pub(crate) use guards::*;
+pub(crate) use merkle::*;
+pub(crate) use rewards::*;
@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, TokenAccount, Transfer, MintTo};
+
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::utils::{earned_base_rewards, total_boost_bps, apply_boost_bps};
+
+/// Mints rewards in `Mint` mode or transfers them out of a vault-owned
+/// reward pool in `Pool` mode. `source` is the mint or the pool respectively,
+/// already checked by `reward_source` or `check_reward_source`; the token
+/// program rejects overdrafts, so nothing is deserialized per payout.
+pub(crate) fn pay_out_rewards<'info>(
+    vault: &Account<'info, VaultAccount>,
+    reward_mode: RewardMode,
+    source: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"vault".as_ref(), &[vault.bump]];
+    let signer = &[&seeds[..]];
+
+    match reward_mode {
+        RewardMode::Mint => {
+            let mint_ctx = CpiContext::new_with_signer(
+                token_program,
+                MintTo {
+                    mint: source,
+                    to: destination,
+                    authority: vault.to_account_info(),
+                },
+                signer,
+            );
+            token::mint_to(mint_ctx, amount)
+        }
+        RewardMode::Pool => {
+            let transfer_ctx = CpiContext::new_with_signer(
+                token_program,
+                Transfer {
+                    from: source,
+                    to: destination,
+                    authority: vault.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(transfer_ctx, amount)
+        }
+    }
+}
+
+/// Picks the mint or pool `reward_mint` is paid from. Both were already
+/// deserialized by Anchor and pools are pinned to the vault's ATA by their
+/// constraints, so only the mint and its authority are left to check.
+pub(crate) fn reward_source<'info>(
+    vault: &Account<'info, VaultAccount>,
+    reward_mode: RewardMode,
+    reward_mint: Pubkey,
+    reward_token_mint: &Account<'info, Mint>,
+    reward_pool: Option<&Account<'info, TokenAccount>>,
+) -> Result<AccountInfo<'info>> {
+    require!(reward_token_mint.key() == reward_mint, ErrorCode::InvalidRewardMint);
+
+    match reward_mode {
+        RewardMode::Mint => {
+            require!(
+                reward_token_mint.mint_authority == COption::Some(vault.key()),
+                ErrorCode::InvalidMintAuthority
+            );
+            Ok(reward_token_mint.to_account_info())
+        }
+        RewardMode::Pool => Ok(reward_pool
+            .ok_or(ErrorCode::RewardPoolRequired)?
+            .to_account_info()),
+    }
+}
+
+/// Holds a one-off primary reward payout (coupon, mission, distribution) to
+/// the shard breaker, daily limit, supply cap and allowance `claim_rewards`
+/// is held to, and books it on the shard. Unlike a claim nothing is
+/// forfeited: the coupon or proof can't be used twice, so a payout that
+/// doesn't fit is rejected and can be retried after a fold.
+pub(crate) fn book_primary_payout(
+    vault: &VaultAccount,
+    config: &VaultConfig,
+    shard: &mut VaultShard,
+    amount: u64,
+    current_timestamp: i64,
+) -> Result<()> {
+    shard.sync_config(config);
+    require!(
+        shard.can_execute(config, current_timestamp),
+        ErrorCode::CircuitBreakerActive
+    );
+    shard.daily_limit.reset_if_new_day(current_timestamp);
+    shard.daily_limit.check_claim(amount)?;
+    require!(
+        amount <= shard.remaining_reward_supply(vault),
+        ErrorCode::RewardSupplyExhausted
+    );
+    require!(
+        amount <= shard.reward_allowance(vault),
+        ErrorCode::ShardAllowanceExhausted
+    );
+
+    shard.record_rewards_minted(amount, 0)?;
+    shard.record_claim(amount, current_timestamp)?;
+    shard.daily_limit.record_claim(amount);
+    Ok(())
+}
+
+/// Settles a secondary reward position against the NFT-seconds the user
+/// accumulated since the last checkpoint and returns the amount owed.
+pub(crate) fn settle_secondary_rewards(
+    reward_config: &RewardConfig,
+    user_reward: &mut UserRewardState,
+    user_stake: &UserStakeAccount,
+    current_timestamp: i64,
+) -> Result<u64> {
+    let nft_seconds_now = user_stake.nft_seconds_at(current_timestamp)?;
+    if nft_seconds_now < user_reward.nft_seconds_checkpoint {
+        // The stake account was closed and recreated since the last claim
+        user_reward.nft_seconds_checkpoint = 0;
+    }
+    let nft_seconds = nft_seconds_now
+        .checked_sub(user_reward.nft_seconds_checkpoint)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    user_reward.nft_seconds_checkpoint = nft_seconds_now;
+
+    if !reward_config.active {
+        return Ok(0);
+    }
+
+    let amount = nft_seconds
+        .checked_mul(reward_config.reward_rate_per_second)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    user_reward.total_claimed = user_reward.total_claimed
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(amount)
+}
+
+/// Rewards accrued since the user's last update, including any boost.
+pub(crate) fn accrue_user_rewards(
+    vault: &VaultAccount,
+    config: &VaultConfig,
+    user_stake: &UserStakeAccount,
+    current_timestamp: i64,
+) -> Result<u64> {
+    if user_stake.staked_nfts == 0 {
+        return Ok(0);
+    }
+    require!(
+        user_stake.season_id == vault.current_season_id,
+        ErrorCode::SeasonRolloverRequired
+    );
+
+    let from = user_stake.last_update_timestamp;
+    let base_rewards = earned_base_rewards(vault, config, user_stake, from, current_timestamp)?;
+    let mut rewards = apply_boost_bps(base_rewards, total_boost_bps(vault, config, user_stake))?;
+
+    // The lock boost only covers the part of the window before the lock ends
+    let lock_end = user_stake.locked_until.min(current_timestamp);
+    if user_stake.lock_boost_bps > 0 && lock_end > from {
+        let locked_base = earned_base_rewards(vault, config, user_stake, from, lock_end)?;
+        let lock_bonus = apply_boost_bps(locked_base, user_stake.lock_boost_bps)?
+            .checked_sub(locked_base)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        rewards = rewards
+            .checked_add(lock_bonus)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    Ok(rewards)
+}
+
+/// Anti-exploitation bound on a claim out of accrual: no more than the
+/// position could have earned at its current boosted rate over every
+/// NFT-second it has accumulated. Cumulative, so rewards left pending after
+/// a full unstake stay claimable, and accrual left alone for a while isn't
+/// locked out. `bonus_bps` are the claim-time boosts on top of the
+/// position's own. Returns the boosted rate and the bound.
+pub(crate) fn claim_bound(
+    vault: &VaultAccount,
+    config: &VaultConfig,
+    user_stake: &UserStakeAccount,
+    bonus_bps: u64,
+    current_timestamp: i64,
+) -> Result<(u64, u64)> {
+    let effective_rate = apply_boost_bps(
+        config.reward_rate_ceil(),
+        total_boost_bps(vault, config, user_stake)
+            .saturating_add(user_stake.lock_boost_bps)
+            .saturating_add(bonus_bps),
+    )?;
+    let max_total_reward = (effective_rate as u128)
+        .checked_mul(user_stake.nft_seconds_at(current_timestamp)? as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .min(u64::MAX as u128) as u64;
+
+    Ok((effective_rate, max_total_reward))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_rewards_stay_claimable_after_full_unstake() {
+        let vault = VaultAccount::deserialize(&mut vec![0u8; VaultAccount::INIT_SPACE].as_slice())
+            .unwrap();
+        let mut config: VaultConfig = bytemuck::Zeroable::zeroed();
+        config.reward_rate_per_second = 10;
+        let mut stake = UserStakeAccount::deserialize(
+            &mut vec![0u8; UserStakeAccount::INIT_SPACE].as_slice(),
+        )
+        .unwrap();
+        stake.staked_nfts = 2;
+        stake.last_update_timestamp = 1_000;
+
+        // Two NFTs for 100 seconds, then everything is unstaked
+        stake.touch(&vault, 1_100).unwrap();
+        stake.staked_nfts = 0;
+        stake.pending_rewards = 2_000;
+
+        let (rate, allowed) = claim_bound(&vault, &config, &stake, 0, 5_000).unwrap();
+        assert_eq!(rate, 10);
+        assert_eq!(allowed, 2_000);
+        assert!(stake.pending_rewards <= allowed);
+    }
+
+    #[test]
+    fn one_off_payouts_count_against_the_shard_cap() {
+        let mut vault = VaultAccount::deserialize(&mut vec![0u8; VaultAccount::INIT_SPACE].as_slice())
+            .unwrap();
+        vault.max_reward_supply = 8_000;
+        let mut config: VaultConfig = bytemuck::Zeroable::zeroed();
+        config.daily_limit.max_claims_per_day = 800;
+        config.daily_limit.max_total_rewards_per_day = 800_000;
+        let mut shard: VaultShard = bytemuck::Zeroable::zeroed();
+        shard.index = 1;
+
+        // Each of the eight shards may mint 1_000 before the next fold
+        book_primary_payout(&vault, &config, &mut shard, 600, 0).unwrap();
+        assert_eq!(shard.rewards_minted, 600);
+        assert_eq!(shard.daily_limit.claims_today, 1);
+        assert!(book_primary_payout(&vault, &config, &mut shard, 500, 0).is_err());
+        book_primary_payout(&vault, &config, &mut shard, 400, 0).unwrap();
+        assert_eq!(shard.reward_allowance(&vault), 0);
+    }
+}
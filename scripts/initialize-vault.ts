@@ -20,7 +20,7 @@ const NETWORK = process.env.SOLANA_NETWORK || "devnet";
 const PROGRAM_ID = new PublicKey(process.env.PROGRAM_ID || "DjVE6JNiYqPL2QXyCUUh8rNjHrbz9hXHNYt99MQ59qw1");
 
 // Vault configuration
-const REWARD_RATE_PER_SECOND = new anchor.BN(1_000_000); // 1 token per second (6 decimals)
+const REWARD_TOKENS_PER_DAY = new anchor.BN(86_400); // 1 token per second
 const COLLECTION_MINT = new PublicKey("11111111111111111111111111111111"); // Replace with actual collection mint
 
 async function main() {
@@ -80,7 +80,7 @@ async function main() {
       programId: PROGRAM_ID,
       data: Buffer.concat([
         Buffer.from([0]), // Instruction discriminator for initialize_vault
-        REWARD_RATE_PER_SECOND.toBuffer("le", 8),
+        REWARD_TOKENS_PER_DAY.toBuffer("le", 8),
         COLLECTION_MINT.toBuffer(),
       ]),
     };
@@ -90,7 +90,16 @@ async function main() {
     // In a real implementation with proper IDL:
     /*
     const tx = await program.methods
-      .initializeVault(REWARD_RATE_PER_SECOND, COLLECTION_MINT)
+      .initializeVault(
+        REWARD_TOKENS_PER_DAY,
+        COLLECTION_MINT,
+        { mint: {} },
+        100, // max stakes per day
+        50, // max claims per day
+        new anchor.BN(1_000), // max rewards per day, in whole tokens
+        10, // circuit breaker failure threshold
+        new anchor.BN(600), // circuit breaker reset timeout
+      )
       .accounts({
         vault: vaultPda,
         authority: wallet.publicKey,
@@ -108,7 +117,7 @@ async function main() {
     console.log("   - Vault PDA:", vaultPda.toString());
     console.log("   - Authority:", wallet.publicKey.toString());
     console.log("   - Reward Token Mint:", rewardTokenMint.toString());
-    console.log("   - Reward Rate:", REWARD_RATE_PER_SECOND.toString(), "tokens/second");
+    console.log("   - Reward Rate:", REWARD_TOKENS_PER_DAY.toString(), "tokens/day");
     console.log("   - Collection Mint:", COLLECTION_MINT.toString());
 
     // Transfer mint authority to vault PDA
@@ -135,7 +144,7 @@ async function main() {
     console.log("   Vault PDA:", vaultPda.toString());
     console.log("   Reward Token Mint:", rewardTokenMint.toString());
     console.log("   Collection Mint:", COLLECTION_MINT.toString());
-    console.log("   Reward Rate:", REWARD_RATE_PER_SECOND.toString(), "per second");
+    console.log("   Reward Rate:", REWARD_TOKENS_PER_DAY.toString(), "per day");
 
     console.log("\n🔧 Next Steps:");
     console.log("1. Update your frontend .env.local with the reward token mint:");
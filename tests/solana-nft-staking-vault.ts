@@ -1,6 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { SolanaNftStakingVault } from "../target/types/solana_nft_staking_vault";
+import { EscrowStaker } from "../target/types/escrow_staker";
 import { 
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -16,6 +17,7 @@ import {
   Keypair, 
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
 } from "@solana/web3.js";
 import { 
   Metaplex, 
@@ -30,6 +32,7 @@ describe("solana-nft-staking-vault", () => {
   anchor.setProvider(provider);
 
   const program = anchor.workspace.SolanaNftStakingVault as Program<SolanaNftStakingVault>;
+  const escrowProgram = anchor.workspace.EscrowStaker as Program<EscrowStaker>;
   
   // Test accounts
   const authority = Keypair.generate();
@@ -43,11 +46,64 @@ describe("solana-nft-staking-vault", () => {
   let userNftTokenAccount: PublicKey;
   let vaultPda: PublicKey;
   let userStakePda: PublicKey;
+  let userStatusPda: PublicKey;
+  let vaultConfigPda: PublicKey;
+  let vaultStatsPda: PublicKey;
+  let vaultMetricsPda: PublicKey;
+  let stakeRecordPda: PublicKey;
+  let receiptMintPda: PublicKey;
+  let nftGroupTagPda: PublicKey;
+  let nftMultiplierPda: PublicKey;
+  let voterWeightRecordPda: PublicKey;
+  let userReceiptTokenAccount: PublicKey;
   let vaultBump: number;
   let metaplex: Metaplex;
 
-  const REWARD_RATE_PER_SECOND = new anchor.BN(1000000); // 1 token per second (with 6 decimals)
+  const REWARD_TOKENS_PER_DAY = new anchor.BN(86_400); // 1 token per second
+  const REWARD_RATE_PER_SECOND = new anchor.BN(1000000); // the same, in base units (6 decimals)
   const METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+  const VAULT_SHARD_COUNT = 8;
+  const USER_STAKE_VERSION = 29; // UserStakeAccount::CURRENT_VERSION
+
+  // Mirrors `VaultShard::index_for`
+  const vaultShardPda = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_shard"), Buffer.from([owner.toBuffer()[0] % VAULT_SHARD_COUNT])],
+      program.programId
+    )[0];
+
+  // Compute units each instruction must stay under. Raise one only
+  // deliberately, when a change needs the extra headroom.
+  const COMPUTE_BUDGETS = {
+    initializeVault: 80_000,
+    stakeNft: 190_000,
+    claimRewards: 120_000,
+    unstakeNft: 100_000,
+  };
+
+  const expectWithinComputeBudget = async (
+    signature: string,
+    instruction: keyof typeof COMPUTE_BUDGETS
+  ) => {
+    await provider.connection.confirmTransaction(signature, "confirmed");
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    expect(tx.meta.computeUnitsConsumed).to.be.at.most(COMPUTE_BUDGETS[instruction]);
+  };
+
+  const foldVaultShard = (owner: PublicKey) =>
+    program.methods
+      .foldVaultShard()
+      .accounts({
+        vault: vaultPda,
+        vaultConfig: vaultConfigPda,
+        vaultStats: vaultStatsPda,
+        vaultMetrics: vaultMetricsPda,
+        vaultShard: vaultShardPda(owner),
+      })
+      .rpc();
 
   before(async () => {
     // Airdrop SOL to test accounts
@@ -129,17 +185,80 @@ describe("solana-nft-staking-vault", () => {
       program.programId
     );
 
+    [vaultConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_config")],
+      program.programId
+    );
+
+    [vaultStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_stats")],
+      program.programId
+    );
+
+    [vaultMetricsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_metrics")],
+      program.programId
+    );
+
     [userStakePda] = PublicKey.findProgramAddressSync(
       [Buffer.from("user_stake"), user.publicKey.toBuffer()],
       program.programId
     );
+
+    [userStatusPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_status"), user.publicKey.toBuffer()],
+      program.programId
+    );
+
+    [stakeRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake_record"), nft.mintAddress.toBuffer()],
+      program.programId
+    );
+
+    [receiptMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), nft.mintAddress.toBuffer()],
+      program.programId
+    );
+
+    [nftGroupTagPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nft_group"), nft.mintAddress.toBuffer()],
+      program.programId
+    );
+
+    [nftMultiplierPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("nft_multiplier"), nft.mintAddress.toBuffer()],
+      program.programId
+    );
+
+    [voterWeightRecordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voter_weight_record"), user.publicKey.toBuffer()],
+      program.programId
+    );
+
+    userReceiptTokenAccount = await getAssociatedTokenAddress(
+      receiptMintPda,
+      user.publicKey
+    );
   });
 
   it("Initialize vault", async () => {
-    await program.methods
-      .initializeVault(REWARD_RATE_PER_SECOND, collectionNft.mintAddress)
+    const signature = await program.methods
+      .initializeVault(
+        REWARD_TOKENS_PER_DAY,
+        collectionNft.mintAddress,
+        { mint: {} },
+        100, // max stakes per day
+        50, // max claims per day
+        new anchor.BN(1_000), // max rewards per day, in whole tokens
+        10, // circuit breaker failure threshold
+        new anchor.BN(600), // circuit breaker reset timeout
+        new anchor.BN(300), // per-NFT unstake cooldown
+        new anchor.BN(60), // claim cooldown
+      )
       .accounts({
         vault: vaultPda,
+        vaultConfig: vaultConfigPda,
+        vaultStats: vaultStatsPda,
         authority: authority.publicKey,
         rewardTokenMint: rewardTokenMint,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -147,22 +266,74 @@ describe("solana-nft-staking-vault", () => {
       })
       .signers([authority])
       .rpc();
+    await expectWithinComputeBudget(signature, "initializeVault");
 
     // Verify vault was initialized correctly
     const vaultAccount = await program.account.vaultAccount.fetch(vaultPda);
     expect(vaultAccount.authority.toString()).to.equal(authority.publicKey.toString());
     expect(vaultAccount.totalStaked).to.equal(0);
     expect(vaultAccount.rewardTokenMint.toString()).to.equal(rewardTokenMint.toString());
-    expect(vaultAccount.rewardRatePerSecond.toString()).to.equal(REWARD_RATE_PER_SECOND.toString());
     expect(vaultAccount.collectionMint.toString()).to.equal(collectionNft.mintAddress.toString());
-    expect(vaultAccount.paused).to.equal(false);
     expect(vaultAccount.bump).to.equal(vaultBump);
 
+    const vaultConfig = await program.account.vaultConfig.fetch(vaultConfigPda);
+    expect(vaultConfig.rewardRatePerSecond.toString()).to.equal(REWARD_RATE_PER_SECOND.toString());
+    expect(vaultConfig.pauseFlags).to.equal(0);
+
+    // The initializer is the first SuperAdmin
+    const [authorityRolePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), authority.publicKey.toBuffer()],
+      program.programId
+    );
+    const authorityRole = await program.account.accountRole.fetch(authorityRolePda);
+    expect(authorityRole.role).to.deep.equal({ superAdmin: {} });
+
     // Verify that mint authority was transferred to vault
     const mintInfo = await provider.connection.getAccountInfo(rewardTokenMint);
     expect(mintInfo).to.not.be.null;
   });
 
+  it("Initialize vault metrics", async () => {
+    await program.methods
+      .initializeVaultMetrics()
+      .accounts({
+        vault: vaultPda,
+        vaultMetrics: vaultMetricsPda,
+        payer: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const metrics = await program.account.vaultMetrics.fetch(vaultMetricsPda);
+    expect(metrics.lastTotalStaked).to.equal(0);
+    expect(metrics.epochRewards.toString()).to.equal("0");
+  });
+
+  it("Initialize vault shards", async () => {
+    for (let index = 0; index < VAULT_SHARD_COUNT; index++) {
+      const [vaultShard] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_shard"), Buffer.from([index])],
+        program.programId
+      );
+      await program.methods
+        .initializeVaultShard(index)
+        .accounts({
+          vaultConfig: vaultConfigPda,
+          vaultShard,
+          payer: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+    }
+
+    const shard = await program.account.vaultShard.fetch(vaultShardPda(user.publicKey));
+    expect(shard.stakedDelta.toString()).to.equal("0");
+    // The daily stake limit is split evenly across shards
+    expect(shard.dailyLimit.maxStakesPerDay).to.equal(Math.ceil(100 / VAULT_SHARD_COUNT));
+  });
+
   it("Stake NFT", async () => {
     // Get user's NFT token account
     const userNftTokenAccount = await getAssociatedTokenAddress(
@@ -185,17 +356,41 @@ describe("solana-nft-staking-vault", () => {
       ],
       METADATA_PROGRAM_ID
     );
+    const [nftMasterEditionPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('metadata'),
+        METADATA_PROGRAM_ID.toBuffer(),
+        nft.mintAddress.toBuffer(),
+        Buffer.from('edition'),
+      ],
+      METADATA_PROGRAM_ID
+    );
 
-    await program.methods
+    const signature = await program.methods
       .stakeNft()
       .accounts({
         vault: vaultPda,
+        vaultConfig: vaultConfigPda,
+        vaultShard: vaultShardPda(user.publicKey),
         userStake: userStakePda,
         user: user.publicKey,
+        payer: user.publicKey,
+        userStatus: userStatusPda,
+        whitelistEntry: null,
         nftMint: nft.mintAddress,
+        traitPass: null,
+        stakeRecord: stakeRecordPda,
+        referral: null,
+        nftGroupTag: nftGroupTagPda,
+        nftMultiplier: nftMultiplierPda,
+        voterWeightRecord: voterWeightRecordPda,
+        receiptMint: receiptMintPda,
+        userReceiptTokenAccount: userReceiptTokenAccount,
         nftMetadata: nftMetadataPda,
+        nftMasterEdition: nftMasterEditionPda,
         userNftTokenAccount: userNftTokenAccount,
         vaultNftTokenAccount: vaultNftTokenAccount,
+        partner: null,
         metadataProgram: METADATA_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -203,6 +398,7 @@ describe("solana-nft-staking-vault", () => {
       })
       .signers([user])
       .rpc();
+    await expectWithinComputeBudget(signature, "stakeNft");
 
     // Verify NFT was transferred to vault
     const vaultNftAccount = await provider.connection.getTokenAccountBalance(vaultNftTokenAccount);
@@ -216,30 +412,78 @@ describe("solana-nft-staking-vault", () => {
     expect(userStakeAccount.user.toString()).to.equal(user.publicKey.toString());
     expect(userStakeAccount.stakedNfts).to.equal(1);
     expect(userStakeAccount.pendingRewards.toString()).to.equal("0");
+    expect(userStakeAccount.version).to.equal(USER_STAKE_VERSION);
+    expect(userStakeAccount.firstStakeTimestamp.toNumber()).to.be.greaterThan(0);
+
+    // Verify per-NFT stake record
+    const stakeRecord = await program.account.stakeRecord.fetch(stakeRecordPda);
+    expect(stakeRecord.user.toString()).to.equal(user.publicKey.toString());
+    expect(stakeRecord.nftMint.toString()).to.equal(nft.mintAddress.toString());
+
+    // Verify the position receipt was issued
+    const receiptBalance = await provider.connection.getTokenAccountBalance(userReceiptTokenAccount);
+    expect(parseInt(receiptBalance.value.amount)).to.equal(1);
+
+    // The stake sits in the user's shard until it is folded
+    const shard = await program.account.vaultShard.fetch(vaultShardPda(user.publicKey));
+    expect(shard.stakedDelta.toString()).to.equal("1");
+    await foldVaultShard(user.publicKey);
 
     // Verify vault total staked
     const vaultAccount = await program.account.vaultAccount.fetch(vaultPda);
     expect(vaultAccount.totalStaked).to.equal(1);
+
+    const vaultStats = await program.account.vaultStats.fetch(vaultStatsPda);
+    expect(vaultStats.uniqueStakers).to.equal(1);
+    expect(vaultStats.peakTotalStaked).to.equal(1);
   });
 
   it("Claim rewards after time passes", async () => {
     // Wait for some time to accumulate rewards (more than 60 seconds for rate limiting)
     await new Promise(resolve => setTimeout(resolve, 65000));
 
-    await program.methods
+    // Quote pending rewards through the read-only view instruction
+    const quote = await program.methods
+      .getPendingRewards()
+      .accounts({
+        vault: vaultPda,
+        vaultConfig: vaultConfigPda,
+        userStake: userStakePda,
+        user: user.publicKey,
+      })
+      .view();
+    expect(quote.toNumber()).to.be.greaterThan(0);
+
+    const signature = await program.methods
       .claimRewards()
       .accounts({
         vault: vaultPda,
+        vaultConfig: vaultConfigPda,
+        vaultShard: vaultShardPda(user.publicKey),
         userStake: userStakePda,
         user: user.publicKey,
+        claimer: user.publicKey,
+        sessionAuthority: null,
+        userStatus: userStatusPda,
         rewardTokenMint: rewardTokenMint,
         userRewardTokenAccount: userRewardTokenAccount,
+        destination: null,
+        rewardPool: null,
+        feeDestination: null,
+        insurancePool: null,
+        referral: null,
+        referrerRewardTokenAccount: null,
+        levelConfig: null,
+        setBonus: null,
+        vestingPosition: null,
+        partner: null,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
       .signers([user])
       .rpc();
+    await expectWithinComputeBudget(signature, "claimRewards");
 
     // Verify rewards were received
     const userRewardBalance = await provider.connection.getTokenAccountBalance(userRewardTokenAccount);
@@ -248,6 +492,7 @@ describe("solana-nft-staking-vault", () => {
     // Verify pending rewards were reset
     const userStakeAccount = await program.account.userStakeAccount.fetch(userStakePda);
     expect(userStakeAccount.pendingRewards.toString()).to.equal("0");
+    expect(userStakeAccount.totalRewardsClaimed.toString()).to.equal(userRewardBalance.value.amount);
   });
 
   it("Unstake NFT", async () => {
@@ -257,33 +502,187 @@ describe("solana-nft-staking-vault", () => {
       true
     );
 
-    await program.methods
+    const signature = await program.methods
       .unstakeNft()
       .accounts({
         vault: vaultPda,
+        vaultConfig: vaultConfigPda,
+        vaultShard: vaultShardPda(user.publicKey),
         userStake: userStakePda,
         user: user.publicKey,
+        rentReceiver: user.publicKey,
         nftMint: nftMint,
+        stakeRecord: stakeRecordPda,
+        receiptMint: receiptMintPda,
+        userReceiptTokenAccount: userReceiptTokenAccount,
+        nftGroupTag: nftGroupTagPda,
+        nftMultiplier: nftMultiplierPda,
+        voterWeightRecord: voterWeightRecordPda,
         userNftTokenAccount: userNftTokenAccount,
         vaultNftTokenAccount: vaultNftTokenAccount,
+        partner: null,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([user])
       .rpc();
+    await expectWithinComputeBudget(signature, "unstakeNft");
 
     // Verify NFT was returned to user
     const userNftAccount = await provider.connection.getTokenAccountBalance(userNftTokenAccount);
     expect(parseInt(userNftAccount.value.amount)).to.equal(1);
 
-    const vaultNftAccount = await provider.connection.getTokenAccountBalance(vaultNftTokenAccount);
-    expect(parseInt(vaultNftAccount.value.amount)).to.equal(0);
+    // Vault NFT account is closed and its rent refunded
+    const vaultNftAccountInfo = await provider.connection.getAccountInfo(vaultNftTokenAccount);
+    expect(vaultNftAccountInfo).to.be.null;
+
+    // Stake record is closed with the position
+    const stakeRecordInfo = await provider.connection.getAccountInfo(stakeRecordPda);
+    expect(stakeRecordInfo).to.be.null;
+
+    // Receipt is burned and its account closed
+    const receiptInfo = await provider.connection.getAccountInfo(userReceiptTokenAccount);
+    expect(receiptInfo).to.be.null;
 
     // Verify user stake account
     const userStakeAccount = await program.account.userStakeAccount.fetch(userStakePda);
     expect(userStakeAccount.stakedNfts).to.equal(0);
 
     // Verify vault total staked
+    await foldVaultShard(user.publicKey);
     const vaultAccount = await program.account.vaultAccount.fetch(vaultPda);
     expect(vaultAccount.totalStaked).to.equal(0);
   });
-});
\ No newline at end of file
+
+  it("Stake from a program-owned escrow through CPI", async () => {
+    const [escrowPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), user.publicKey.toBuffer()],
+      escrowProgram.programId
+    );
+
+    // The escrow PDA holds the NFT, as a lending protocol's escrow would
+    const escrowedNft = await metaplex.nfts().create({
+      uri: "https://example.com/escrowed.json",
+      name: "Escrowed NFT",
+      symbol: "TNFT",
+      sellerFeeBasisPoints: 500,
+      tokenOwner: escrowPda,
+      collection: {
+        key: collectionNft.mintAddress,
+        verified: false,
+      },
+      creators: [{ address: collectionAuthority.publicKey, share: 100, verified: true }],
+    });
+    await metaplex.nfts().verifyCollection({
+      mintAddress: escrowedNft.mintAddress,
+      collectionMintAddress: collectionNft.mintAddress,
+      collectionAuthority: collectionAuthority,
+    });
+    const mint = escrowedNft.mintAddress;
+
+    // Register the escrow program as a partner allowed to stake
+    const [partnerPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("partner"), escrowProgram.programId.toBuffer()],
+      program.programId
+    );
+    const [authorityRolePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("role"), authority.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .configurePartnerProgram(true, false, true)
+      .accounts({
+        vault: vaultPda,
+        partnerProgram: escrowProgram.programId,
+        partner: partnerPda,
+        updater: authority.publicKey,
+        updaterRole: authorityRolePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const pda = (seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    const [escrowStakePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_stake"), escrowPda.toBuffer()],
+      program.programId
+    );
+    const escrowReceiptMint = pda([Buffer.from("receipt_mint"), mint.toBuffer()]);
+    const vaultAccounts = {
+      vault: vaultPda,
+      vaultConfig: vaultConfigPda,
+      vaultShard: vaultShardPda(escrowPda),
+      userStake: escrowStakePda,
+      userStatus: pda([Buffer.from("user_status"), escrowPda.toBuffer()]),
+      nftMint: mint,
+      stakeRecord: pda([Buffer.from("stake_record"), mint.toBuffer()]),
+      nftGroupTag: pda([Buffer.from("nft_group"), mint.toBuffer()]),
+      nftMultiplier: pda([Buffer.from("nft_multiplier"), mint.toBuffer()]),
+      voterWeightRecord: pda([Buffer.from("voter_weight_record"), escrowPda.toBuffer()]),
+      receiptMint: escrowReceiptMint,
+      escrowReceiptTokenAccount: await getAssociatedTokenAddress(escrowReceiptMint, escrowPda, true),
+      escrowNftTokenAccount: await getAssociatedTokenAddress(mint, escrowPda, true),
+      vaultNftTokenAccount: await getAssociatedTokenAddress(mint, vaultPda, true),
+      instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      partner: partnerPda,
+      eventAuthority: pda([Buffer.from("__event_authority")]),
+    };
+
+    const [nftMetadataPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('metadata'), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      METADATA_PROGRAM_ID
+    );
+    const [nftMasterEditionPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('metadata'), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from('edition')],
+      METADATA_PROGRAM_ID
+    );
+
+    // The user pays for the position; the escrow PDA signs as the staker
+    await escrowProgram.methods
+      .stakeEscrowed()
+      .accounts({
+        escrow: escrowPda,
+        depositor: user.publicKey,
+        vaultAccounts,
+        nftMetadata: nftMetadataPda,
+        nftMasterEdition: nftMasterEditionPda,
+        traitPass: null,
+        stakingProgram: program.programId,
+        metadataProgram: METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    const escrowStake = await program.account.userStakeAccount.fetch(escrowStakePda);
+    expect(escrowStake.user.toString()).to.equal(escrowPda.toString());
+    expect(escrowStake.stakedNfts).to.equal(1);
+    expect(escrowStake.rentPayer.toString()).to.equal(user.publicKey.toString());
+
+    const vaultNftBalance = await provider.connection.getTokenAccountBalance(vaultAccounts.vaultNftTokenAccount);
+    expect(parseInt(vaultNftBalance.value.amount)).to.equal(1);
+
+    // Wait out the per-NFT unstake cooldown
+    await new Promise(resolve => setTimeout(resolve, 305000));
+
+    await escrowProgram.methods
+      .unstakeEscrowed()
+      .accounts({
+        escrow: escrowPda,
+        depositor: user.publicKey,
+        vaultAccounts,
+        stakingProgram: program.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+
+    // The NFT is back in the escrow
+    const escrowNftBalance = await provider.connection.getTokenAccountBalance(vaultAccounts.escrowNftTokenAccount);
+    expect(parseInt(escrowNftBalance.value.amount)).to.equal(1);
+
+    const escrowStakeAfter = await program.account.userStakeAccount.fetch(escrowStakePda);
+    expect(escrowStakeAfter.stakedNfts).to.equal(0);
+  });
+});